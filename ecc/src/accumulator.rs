@@ -0,0 +1,146 @@
+// RSA アキュムレータ (Benaloh–de Mare, Camenisch–Lysyanskaya)。要素 x を素数 p_x に写し、
+// 集合全体を A = g^{Π p_x} mod n の 1 つの値にまとめる。x の所属は w^{p_x} = A となる
+// w = g^{Π_{y≠x} p_y} で示せ、w の大きさは集合の大きさによらない。
+// Merkle 木と違って証明は定数サイズだが、n の素因数分解を誰も知らないことが前提になる
+use crate::bignum::BigUint;
+use crate::number_theory::{generate_prime, hash_to_prime};
+use crate::rand::RngCore;
+
+// 要素を写す素数の大きさ
+const ELEMENT_PRIME_BITS: usize = 128;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MembershipWitness(pub BigUint);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RsaAccumulator {
+    n: BigUint,
+    value: BigUint,
+}
+
+pub fn element_prime(element: &[u8]) -> BigUint {
+    hash_to_prime(
+        &[b"rsa-accumulator/".as_slice(), element].concat(),
+        ELEMENT_PRIME_BITS,
+    )
+}
+
+fn product(elements: &[&[u8]]) -> BigUint {
+    elements
+        .iter()
+        .fold(BigUint::one(), |acc, e| acc.mul(&element_prime(e)))
+}
+
+impl RsaAccumulator {
+    // 信頼できるセットアップ: 素数 p, q を作って n = pq を公開し、p, q は捨てる
+    pub fn setup<R: RngCore>(bits: usize, rng: &mut R) -> Self {
+        let p = generate_prime(bits / 2, rng);
+        let q = generate_prime(bits - bits / 2, rng);
+        let n = p.mul(&q);
+        // 平方剰余を生成元にする
+        let r = BigUint::random_below(&n, rng);
+        let g = r.mul(&r).rem(&n);
+        RsaAccumulator::new(n, g)
+    }
+
+    pub fn new(n: BigUint, g: BigUint) -> Self {
+        RsaAccumulator { value: g, n }
+    }
+
+    pub fn modulus(&self) -> &BigUint {
+        &self.n
+    }
+
+    pub fn value(&self) -> &BigUint {
+        &self.value
+    }
+
+    // 追加前の値がそのまま新しい要素の証明になる
+    pub fn add(&mut self, element: &[u8]) -> MembershipWitness {
+        let witness = MembershipWitness(self.value.clone());
+        self.value = self.value.modpow(&element_prime(element), &self.n);
+        witness
+    }
+
+    // 複数の要素を 1 回の冪乗で追加し、それぞれの証明 A^{Π_{j≠i} p_j} も返す
+    pub fn batch_add(&mut self, elements: &[&[u8]]) -> Vec<MembershipWitness> {
+        let primes: Vec<BigUint> = elements.iter().map(|e| element_prime(e)).collect();
+        let witnesses = (0..primes.len())
+            .map(|i| {
+                let others = primes
+                    .iter()
+                    .enumerate()
+                    .filter(|&(j, _)| j != i)
+                    .fold(BigUint::one(), |acc, (_, p)| acc.mul(p));
+                MembershipWitness(self.value.modpow(&others, &self.n))
+            })
+            .collect();
+        self.value = self.value.modpow(&product(elements), &self.n);
+        witnesses
+    }
+
+    pub fn verify(&self, element: &[u8], witness: &MembershipWitness) -> bool {
+        witness.0.modpow(&element_prime(element), &self.n) == self.value
+    }
+
+    // 証明を得た後に追加された要素の分だけ証明を持ち上げる: w' = w^{Π p_added}
+    pub fn update_witness(
+        &self,
+        witness: &MembershipWitness,
+        added: &[&[u8]],
+    ) -> MembershipWitness {
+        MembershipWitness(witness.0.modpow(&product(added), &self.n))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::TestRng;
+
+    fn accumulator() -> RsaAccumulator {
+        RsaAccumulator::setup(512, &mut TestRng::from_label(b"rsa-accumulator"))
+    }
+
+    #[test]
+    fn test_membership_and_witness_update() {
+        let mut acc = accumulator();
+        let w_alice = acc.add(b"alice");
+        assert!(acc.verify(b"alice", &w_alice));
+        assert!(!acc.verify(b"mallory", &w_alice));
+        let w_bob = acc.add(b"bob");
+        // bob の追加で alice の証明は古くなるので更新する
+        assert!(!acc.verify(b"alice", &w_alice));
+        let w_alice = acc.update_witness(&w_alice, &[b"bob"]);
+        assert!(acc.verify(b"alice", &w_alice));
+        assert!(acc.verify(b"bob", &w_bob));
+        // 同じ証明を別の要素に流用できない
+        assert!(!acc.verify(b"bob", &w_alice));
+    }
+
+    #[test]
+    fn test_batch_add_matches_sequential() {
+        let mut batched = accumulator();
+        let mut sequential = batched.clone();
+        let elements: [&[u8]; 3] = [b"x", b"y", b"z"];
+        let witnesses = batched.batch_add(&elements);
+        for e in elements {
+            sequential.add(e);
+        }
+        // 素数の積は順序によらないので値は一致する
+        assert_eq!(batched.value(), sequential.value());
+        for (e, w) in elements.iter().zip(&witnesses) {
+            assert!(batched.verify(e, w));
+        }
+        let w = batched.add(b"w");
+        let updated: Vec<MembershipWitness> = witnesses
+            .iter()
+            .map(|x| batched.update_witness(x, &[b"w"]))
+            .collect();
+        assert!(batched.verify(b"w", &w));
+        assert!(elements
+            .iter()
+            .zip(&updated)
+            .all(|(e, w)| batched.verify(e, w)));
+    }
+}
@@ -0,0 +1,72 @@
+// Berlekamp–Massey 法: 2L ビットの鍵ストリームから、それを生成する最短の LFSR を求める
+use crate::symmetric::lfsr::Lfsr;
+
+// 戻り値は (線形複雑度 L, 接続多項式の係数 c_1..c_L)
+pub fn berlekamp_massey(bits: &[bool]) -> (usize, Vec<bool>) {
+    let n = bits.len();
+    // c と b は係数 c_0 = 1 を含む多項式
+    let mut c = vec![false; n + 1];
+    let mut b = vec![false; n + 1];
+    c[0] = true;
+    b[0] = true;
+    let mut l = 0;
+    let mut m = 1;
+    for i in 0..n {
+        // 不一致 d = s_i + Σ c_j s_{i-j}
+        let d = (1..=l).fold(bits[i], |acc, j| acc ^ (c[j] & bits[i - j]));
+        if !d {
+            m += 1;
+            continue;
+        }
+        let prev = c.clone();
+        for j in 0..=n - m {
+            c[j + m] ^= b[j];
+        }
+        if 2 * l <= i {
+            l = i + 1 - l;
+            b = prev;
+            m = 1;
+        } else {
+            m += 1;
+        }
+    }
+    (l, c[1..=l].to_vec())
+}
+
+// 観測した鍵ストリームの先頭から LFSR を復元する。返す LFSR は観測の先頭から同じ列を生成する
+pub fn recover_lfsr(bits: &[bool]) -> Lfsr {
+    let (l, taps) = berlekamp_massey(bits);
+    Lfsr::new(&taps, &bits[..l])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::symmetric::lfsr::Geffe;
+
+    #[test]
+    fn test_recover_lfsr_from_2l_bits() {
+        let mut secret = Lfsr::from_bits(17, 0b1_0010_0000_0000_0000, 0x1abcd);
+        let stream = secret.bits(200);
+        let (l, _) = berlekamp_massey(&stream[..34]);
+        assert_eq!(l, 17);
+        // 34 ビットだけから、その先の鍵ストリームをすべて予測できる
+        let mut clone = recover_lfsr(&stream[..34]);
+        assert_eq!(clone.bits(200), stream);
+    }
+
+    #[test]
+    fn test_geffe_linear_complexity() {
+        // Geffe の線形複雑度は L1 L2 + L2 L3 + L3 = 35 + 77 + 11 = 123 で、
+        // 非線形に組み合わせても 246 ビットあれば線形に復元できてしまう
+        let mut geffe = Geffe::new(
+            Lfsr::from_bits(5, 0b10100, 0b10011),
+            Lfsr::from_bits(7, 0b1100000, 0b0101101),
+            Lfsr::from_bits(11, 0b10100000000, 0b11100001010),
+        );
+        let stream = geffe.bits(600);
+        let (l, _) = berlekamp_massey(&stream[..300]);
+        assert_eq!(l, 123);
+        assert_eq!(recover_lfsr(&stream[..300]).bits(600), stream);
+    }
+}
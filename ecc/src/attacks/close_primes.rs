@@ -0,0 +1,60 @@
+// p と q が近すぎる RSA 鍵はフェルマー法で分解できる
+use crate::bignum::BigUint;
+use crate::number_theory::{fermat_factor, generate_prime, is_prime_big};
+use crate::rand::RngCore;
+use crate::rsa::{RsaPrivateKey, RsaPublicKey};
+
+// この回数で分解できるなら |p - q| はおおよそ n^(1/4) * 2^10 以下
+const FERMAT_AUDIT_STEPS: usize = 100_000;
+
+// 危険なら見つかった (p, q) を返す
+pub fn check_close_primes(n: &BigUint) -> Option<(BigUint, BigUint)> {
+    if n.is_even() {
+        return Some((BigUint::from_u64(2), n.shr(1)));
+    }
+    fermat_factor(n, FERMAT_AUDIT_STEPS)
+}
+
+// p の直後の素数を q にした、フェルマー法で即座に破れる鍵
+pub fn generate_close_prime_key<R: RngCore>(
+    bits: usize,
+    rng: &mut R,
+) -> (RsaPublicKey, RsaPrivateKey) {
+    let e = BigUint::from_u64(65537);
+    let two = BigUint::from_u64(2);
+    loop {
+        let p = generate_prime(bits / 2, rng);
+        let mut q = p.add(&two);
+        while !is_prime_big(&q, 32, rng) {
+            q = q.add(&two);
+        }
+        if let Some(sk) = RsaPrivateKey::from_primes(p, q, &e) {
+            let pk = RsaPublicKey {
+                n: sk.n.clone(),
+                e: e.clone(),
+            };
+            return (pk, sk);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::OsRng;
+    use crate::rsa::keygen;
+
+    #[test]
+    fn test_close_primes_are_flagged() {
+        let (pk, sk) = generate_close_prime_key(512, &mut OsRng);
+        let (p, q) = check_close_primes(&pk.n).unwrap();
+        let (sp, sq) = sk.primes();
+        assert_eq!((&p, &q), (sp.min(sq), sp.max(sq)));
+    }
+
+    #[test]
+    fn test_normal_key_passes_audit() {
+        let (pk, _) = keygen(512, &mut OsRng);
+        assert_eq!(check_close_primes(&pk.n), None);
+    }
+}
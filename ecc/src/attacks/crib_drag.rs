@@ -0,0 +1,53 @@
+// 鍵ストリームの使い回し: c1 ^ c2 = m1 ^ m2 なので鍵が消える。
+// 一方の平文にありそうな語 (crib) を各位置で XOR し、もう一方の平文の断片を探す
+
+pub fn xor_ciphertexts(c1: &[u8], c2: &[u8]) -> Vec<u8> {
+    c1.iter().zip(c2).map(|(a, b)| a ^ b).collect()
+}
+
+fn is_plausible_text(fragment: &[u8]) -> bool {
+    fragment
+        .iter()
+        .all(|&b| b.is_ascii_alphanumeric() || b" .,;:'!?-".contains(&b))
+}
+
+// crib を各位置に置いたとき、もう一方の平文側に現れる印字可能な断片を (位置, 断片) で返す
+pub fn crib_drag(c1: &[u8], c2: &[u8], crib: &[u8]) -> Vec<(usize, Vec<u8>)> {
+    let x = xor_ciphertexts(c1, c2);
+    if crib.is_empty() || crib.len() > x.len() {
+        return Vec::new();
+    }
+    (0..=x.len() - crib.len())
+        .filter_map(|pos| {
+            let fragment: Vec<u8> = x[pos..pos + crib.len()]
+                .iter()
+                .zip(crib)
+                .map(|(a, b)| a ^ b)
+                .collect();
+            is_plausible_text(&fragment).then_some((pos, fragment))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::OsRng;
+    use crate::symmetric::otp::{generate_pad, otp_encrypt};
+
+    #[test]
+    fn test_pad_reuse_leaks_plaintext() {
+        let m1 = b"attack the northern bridge at dawn";
+        let m2 = b"we will hold the river until night";
+        let pad = generate_pad(m1.len(), &mut OsRng);
+        let c1 = otp_encrypt(&pad, m1);
+        let c2 = otp_encrypt(&pad, m2);
+        assert_eq!(xor_ciphertexts(&c1, &c2), xor_ciphertexts(m1, m2));
+        // m1 に " the " があると推測すると、その位置で m2 の断片が読める
+        let hits = crib_drag(&c1, &c2, b" the ");
+        assert!(hits.contains(&(6, m2[6..11].to_vec())));
+        // 正しい位置が分かれば m2 側の推測から m1 をさらに広げられる
+        let hits = crib_drag(&c2, &c1, b"river");
+        assert!(hits.contains(&(17, m1[17..22].to_vec())));
+    }
+}
@@ -0,0 +1,84 @@
+// 長さ拡張攻撃: Merkle–Damgård ハッシュでは H(key || msg) から鍵を知らずに
+// H(key || msg || padding || suffix) が計算できる。HMAC が必要な理由
+use crate::hash::{Sha1, Sha256};
+
+// 脆弱な MAC。鍵をメッセージの前に付けてハッシュするだけ
+pub fn secret_prefix_mac(key: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut h = Sha256::new();
+    h.update(key);
+    h.update(message);
+    h.finalize()
+}
+
+// len バイトのメッセージに SHA-1 / SHA-256 が付けるパディング
+pub fn md_padding(len: u64) -> Vec<u8> {
+    let mut padding = vec![0x80];
+    while (len as usize + padding.len()) % 64 != 56 {
+        padding.push(0);
+    }
+    padding.extend_from_slice(&len.wrapping_mul(8).to_be_bytes());
+    padding
+}
+
+// 鍵長 key_len の推測のもとで、(偽造メッセージ, その MAC) を返す
+pub fn extend_sha256(
+    tag: &[u8; 32],
+    message: &[u8],
+    key_len: usize,
+    suffix: &[u8],
+) -> (Vec<u8>, [u8; 32]) {
+    let glue = md_padding((key_len + message.len()) as u64);
+    let processed = (key_len + message.len() + glue.len()) as u64;
+    let mut h = Sha256::resume_from_state(tag, processed);
+    h.update(suffix);
+    ([message, &glue, suffix].concat(), h.finalize())
+}
+
+pub fn extend_sha1(
+    tag: &[u8; 20],
+    message: &[u8],
+    key_len: usize,
+    suffix: &[u8],
+) -> (Vec<u8>, [u8; 20]) {
+    let glue = md_padding((key_len + message.len()) as u64);
+    let processed = (key_len + message.len() + glue.len()) as u64;
+    let mut h = Sha1::resume_from_state(tag, processed);
+    h.update(suffix);
+    ([message, &glue, suffix].concat(), h.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::sha1;
+    use crate::mac::hmac::hmac;
+
+    #[test]
+    fn test_forge_secret_prefix_mac() {
+        let key = b"server-side secret";
+        let message = b"user=alice&role=guest";
+        let tag = secret_prefix_mac(key, message);
+        // 攻撃者は鍵長だけを総当たりし、サーバの検証を通る偽造を見つける
+        let forged = (1..64).find_map(|key_len| {
+            let (m, t) = extend_sha256(&tag, message, key_len, b"&role=admin");
+            (secret_prefix_mac(key, &m) == t).then_some((key_len, m))
+        });
+        let (key_len, forged_message) = forged.unwrap();
+        assert_eq!(key_len, key.len());
+        assert!(forged_message.starts_with(message));
+        assert!(forged_message.ends_with(b"&role=admin"));
+        // HMAC は同じ手口では破れない
+        let hmac_tag: [u8; 32] = hmac::<Sha256>(key, message).try_into().unwrap();
+        let (m, t) = extend_sha256(&hmac_tag, message, key.len(), b"&role=admin");
+        assert_ne!(hmac::<Sha256>(key, &m), t.to_vec());
+    }
+
+    #[test]
+    fn test_sha1_extension() {
+        let key = [0x42u8; 16];
+        let message = b"amount=10";
+        let tag = sha1(&[&key[..], message].concat());
+        let (m, t) = extend_sha1(&tag, message, key.len(), b"0000");
+        assert_eq!(sha1(&[&key[..], &m].concat()), t);
+    }
+}
@@ -0,0 +1,14 @@
+// 教育用の攻撃デモ。各モジュールは脆弱な鍵の生成関数と攻撃関数を持つ。
+pub mod berlekamp_massey;
+pub mod close_primes;
+pub mod crib_drag;
+pub mod length_extension;
+pub mod padding_oracle;
+pub mod wiener;
+
+pub use berlekamp_massey::{berlekamp_massey, recover_lfsr};
+pub use close_primes::{check_close_primes, generate_close_prime_key};
+pub use crib_drag::{crib_drag, xor_ciphertexts};
+pub use length_extension::{extend_sha1, extend_sha256, md_padding, secret_prefix_mac};
+pub use padding_oracle::padding_oracle_decrypt;
+pub use wiener::{generate_wiener_vulnerable_key, wiener_attack};
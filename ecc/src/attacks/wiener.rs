@@ -0,0 +1,86 @@
+// Wiener の攻撃: d < n^(1/4) / 3 なら k/d は e/n の連分数の近似分数に現れる
+use crate::bignum::BigUint;
+use crate::number_theory::{
+    continued_fraction, convergents, gcd_big, generate_prime, mod_inverse_big,
+};
+use crate::rand::RngCore;
+use crate::rsa::{RsaPrivateKey, RsaPublicKey};
+
+// 成功すれば秘密指数 d を返す
+pub fn wiener_attack(n: &BigUint, e: &BigUint) -> Option<BigUint> {
+    let one = BigUint::one();
+    for (k, d) in convergents(&continued_fraction(e, n)) {
+        if k.is_zero() {
+            continue;
+        }
+        // ed - 1 = kφ(n)
+        let (phi, r) = e.mul(&d).sub(&one).divrem(&k);
+        if !r.is_zero() || phi >= *n {
+            continue;
+        }
+        // p + q = n - φ + 1 として x^2 - (p + q)x + n = 0 が整数解を持つか
+        let s = n.sub(&phi).add(&one);
+        let s2 = s.mul(&s);
+        let four_n = n.shl(2);
+        if s2 < four_n {
+            continue;
+        }
+        let disc = s2.sub(&four_n);
+        let root = disc.isqrt();
+        if root.mul(&root) == disc && !s.add(&root).bit(0) {
+            let p = s.add(&root).shr(1);
+            if p.mul(&s.sub(&p)) == *n {
+                return Some(d);
+            }
+        }
+    }
+    None
+}
+
+// d を n^(1/4) / 3 未満に取った、Wiener の攻撃で破れる RSA 鍵
+pub fn generate_wiener_vulnerable_key<R: RngCore>(
+    bits: usize,
+    rng: &mut R,
+) -> (RsaPublicKey, RsaPrivateKey) {
+    let one = BigUint::one();
+    loop {
+        let p = generate_prime(bits / 2, rng);
+        let q = generate_prime(bits - bits / 2, rng);
+        if p == q {
+            continue;
+        }
+        let n = p.mul(&q);
+        let phi = p.sub(&one).mul(&q.sub(&one));
+        // 3d < n^(1/4) を保証するため bits / 4 - 2 ビットにする
+        let mut d = BigUint::random_bits(bits / 4 - 2, rng);
+        d.set_bit(0);
+        if !gcd_big(&d, &phi).is_one() {
+            continue;
+        }
+        let e = mod_inverse_big(&d, &phi).unwrap();
+        let sk = RsaPrivateKey::from_primes(p, q, &e).unwrap();
+        return (RsaPublicKey { n, e }, sk);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::OsRng;
+    use crate::rsa::keygen;
+
+    #[test]
+    fn test_wiener_recovers_small_private_exponent() {
+        let mut rng = OsRng;
+        for _ in 0..3 {
+            let (pk, sk) = generate_wiener_vulnerable_key(512, &mut rng);
+            assert_eq!(wiener_attack(&pk.n, &pk.e), Some(sk.d.clone()));
+        }
+    }
+
+    #[test]
+    fn test_wiener_fails_on_normal_key() {
+        let (pk, _) = keygen(512, &mut OsRng);
+        assert_eq!(wiener_attack(&pk.n, &pk.e), None);
+    }
+}
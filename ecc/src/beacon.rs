@@ -0,0 +1,194 @@
+// 検証可能な乱数ビーコン。各ラウンドで参加者は
+//   1. 乱数 r_i にハッシュコミットメント C_i を公開し、
+//   2. 全員のコミットメントが揃ってから r_i を開示し、同時に
+//      α = H(ラウンド番号, 前回の出力, C_1..C_n) に対する VRF 出力 y_i を証明付きで出す。
+// 出力は全記録を吸収したトランスクリプトから導く。コミットメントのおかげで後出しで
+// r_i を選べず、VRF は参加者の鍵と α から一意に決まるので、誰か 1 人でも正直なら
+// 出力は予測できない。記録と公開鍵があれば誰でも出力を再計算して確かめられる
+use crate::commitment::{commit_random, Commitment, Opening};
+use crate::hash::Sha256;
+use crate::rand::RngCore;
+use crate::transcript::Transcript;
+use crate::vrf::{self, VrfProof, VrfPublicKey, VrfSecretKey};
+
+const CONTRIBUTION_LEN: usize = 32;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BeaconError {
+    WrongParticipantCount,
+    // 開示がコミットメントと合わない参加者の番号
+    InvalidOpening(usize),
+    InvalidVrfProof(usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reveal {
+    pub opening: Opening,
+    pub vrf_output: [u8; 32],
+    pub vrf_proof: VrfProof,
+}
+
+// 1 ラウンドの公開記録。並びは参加者の登録順
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BeaconRound {
+    pub round: u64,
+    pub previous: [u8; 32],
+    pub commitments: Vec<Commitment>,
+    pub reveals: Vec<Reveal>,
+}
+
+fn round_transcript(round: u64, previous: &[u8; 32], commitments: &[Commitment]) -> Transcript {
+    let mut t = Transcript::new(b"randomness-beacon");
+    t.append_u64(b"round", round);
+    t.append_message(b"previous", previous);
+    for c in commitments {
+        t.append_message(b"commitment", c.as_bytes());
+    }
+    t
+}
+
+// コミットメントが揃ってから決まる VRF の入力
+pub fn vrf_input(round: u64, previous: &[u8; 32], commitments: &[Commitment]) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    round_transcript(round, previous, commitments).challenge_bytes(b"vrf-input", &mut out);
+    out
+}
+
+pub struct Participant {
+    key: VrfSecretKey,
+    pending: Option<Opening>,
+}
+
+impl Participant {
+    pub fn new<R: RngCore>(rng: &mut R) -> Self {
+        let (key, _) = vrf::keygen(rng);
+        Participant { key, pending: None }
+    }
+
+    pub fn public_key(&self) -> VrfPublicKey {
+        self.key.public_key()
+    }
+
+    pub fn commit<R: RngCore>(&mut self, rng: &mut R) -> Commitment {
+        let mut contribution = [0u8; CONTRIBUTION_LEN];
+        rng.fill_bytes(&mut contribution);
+        let (commitment, opening) = commit_random::<Sha256, R>(&contribution, rng);
+        self.pending = Some(opening);
+        commitment
+    }
+
+    pub fn reveal<R: RngCore>(
+        &mut self,
+        round: u64,
+        previous: &[u8; 32],
+        commitments: &[Commitment],
+        rng: &mut R,
+    ) -> Reveal {
+        let opening = self.pending.take().expect("Reveal called before commit");
+        let (vrf_output, vrf_proof) = self
+            .key
+            .prove(&vrf_input(round, previous, commitments), rng);
+        Reveal {
+            opening,
+            vrf_output,
+            vrf_proof,
+        }
+    }
+}
+
+impl BeaconRound {
+    // すべての開示と VRF 証明を確かめ、ラウンドの出力を返す
+    pub fn output(&self, keys: &[VrfPublicKey]) -> Result<[u8; 32], BeaconError> {
+        if self.commitments.len() != keys.len() || self.reveals.len() != keys.len() {
+            return Err(BeaconError::WrongParticipantCount);
+        }
+        let input = vrf_input(self.round, &self.previous, &self.commitments);
+        let mut t = round_transcript(self.round, &self.previous, &self.commitments);
+        for (i, ((c, r), pk)) in self
+            .commitments
+            .iter()
+            .zip(&self.reveals)
+            .zip(keys)
+            .enumerate()
+        {
+            if c.open::<Sha256>(&r.opening).is_none() {
+                return Err(BeaconError::InvalidOpening(i));
+            }
+            if pk.verify(&input, &r.vrf_proof) != Some(r.vrf_output) {
+                return Err(BeaconError::InvalidVrfProof(i));
+            }
+            t.append_message(b"contribution", &r.opening.message);
+            t.append_message(b"vrf-output", &r.vrf_output);
+        }
+        let mut out = [0u8; 32];
+        t.challenge_bytes(b"output", &mut out);
+        Ok(out)
+    }
+}
+
+// 全員をまとめて 1 ラウンド進めるシミュレーション
+pub fn run_round<R: RngCore>(
+    participants: &mut [Participant],
+    round: u64,
+    previous: &[u8; 32],
+    rng: &mut R,
+) -> BeaconRound {
+    let commitments: Vec<Commitment> = participants.iter_mut().map(|p| p.commit(rng)).collect();
+    let reveals = participants
+        .iter_mut()
+        .map(|p| p.reveal(round, previous, &commitments, rng))
+        .collect();
+    BeaconRound {
+        round,
+        previous: *previous,
+        commitments,
+        reveals,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::TestRng;
+
+    fn setup(n: usize, rng: &mut TestRng) -> (Vec<Participant>, Vec<VrfPublicKey>) {
+        let participants: Vec<Participant> = (0..n).map(|_| Participant::new(rng)).collect();
+        let keys = participants.iter().map(|p| p.public_key()).collect();
+        (participants, keys)
+    }
+
+    #[test]
+    fn test_chained_rounds_verify() {
+        let mut rng = TestRng::from_label(b"beacon");
+        let (mut participants, keys) = setup(3, &mut rng);
+        let mut previous = [0u8; 32];
+        let mut outputs = vec![];
+        for round in 1..=3 {
+            let record = run_round(&mut participants, round, &previous, &mut rng);
+            previous = record.output(&keys).unwrap();
+            outputs.push(previous);
+        }
+        assert_ne!(outputs[0], outputs[1]);
+        assert_ne!(outputs[1], outputs[2]);
+    }
+
+    #[test]
+    fn test_tampered_records_are_rejected() {
+        let mut rng = TestRng::from_label(b"beacon-bad");
+        let (mut participants, keys) = setup(3, &mut rng);
+        let record = run_round(&mut participants, 1, &[0; 32], &mut rng);
+        // 開示後に寄与を差し替える
+        let mut bad = record.clone();
+        bad.reveals[1].opening.message[0] ^= 1;
+        assert_eq!(bad.output(&keys), Err(BeaconError::InvalidOpening(1)));
+        // VRF 出力を選び直す
+        let mut bad = record.clone();
+        bad.reveals[2].vrf_output = [7; 32];
+        assert_eq!(bad.output(&keys), Err(BeaconError::InvalidVrfProof(2)));
+        // 開示しない参加者がいる
+        let mut bad = record.clone();
+        bad.reveals.pop();
+        assert_eq!(bad.output(&keys), Err(BeaconError::WrongParticipantCount));
+        assert!(record.output(&keys).is_ok());
+    }
+}
@@ -0,0 +1,123 @@
+// 符号付き多倍長整数 (符号 + 絶対値)
+use super::BigUint;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BigInt {
+    negative: bool,
+    magnitude: BigUint,
+}
+
+impl BigInt {
+    pub fn zero() -> Self {
+        BigInt::default()
+    }
+
+    pub fn from_biguint(magnitude: BigUint) -> Self {
+        BigInt {
+            negative: false,
+            magnitude,
+        }
+    }
+
+    pub fn from_parts(negative: bool, magnitude: BigUint) -> Self {
+        // 0 は常に非負として扱う
+        let negative = negative && !magnitude.is_zero();
+        BigInt {
+            negative,
+            magnitude,
+        }
+    }
+
+    pub fn from_i128(n: i128) -> Self {
+        BigInt::from_parts(n < 0, BigUint::from_u128(n.unsigned_abs()))
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_zero()
+    }
+
+    pub fn magnitude(&self) -> &BigUint {
+        &self.magnitude
+    }
+
+    pub fn neg(&self) -> BigInt {
+        BigInt::from_parts(!self.negative, self.magnitude.clone())
+    }
+
+    pub fn add(&self, other: &BigInt) -> BigInt {
+        if self.negative == other.negative {
+            return BigInt::from_parts(self.negative, self.magnitude.add(&other.magnitude));
+        }
+        // 符号が異なる場合は絶対値の大きい方から小さい方を引く
+        if self.magnitude >= other.magnitude {
+            BigInt::from_parts(self.negative, self.magnitude.sub(&other.magnitude))
+        } else {
+            BigInt::from_parts(other.negative, other.magnitude.sub(&self.magnitude))
+        }
+    }
+
+    pub fn sub(&self, other: &BigInt) -> BigInt {
+        self.add(&other.neg())
+    }
+
+    pub fn mul(&self, other: &BigInt) -> BigInt {
+        BigInt::from_parts(
+            self.negative != other.negative,
+            self.magnitude.mul(&other.magnitude),
+        )
+    }
+
+    // 数学的な剰余 (結果は常に [0, m))
+    pub fn mod_floor(&self, m: &BigUint) -> BigUint {
+        let r = self.magnitude.rem(m);
+        if self.negative && !r.is_zero() {
+            m.sub(&r)
+        } else {
+            r
+        }
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.negative {
+            write!(f, "-")?;
+        }
+        write!(f, "{}", self.magnitude)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signed_arithmetic() {
+        let a = BigInt::from_i128(-170141183460469231731687303715884105727);
+        let b = BigInt::from_i128(12345);
+        assert_eq!(
+            a.add(&b),
+            BigInt::from_i128(-170141183460469231731687303715884093382)
+        );
+        assert_eq!(b.sub(&b), BigInt::zero());
+        assert!(!b.sub(&b).is_negative());
+        assert_eq!(
+            BigInt::from_i128(-7).mul(&BigInt::from_i128(-6)),
+            BigInt::from_i128(42)
+        );
+        assert_eq!(BigInt::from_i128(-7).to_string(), "-7");
+    }
+
+    #[test]
+    fn test_mod_floor() {
+        let m = BigUint::from_u64(5);
+        assert_eq!(BigInt::from_i128(-7).mod_floor(&m), BigUint::from_u64(3));
+        assert_eq!(BigInt::from_i128(-10).mod_floor(&m), BigUint::zero());
+        assert_eq!(BigInt::from_i128(7).mod_floor(&m), BigUint::from_u64(2));
+    }
+}
@@ -0,0 +1,473 @@
+// 任意精度の符号なし整数 (u64 リム、リトルエンディアン)
+pub mod bigint;
+pub mod montgomery;
+pub mod roots;
+
+pub use bigint::BigInt;
+pub use montgomery::MontgomeryContext;
+
+use crate::rand::RngCore;
+use std::cmp::Ordering;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigUint {
+    limbs: Vec<u64>,
+}
+
+impl BigUint {
+    pub fn zero() -> Self {
+        BigUint { limbs: vec![] }
+    }
+
+    pub fn one() -> Self {
+        BigUint::from_u64(1)
+    }
+
+    pub fn from_u64(n: u64) -> Self {
+        BigUint::from_limbs(vec![n])
+    }
+
+    pub fn from_u128(n: u128) -> Self {
+        BigUint::from_limbs(vec![n as u64, (n >> 64) as u64])
+    }
+
+    pub fn from_limbs(limbs: Vec<u64>) -> Self {
+        let mut n = BigUint { limbs };
+        n.normalize();
+        n
+    }
+
+    pub fn limbs(&self) -> &[u64] {
+        &self.limbs
+    }
+
+    fn normalize(&mut self) {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+    }
+
+    pub fn from_bytes_be(bytes: &[u8]) -> Self {
+        let limbs = bytes
+            .rchunks(8)
+            .map(|chunk| {
+                let mut buf = [0u8; 8];
+                buf[8 - chunk.len()..].copy_from_slice(chunk);
+                u64::from_be_bytes(buf)
+            })
+            .collect();
+        BigUint::from_limbs(limbs)
+    }
+
+    pub fn to_bytes_be(&self) -> Vec<u8> {
+        let mut out: Vec<u8> = self
+            .limbs
+            .iter()
+            .rev()
+            .flat_map(|l| l.to_be_bytes())
+            .skip_while(|&b| b == 0)
+            .collect();
+        if out.is_empty() {
+            out.push(0);
+        }
+        out
+    }
+
+    // 固定長 (左側を 0 で埋める) のビッグエンディアン表現
+    pub fn to_bytes_be_padded(&self, len: usize) -> Vec<u8> {
+        let bytes = self.to_bytes_be();
+        if bytes.len() > len {
+            panic!("{} does not fit in {} bytes", self, len);
+        }
+        let mut out = vec![0u8; len - bytes.len()];
+        out.extend_from_slice(&bytes);
+        out
+    }
+
+    pub fn from_str_radix(s: &str, radix: u32) -> Option<Self> {
+        if s.is_empty() || !(2..=36).contains(&radix) {
+            return None;
+        }
+        let mut n = BigUint::zero();
+        let r = BigUint::from_u64(radix as u64);
+        for c in s.chars() {
+            let digit = c.to_digit(radix)?;
+            n = n.mul(&r).add(&BigUint::from_u64(digit as u64));
+        }
+        Some(n)
+    }
+
+    pub fn from_hex(s: &str) -> Option<Self> {
+        BigUint::from_str_radix(s, 16)
+    }
+
+    pub fn to_hex(&self) -> String {
+        if self.is_zero() {
+            return "0".to_string();
+        }
+        let mut s = format!("{:x}", self.limbs.last().unwrap());
+        for limb in self.limbs.iter().rev().skip(1) {
+            s.push_str(&format!("{:016x}", limb));
+        }
+        s
+    }
+
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.limbs.len() {
+            0 => Some(0),
+            1 => Some(self.limbs[0]),
+            _ => None,
+        }
+    }
+
+    pub fn to_u128(&self) -> Option<u128> {
+        match self.limbs.len() {
+            0..=2 => Some(
+                self.limbs
+                    .iter()
+                    .rev()
+                    .fold(0, |acc, &l| (acc << 64) | l as u128),
+            ),
+            _ => None,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    pub fn is_one(&self) -> bool {
+        self.limbs == [1]
+    }
+
+    pub fn is_even(&self) -> bool {
+        self.limbs.first().is_none_or(|l| l & 1 == 0)
+    }
+
+    pub fn bits(&self) -> usize {
+        match self.limbs.last() {
+            Some(top) => self.limbs.len() * 64 - top.leading_zeros() as usize,
+            None => 0,
+        }
+    }
+
+    pub fn bit(&self, i: usize) -> bool {
+        self.limbs
+            .get(i / 64)
+            .is_some_and(|l| (l >> (i % 64)) & 1 == 1)
+    }
+
+    pub fn set_bit(&mut self, i: usize) {
+        if self.limbs.len() <= i / 64 {
+            self.limbs.resize(i / 64 + 1, 0);
+        }
+        self.limbs[i / 64] |= 1 << (i % 64);
+    }
+
+    pub fn trailing_zeros(&self) -> usize {
+        match self.limbs.iter().position(|&l| l != 0) {
+            Some(i) => i * 64 + self.limbs[i].trailing_zeros() as usize,
+            None => 0,
+        }
+    }
+
+    pub fn add(&self, other: &BigUint) -> BigUint {
+        let len = self.limbs.len().max(other.limbs.len());
+        let mut limbs = Vec::with_capacity(len + 1);
+        let mut carry = 0u128;
+        for i in 0..len {
+            let a = *self.limbs.get(i).unwrap_or(&0) as u128;
+            let b = *other.limbs.get(i).unwrap_or(&0) as u128;
+            let sum = a + b + carry;
+            limbs.push(sum as u64);
+            carry = sum >> 64;
+        }
+        limbs.push(carry as u64);
+        BigUint::from_limbs(limbs)
+    }
+
+    pub fn sub(&self, other: &BigUint) -> BigUint {
+        if *self < *other {
+            panic!("Subtraction underflow: {} - {}", self, other);
+        }
+        let mut limbs = Vec::with_capacity(self.limbs.len());
+        let mut borrow = 0u64;
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let b = *other.limbs.get(i).unwrap_or(&0);
+            let (d1, o1) = a.overflowing_sub(b);
+            let (d2, o2) = d1.overflowing_sub(borrow);
+            limbs.push(d2);
+            borrow = (o1 || o2) as u64;
+        }
+        BigUint::from_limbs(limbs)
+    }
+
+    pub fn mul(&self, other: &BigUint) -> BigUint {
+        if self.is_zero() || other.is_zero() {
+            return BigUint::zero();
+        }
+        let mut limbs = vec![0u64; self.limbs.len() + other.limbs.len()];
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u128;
+            for (j, &b) in other.limbs.iter().enumerate() {
+                let t = a as u128 * b as u128 + limbs[i + j] as u128 + carry;
+                limbs[i + j] = t as u64;
+                carry = t >> 64;
+            }
+            limbs[i + other.limbs.len()] = carry as u64;
+        }
+        BigUint::from_limbs(limbs)
+    }
+
+    pub fn shl(&self, bits: usize) -> BigUint {
+        if self.is_zero() {
+            return BigUint::zero();
+        }
+        let (words, shift) = (bits / 64, bits % 64);
+        let mut limbs = vec![0u64; words];
+        let mut carry = 0u64;
+        for &l in &self.limbs {
+            if shift == 0 {
+                limbs.push(l);
+            } else {
+                limbs.push((l << shift) | carry);
+                carry = l >> (64 - shift);
+            }
+        }
+        limbs.push(carry);
+        BigUint::from_limbs(limbs)
+    }
+
+    pub fn shr(&self, bits: usize) -> BigUint {
+        let (words, shift) = (bits / 64, bits % 64);
+        if words >= self.limbs.len() {
+            return BigUint::zero();
+        }
+        let src = &self.limbs[words..];
+        let limbs = (0..src.len())
+            .map(|i| {
+                let next = src.get(i + 1).copied().unwrap_or(0);
+                if shift == 0 {
+                    src[i]
+                } else {
+                    (src[i] >> shift) | (next << (64 - shift))
+                }
+            })
+            .collect();
+        BigUint::from_limbs(limbs)
+    }
+
+    fn divrem_u64(&self, d: u64) -> (BigUint, u64) {
+        let mut q = vec![0u64; self.limbs.len()];
+        let mut rem = 0u128;
+        for i in (0..self.limbs.len()).rev() {
+            let cur = (rem << 64) | self.limbs[i] as u128;
+            q[i] = (cur / d as u128) as u64;
+            rem = cur % d as u128;
+        }
+        (BigUint::from_limbs(q), rem as u64)
+    }
+
+    // Knuth の Algorithm D
+    pub fn divrem(&self, divisor: &BigUint) -> (BigUint, BigUint) {
+        if divisor.is_zero() {
+            panic!("Division by zero");
+        }
+        if *self < *divisor {
+            return (BigUint::zero(), self.clone());
+        }
+        if divisor.limbs.len() == 1 {
+            let (q, r) = self.divrem_u64(divisor.limbs[0]);
+            return (q, BigUint::from_u64(r));
+        }
+
+        // 除数の最上位リムの最上位ビットが立つように正規化する
+        let shift = divisor.limbs.last().unwrap().leading_zeros() as usize;
+        let v = divisor.shl(shift).limbs;
+        let mut u = self.shl(shift).limbs;
+        u.push(0);
+        if u.len() < self.limbs.len() + 1 {
+            u.push(0);
+        }
+        let n = v.len();
+        let m = u.len() - n - 1;
+        let mut q = vec![0u64; m + 1];
+        let base = 1u128 << 64;
+
+        for j in (0..=m).rev() {
+            let num = ((u[j + n] as u128) << 64) | u[j + n - 1] as u128;
+            let mut qhat = num / v[n - 1] as u128;
+            let mut rhat = num % v[n - 1] as u128;
+            while qhat >= base || qhat * v[n - 2] as u128 > ((rhat << 64) | u[j + n - 2] as u128) {
+                qhat -= 1;
+                rhat += v[n - 1] as u128;
+                if rhat >= base {
+                    break;
+                }
+            }
+
+            // u[j..j+n] -= qhat * v
+            let mut borrow = 0i128;
+            let mut carry = 0u128;
+            for i in 0..n {
+                let p = qhat * v[i] as u128 + carry;
+                carry = p >> 64;
+                let t = u[i + j] as i128 - borrow - (p as u64) as i128;
+                u[i + j] = t as u64;
+                borrow = (t < 0) as i128;
+            }
+            let t = u[j + n] as i128 - borrow - carry as i128;
+            u[j + n] = t as u64;
+
+            // 引きすぎた場合は一度だけ足し戻す
+            if t < 0 {
+                qhat -= 1;
+                let mut c = 0u128;
+                for i in 0..n {
+                    let s = u[i + j] as u128 + v[i] as u128 + c;
+                    u[i + j] = s as u64;
+                    c = s >> 64;
+                }
+                u[j + n] = u[j + n].wrapping_add(c as u64);
+            }
+            q[j] = qhat as u64;
+        }
+
+        u.truncate(n);
+        let rem = BigUint::from_limbs(u).shr(shift);
+        (BigUint::from_limbs(q), rem)
+    }
+
+    pub fn rem_u64(&self, d: u64) -> u64 {
+        if d == 0 {
+            panic!("Division by zero");
+        }
+        self.divrem_u64(d).1
+    }
+
+    pub fn div(&self, other: &BigUint) -> BigUint {
+        self.divrem(other).0
+    }
+
+    pub fn rem(&self, other: &BigUint) -> BigUint {
+        self.divrem(other).1
+    }
+
+    // ちょうど bits ビット以下の一様乱数
+    pub fn random_bits<R: RngCore>(bits: usize, rng: &mut R) -> BigUint {
+        let mut bytes = vec![0u8; bits.div_ceil(8)];
+        rng.fill_bytes(&mut bytes);
+        if !bits.is_multiple_of(8) {
+            bytes[0] &= (1u8 << (bits % 8)) - 1;
+        }
+        BigUint::from_bytes_be(&bytes)
+    }
+
+    // [0, bound) の一様乱数 (棄却サンプリング)
+    pub fn random_below<R: RngCore>(bound: &BigUint, rng: &mut R) -> BigUint {
+        if bound.is_zero() {
+            panic!("Upper bound must be positive");
+        }
+        loop {
+            let candidate = BigUint::random_bits(bound.bits(), rng);
+            if candidate < *bound {
+                return candidate;
+            }
+        }
+    }
+
+    pub fn modpow(&self, exponent: &BigUint, modulus: &BigUint) -> BigUint {
+        if modulus.is_one() {
+            return BigUint::zero();
+        }
+        let mut result = BigUint::one();
+        let mut base = self.rem(modulus);
+        for i in 0..exponent.bits() {
+            if exponent.bit(i) {
+                result = result.mul(&base).rem(modulus);
+            }
+            base = base.mul(&base).rem(modulus);
+        }
+        result
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+        // 10^19 ごとに区切って 10 進に変換する
+        let chunk = 10_000_000_000_000_000_000u64;
+        let mut parts = Vec::new();
+        let mut n = self.clone();
+        while !n.is_zero() {
+            let (q, r) = n.divrem_u64(chunk);
+            parts.push(r);
+            n = q;
+        }
+        write!(f, "{}", parts.last().unwrap())?;
+        for part in parts.iter().rev().skip(1) {
+            write!(f, "{:019}", part)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dec(s: &str) -> BigUint {
+        BigUint::from_str_radix(s, 10).unwrap()
+    }
+
+    #[test]
+    fn test_arithmetic_against_known_values() {
+        let a = dec("123456789012345678901234567890123456789012345678901234567890");
+        let b = dec("987654321098765432109876543210");
+        assert_eq!(
+            a.mul(&b).to_string(),
+            "121932631137021795226185032733744855963374485596337448559633622923332237463801111263526900"
+        );
+        let (q, r) = a.divrem(&b);
+        assert_eq!(q.to_string(), "124999998860937500014238281249");
+        assert_eq!(r.to_string(), "935329860093532986009353298600");
+        assert_eq!(q.mul(&b).add(&r), a);
+        assert!(r < b);
+    }
+
+    #[test]
+    fn test_modpow_and_shifts() {
+        // 2^127 - 1 はメルセンヌ素数なので 3^(p-1) ≡ 1 (mod p)
+        let p = BigUint::one().shl(127).sub(&BigUint::one());
+        let e = p.sub(&BigUint::one());
+        assert!(BigUint::from_u64(3).modpow(&e, &p).is_one());
+        assert_eq!(p.shr(100), BigUint::from_u64((1 << 27) - 1));
+        assert_eq!(p.bits(), 127);
+    }
+
+    #[test]
+    fn test_bytes_and_hex_roundtrip() {
+        let n = BigUint::from_hex("0102030405060708090a0b0c0d0e0f10").unwrap();
+        assert_eq!(n.to_hex(), "102030405060708090a0b0c0d0e0f10");
+        assert_eq!(BigUint::from_bytes_be(&n.to_bytes_be()), n);
+        assert_eq!(n.to_bytes_be_padded(18)[..3], [0, 0, 1]);
+    }
+}
@@ -0,0 +1,161 @@
+// Montgomery 乗算と固定ウィンドウ冪乗。
+// 指数のビット値によって演算列が変わらないので、秘密指数の冪乗 (RSA/DH の秘密鍵操作) に使う。
+use super::BigUint;
+
+const WINDOW: usize = 4;
+
+pub struct MontgomeryContext {
+    modulus: Vec<u64>,
+    // -N^(-1) mod 2^64
+    n0_inv: u64,
+    r2: Vec<u64>,
+}
+
+impl MontgomeryContext {
+    pub fn new(modulus: &BigUint) -> Self {
+        if modulus.is_even() {
+            panic!("Montgomery arithmetic requires an odd modulus");
+        }
+        let n = modulus.limbs().to_vec();
+        // ニュートン法で N[0]^(-1) mod 2^64 を求める
+        let mut inv: u64 = 1;
+        for _ in 0..6 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(n[0].wrapping_mul(inv)));
+        }
+        let r2 = BigUint::one().shl(128 * n.len()).rem(modulus);
+        let mut ctx = MontgomeryContext {
+            n0_inv: inv.wrapping_neg(),
+            r2: vec![],
+            modulus: n,
+        };
+        ctx.r2 = ctx.pad(&r2);
+        ctx
+    }
+
+    fn pad(&self, a: &BigUint) -> Vec<u64> {
+        let mut limbs = a.limbs().to_vec();
+        limbs.resize(self.modulus.len(), 0);
+        limbs
+    }
+
+    // CIOS 法による a * b * R^(-1) mod N
+    fn mul(&self, a: &[u64], b: &[u64]) -> Vec<u64> {
+        let n = self.modulus.len();
+        let mut t = vec![0u64; n + 2];
+        for &bi in b.iter().take(n) {
+            let mut carry = 0u128;
+            for j in 0..n {
+                let s = t[j] as u128 + a[j] as u128 * bi as u128 + carry;
+                t[j] = s as u64;
+                carry = s >> 64;
+            }
+            let s = t[n] as u128 + carry;
+            t[n] = s as u64;
+            t[n + 1] = (s >> 64) as u64;
+
+            let m = t[0].wrapping_mul(self.n0_inv);
+            let mut carry = (t[0] as u128 + m as u128 * self.modulus[0] as u128) >> 64;
+            for j in 1..n {
+                let s = t[j] as u128 + m as u128 * self.modulus[j] as u128 + carry;
+                t[j - 1] = s as u64;
+                carry = s >> 64;
+            }
+            let s = t[n] as u128 + carry;
+            t[n - 1] = s as u64;
+            t[n] = t[n + 1] + (s >> 64) as u64;
+        }
+
+        // t >= N なら N を引く (分岐せずマスクで選ぶ)
+        let mut diff = vec![0u64; n];
+        let mut borrow = 0u64;
+        for j in 0..n {
+            let (d1, o1) = t[j].overflowing_sub(self.modulus[j]);
+            let (d2, o2) = d1.overflowing_sub(borrow);
+            diff[j] = d2;
+            borrow = (o1 | o2) as u64;
+        }
+        let keep_t = (borrow & (t[n] == 0) as u64).wrapping_neg();
+        (0..n)
+            .map(|j| (t[j] & keep_t) | (diff[j] & !keep_t))
+            .collect()
+    }
+
+    fn select(table: &[Vec<u64>], index: usize) -> Vec<u64> {
+        let mut out = vec![0u64; table[0].len()];
+        for (k, entry) in table.iter().enumerate() {
+            let mask = ((k == index) as u64).wrapping_neg();
+            for (o, e) in out.iter_mut().zip(entry) {
+                *o |= e & mask;
+            }
+        }
+        out
+    }
+
+    pub fn modpow(&self, base: &BigUint, exponent: &BigUint) -> BigUint {
+        let modulus = BigUint::from_limbs(self.modulus.clone());
+        let one = self.mul(&self.pad(&BigUint::one()), &self.r2);
+        let b = self.mul(&self.pad(&base.rem(&modulus)), &self.r2);
+
+        // table[k] = base^k (Montgomery 表現)
+        let mut table = vec![one.clone()];
+        for k in 1..1 << WINDOW {
+            let next = self.mul(&table[k - 1], &b);
+            table.push(next);
+        }
+
+        // 指数の値ではなくリム数だけで決まる回数だけウィンドウを処理する
+        let bits = exponent.limbs().len() * 64;
+        let mut acc = one;
+        for w in (0..bits / WINDOW).rev() {
+            for _ in 0..WINDOW {
+                acc = self.mul(&acc, &acc);
+            }
+            let index = (0..WINDOW).fold(0, |idx, i| {
+                idx | ((exponent.bit(w * WINDOW + i) as usize) << i)
+            });
+            acc = self.mul(&acc, &Self::select(&table, index));
+        }
+
+        let mut unit = vec![0u64; self.modulus.len()];
+        unit[0] = 1;
+        BigUint::from_limbs(self.mul(&acc, &unit))
+    }
+}
+
+impl BigUint {
+    // 定数時間 (指数のビットに依存しない) のべき乗剰余。法は奇数でなければならない
+    pub fn modpow_ct(&self, exponent: &BigUint, modulus: &BigUint) -> BigUint {
+        if modulus.is_one() {
+            return BigUint::zero();
+        }
+        MontgomeryContext::new(modulus).modpow(self, exponent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::OsRng;
+
+    #[test]
+    fn test_modpow_ct_matches_naive() {
+        for bits in [64, 130, 512] {
+            let mut m = BigUint::random_bits(bits, &mut OsRng);
+            m.set_bit(0);
+            m.set_bit(bits - 1);
+            let b = BigUint::random_bits(bits + 10, &mut OsRng);
+            let e = BigUint::random_bits(bits, &mut OsRng);
+            assert_eq!(b.modpow_ct(&e, &m), b.modpow(&e, &m));
+        }
+        let m = BigUint::from_u64(1_000_003);
+        assert!(BigUint::from_u64(7)
+            .modpow_ct(&BigUint::zero(), &m)
+            .is_one());
+    }
+
+    #[test]
+    #[should_panic(expected = "odd modulus")]
+    fn test_even_modulus_is_rejected() {
+        MontgomeryContext::new(&BigUint::from_u64(1 << 20));
+    }
+}
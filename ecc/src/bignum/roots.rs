@@ -0,0 +1,118 @@
+// 整数の冪根と完全冪の判定
+use super::BigUint;
+
+impl BigUint {
+    pub fn pow(&self, exponent: u32) -> BigUint {
+        let mut result = BigUint::one();
+        let mut base = self.clone();
+        let mut e = exponent;
+        while e > 0 {
+            if e & 1 == 1 {
+                result = result.mul(&base);
+            }
+            e >>= 1;
+            if e > 0 {
+                base = base.mul(&base);
+            }
+        }
+        result
+    }
+
+    // ニュートン法による整数平方根 floor(sqrt(self))
+    pub fn isqrt(&self) -> BigUint {
+        self.nth_root(2)
+    }
+
+    pub fn is_perfect_square(&self) -> bool {
+        let r = self.isqrt();
+        r.mul(&r) == *self
+    }
+
+    // floor(self^(1/k))。x_{i+1} = ((k - 1) x_i + self / x_i^(k-1)) / k で上から近づける
+    pub fn nth_root(&self, k: u32) -> BigUint {
+        if k == 0 {
+            panic!("0th root is undefined");
+        }
+        if k == 1 || self.is_zero() {
+            return self.clone();
+        }
+        let k_big = BigUint::from_u64(k as u64);
+        let k_minus_1 = BigUint::from_u64(k as u64 - 1);
+        // 初期値は真の根以上である 2^ceil(bits / k)
+        let mut x = BigUint::one().shl(self.bits().div_ceil(k as usize));
+        loop {
+            let y = k_minus_1.mul(&x).add(&self.div(&x.pow(k - 1))).div(&k_big);
+            if y >= x {
+                return x;
+            }
+            x = y;
+        }
+    }
+
+    // self = b^k (k >= 2) と書けるなら k が最大となる (b, k)
+    pub fn is_perfect_power(&self) -> Option<(BigUint, u32)> {
+        if self.bits() <= 1 {
+            return None;
+        }
+        // 素数の指数だけ調べれば十分で、見つかった根を再帰的に分解する
+        for k in 2..=self.bits() as u32 {
+            if (2..k).any(|d| k.is_multiple_of(d)) {
+                continue;
+            }
+            let root = self.nth_root(k);
+            if root.pow(k) == *self {
+                return Some(match root.is_perfect_power() {
+                    Some((b, j)) => (b, j * k),
+                    None => (root, k),
+                });
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roots_against_u128() {
+        for n in [
+            0u128,
+            1,
+            2,
+            3,
+            15,
+            16,
+            17,
+            1 << 100,
+            u128::MAX,
+            999_999_999_999_999_999,
+        ] {
+            let b = BigUint::from_u128(n);
+            let r = b.isqrt().to_u128().unwrap();
+            assert!(r * r <= n && (r + 1).checked_mul(r + 1).is_none_or(|s| s > n));
+            let c = b.nth_root(3).to_u128().unwrap();
+            assert!(c.pow(3) <= n && (c + 1).checked_pow(3).is_none_or(|s| s > n));
+        }
+        let big = BigUint::from_u64(123_456_789).pow(7).add(&BigUint::one());
+        assert_eq!(big.nth_root(7), BigUint::from_u64(123_456_789));
+    }
+
+    #[test]
+    fn test_is_perfect_power() {
+        let p = |b: u64, k: u32| BigUint::from_u64(b).pow(k);
+        assert_eq!(
+            p(2, 64).is_perfect_power(),
+            Some((BigUint::from_u64(2), 64))
+        );
+        assert_eq!(p(6, 6).is_perfect_power(), Some((BigUint::from_u64(6), 6)));
+        assert_eq!(
+            p(1_000_003, 5).is_perfect_power(),
+            Some((BigUint::from_u64(1_000_003), 5))
+        );
+        assert_eq!(p(12, 3).add(&BigUint::one()).is_perfect_power(), None);
+        assert!(p(1 << 31, 2).is_perfect_square());
+        assert!(!BigUint::from_u64(2).is_perfect_square());
+    }
+}
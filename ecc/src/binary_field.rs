@@ -0,0 +1,150 @@
+// 標数 2 の有限体。加算は XOR、乗算は既約多項式を法とした多項式の積
+
+// GF(2^8) = GF(2)[x] / (x^8 + x^4 + x^3 + x + 1)。AES が使う体
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Gf256(pub u8);
+
+impl Gf256 {
+    pub const ZERO: Gf256 = Gf256(0);
+    pub const ONE: Gf256 = Gf256(1);
+
+    pub fn add(&self, other: &Gf256) -> Gf256 {
+        Gf256(self.0 ^ other.0)
+    }
+
+    // x を掛ける (AES の xtime)
+    pub fn double(&self) -> Gf256 {
+        let carry = self.0 >> 7;
+        Gf256((self.0 << 1) ^ (carry * 0x1b))
+    }
+
+    pub fn mul(&self, other: &Gf256) -> Gf256 {
+        let mut a = *self;
+        let mut b = other.0;
+        let mut result = Gf256::ZERO;
+        while b != 0 {
+            if b & 1 == 1 {
+                result = result.add(&a);
+            }
+            a = a.double();
+            b >>= 1;
+        }
+        result
+    }
+
+    pub fn pow(&self, exponent: u32) -> Gf256 {
+        let mut base = *self;
+        let mut exp = exponent;
+        let mut result = Gf256::ONE;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result.mul(&base);
+            }
+            base = base.mul(&base);
+            exp >>= 1;
+        }
+        result
+    }
+
+    // 乗法群の位数は 255 なので a^-1 = a^254。0 の逆元は AES の慣習に合わせて 0 とする
+    pub fn inv(&self) -> Gf256 {
+        self.pow(254)
+    }
+}
+
+// GF(2^128) を GCM の表現で扱う。x^128 + x^7 + x^2 + x + 1 を法とし、
+// ブロックの最上位ビットが x^0 の係数になる (ビット順が反転している)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Gf128(pub u128);
+
+impl Gf128 {
+    pub const ZERO: Gf128 = Gf128(0);
+    // 多項式 x
+    pub const X: Gf128 = Gf128(1 << 126);
+    const R: u128 = 0xe1 << 120;
+
+    pub fn from_bytes(bytes: &[u8; 16]) -> Gf128 {
+        Gf128(u128::from_be_bytes(*bytes))
+    }
+
+    pub fn to_bytes(&self) -> [u8; 16] {
+        self.0.to_be_bytes()
+    }
+
+    // x^0 の係数をリトルエンディアンの最下位ビットに置く表現 (XTS などが使う) との変換
+    pub fn from_le_poly_bytes(bytes: &[u8; 16]) -> Gf128 {
+        Gf128(u128::from_le_bytes(*bytes).reverse_bits())
+    }
+
+    pub fn to_le_poly_bytes(&self) -> [u8; 16] {
+        self.0.reverse_bits().to_le_bytes()
+    }
+
+    pub fn add(&self, other: &Gf128) -> Gf128 {
+        Gf128(self.0 ^ other.0)
+    }
+
+    // SP 800-38D のアルゴリズム 1
+    pub fn mul(&self, other: &Gf128) -> Gf128 {
+        let mut z = 0u128;
+        let mut v = other.0;
+        for i in (0..128).rev() {
+            if (self.0 >> i) & 1 == 1 {
+                z ^= v;
+            }
+            v = if v & 1 == 1 {
+                (v >> 1) ^ Self::R
+            } else {
+                v >> 1
+            };
+        }
+        Gf128(z)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gf256_fips197_examples() {
+        // FIPS 197 4.2: {57} • {83} = {c1}, {57} • {13} = {fe}
+        assert_eq!(Gf256(0x57).mul(&Gf256(0x83)), Gf256(0xc1));
+        assert_eq!(Gf256(0x57).mul(&Gf256(0x13)), Gf256(0xfe));
+        assert_eq!(Gf256(0x57).add(&Gf256(0x83)), Gf256(0xd4));
+        assert_eq!(Gf256(0x57).double(), Gf256(0xae));
+        assert_eq!(Gf256(0x80).double(), Gf256(0x1b));
+    }
+
+    #[test]
+    fn test_gf256_inverse() {
+        for a in 1..=255u8 {
+            assert_eq!(Gf256(a).mul(&Gf256(a).inv()), Gf256::ONE);
+        }
+        assert_eq!(Gf256::ZERO.inv(), Gf256::ZERO);
+    }
+
+    #[test]
+    fn test_gf128_mul() {
+        // 最上位ビットだけ立った値が乗法単位元
+        let one = Gf128(1 << 127);
+        let a = Gf128(0x66e94bd4ef8a2c3b884cfa59ca342b2e);
+        let b = Gf128(0x0388dace60b6a392f328c2b971b2fe78);
+        assert_eq!(a.mul(&one), a);
+        assert_eq!(a.mul(&b), b.mul(&a));
+        // x^127 * x = x^128 = x^7 + x^2 + x + 1
+        assert_eq!(Gf128(1).mul(&Gf128(1 << 126)), Gf128(0xe1 << 120));
+        let c = Gf128(0x1234);
+        assert_eq!(a.mul(&b.add(&c)), a.mul(&b).add(&a.mul(&c)));
+        // リトルエンディアン表現では x 倍は 1 ビット左シフトで、あふれたら 0x87 を XOR する
+        let mut t = [0u8; 16];
+        t[15] = 0x80;
+        t[0] = 0x01;
+        let doubled = Gf128::from_le_poly_bytes(&t)
+            .mul(&Gf128::X)
+            .to_le_poly_bytes();
+        let mut expected = [0u8; 16];
+        expected[0] = 0x02 ^ 0x87;
+        assert_eq!(doubled, expected);
+    }
+}
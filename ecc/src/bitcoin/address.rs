@@ -0,0 +1,210 @@
+// 公開鍵から作る 3 種類のアドレス:
+//   P2PKH  : Base58Check(version || HASH160(pubkey))            1... / m..., n...
+//   P2WPKH : Bech32(witness v0, HASH160(pubkey))                bc1q... / tb1q...
+//   P2TR   : Bech32m(witness v1, 出力鍵 Q の x 座標)            bc1p... / tb1p...
+// P2TR に渡すのは調整済みの出力鍵で、内部鍵をそのまま使うと BIP-341 に従わない
+// (内部鍵からは taproot の p2tr_key_path で作る)
+use crate::encoding::{base58, bech32, Base58Error, Bech32Error};
+use crate::hash::hash160;
+use crate::secp256k1::PublicKey;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Network {
+    Mainnet,
+    Testnet,
+}
+
+impl Network {
+    pub fn p2pkh_version(self) -> u8 {
+        match self {
+            Network::Mainnet => 0x00,
+            Network::Testnet => 0x6f,
+        }
+    }
+
+    // WIF 秘密鍵の先頭バイト
+    pub fn wif_version(self) -> u8 {
+        match self {
+            Network::Mainnet => 0x80,
+            Network::Testnet => 0xef,
+        }
+    }
+
+    pub fn hrp(self) -> &'static str {
+        match self {
+            Network::Mainnet => "bc",
+            Network::Testnet => "tb",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Payload {
+    PubkeyHash([u8; 20]),
+    WitnessPubkeyHash([u8; 20]),
+    Taproot([u8; 32]),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Address {
+    pub network: Network,
+    pub payload: Payload,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AddressError {
+    Base58(Base58Error),
+    Bech32(Bech32Error),
+    UnknownVersion(u8),
+    // このモジュールで扱わない witness version とプログラム長の組
+    UnsupportedWitness(u8, usize),
+    InvalidLength,
+}
+
+impl Address {
+    pub fn p2pkh(pubkey: &PublicKey, network: Network) -> Self {
+        Address {
+            network,
+            payload: Payload::PubkeyHash(hash160(&pubkey.to_compressed())),
+        }
+    }
+
+    // segwit では圧縮公開鍵しか使えない
+    pub fn p2wpkh(pubkey: &PublicKey, network: Network) -> Self {
+        Address {
+            network,
+            payload: Payload::WitnessPubkeyHash(hash160(&pubkey.to_compressed())),
+        }
+    }
+
+    pub fn p2tr(output_key: &[u8; 32], network: Network) -> Self {
+        Address {
+            network,
+            payload: Payload::Taproot(*output_key),
+        }
+    }
+
+    // scriptPubKey (出力に置くロックスクリプト)
+    pub fn script_pubkey(&self) -> Vec<u8> {
+        match &self.payload {
+            // OP_DUP OP_HASH160 <20> OP_EQUALVERIFY OP_CHECKSIG
+            Payload::PubkeyHash(h) => [&[0x76, 0xa9, 0x14][..], h, &[0x88, 0xac]].concat(),
+            // OP_0 <20>
+            Payload::WitnessPubkeyHash(h) => [&[0x00, 0x14][..], h].concat(),
+            // OP_1 <32>
+            Payload::Taproot(k) => [&[0x51, 0x20][..], k].concat(),
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        let segwit = |version, program: &[u8]| {
+            bech32::encode_segwit(self.network.hrp(), version, program)
+                .expect("Witness program has a valid length")
+        };
+        match &self.payload {
+            Payload::PubkeyHash(h) => base58::encode_check(self.network.p2pkh_version(), h),
+            Payload::WitnessPubkeyHash(h) => segwit(0, h),
+            Payload::Taproot(k) => segwit(1, k),
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Self, AddressError> {
+        let lower = s.to_ascii_lowercase();
+        for network in [Network::Mainnet, Network::Testnet] {
+            if lower.starts_with(&format!("{}1", network.hrp())) {
+                return Address::parse_segwit(s, network);
+            }
+        }
+        let (version, payload) = base58::decode_check(s).map_err(AddressError::Base58)?;
+        let network = [Network::Mainnet, Network::Testnet]
+            .into_iter()
+            .find(|n| n.p2pkh_version() == version)
+            .ok_or(AddressError::UnknownVersion(version))?;
+        let hash = payload
+            .try_into()
+            .map_err(|_| AddressError::InvalidLength)?;
+        Ok(Address {
+            network,
+            payload: Payload::PubkeyHash(hash),
+        })
+    }
+
+    fn parse_segwit(s: &str, network: Network) -> Result<Self, AddressError> {
+        let (version, program) =
+            bech32::decode_segwit(network.hrp(), s).map_err(AddressError::Bech32)?;
+        let payload = match (version, program.len()) {
+            (0, 20) => Payload::WitnessPubkeyHash(program.try_into().unwrap()),
+            (1, 32) => Payload::Taproot(program.try_into().unwrap()),
+            (v, len) => return Err(AddressError::UnsupportedWitness(v, len)),
+        };
+        Ok(Address { network, payload })
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.encode())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bignum::BigUint;
+    use crate::secp256k1::SecretKey;
+    use crate::test_util::{hex, unhex};
+
+    #[test]
+    fn test_addresses_for_key_one() {
+        let pk = SecretKey::from_scalar(BigUint::one()).unwrap().public_key();
+        let legacy = Address::p2pkh(&pk, Network::Mainnet);
+        assert_eq!(legacy.to_string(), "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH");
+        let segwit = Address::p2wpkh(&pk, Network::Mainnet);
+        assert_eq!(
+            segwit.to_string(),
+            "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+        );
+        assert_eq!(
+            hex(&segwit.script_pubkey()),
+            "0014751e76e8199196d454941c45d1b3a323f1433bd6"
+        );
+        for address in [legacy, segwit] {
+            assert_eq!(Address::parse(&address.to_string()), Ok(address));
+        }
+        let testnet = Address::p2wpkh(&pk, Network::Testnet);
+        assert!(testnet.to_string().starts_with("tb1q"));
+        assert_eq!(Address::parse(&testnet.to_string()), Ok(testnet));
+    }
+
+    #[test]
+    fn test_taproot_and_errors() {
+        // BIP-86 の m/86'/0'/0'/0/0 の出力鍵
+        let key: [u8; 32] =
+            unhex("a60869f0dbcf1dc659c9cecbaf8050135ea9e8cdc487053f1dc6880949dc684c")
+                .try_into()
+                .unwrap();
+        let address = Address::p2tr(&key, Network::Mainnet);
+        assert_eq!(
+            address.to_string(),
+            "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr"
+        );
+        assert_eq!(Address::parse(&address.to_string()), Ok(address));
+        // P2SH (version 0x05) はここでは扱わない
+        let p2sh = base58::encode_check(0x05, &[0; 20]);
+        assert_eq!(
+            Address::parse(&p2sh),
+            Err(AddressError::UnknownVersion(0x05))
+        );
+        // P2WSH (v0, 32 バイト)
+        let p2wsh = bech32::encode_segwit("bc", 0, &[0; 32]).unwrap();
+        assert_eq!(
+            Address::parse(&p2wsh),
+            Err(AddressError::UnsupportedWitness(0, 32))
+        );
+        assert_eq!(
+            Address::parse("1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMJ"),
+            Err(AddressError::Base58(Base58Error::InvalidChecksum))
+        );
+    }
+}
@@ -0,0 +1,450 @@
+// BIP-32 階層的決定性鍵。(鍵, チェーンコード) の組から子を
+//   I = HMAC-SHA512(チェーンコード, データ || i),  子の秘密鍵 = I_L + k (mod n),  子のチェーンコード = I_R
+// で導く。データは通常の子なら親の圧縮公開鍵、強化 (hardened, i ≥ 2^31) なら 0x00 || 秘密鍵。
+// 通常の子は公開鍵だけからも Q + I_L G として導けるので xpub だけで受け取りアドレスを作れるが、
+// xpub と子の秘密鍵が 1 つ漏れると親の秘密鍵が戻る。強化導出はこの経路を断つ
+use super::Network;
+use crate::bignum::BigUint;
+use crate::encoding::{base58, Base58Error};
+use crate::hash::{hash160, Sha512};
+use crate::mac::hmac;
+use crate::secp256k1::{self, Point, PublicKey, SecretKey};
+use std::fmt;
+
+pub const HARDENED: u32 = 1 << 31;
+const SERIALIZED_LEN: usize = 78;
+const MASTER_KEY: &[u8] = b"Bitcoin seed";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Bip32Error {
+    InvalidPath,
+    // 公開鍵だけから強化された子は導けない
+    HardenedFromPublic,
+    // I_L ≥ n か子が 0 / 無限遠点になった (確率 2^-127 程度)。次の番号を使う
+    InvalidChild,
+    InvalidSeedLength,
+    Base58(Base58Error),
+    InvalidLength,
+    UnknownVersion([u8; 4]),
+    InvalidKey,
+    // depth 0 (マスター鍵) なのに親の fingerprint か子の番号が 0 でない
+    InvalidMaster,
+}
+
+// "m/84'/0'/0'/0/0" の形の導出パス。強化は ' か h で表す
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DerivationPath(pub Vec<u32>);
+
+impl DerivationPath {
+    pub fn parse(s: &str) -> Result<Self, Bip32Error> {
+        let mut parts = s.split('/');
+        if parts.next() != Some("m") {
+            return Err(Bip32Error::InvalidPath);
+        }
+        parts
+            .map(|part| {
+                let (digits, hardened) = match part.strip_suffix(['\'', 'h']) {
+                    Some(digits) => (digits, true),
+                    None => (part, false),
+                };
+                if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                    return Err(Bip32Error::InvalidPath);
+                }
+                let index: u32 = digits.parse().map_err(|_| Bip32Error::InvalidPath)?;
+                if index >= HARDENED {
+                    return Err(Bip32Error::InvalidPath);
+                }
+                Ok(if hardened { index | HARDENED } else { index })
+            })
+            .collect::<Result<Vec<u32>, _>>()
+            .map(DerivationPath)
+    }
+}
+
+impl fmt::Display for DerivationPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "m")?;
+        for &i in &self.0 {
+            if i >= HARDENED {
+                write!(f, "/{}'", i - HARDENED)?;
+            } else {
+                write!(f, "/{}", i)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn version(network: Network, private: bool) -> [u8; 4] {
+    match (network, private) {
+        (Network::Mainnet, true) => [0x04, 0x88, 0xad, 0xe4],
+        (Network::Mainnet, false) => [0x04, 0x88, 0xb2, 0x1e],
+        (Network::Testnet, true) => [0x04, 0x35, 0x83, 0x94],
+        (Network::Testnet, false) => [0x04, 0x35, 0x87, 0xcf],
+    }
+}
+
+fn split(i: &[u8]) -> (BigUint, [u8; 32]) {
+    (
+        BigUint::from_bytes_be(&i[..32]),
+        i[32..].try_into().unwrap(),
+    )
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtendedPrivateKey {
+    pub network: Network,
+    pub depth: u8,
+    pub parent_fingerprint: [u8; 4],
+    pub child_number: u32,
+    pub chain_code: [u8; 32],
+    pub key: SecretKey,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtendedPublicKey {
+    pub network: Network,
+    pub depth: u8,
+    pub parent_fingerprint: [u8; 4],
+    pub child_number: u32,
+    pub chain_code: [u8; 32],
+    pub key: PublicKey,
+}
+
+// 公開鍵の HASH160 の先頭 4 バイト。子の直列化に親を示すために入れる
+fn fingerprint(key: &PublicKey) -> [u8; 4] {
+    hash160(&key.to_compressed())[..4].try_into().unwrap()
+}
+
+// version || depth || 親の指紋 || 子番号 || チェーンコード || 鍵 (33 バイト)
+fn serialize(
+    version: [u8; 4],
+    depth: u8,
+    parent: &[u8; 4],
+    child: u32,
+    chain_code: &[u8; 32],
+    key: &[u8; 33],
+) -> String {
+    let mut data = Vec::with_capacity(SERIALIZED_LEN);
+    data.extend_from_slice(&version);
+    data.push(depth);
+    data.extend_from_slice(parent);
+    data.extend_from_slice(&child.to_be_bytes());
+    data.extend_from_slice(chain_code);
+    data.extend_from_slice(key);
+    base58::encode_checked(&data)
+}
+
+struct Fields {
+    network: Network,
+    private: bool,
+    depth: u8,
+    parent_fingerprint: [u8; 4],
+    child_number: u32,
+    chain_code: [u8; 32],
+    key: [u8; 33],
+}
+
+fn deserialize(s: &str) -> Result<Fields, Bip32Error> {
+    let data = base58::decode_checked(s).map_err(Bip32Error::Base58)?;
+    if data.len() != SERIALIZED_LEN {
+        return Err(Bip32Error::InvalidLength);
+    }
+    let v: [u8; 4] = data[..4].try_into().unwrap();
+    let (network, private) = [Network::Mainnet, Network::Testnet]
+        .into_iter()
+        .flat_map(|n| [(n, true), (n, false)])
+        .find(|&(n, p)| version(n, p) == v)
+        .ok_or(Bip32Error::UnknownVersion(v))?;
+    if data[4] == 0 && data[5..13].iter().any(|&b| b != 0) {
+        return Err(Bip32Error::InvalidMaster);
+    }
+    Ok(Fields {
+        network,
+        private,
+        depth: data[4],
+        parent_fingerprint: data[5..9].try_into().unwrap(),
+        child_number: u32::from_be_bytes(data[9..13].try_into().unwrap()),
+        chain_code: data[13..45].try_into().unwrap(),
+        key: data[45..].try_into().unwrap(),
+    })
+}
+
+impl ExtendedPrivateKey {
+    // シードは 16〜64 バイト
+    pub fn new_master(seed: &[u8], network: Network) -> Result<Self, Bip32Error> {
+        if !(16..=64).contains(&seed.len()) {
+            return Err(Bip32Error::InvalidSeedLength);
+        }
+        let (k, chain_code) = split(&hmac::<Sha512>(MASTER_KEY, seed));
+        let key = SecretKey::from_scalar(k).map_err(|_| Bip32Error::InvalidKey)?;
+        Ok(ExtendedPrivateKey {
+            network,
+            depth: 0,
+            parent_fingerprint: [0; 4],
+            child_number: 0,
+            chain_code,
+            key,
+        })
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        self.key.public_key()
+    }
+
+    pub fn fingerprint(&self) -> [u8; 4] {
+        fingerprint(&self.public_key())
+    }
+
+    pub fn derive_child(&self, index: u32) -> Result<Self, Bip32Error> {
+        let mut data = Vec::with_capacity(37);
+        if index >= HARDENED {
+            data.push(0);
+            data.extend_from_slice(&self.key.to_bytes());
+        } else {
+            data.extend_from_slice(&self.public_key().to_compressed());
+        }
+        data.extend_from_slice(&index.to_be_bytes());
+        let (tweak, chain_code) = split(&hmac::<Sha512>(&self.chain_code, &data));
+        if &tweak >= secp256k1::n() {
+            return Err(Bip32Error::InvalidChild);
+        }
+        let k = tweak.add(self.key.scalar()).rem(secp256k1::n());
+        let key = SecretKey::from_scalar(k).map_err(|_| Bip32Error::InvalidChild)?;
+        Ok(ExtendedPrivateKey {
+            network: self.network,
+            depth: self.depth.checked_add(1).ok_or(Bip32Error::InvalidChild)?,
+            parent_fingerprint: self.fingerprint(),
+            child_number: index,
+            chain_code,
+            key,
+        })
+    }
+
+    pub fn derive_path(&self, path: &DerivationPath) -> Result<Self, Bip32Error> {
+        path.0
+            .iter()
+            .try_fold(self.clone(), |key, &i| key.derive_child(i))
+    }
+
+    pub fn to_extended_public_key(&self) -> ExtendedPublicKey {
+        ExtendedPublicKey {
+            network: self.network,
+            depth: self.depth,
+            parent_fingerprint: self.parent_fingerprint,
+            child_number: self.child_number,
+            chain_code: self.chain_code,
+            key: self.public_key(),
+        }
+    }
+
+    pub fn to_base58(&self) -> String {
+        let mut key = [0u8; 33];
+        key[1..].copy_from_slice(&self.key.to_bytes());
+        serialize(
+            version(self.network, true),
+            self.depth,
+            &self.parent_fingerprint,
+            self.child_number,
+            &self.chain_code,
+            &key,
+        )
+    }
+
+    pub fn from_base58(s: &str) -> Result<Self, Bip32Error> {
+        let fields = deserialize(s)?;
+        if !fields.private || fields.key[0] != 0 {
+            return Err(Bip32Error::InvalidKey);
+        }
+        let key = SecretKey::from_bytes(fields.key[1..].try_into().unwrap())
+            .map_err(|_| Bip32Error::InvalidKey)?;
+        Ok(ExtendedPrivateKey {
+            network: fields.network,
+            depth: fields.depth,
+            parent_fingerprint: fields.parent_fingerprint,
+            child_number: fields.child_number,
+            chain_code: fields.chain_code,
+            key,
+        })
+    }
+}
+
+impl ExtendedPublicKey {
+    pub fn fingerprint(&self) -> [u8; 4] {
+        fingerprint(&self.key)
+    }
+
+    // 通常の子だけ: 子の公開鍵 = I_L G + Q
+    pub fn derive_child(&self, index: u32) -> Result<Self, Bip32Error> {
+        if index >= HARDENED {
+            return Err(Bip32Error::HardenedFromPublic);
+        }
+        let mut data = self.key.to_compressed().to_vec();
+        data.extend_from_slice(&index.to_be_bytes());
+        let (tweak, chain_code) = split(&hmac::<Sha512>(&self.chain_code, &data));
+        if &tweak >= secp256k1::n() {
+            return Err(Bip32Error::InvalidChild);
+        }
+        let point = Point::mul_base(&tweak).add(&self.key.0);
+        if point.is_infinity() {
+            return Err(Bip32Error::InvalidChild);
+        }
+        Ok(ExtendedPublicKey {
+            network: self.network,
+            depth: self.depth.checked_add(1).ok_or(Bip32Error::InvalidChild)?,
+            parent_fingerprint: self.fingerprint(),
+            child_number: index,
+            chain_code,
+            key: PublicKey(point),
+        })
+    }
+
+    pub fn derive_path(&self, path: &DerivationPath) -> Result<Self, Bip32Error> {
+        path.0
+            .iter()
+            .try_fold(self.clone(), |key, &i| key.derive_child(i))
+    }
+
+    pub fn to_base58(&self) -> String {
+        serialize(
+            version(self.network, false),
+            self.depth,
+            &self.parent_fingerprint,
+            self.child_number,
+            &self.chain_code,
+            &self.key.to_compressed(),
+        )
+    }
+
+    pub fn from_base58(s: &str) -> Result<Self, Bip32Error> {
+        let fields = deserialize(s)?;
+        if fields.private {
+            return Err(Bip32Error::InvalidKey);
+        }
+        let key = PublicKey::from_sec1(&fields.key).map_err(|_| Bip32Error::InvalidKey)?;
+        Ok(ExtendedPublicKey {
+            network: fields.network,
+            depth: fields.depth,
+            parent_fingerprint: fields.parent_fingerprint,
+            child_number: fields.child_number,
+            chain_code: fields.chain_code,
+            key,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::unhex;
+
+    #[test]
+    fn test_bip32_vector_1() {
+        let seed = unhex("000102030405060708090a0b0c0d0e0f");
+        let master = ExtendedPrivateKey::new_master(&seed, Network::Mainnet).unwrap();
+        let cases = [
+            (
+                "m",
+                "xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi",
+                "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8",
+            ),
+            (
+                "m/0'",
+                "xprv9uHRZZhk6KAJC1avXpDAp4MDc3sQKNxDiPvvkX8Br5ngLNv1TxvUxt4cV1rGL5hj6KCesnDYUhd7oWgT11eZG7XnxHrnYeSvkzY7d2bhkJ7",
+                "xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw",
+            ),
+            (
+                "m/0'/1",
+                "xprv9wTYmMFdV23N2TdNG573QoEsfRrWKQgWeibmLntzniatZvR9BmLnvSxqu53Kw1UmYPxLgboyZQaXwTCg8MSY3H2EU4pWcQDnRnrVA1xe8fs",
+                "xpub6ASuArnXKPbfEwhqN6e3mwBcDTgzisQN1wXN9BJcM47sSikHjJf3UFHKkNAWbWMiGj7Wf5uMash7SyYq527Hqck2AxYysAA7xmALppuCkwQ",
+            ),
+            (
+                "m/0'/1/2'/2/1000000000",
+                "xprvA41z7zogVVwxVSgdKUHDy1SKmdb533PjDz7J6N6mV6uS3ze1ai8FHa8kmHScGpWmj4WggLyQjgPie1rFSruoUihUZREPSL39UNdE3BBDu76",
+                "xpub6H1LXWLaKsWFhvm6RVpEL9P4KfRZSW7abD2ttkWP3SSQvnyA8FSVqNTEcYFgJS2UaFcxupHiYkro49S8yGasTvXEYBVPamhGW6cFJodrTHy",
+            ),
+        ];
+        for (path, xprv, xpub) in cases {
+            let key = master
+                .derive_path(&DerivationPath::parse(path).unwrap())
+                .unwrap();
+            assert_eq!(key.to_base58(), xprv, "{}", path);
+            assert_eq!(key.to_extended_public_key().to_base58(), xpub, "{}", path);
+            assert_eq!(ExtendedPrivateKey::from_base58(xprv), Ok(key.clone()));
+            assert_eq!(
+                ExtendedPublicKey::from_base58(xpub),
+                Ok(key.to_extended_public_key())
+            );
+        }
+    }
+
+    #[test]
+    fn test_public_derivation_and_paths() {
+        let master = ExtendedPrivateKey::new_master(&[7; 32], Network::Testnet).unwrap();
+        let account = master
+            .derive_path(&DerivationPath::parse("m/84h/1h/0h").unwrap())
+            .unwrap();
+        assert!(account.to_base58().starts_with("tprv"));
+        // xpub から導いた通常の子は秘密鍵側と一致する
+        let xpub = account.to_extended_public_key();
+        let path = DerivationPath::parse("m/0/5").unwrap();
+        assert_eq!(
+            xpub.derive_path(&path).unwrap(),
+            account.derive_path(&path).unwrap().to_extended_public_key()
+        );
+        assert_eq!(
+            xpub.derive_child(HARDENED),
+            Err(Bip32Error::HardenedFromPublic)
+        );
+        let path = DerivationPath::parse("m/84'/0'/0'/0/0").unwrap();
+        assert_eq!(path.0, vec![84 | HARDENED, HARDENED, HARDENED, 0, 0]);
+        assert_eq!(path.to_string(), "m/84'/0'/0'/0/0");
+        for bad in ["", "84'/0", "m/", "m/x", "m/2147483648", "m/1''"] {
+            assert_eq!(
+                DerivationPath::parse(bad),
+                Err(Bip32Error::InvalidPath),
+                "{}",
+                bad
+            );
+        }
+        assert_eq!(
+            ExtendedPrivateKey::new_master(&[0; 8], Network::Mainnet),
+            Err(Bip32Error::InvalidSeedLength)
+        );
+    }
+
+    #[test]
+    fn test_bip32_vector_5_invalid_keys() {
+        let cases = [
+            // 公開鍵の version に秘密鍵、秘密鍵の version に公開鍵
+            ("xpub661MyMwAqRbcEYS8w7XLSVeEsBXy79zSzH1J8vCdxAZningWLdN3zgtU6LBpB85b3D2yc8sfvZU521AAwdZafEz7mnzBBsz4wKY5fTtTQBm", Bip32Error::InvalidKey),
+            ("xprv9s21ZrQH143K24Mfq5zL5MhWK9hUhhGbd45hLXo2Pq2oqzMMo63oStZzFGTQQD3dC4H2D5GBj7vWvSQaaBv5cxi9gafk7NF3pnBju6dwKvH", Bip32Error::InvalidKey),
+            // 鍵の先頭バイトが 04 / 01
+            ("xpub661MyMwAqRbcEYS8w7XLSVeEsBXy79zSzH1J8vCdxAZningWLdN3zgtU6Txnt3siSujt9RCVYsx4qHZGc62TG4McvMGcAUjeuwZdduYEvFn", Bip32Error::InvalidKey),
+            ("xprv9s21ZrQH143K24Mfq5zL5MhWK9hUhhGbd45hLXo2Pq2oqzMMo63oStZzFGpWnsj83BHtEy5Zt8CcDr1UiRXuWCmTQLxEK9vbz5gPstX92JQ", Bip32Error::InvalidKey),
+            ("xpub661MyMwAqRbcEYS8w7XLSVeEsBXy79zSzH1J8vCdxAZningWLdN3zgtU6N8ZMMXctdiCjxTNq964yKkwrkBJJwpzZS4HS2fxvyYUA4q2Xe4", Bip32Error::InvalidKey),
+            ("xprv9s21ZrQH143K24Mfq5zL5MhWK9hUhhGbd45hLXo2Pq2oqzMMo63oStZzFAzHGBP2UuGCqWLTAPLcMtD9y5gkZ6Eq3Rjuahrv17fEQ3Qen6J", Bip32Error::InvalidKey),
+            // depth 0 で親の fingerprint や子の番号が 0 でない
+            ("xprv9s2SPatNQ9Vc6GTbVMFPFo7jsaZySyzk7L8n2uqKXJen3KUmvQNTuLh3fhZMBoG3G4ZW1N2kZuHEPY53qmbZzCHshoQnNf4GvELZfqTUrcv", Bip32Error::InvalidMaster),
+            ("xpub661no6RGEX3uJkY4bNnPcw4URcQTrSibUZ4NqJEw5eBkv7ovTwgiT91XX27VbEXGENhYRCf7hyEbWrR3FewATdCEebj6znwMfQkhRYHRLpJ", Bip32Error::InvalidMaster),
+            ("xprv9s21ZrQH4r4TsiLvyLXqM9P7k1K3EYhA1kkD6xuquB5i39AU8KF42acDyL3qsDbU9NmZn6MsGSUYZEsuoePmjzsB3eFKSUEh3Gu1N3cqVUN", Bip32Error::InvalidMaster),
+            ("xpub661MyMwAuDcm6CRQ5N4qiHKrJ39Xe1R1NyfouMKTTWcguwVcfrZJaNvhpebzGerh7gucBvzEQWRugZDuDXjNDRmXzSZe4c7mnTK97pTvGS8", Bip32Error::InvalidMaster),
+            // 未知の version
+            ("DMwo58pR1QLEFihHiXPVykYB6fJmsTeHvyTp7hRThAtCX8CvYzgPcn8XnmdfHGMQzT7ayAmfo4z3gY5KfbrZWZ6St24UVf2Qgo6oujFktLHdHY4", Bip32Error::UnknownVersion([1, 1, 1, 1])),
+            ("DMwo58pR1QLEFihHiXPVykYB6fJmsTeHvyTp7hRThAtCX8CvYzgPcn8XnmdfHPmHJiEDXkTiJTVV9rHEBUem2mwVbbNfvT2MTcAqj3nesx8uBf9", Bip32Error::UnknownVersion([1, 1, 1, 1])),
+            // 秘密鍵 0 と n、曲線上にない公開鍵
+            ("xprv9s21ZrQH143K24Mfq5zL5MhWK9hUhhGbd45hLXo2Pq2oqzMMo63oStZzF93Y5wvzdUayhgkkFoicQZcP3y52uPPxFnfoLZB21Teqt1VvEHx", Bip32Error::InvalidKey),
+            ("xprv9s21ZrQH143K24Mfq5zL5MhWK9hUhhGbd45hLXo2Pq2oqzMMo63oStZzFAzHGBP2UuGCqWLTAPLcMtD5SDKr24z3aiUvKr9bJpdrcLg1y3G", Bip32Error::InvalidKey),
+            ("xpub661MyMwAqRbcEYS8w7XLSVeEsBXy79zSzH1J8vCdxAZningWLdN3zgtU6Q5JXayek4PRsn35jii4veMimro1xefsM58PgBMrvdYre8QyULY", Bip32Error::InvalidKey),
+            ("xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHL", Bip32Error::Base58(Base58Error::InvalidChecksum)),
+        ];
+        for (s, expected) in cases {
+            let result = if s.starts_with("xpub") {
+                ExtendedPublicKey::from_base58(s).map(|_| ())
+            } else {
+                ExtendedPrivateKey::from_base58(s).map(|_| ())
+            };
+            assert_eq!(result, Err(expected), "{}", s);
+        }
+    }
+}
@@ -0,0 +1,311 @@
+// BIP-39 ニーモニック。ENT ビットのエントロピー (128〜256、32 の倍数) に SHA-256 の先頭 ENT/32 ビットを
+// チェックサムとして足し、11 ビットずつ 2048 語の単語リストの番号にする (12〜24 語)。
+// シードは PBKDF2-HMAC-SHA512(フレーズ, "mnemonic" || パスフレーズ, 2048 回, 64 バイト) で、
+// チェックサムも単語リストも使わないので、パスフレーズが違えばまったく別の鍵束になる。
+// 仕様では両方を NFKD 正規化するが、ここでは正規化済み (ASCII なら不要) のものを受け取る
+use super::{Bip32Error, ExtendedPrivateKey, Network};
+use crate::hash::{sha256, Sha512};
+use crate::kdf::pbkdf2;
+use crate::rand::RngCore;
+use std::sync::OnceLock;
+
+const WORDLIST_LEN: usize = 2048;
+const SEED_ITERATIONS: u32 = 2048;
+const ENGLISH: &str = include_str!("bip39_english.txt");
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Bip39Error {
+    InvalidEntropyLength(usize),
+    InvalidWordCount(usize),
+    UnknownWord(String),
+    InvalidChecksum,
+    // 単語リストが 2048 語でない、または同じ語を 2 度含む
+    InvalidWordlistLength(usize),
+    DuplicateWord(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Wordlist {
+    words: Vec<String>,
+}
+
+impl Wordlist {
+    // 空白区切りの 2048 語。重複があると番号が決まらない
+    pub fn from_text(text: &str) -> Result<Self, Bip39Error> {
+        let words: Vec<String> = text.split_whitespace().map(str::to_string).collect();
+        if words.len() != WORDLIST_LEN {
+            return Err(Bip39Error::InvalidWordlistLength(words.len()));
+        }
+        let mut sorted: Vec<&String> = words.iter().collect();
+        sorted.sort();
+        if let Some(pair) = sorted.windows(2).find(|pair| pair[0] == pair[1]) {
+            return Err(Bip39Error::DuplicateWord(pair[0].clone()));
+        }
+        Ok(Wordlist { words })
+    }
+
+    pub fn english() -> &'static Wordlist {
+        static LIST: OnceLock<Wordlist> = OnceLock::new();
+        LIST.get_or_init(|| Wordlist::from_text(ENGLISH).expect("English wordlist is valid"))
+    }
+
+    pub fn word(&self, index: usize) -> &str {
+        &self.words[index]
+    }
+
+    pub fn index_of(&self, word: &str) -> Option<usize> {
+        self.words.iter().position(|w| w == word)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct Mnemonic {
+    entropy: Vec<u8>,
+    words: Vec<String>,
+}
+
+// フレーズは秘密鍵そのものなので表示しない
+impl std::fmt::Debug for Mnemonic {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Mnemonic({} words)", self.words.len())
+    }
+}
+
+fn checksum_bits(entropy: &[u8]) -> usize {
+    entropy.len() * 8 / 32
+}
+
+fn bit(data: &[u8], i: usize) -> u16 {
+    ((data[i / 8] >> (7 - i % 8)) & 1) as u16
+}
+
+impl Mnemonic {
+    pub fn from_entropy(entropy: &[u8]) -> Result<Self, Bip39Error> {
+        Mnemonic::from_entropy_in(Wordlist::english(), entropy)
+    }
+
+    pub fn from_entropy_in(wordlist: &Wordlist, entropy: &[u8]) -> Result<Self, Bip39Error> {
+        if !(16..=32).contains(&entropy.len()) || !entropy.len().is_multiple_of(4) {
+            return Err(Bip39Error::InvalidEntropyLength(entropy.len()));
+        }
+        let mut data = entropy.to_vec();
+        data.extend_from_slice(&sha256(entropy));
+        let total = entropy.len() * 8 + checksum_bits(entropy);
+        let words = (0..total / 11)
+            .map(|w| {
+                let index = (0..11).fold(0, |acc, i| (acc << 1) | bit(&data, w * 11 + i));
+                wordlist.word(index as usize).to_string()
+            })
+            .collect();
+        Ok(Mnemonic {
+            entropy: entropy.to_vec(),
+            words,
+        })
+    }
+
+    // 12, 15, 18, 21, 24 語のいずれか
+    pub fn generate<R: RngCore>(word_count: usize, rng: &mut R) -> Self {
+        assert!(
+            (12..=24).contains(&word_count) && word_count.is_multiple_of(3),
+            "Word count must be 12, 15, 18, 21 or 24"
+        );
+        let mut entropy = vec![0u8; word_count / 3 * 4];
+        rng.fill_bytes(&mut entropy);
+        Mnemonic::from_entropy(&entropy).unwrap()
+    }
+
+    pub fn parse(phrase: &str) -> Result<Self, Bip39Error> {
+        Mnemonic::parse_in(Wordlist::english(), phrase)
+    }
+
+    pub fn parse_in(wordlist: &Wordlist, phrase: &str) -> Result<Self, Bip39Error> {
+        let words: Vec<&str> = phrase.split_whitespace().collect();
+        if !(12..=24).contains(&words.len()) || !words.len().is_multiple_of(3) {
+            return Err(Bip39Error::InvalidWordCount(words.len()));
+        }
+        let indices = words
+            .iter()
+            .map(|w| {
+                wordlist
+                    .index_of(w)
+                    .ok_or_else(|| Bip39Error::UnknownWord(w.to_string()))
+            })
+            .collect::<Result<Vec<usize>, _>>()?;
+        let bits: Vec<u8> = indices
+            .iter()
+            .flat_map(|&index| (0..11).rev().map(move |i| ((index >> i) & 1) as u8))
+            .collect();
+        let entropy_bits = bits.len() * 32 / 33;
+        let entropy: Vec<u8> = bits[..entropy_bits]
+            .chunks(8)
+            .map(|byte| byte.iter().fold(0, |acc, b| (acc << 1) | b))
+            .collect();
+        // 単語から戻したエントロピーで作り直し、チェックサムの単語まで一致するか見る
+        let expected = Mnemonic::from_entropy_in(wordlist, &entropy)?;
+        if expected.words != words {
+            return Err(Bip39Error::InvalidChecksum);
+        }
+        Ok(expected)
+    }
+
+    pub fn entropy(&self) -> &[u8] {
+        &self.entropy
+    }
+
+    pub fn words(&self) -> &[String] {
+        &self.words
+    }
+
+    pub fn phrase(&self) -> String {
+        self.words.join(" ")
+    }
+
+    pub fn to_seed(&self, passphrase: &str) -> [u8; 64] {
+        let salt = format!("mnemonic{}", passphrase);
+        pbkdf2::<Sha512>(
+            self.phrase().as_bytes(),
+            salt.as_bytes(),
+            SEED_ITERATIONS,
+            64,
+        )
+        .try_into()
+        .unwrap()
+    }
+
+    // BIP-32 のマスター鍵。ここから derive_path で鍵束全体を導く
+    pub fn to_master_key(
+        &self,
+        passphrase: &str,
+        network: Network,
+    ) -> Result<ExtendedPrivateKey, Bip32Error> {
+        ExtendedPrivateKey::new_master(&self.to_seed(passphrase), network)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitcoin::{Address, DerivationPath};
+    use crate::rand::TestRng;
+    use crate::test_util::hex;
+
+    #[test]
+    fn test_trezor_vectors() {
+        let vectors = [
+            (
+                vec![0x00; 16],
+                "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+                "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04",
+            ),
+            (
+                vec![0x7f; 16],
+                "legal winner thank year wave sausage worth useful legal winner thank yellow",
+                "2e8905819b8723fe2c1d161860e5ee1830318dbf49a83bd451cfb8440c28bd6fa457fe1296106559a3c80937a1c1069be3a3a5bd381ee6260e8d9739fce1f607",
+            ),
+            (
+                vec![0xff; 32],
+                "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo vote",
+                "dd48c104698c30cfe2b6142103248622fb7bb0ff692eebb00089b32d22484e1613912f0a5b694407be899ffd31ed3992c456cdf60f5d4564b8ba3f05a69890ad",
+            ),
+        ];
+        for (entropy, phrase, seed) in vectors {
+            let mnemonic = Mnemonic::from_entropy(&entropy).unwrap();
+            assert_eq!(mnemonic.phrase(), phrase);
+            assert_eq!(hex(&mnemonic.to_seed("TREZOR")), seed);
+            assert_eq!(Mnemonic::parse(phrase).unwrap().entropy(), &entropy[..]);
+        }
+        let master = Mnemonic::from_entropy(&[0; 16])
+            .unwrap()
+            .to_master_key("TREZOR", Network::Mainnet)
+            .unwrap();
+        assert_eq!(
+            master.to_base58(),
+            "xprv9s21ZrQH143K3h3fDYiay8mocZ3afhfULfb5GX8kCBdno77K4HiA15Tg23wpbeF1pLfs1c5SPmYHrEpTuuRhxMwvKDwqdKiGJS9XFKzUsAF"
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_phrases() {
+        // 最後の語を変えるとチェックサムが合わない
+        let bad = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon";
+        assert_eq!(Mnemonic::parse(bad), Err(Bip39Error::InvalidChecksum));
+        assert_eq!(
+            Mnemonic::parse("abandon abandon about"),
+            Err(Bip39Error::InvalidWordCount(3))
+        );
+        let unknown = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon bitcoin";
+        assert_eq!(
+            Mnemonic::parse(unknown),
+            Err(Bip39Error::UnknownWord("bitcoin".to_string()))
+        );
+        assert_eq!(
+            Mnemonic::from_entropy(&[0; 19]),
+            Err(Bip39Error::InvalidEntropyLength(19))
+        );
+        // 余分な空白は区切りとして扱う
+        let spaced =
+            "  legal winner thank year wave sausage worth useful legal winner thank\tyellow ";
+        assert_eq!(Mnemonic::parse(spaced).unwrap().entropy(), &[0x7f; 16]);
+    }
+
+    #[test]
+    fn test_custom_wordlist() {
+        let words: Vec<String> = (0..2048).map(|i| format!("w{}", i)).collect();
+        let list = Wordlist::from_text(&words.join("\n")).unwrap();
+        let mnemonic = Mnemonic::from_entropy_in(&list, &[0x7f; 16]).unwrap();
+        assert_eq!(
+            Mnemonic::parse_in(&list, &mnemonic.phrase())
+                .unwrap()
+                .entropy(),
+            &[0x7f; 16]
+        );
+        assert_eq!(
+            Wordlist::from_text(&words[..2047].join(" ")),
+            Err(Bip39Error::InvalidWordlistLength(2047))
+        );
+        let mut duplicated = words.clone();
+        duplicated[5] = "w4".to_string();
+        assert_eq!(
+            Wordlist::from_text(&duplicated.join(" ")),
+            Err(Bip39Error::DuplicateWord("w4".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_generated_wallet_keychain() {
+        let mut rng = TestRng::from_label(b"bip39");
+        let mnemonic = Mnemonic::generate(24, &mut rng);
+        assert_eq!(mnemonic.words().len(), 24);
+        let parsed = Mnemonic::parse(&mnemonic.phrase()).unwrap();
+        assert_eq!(parsed, mnemonic);
+        // パスフレーズごとに別の鍵束になる
+        let path = DerivationPath::parse("m/84'/0'/0'/0/0").unwrap();
+        let account = |passphrase| {
+            let key = parsed
+                .to_master_key(passphrase, Network::Mainnet)
+                .unwrap()
+                .derive_path(&path)
+                .unwrap();
+            Address::p2wpkh(&key.public_key(), Network::Mainnet)
+        };
+        assert_eq!(account(""), account(""));
+        assert_ne!(account(""), account("extra"));
+        // BIP-84 の最初の受け取りアドレス
+        let key = Mnemonic::from_entropy(&[0; 16])
+            .unwrap()
+            .to_master_key("", Network::Mainnet)
+            .unwrap()
+            .derive_path(&path)
+            .unwrap();
+        assert_eq!(
+            Address::p2wpkh(&key.public_key(), Network::Mainnet).to_string(),
+            "bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Word count must be 12, 15, 18, 21 or 24")]
+    fn test_generate_rejects_word_count() {
+        Mnemonic::generate(13, &mut TestRng::from_label(b"bip39"));
+    }
+}
@@ -0,0 +1,14 @@
+// Bitcoin の鍵、アドレス、トランザクションまわり。曲線演算は secp256k1、符号化は encoding に置いたものを使う
+pub mod address;
+pub mod bip32;
+pub mod bip39;
+pub mod taproot;
+pub mod transaction;
+pub mod wif;
+
+pub use address::{Address, AddressError, Network, Payload};
+pub use bip32::{Bip32Error, DerivationPath, ExtendedPrivateKey, ExtendedPublicKey};
+pub use bip39::{Bip39Error, Mnemonic, Wordlist};
+pub use taproot::TaprootError;
+pub use transaction::{OutPoint, Transaction, TxIn, TxOut, SIGHASH_ALL};
+pub use wif::{PrivateKey, WifError};
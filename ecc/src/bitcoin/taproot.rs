@@ -0,0 +1,145 @@
+// BIP-341 のキーパス。内部鍵 P (x-only) をスクリプト木の根とあわせて
+//   t = H_TapTweak(P.x || merkle_root),  Q = P + t·G
+// と調整した出力鍵 Q をアドレスに載せる。スクリプトを使わないときも (根なしで) 調整するので、
+// 出力鍵に隠れたスクリプトがないことを示せる (BIP-86)。
+// キーパスで使うときは秘密鍵を同じだけずらし、d' = d + t (P の y が奇数なら d を n - d にしてから)
+// で Q に対する BIP-340 署名を作る
+use super::{Address, Network};
+use crate::bignum::BigUint;
+use crate::secp256k1::{self, schnorr, Point, PublicKey, SecretKey};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaprootError {
+    // x 座標が曲線上の点にならない
+    InvalidInternalKey,
+    // t ≥ n か Q が無限遠点 (確率は無視できる)
+    InvalidTweak,
+}
+
+pub fn tap_tweak(internal_key: &[u8; 32], merkle_root: Option<&[u8; 32]>) -> BigUint {
+    let root: &[u8] = merkle_root.map_or(&[], |r| r);
+    BigUint::from_bytes_be(&schnorr::tagged_hash("TapTweak", &[internal_key, root]))
+}
+
+// (出力鍵の x 座標, 出力鍵の y が奇数か)。奇偶はスクリプトパスの control block に入る
+pub fn tweak_public_key(
+    internal_key: &[u8; 32],
+    merkle_root: Option<&[u8; 32]>,
+) -> Result<([u8; 32], bool), TaprootError> {
+    let p = Point::lift_x(&BigUint::from_bytes_be(internal_key))
+        .ok_or(TaprootError::InvalidInternalKey)?;
+    let t = tap_tweak(internal_key, merkle_root);
+    if &t >= secp256k1::n() {
+        return Err(TaprootError::InvalidTweak);
+    }
+    let q = p.add(&Point::mul_base(&t));
+    if q.is_infinity() {
+        return Err(TaprootError::InvalidTweak);
+    }
+    Ok((PublicKey(q.clone()).x_only(), !q.has_even_y()))
+}
+
+pub fn tweak_secret_key(
+    secret: &SecretKey,
+    merkle_root: Option<&[u8; 32]>,
+) -> Result<SecretKey, TaprootError> {
+    let public = secret.public_key();
+    let d = if public.0.has_even_y() {
+        secret.scalar().clone()
+    } else {
+        secp256k1::n().sub(secret.scalar())
+    };
+    let t = tap_tweak(&public.x_only(), merkle_root);
+    if &t >= secp256k1::n() {
+        return Err(TaprootError::InvalidTweak);
+    }
+    SecretKey::from_scalar(d.add(&t).rem(secp256k1::n())).map_err(|_| TaprootError::InvalidTweak)
+}
+
+// キーパス支出の署名。msg は BIP-341 の sighash
+pub fn sign_key_path(
+    secret: &SecretKey,
+    merkle_root: Option<&[u8; 32]>,
+    msg: &[u8; 32],
+    aux_rand: &[u8; 32],
+) -> Result<[u8; 64], TaprootError> {
+    let tweaked = tweak_secret_key(secret, merkle_root)?;
+    Ok(schnorr::sign(&tweaked, msg, aux_rand))
+}
+
+impl Address {
+    // 内部鍵からスクリプトなしの P2TR アドレスを作る
+    pub fn p2tr_key_path(internal_key: &PublicKey, network: Network) -> Self {
+        let (output_key, _) =
+            tweak_public_key(&internal_key.x_only(), None).expect("Tweak of a valid key is valid");
+        Address::p2tr(&output_key, network)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitcoin::{DerivationPath, Mnemonic};
+    use crate::test_util::hex;
+
+    fn bip86_key() -> SecretKey {
+        let mnemonic = Mnemonic::from_entropy(&[0; 16]).unwrap();
+        let path = DerivationPath::parse("m/86'/0'/0'/0/0").unwrap();
+        mnemonic
+            .to_master_key("", Network::Mainnet)
+            .unwrap()
+            .derive_path(&path)
+            .unwrap()
+            .key
+    }
+
+    #[test]
+    fn test_bip86_output_key() {
+        // BIP-86 の最初の受け取りアドレス
+        let secret = bip86_key();
+        let internal = secret.public_key();
+        assert_eq!(
+            hex(&internal.x_only()),
+            "cc8a4bc64d897bddc5fbc2f670f7a8ba0b386779106cf1223c6fc5d7cd6fc115"
+        );
+        let (output, _) = tweak_public_key(&internal.x_only(), None).unwrap();
+        assert_eq!(
+            hex(&output),
+            "a60869f0dbcf1dc659c9cecbaf8050135ea9e8cdc487053f1dc6880949dc684c"
+        );
+        assert_eq!(
+            Address::p2tr_key_path(&internal, Network::Mainnet).to_string(),
+            "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr"
+        );
+        // 調整した秘密鍵の公開鍵が出力鍵になる
+        let tweaked = tweak_secret_key(&secret, None).unwrap();
+        assert_eq!(tweaked.public_key().x_only(), output);
+    }
+
+    #[test]
+    fn test_key_path_signature() {
+        let secret = SecretKey::from_bytes(&[9; 32]).unwrap();
+        let internal = secret.public_key().x_only();
+        let msg = [0x42; 32];
+        for root in [None, Some(&[0x11; 32])] {
+            let (output, parity) = tweak_public_key(&internal, root).unwrap();
+            let sig = sign_key_path(&secret, root, &msg, &[0; 32]).unwrap();
+            assert!(schnorr::verify(&output, &msg, &sig));
+            // 内部鍵に対しては通らない
+            assert!(!schnorr::verify(&internal, &msg, &sig));
+            let q = tweak_secret_key(&secret, root).unwrap().public_key().0;
+            assert_eq!(parity, !q.has_even_y());
+        }
+        // 根が違えば出力鍵も違う
+        assert_ne!(
+            tweak_public_key(&internal, None).unwrap().0,
+            tweak_public_key(&internal, Some(&[0x11; 32])).unwrap().0
+        );
+        let mut off_curve = [0u8; 32];
+        off_curve[31] = 5;
+        assert_eq!(
+            tweak_public_key(&off_curve, None),
+            Err(TaprootError::InvalidInternalKey)
+        );
+    }
+}
@@ -0,0 +1,436 @@
+// 最小限のトランザクション。署名するのは tx 全体ではなく「sighash」で、入力ごとに作り方が違う:
+//   legacy  : scriptSig を全部空にし、署名する入力だけに前の出力のスクリプトを入れて直列化 → SHA256d
+//   BIP-143 : segwit v0。前の出力の金額も含め、全入力・全出力のハッシュを共有して二乗の手間を避ける
+//   BIP-341 : taproot。全入力の金額と scriptPubKey を含むタグ付きハッシュ
+// ここでは SIGHASH_ALL (taproot は SIGHASH_DEFAULT) だけを扱う
+use super::{Address, PrivateKey};
+use crate::hash::{hash160, sha256, sha256d};
+use crate::secp256k1::{ecdsa, schnorr, SecretKey};
+
+pub const SIGHASH_ALL: u32 = 0x01;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutPoint {
+    // 直列化したときのバイト順 (表示は逆順)
+    pub txid: [u8; 32],
+    pub vout: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TxIn {
+    pub previous_output: OutPoint,
+    pub script_sig: Vec<u8>,
+    pub sequence: u32,
+    pub witness: Vec<Vec<u8>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TxOut {
+    pub value: u64,
+    pub script_pubkey: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transaction {
+    pub version: u32,
+    pub inputs: Vec<TxIn>,
+    pub outputs: Vec<TxOut>,
+    pub lock_time: u32,
+}
+
+fn write_compact_size(out: &mut Vec<u8>, n: usize) {
+    match n {
+        0..=0xfc => out.push(n as u8),
+        0xfd..=0xffff => {
+            out.push(0xfd);
+            out.extend_from_slice(&(n as u16).to_le_bytes());
+        }
+        0x10000..=0xffff_ffff => {
+            out.push(0xfe);
+            out.extend_from_slice(&(n as u32).to_le_bytes());
+        }
+        _ => {
+            out.push(0xff);
+            out.extend_from_slice(&(n as u64).to_le_bytes());
+        }
+    }
+}
+
+fn write_bytes(out: &mut Vec<u8>, data: &[u8]) {
+    write_compact_size(out, data.len());
+    out.extend_from_slice(data);
+}
+
+// スクリプトにデータを積む命令
+pub fn push_data(script: &mut Vec<u8>, data: &[u8]) {
+    match data.len() {
+        0..=75 => script.push(data.len() as u8),
+        76..=255 => script.extend_from_slice(&[0x4c, data.len() as u8]),
+        _ => panic!("Push data is too long"),
+    }
+    script.extend_from_slice(data);
+}
+
+// P2PKH の scriptPubKey。P2WPKH の scriptCode もこれ
+fn p2pkh_script(pubkey_hash: &[u8; 20]) -> Vec<u8> {
+    [&[0x76, 0xa9, 0x14][..], pubkey_hash, &[0x88, 0xac]].concat()
+}
+
+impl OutPoint {
+    fn write(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.txid);
+        out.extend_from_slice(&self.vout.to_le_bytes());
+    }
+}
+
+impl TxIn {
+    pub fn new(previous_output: OutPoint, sequence: u32) -> Self {
+        TxIn {
+            previous_output,
+            script_sig: Vec::new(),
+            sequence,
+            witness: Vec::new(),
+        }
+    }
+}
+
+impl TxOut {
+    pub fn to_address(value: u64, address: &Address) -> Self {
+        TxOut {
+            value,
+            script_pubkey: address.script_pubkey(),
+        }
+    }
+
+    fn write(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.value.to_le_bytes());
+        write_bytes(out, &self.script_pubkey);
+    }
+}
+
+impl Transaction {
+    // witness を含めない直列化。txid と legacy sighash はこちらから作る
+    pub fn serialize_legacy(&self) -> Vec<u8> {
+        self.serialize_with(false)
+    }
+
+    // witness があれば marker 0x00 と flag 0x01 を挟み、出力の後に入力ごとの witness を置く
+    pub fn serialize(&self) -> Vec<u8> {
+        self.serialize_with(self.inputs.iter().any(|i| !i.witness.is_empty()))
+    }
+
+    fn serialize_with(&self, witness: bool) -> Vec<u8> {
+        let mut out = self.version.to_le_bytes().to_vec();
+        if witness {
+            out.extend_from_slice(&[0x00, 0x01]);
+        }
+        write_compact_size(&mut out, self.inputs.len());
+        for input in &self.inputs {
+            input.previous_output.write(&mut out);
+            write_bytes(&mut out, &input.script_sig);
+            out.extend_from_slice(&input.sequence.to_le_bytes());
+        }
+        write_compact_size(&mut out, self.outputs.len());
+        for output in &self.outputs {
+            output.write(&mut out);
+        }
+        if witness {
+            for input in &self.inputs {
+                write_compact_size(&mut out, input.witness.len());
+                for item in &input.witness {
+                    write_bytes(&mut out, item);
+                }
+            }
+        }
+        out.extend_from_slice(&self.lock_time.to_le_bytes());
+        out
+    }
+
+    pub fn txid(&self) -> [u8; 32] {
+        sha256d(&self.serialize_legacy())
+    }
+
+    pub fn legacy_sighash(&self, index: usize, script_code: &[u8], sighash_type: u32) -> [u8; 32] {
+        assert!(index < self.inputs.len(), "Input index out of range");
+        assert_eq!(sighash_type, SIGHASH_ALL, "Only SIGHASH_ALL is supported");
+        let mut tx = self.clone();
+        for (i, input) in tx.inputs.iter_mut().enumerate() {
+            input.script_sig = if i == index {
+                script_code.to_vec()
+            } else {
+                Vec::new()
+            };
+        }
+        let mut preimage = tx.serialize_legacy();
+        preimage.extend_from_slice(&sighash_type.to_le_bytes());
+        sha256d(&preimage)
+    }
+
+    // value は使う出力の金額 (satoshi)
+    pub fn segwit_v0_sighash(
+        &self,
+        index: usize,
+        script_code: &[u8],
+        value: u64,
+        sighash_type: u32,
+    ) -> [u8; 32] {
+        assert!(index < self.inputs.len(), "Input index out of range");
+        assert_eq!(sighash_type, SIGHASH_ALL, "Only SIGHASH_ALL is supported");
+        let mut prevouts = Vec::new();
+        let mut sequences = Vec::new();
+        for input in &self.inputs {
+            input.previous_output.write(&mut prevouts);
+            sequences.extend_from_slice(&input.sequence.to_le_bytes());
+        }
+        let mut outputs = Vec::new();
+        for output in &self.outputs {
+            output.write(&mut outputs);
+        }
+        let input = &self.inputs[index];
+        let mut preimage = self.version.to_le_bytes().to_vec();
+        preimage.extend_from_slice(&sha256d(&prevouts));
+        preimage.extend_from_slice(&sha256d(&sequences));
+        input.previous_output.write(&mut preimage);
+        write_bytes(&mut preimage, script_code);
+        preimage.extend_from_slice(&value.to_le_bytes());
+        preimage.extend_from_slice(&input.sequence.to_le_bytes());
+        preimage.extend_from_slice(&sha256d(&outputs));
+        preimage.extend_from_slice(&self.lock_time.to_le_bytes());
+        preimage.extend_from_slice(&sighash_type.to_le_bytes());
+        sha256d(&preimage)
+    }
+
+    // キーパス支出、SIGHASH_DEFAULT。spent は全入力が使う出力 (入力と同じ順)
+    pub fn taproot_sighash(&self, index: usize, spent: &[TxOut]) -> [u8; 32] {
+        assert!(index < self.inputs.len(), "Input index out of range");
+        assert_eq!(spent.len(), self.inputs.len(), "Need every spent output");
+        let mut prevouts = Vec::new();
+        let mut sequences = Vec::new();
+        for input in &self.inputs {
+            input.previous_output.write(&mut prevouts);
+            sequences.extend_from_slice(&input.sequence.to_le_bytes());
+        }
+        let amounts: Vec<u8> = spent.iter().flat_map(|o| o.value.to_le_bytes()).collect();
+        let mut scripts = Vec::new();
+        for output in spent {
+            write_bytes(&mut scripts, &output.script_pubkey);
+        }
+        let mut outputs = Vec::new();
+        for output in &self.outputs {
+            output.write(&mut outputs);
+        }
+        // epoch 0, hash_type 0x00
+        let mut preimage = vec![0x00, 0x00];
+        preimage.extend_from_slice(&self.version.to_le_bytes());
+        preimage.extend_from_slice(&self.lock_time.to_le_bytes());
+        for part in [prevouts, amounts, scripts, sequences, outputs] {
+            preimage.extend_from_slice(&sha256(&part));
+        }
+        // spend_type 0: キーパス、annex なし
+        preimage.push(0x00);
+        preimage.extend_from_slice(&(index as u32).to_le_bytes());
+        schnorr::tagged_hash("TapSighash", &[&preimage])
+    }
+
+    // scriptSig = <署名 || SIGHASH_ALL> <公開鍵>
+    pub fn sign_p2pkh(&mut self, index: usize, key: &PrivateKey) {
+        let pubkey = key.public_key_bytes();
+        let script_code = p2pkh_script(&hash160(&pubkey));
+        let sighash = self.legacy_sighash(index, &script_code, SIGHASH_ALL);
+        let mut script_sig = Vec::new();
+        push_data(&mut script_sig, &ecdsa_signature(&key.key, &sighash));
+        push_data(&mut script_sig, &pubkey);
+        self.inputs[index].script_sig = script_sig;
+    }
+
+    // witness = [署名 || SIGHASH_ALL, 圧縮公開鍵]
+    pub fn sign_p2wpkh(&mut self, index: usize, key: &SecretKey, value: u64) {
+        let pubkey = key.public_key().to_compressed();
+        let script_code = p2pkh_script(&hash160(&pubkey));
+        let sighash = self.segwit_v0_sighash(index, &script_code, value, SIGHASH_ALL);
+        let input = &mut self.inputs[index];
+        input.script_sig.clear();
+        input.witness = vec![ecdsa_signature(key, &sighash), pubkey.to_vec()];
+    }
+
+    // witness = [64 バイトの BIP-340 署名]。key は調整前の内部鍵の秘密鍵
+    pub fn sign_p2tr_key_path(
+        &mut self,
+        index: usize,
+        key: &SecretKey,
+        spent: &[TxOut],
+        aux_rand: &[u8; 32],
+    ) {
+        let sighash = self.taproot_sighash(index, spent);
+        let sig = super::taproot::sign_key_path(key, None, &sighash, aux_rand)
+            .expect("Tweak of a valid key is valid");
+        let input = &mut self.inputs[index];
+        input.script_sig.clear();
+        input.witness = vec![sig.to_vec()];
+    }
+}
+
+fn ecdsa_signature(key: &SecretKey, sighash: &[u8; 32]) -> Vec<u8> {
+    let mut sig = ecdsa::sign(key, sighash).to_der();
+    sig.push(SIGHASH_ALL as u8);
+    sig
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitcoin::Network;
+    use crate::secp256k1::PublicKey;
+    use crate::test_util::{hex, unhex};
+
+    fn secret(s: &str) -> SecretKey {
+        SecretKey::from_bytes(&unhex(s).try_into().unwrap()).unwrap()
+    }
+
+    fn outpoint(txid: &str, vout: u32) -> OutPoint {
+        OutPoint {
+            txid: unhex(txid).try_into().unwrap(),
+            vout,
+        }
+    }
+
+    // BIP-143 の native P2WPKH の例: 入力 0 が P2PK、入力 1 が P2WPKH (6 BTC)
+    fn bip143_transaction() -> Transaction {
+        Transaction {
+            version: 1,
+            inputs: vec![
+                TxIn::new(
+                    outpoint(
+                        "fff7f7881a8099afa6940d42d1e7f6362bec38171ea3edf433541db4e4ad969f",
+                        0,
+                    ),
+                    0xffffffee,
+                ),
+                TxIn::new(
+                    outpoint(
+                        "ef51e1b804cc89d182d279655c3aa89e815b1b309fe287d9b2b55d57b90ec68a",
+                        1,
+                    ),
+                    0xffffffff,
+                ),
+            ],
+            outputs: vec![
+                TxOut {
+                    value: 112340000,
+                    script_pubkey: unhex("76a9148280b37df378db99f66f85c95a783a76ac7a6d5988ac"),
+                },
+                TxOut {
+                    value: 223450000,
+                    script_pubkey: unhex("76a9143bde42dbee7e4dbe6a21b2d50ce2f0167faa815988ac"),
+                },
+            ],
+            lock_time: 0x11,
+        }
+    }
+
+    #[test]
+    fn test_bip143_native_p2wpkh() {
+        let mut tx = bip143_transaction();
+        assert_eq!(
+            hex(&tx.serialize()),
+            "0100000002fff7f7881a8099afa6940d42d1e7f6362bec38171ea3edf433541db4e4ad969f0000000000eeffffffef51e1b804cc89d182d279655c3aa89e815b1b309fe287d9b2b55d57b90ec68a0100000000ffffffff02202cb206000000001976a9148280b37df378db99f66f85c95a783a76ac7a6d5988ac9093510d000000001976a9143bde42dbee7e4dbe6a21b2d50ce2f0167faa815988ac11000000"
+        );
+        let wpkh = secret("619c335025c7f4012e556c2a58b2506e30b8511b53ade95ea316fd8c3286feb9");
+        let script_code = p2pkh_script(&hash160(&wpkh.public_key().to_compressed()));
+        assert_eq!(
+            hex(&tx.segwit_v0_sighash(1, &script_code, 600000000, SIGHASH_ALL)),
+            "c37af31116d1b27caf68aae9e3ac82f1477929014d5b917657d0eb49478cb670"
+        );
+        // P2PK は scriptSig に署名だけを積む
+        let pk = secret("bbc27228ddcb9209d7fd6f36b02f7dfa6252af40bb2f1cbc7a557da8027ff866");
+        let mut p2pk = Vec::new();
+        push_data(&mut p2pk, &pk.public_key().to_compressed());
+        p2pk.push(0xac);
+        let sighash = tx.legacy_sighash(0, &p2pk, SIGHASH_ALL);
+        let mut script_sig = Vec::new();
+        push_data(&mut script_sig, &ecdsa_signature(&pk, &sighash));
+        tx.inputs[0].script_sig = script_sig;
+        tx.sign_p2wpkh(1, &wpkh, 600000000);
+        assert_eq!(
+            hex(&tx.serialize()),
+            "01000000000102fff7f7881a8099afa6940d42d1e7f6362bec38171ea3edf433541db4e4ad969f00000000494830450221008b9d1dc26ba6a9cb62127b02742fa9d754cd3bebf337f7a55d114c8e5cdd30be022040529b194ba3f9281a99f2b1c0a19c0489bc22ede944ccf4ecbab4cc618ef3ed01eeffffffef51e1b804cc89d182d279655c3aa89e815b1b309fe287d9b2b55d57b90ec68a0100000000ffffffff02202cb206000000001976a9148280b37df378db99f66f85c95a783a76ac7a6d5988ac9093510d000000001976a9143bde42dbee7e4dbe6a21b2d50ce2f0167faa815988ac000247304402203609e17b84f6a7d30c80bfa610b5b4542f32a8a0d5447a12fb1366d7f01cc44a0220573a954c4518331561406f90300e8f3358f51928d43c212a8caed02de67eebee0121025476c2e83188368da1ff3e292e7acafcdb3566bb0ad253f62fc70f07aeee635711000000"
+        );
+        // witness は txid に影響しない
+        let mut stripped = tx.clone();
+        stripped.inputs[1].witness.clear();
+        assert_eq!(tx.txid(), stripped.txid());
+    }
+
+    #[test]
+    fn test_sign_and_verify_each_input_type() {
+        let legacy = PrivateKey::new(SecretKey::from_bytes(&[1; 32]).unwrap(), Network::Testnet);
+        let segwit = SecretKey::from_bytes(&[2; 32]).unwrap();
+        let taproot_key = SecretKey::from_bytes(&[3; 32]).unwrap();
+        let spent = vec![
+            TxOut::to_address(50_000, &legacy.p2pkh_address()),
+            TxOut::to_address(
+                60_000,
+                &Address::p2wpkh(&segwit.public_key(), Network::Testnet),
+            ),
+            TxOut::to_address(
+                70_000,
+                &Address::p2tr_key_path(&taproot_key.public_key(), Network::Testnet),
+            ),
+        ];
+        let mut tx = Transaction {
+            version: 2,
+            inputs: (0..3)
+                .map(|i| TxIn::new(outpoint(&"ab".repeat(32), i), 0xfffffffd))
+                .collect(),
+            outputs: vec![TxOut::to_address(
+                170_000,
+                &Address::p2wpkh(&segwit.public_key(), Network::Testnet),
+            )],
+            lock_time: 0,
+        };
+        tx.sign_p2pkh(0, &legacy);
+        tx.sign_p2wpkh(1, &segwit, 60_000);
+        tx.sign_p2tr_key_path(2, &taproot_key, &spent, &[0; 32]);
+
+        // P2PKH: scriptSig の署名と公開鍵で legacy sighash を検証
+        let script_sig = &tx.inputs[0].script_sig;
+        let sig_len = script_sig[0] as usize;
+        let der = &script_sig[1..sig_len];
+        assert_eq!(script_sig[sig_len], SIGHASH_ALL as u8);
+        let pubkey = PublicKey::from_sec1(&script_sig[sig_len + 2..]).unwrap();
+        let sighash = tx.legacy_sighash(0, &spent[0].script_pubkey, SIGHASH_ALL);
+        let sig = ecdsa::Signature::from_der(der).unwrap();
+        assert!(sig.is_low_s());
+        assert!(ecdsa::verify(&pubkey, &sighash, &sig));
+
+        // P2WPKH: 金額を変えると sighash が変わり署名が通らない
+        let witness = &tx.inputs[1].witness;
+        let der = &witness[0][..witness[0].len() - 1];
+        let pubkey = PublicKey::from_sec1(&witness[1]).unwrap();
+        let script_code = p2pkh_script(&hash160(&witness[1]));
+        let sig = ecdsa::Signature::from_der(der).unwrap();
+        let good = tx.segwit_v0_sighash(1, &script_code, 60_000, SIGHASH_ALL);
+        let bad = tx.segwit_v0_sighash(1, &script_code, 60_001, SIGHASH_ALL);
+        assert!(ecdsa::verify(&pubkey, &good, &sig));
+        assert!(!ecdsa::verify(&pubkey, &bad, &sig));
+
+        // P2TR: scriptPubKey の出力鍵で BIP-340 署名を検証
+        let output_key: [u8; 32] = spent[2].script_pubkey[2..].try_into().unwrap();
+        let sig: [u8; 64] = tx.inputs[2].witness[0].clone().try_into().unwrap();
+        assert!(schnorr::verify(
+            &output_key,
+            &tx.taproot_sighash(2, &spent),
+            &sig
+        ));
+        // taproot の sighash は他の入力の金額にも依存する
+        let mut other = spent.clone();
+        other[0].value += 1;
+        assert_ne!(tx.taproot_sighash(2, &spent), tx.taproot_sighash(2, &other));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only SIGHASH_ALL is supported")]
+    fn test_rejects_unsupported_sighash_type() {
+        bip143_transaction().legacy_sighash(0, &[], 0x03);
+    }
+}
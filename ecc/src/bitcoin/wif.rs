@@ -0,0 +1,149 @@
+// Wallet Import Format: Base58Check(network || 秘密鍵 32 バイト || [0x01])。
+// 末尾の 0x01 は対応する公開鍵を圧縮形式で使うという印で、アドレスがどちらの形式の
+// HASH160 になるかを決める (同じ秘密鍵でも P2PKH アドレスは 2 通りある)
+use super::{Address, Network, Payload};
+use crate::encoding::{base58, Base58Error};
+use crate::hash::hash160;
+use crate::secp256k1::{PublicKey, Secp256k1Error, SecretKey};
+
+const COMPRESSED_FLAG: u8 = 0x01;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WifError {
+    Base58(Base58Error),
+    UnknownNetwork(u8),
+    // 32 バイトでも 33 バイト (圧縮フラグ付き) でもない
+    InvalidLength,
+    InvalidCompressionFlag,
+    InvalidKey(Secp256k1Error),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrivateKey {
+    pub key: SecretKey,
+    pub network: Network,
+    pub compressed: bool,
+}
+
+impl PrivateKey {
+    pub fn new(key: SecretKey, network: Network) -> Self {
+        PrivateKey {
+            key,
+            network,
+            compressed: true,
+        }
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        self.key.public_key()
+    }
+
+    // 圧縮フラグに従った公開鍵の直列化
+    pub fn public_key_bytes(&self) -> Vec<u8> {
+        let pk = self.public_key();
+        if self.compressed {
+            pk.to_compressed().to_vec()
+        } else {
+            pk.to_uncompressed().to_vec()
+        }
+    }
+
+    pub fn p2pkh_address(&self) -> Address {
+        Address {
+            network: self.network,
+            payload: Payload::PubkeyHash(hash160(&self.public_key_bytes())),
+        }
+    }
+
+    pub fn to_wif(&self) -> String {
+        let mut payload = self.key.to_bytes().to_vec();
+        if self.compressed {
+            payload.push(COMPRESSED_FLAG);
+        }
+        base58::encode_check(self.network.wif_version(), &payload)
+    }
+
+    pub fn from_wif(s: &str) -> Result<Self, WifError> {
+        let (version, payload) = base58::decode_check(s).map_err(WifError::Base58)?;
+        let network = [Network::Mainnet, Network::Testnet]
+            .into_iter()
+            .find(|n| n.wif_version() == version)
+            .ok_or(WifError::UnknownNetwork(version))?;
+        let compressed = match payload.len() {
+            32 => false,
+            33 if payload[32] == COMPRESSED_FLAG => true,
+            33 => return Err(WifError::InvalidCompressionFlag),
+            _ => return Err(WifError::InvalidLength),
+        };
+        let key = SecretKey::from_bytes(payload[..32].try_into().unwrap())
+            .map_err(WifError::InvalidKey)?;
+        Ok(PrivateKey {
+            key,
+            network,
+            compressed,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bignum::BigUint;
+
+    fn key_one() -> SecretKey {
+        SecretKey::from_scalar(BigUint::one()).unwrap()
+    }
+
+    #[test]
+    fn test_wif_vectors() {
+        let compressed = PrivateKey::new(key_one(), Network::Mainnet);
+        assert_eq!(
+            compressed.to_wif(),
+            "KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgd9M7rFU73sVHnoWn"
+        );
+        let uncompressed = PrivateKey {
+            compressed: false,
+            ..compressed.clone()
+        };
+        assert_eq!(
+            uncompressed.to_wif(),
+            "5HpHagT65TZzG1PH3CSu63k8DbpvD8s5ip4nEB3kEsreAnchuDf"
+        );
+        // 圧縮の有無でアドレスが変わる
+        assert_eq!(
+            compressed.p2pkh_address().to_string(),
+            "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH"
+        );
+        assert_eq!(
+            uncompressed.p2pkh_address().to_string(),
+            "1EHNa6Q4Jz2uvNExL497mE43ikXhwF6kZm"
+        );
+        for key in [compressed, uncompressed] {
+            assert_eq!(PrivateKey::from_wif(&key.to_wif()), Ok(key));
+        }
+    }
+
+    #[test]
+    fn test_rejects_malformed_wif() {
+        let testnet = PrivateKey::new(key_one(), Network::Testnet);
+        assert_eq!(PrivateKey::from_wif(&testnet.to_wif()), Ok(testnet));
+        let mut payload = key_one().to_bytes().to_vec();
+        payload.push(0x02);
+        assert_eq!(
+            PrivateKey::from_wif(&base58::encode_check(0x80, &payload)),
+            Err(WifError::InvalidCompressionFlag)
+        );
+        assert_eq!(
+            PrivateKey::from_wif(&base58::encode_check(0x00, &payload)),
+            Err(WifError::UnknownNetwork(0x00))
+        );
+        assert_eq!(
+            PrivateKey::from_wif(&base58::encode_check(0x80, &[0; 32])),
+            Err(WifError::InvalidKey(Secp256k1Error::InvalidSecretKey))
+        );
+        assert_eq!(
+            PrivateKey::from_wif(&base58::encode_check(0x80, &[1; 31])),
+            Err(WifError::InvalidLength)
+        );
+    }
+}
@@ -0,0 +1,35 @@
+// Bulletproofs (Bünz ら, 2018): 信頼できるセットアップなしの対数サイズの範囲証明
+pub mod inner_product;
+pub mod range_proof;
+
+pub use inner_product::{inner_product, prove_ipa, verify_ipa, InnerProductProof};
+pub use range_proof::RangeProof;
+
+use crate::curve;
+use crate::Point;
+
+// ベクトルコミットメント用の生成元。離散対数が誰にも分からないよう hash-to-curve で導く
+#[derive(Debug, Clone)]
+pub struct BulletproofGens {
+    pub g: Vec<Point>,
+    pub h: Vec<Point>,
+    // 内積引数で <a, b> を載せる生成元
+    pub q: Point,
+}
+
+impl BulletproofGens {
+    pub fn new(capacity: usize) -> Self {
+        let derive = |label: &[u8], i: usize| {
+            curve::hash_to_curve(&[label, &(i as u64).to_le_bytes()].concat())
+        };
+        BulletproofGens {
+            g: (0..capacity)
+                .map(|i| derive(b"bulletproofs/G", i))
+                .collect(),
+            h: (0..capacity)
+                .map(|i| derive(b"bulletproofs/H", i))
+                .collect(),
+            q: curve::hash_to_curve(b"bulletproofs/Q"),
+        }
+    }
+}
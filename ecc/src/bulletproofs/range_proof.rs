@@ -0,0 +1,342 @@
+// 範囲証明: Pedersen コミットメント V_j = v_j G + γ_j H の値が [0, 2^n) にあることを示す。
+// v のビット列 a_L と a_R = a_L - 1 について a_L ∘ a_R = 0, <a_L, 2^n> = v を
+// 多項式 t(X) = <l(X), r(X)> にまとめ、最後は内積引数で l, r を送らずに済ませる。
+// m 個の値をまとめると証明サイズは 2 log(nm) + 9 要素に抑えられる
+use super::inner_product::{inner_product, InnerProductProof};
+use super::BulletproofGens;
+use crate::commitment::{pedersen_g, pedersen_h, PedersenCommitment};
+use crate::curve::{msm, Scalar};
+use crate::rand::RngCore;
+use crate::transcript::Transcript;
+use crate::Point;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeProof {
+    pub a: Point,
+    pub s: Point,
+    pub t1: Point,
+    pub t2: Point,
+    pub tau_x: Scalar,
+    pub mu: Scalar,
+    pub t_hat: Scalar,
+    pub ipp: InnerProductProof,
+}
+
+fn powers(x: &Scalar, n: usize) -> Vec<Scalar> {
+    let mut out = Vec::with_capacity(n);
+    let mut acc = Scalar::ONE;
+    for _ in 0..n {
+        out.push(acc);
+        acc = acc.mul(x);
+    }
+    out
+}
+
+// パラメータが不正なら理由を返す。証明側は panic、検証側は false にする
+fn param_error(gens: &BulletproofGens, n: usize, m: usize) -> Option<String> {
+    // 2^n が位数 N (61 ビット) を超えないよう n は 32 まで
+    if !n.is_power_of_two() || n > 32 {
+        return Some(format!(
+            "Range bit size must be a power of two up to 32, got {}",
+            n
+        ));
+    }
+    if m == 0 || !m.is_power_of_two() {
+        return Some("Number of aggregated values must be a power of two".to_string());
+    }
+    if gens.g.len() < n * m {
+        return Some(format!(
+            "Bulletproof generators too small for {} bits",
+            n * m
+        ));
+    }
+    None
+}
+
+fn check_params(gens: &BulletproofGens, n: usize, m: usize) {
+    if let Some(error) = param_error(gens, n, m) {
+        panic!("{}", error);
+    }
+}
+
+fn start(transcript: &mut Transcript, n: usize, commitments: &[PedersenCommitment]) {
+    transcript.append_message(b"dom-sep", b"rangeproof");
+    transcript.append_u64(b"n", n as u64);
+    transcript.append_u64(b"m", commitments.len() as u64);
+    for v in commitments {
+        transcript.append_point(b"V", v.as_point());
+    }
+}
+
+// d_i = z^{2 + i/n} 2^{i mod n}
+fn z_two_terms(z: &Scalar, n: usize, m: usize) -> Vec<Scalar> {
+    let twos = powers(&Scalar::new(2), n);
+    let zs = powers(z, m + 2);
+    (0..n * m)
+        .map(|i| zs[2 + i / n].mul(&twos[i % n]))
+        .collect()
+}
+
+impl RangeProof {
+    pub fn prove_single<R: RngCore>(
+        gens: &BulletproofGens,
+        transcript: &mut Transcript,
+        value: u64,
+        blinding: &Scalar,
+        n: usize,
+        rng: &mut R,
+    ) -> (RangeProof, PedersenCommitment) {
+        let (proof, mut commitments) =
+            RangeProof::prove_multiple(gens, transcript, &[value], &[*blinding], n, rng);
+        (proof, commitments.pop().unwrap())
+    }
+
+    pub fn prove_multiple<R: RngCore>(
+        gens: &BulletproofGens,
+        transcript: &mut Transcript,
+        values: &[u64],
+        blindings: &[Scalar],
+        n: usize,
+        rng: &mut R,
+    ) -> (RangeProof, Vec<PedersenCommitment>) {
+        let m = values.len();
+        check_params(gens, n, m);
+        if blindings.len() != m {
+            panic!("Need one blinding per value");
+        }
+        if values.iter().any(|&v| n < 64 && v >> n != 0) {
+            panic!("Value out of range for {} bits", n);
+        }
+        let (g, h) = (pedersen_g(), pedersen_h());
+        let (gv, hv) = (&gens.g[..n * m], &gens.h[..n * m]);
+        let commitments: Vec<PedersenCommitment> = values
+            .iter()
+            .zip(blindings)
+            .map(|(&v, b)| PedersenCommitment::commit(&Scalar::new(v), b))
+            .collect();
+        start(transcript, n, &commitments);
+
+        let a_l: Vec<Scalar> = (0..n * m)
+            .map(|i| Scalar::new((values[i / n] >> (i % n)) & 1))
+            .collect();
+        let a_r: Vec<Scalar> = a_l.iter().map(|b| b.sub(&Scalar::ONE)).collect();
+        let alpha = Scalar::random(rng);
+        let a = msm(
+            &[&[alpha][..], &a_l, &a_r].concat(),
+            &[&[h.clone()][..], gv, hv].concat(),
+        );
+        let s_l: Vec<Scalar> = (0..n * m).map(|_| Scalar::random(rng)).collect();
+        let s_r: Vec<Scalar> = (0..n * m).map(|_| Scalar::random(rng)).collect();
+        let rho = Scalar::random(rng);
+        let s = msm(
+            &[&[rho][..], &s_l, &s_r].concat(),
+            &[&[h.clone()][..], gv, hv].concat(),
+        );
+        transcript.append_point(b"A", &a);
+        transcript.append_point(b"S", &s);
+        let y = transcript.challenge_scalar(b"y");
+        let z = transcript.challenge_scalar(b"z");
+
+        // l(X) = (a_L - z) + s_L X, r(X) = y^nm ∘ (a_R + z + s_R X) + d
+        let yp = powers(&y, n * m);
+        let d = z_two_terms(&z, n, m);
+        let l0: Vec<Scalar> = a_l.iter().map(|x| x.sub(&z)).collect();
+        let r0: Vec<Scalar> = (0..n * m)
+            .map(|i| yp[i].mul(&a_r[i].add(&z)).add(&d[i]))
+            .collect();
+        let r1: Vec<Scalar> = (0..n * m).map(|i| yp[i].mul(&s_r[i])).collect();
+        let t1 = inner_product(&l0, &r1).add(&inner_product(&s_l, &r0));
+        let t2 = inner_product(&s_l, &r1);
+        let (tau1, tau2) = (Scalar::random(rng), Scalar::random(rng));
+        let t1_point = msm(&[t1, tau1], &[g.clone(), h.clone()]);
+        let t2_point = msm(&[t2, tau2], &[g.clone(), h.clone()]);
+        transcript.append_point(b"T1", &t1_point);
+        transcript.append_point(b"T2", &t2_point);
+        let x = transcript.challenge_scalar(b"x");
+
+        let l: Vec<Scalar> = (0..n * m).map(|i| l0[i].add(&s_l[i].mul(&x))).collect();
+        let r: Vec<Scalar> = (0..n * m).map(|i| r0[i].add(&r1[i].mul(&x))).collect();
+        let t_hat = inner_product(&l, &r);
+        let zs = powers(&z, m + 2);
+        let tau_x = blindings
+            .iter()
+            .enumerate()
+            .fold(tau2.mul(&x).mul(&x).add(&tau1.mul(&x)), |acc, (j, b)| {
+                acc.add(&zs[2 + j].mul(b))
+            });
+        let mu = alpha.add(&rho.mul(&x));
+        transcript.append_scalar(b"tau_x", &tau_x);
+        transcript.append_scalar(b"mu", &mu);
+        transcript.append_scalar(b"t_hat", &t_hat);
+        let w = transcript.challenge_scalar(b"w");
+
+        let q = gens.q.scalar_mul(w.value());
+        let h_prime = h_prime(hv, &y);
+        let ipp = InnerProductProof::prove(transcript, &q, gv.to_vec(), h_prime, l, r);
+        let proof = RangeProof {
+            a,
+            s,
+            t1: t1_point,
+            t2: t2_point,
+            tau_x,
+            mu,
+            t_hat,
+            ipp,
+        };
+        (proof, commitments)
+    }
+
+    pub fn verify_single(
+        &self,
+        gens: &BulletproofGens,
+        transcript: &mut Transcript,
+        commitment: &PedersenCommitment,
+        n: usize,
+    ) -> bool {
+        self.verify_multiple(gens, transcript, std::slice::from_ref(commitment), n)
+    }
+
+    pub fn verify_multiple(
+        &self,
+        gens: &BulletproofGens,
+        transcript: &mut Transcript,
+        commitments: &[PedersenCommitment],
+        n: usize,
+    ) -> bool {
+        let m = commitments.len();
+        if param_error(gens, n, m).is_some() {
+            return false;
+        }
+        let (g, h) = (pedersen_g(), pedersen_h());
+        let (gv, hv) = (&gens.g[..n * m], &gens.h[..n * m]);
+        start(transcript, n, commitments);
+        transcript.append_point(b"A", &self.a);
+        transcript.append_point(b"S", &self.s);
+        let y = transcript.challenge_scalar(b"y");
+        let z = transcript.challenge_scalar(b"z");
+        transcript.append_point(b"T1", &self.t1);
+        transcript.append_point(b"T2", &self.t2);
+        let x = transcript.challenge_scalar(b"x");
+        transcript.append_scalar(b"tau_x", &self.tau_x);
+        transcript.append_scalar(b"mu", &self.mu);
+        transcript.append_scalar(b"t_hat", &self.t_hat);
+        let w = transcript.challenge_scalar(b"w");
+
+        // t̂G + τ_x H = Σ z^{2+j} V_j + δ(y, z) G + x T1 + x^2 T2
+        let yp = powers(&y, n * m);
+        let zs = powers(&z, m + 3);
+        let sum_y = yp.iter().fold(Scalar::ZERO, |acc, v| acc.add(v));
+        let sum_two = Scalar::new((1u64 << n) - 1);
+        let delta = (0..m).fold(z.sub(&zs[2]).mul(&sum_y), |acc, j| {
+            acc.sub(&zs[3 + j].mul(&sum_two))
+        });
+        let lhs = msm(&[self.t_hat, self.tau_x], &[g.clone(), h.clone()]);
+        let mut scalars = vec![delta, x, x.mul(&x)];
+        let mut points = vec![g, self.t1.clone(), self.t2.clone()];
+        for (j, v) in commitments.iter().enumerate() {
+            scalars.push(zs[2 + j]);
+            points.push(v.as_point().clone());
+        }
+        if lhs != msm(&scalars, &points) {
+            return false;
+        }
+
+        // P = A + xS - z<1, G> + <z y^nm + d, H'> - μH に t̂ Q を足して内積引数へ
+        let q = gens.q.scalar_mul(w.value());
+        let h_prime = h_prime(hv, &y);
+        let d = z_two_terms(&z, n, m);
+        let neg_z = z.neg();
+        let mut scalars = vec![Scalar::ONE, x, self.mu.neg(), self.t_hat];
+        let mut points = vec![self.a.clone(), self.s.clone(), h, q.clone()];
+        scalars.extend(std::iter::repeat_n(neg_z, n * m));
+        points.extend_from_slice(gv);
+        scalars.extend((0..n * m).map(|i| z.mul(&yp[i]).add(&d[i])));
+        points.extend(h_prime.iter().cloned());
+        let p = msm(&scalars, &points);
+        self.ipp.verify(transcript, &q, gv.to_vec(), h_prime, &p)
+    }
+}
+
+// H'_i = y^{-i} H_i
+fn h_prime(hv: &[Point], y: &Scalar) -> Vec<Point> {
+    let y_inv = y.inv().expect("zero range proof challenge");
+    hv.iter()
+        .zip(powers(&y_inv, hv.len()))
+        .map(|(p, s)| p.scalar_mul(s.value()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::TestRng;
+
+    #[test]
+    fn test_single_range_proof() {
+        let gens = BulletproofGens::new(32);
+        let mut rng = TestRng::from_label(b"bulletproofs");
+        let blinding = Scalar::random(&mut rng);
+        let (proof, v) = RangeProof::prove_single(
+            &gens,
+            &mut Transcript::new(b"rp"),
+            1_000_000,
+            &blinding,
+            32,
+            &mut rng,
+        );
+        assert!(v.verify(&Scalar::new(1_000_000), &blinding));
+        assert!(proof.verify_single(&gens, &mut Transcript::new(b"rp"), &v, 32));
+        assert_eq!(proof.ipp.l_vec.len(), 5);
+        // 別のコミットメントや改変した証明は通らない
+        let other = PedersenCommitment::commit(&Scalar::new(1_000_001), &blinding);
+        assert!(!proof.verify_single(&gens, &mut Transcript::new(b"rp"), &other, 32));
+        let mut bad = proof.clone();
+        bad.t_hat = bad.t_hat.add(&Scalar::ONE);
+        assert!(!bad.verify_single(&gens, &mut Transcript::new(b"rp"), &v, 32));
+        // 検証側では不正なパラメータも panic せず false
+        assert!(!proof.verify_single(&gens, &mut Transcript::new(b"rp"), &v, 64));
+        assert!(!proof.verify_single(&gens, &mut Transcript::new(b"rp"), &v, 24));
+        assert!(!proof.verify_multiple(&gens, &mut Transcript::new(b"rp"), &[], 32));
+        let small = BulletproofGens::new(16);
+        assert!(!proof.verify_single(&small, &mut Transcript::new(b"rp"), &v, 32));
+    }
+
+    #[test]
+    #[should_panic(expected = "out of range")]
+    fn test_out_of_range_value_panics() {
+        // 2^8 は 8 ビットに収まらないので証明自体を作れない
+        let gens = BulletproofGens::new(8);
+        let mut rng = TestRng::from_label(b"bulletproofs-bad");
+        let blinding = Scalar::random(&mut rng);
+        RangeProof::prove_single(
+            &gens,
+            &mut Transcript::new(b"rp"),
+            256,
+            &blinding,
+            8,
+            &mut rng,
+        );
+    }
+
+    #[test]
+    fn test_aggregated_range_proof() {
+        let gens = BulletproofGens::new(64);
+        let mut rng = TestRng::from_label(b"bulletproofs-agg");
+        let values = [7, 65535, 0, 12345];
+        let blindings: Vec<Scalar> = (0..4).map(|_| Scalar::random(&mut rng)).collect();
+        let (proof, commitments) = RangeProof::prove_multiple(
+            &gens,
+            &mut Transcript::new(b"agg"),
+            &values,
+            &blindings,
+            16,
+            &mut rng,
+        );
+        assert!(proof.verify_multiple(&gens, &mut Transcript::new(b"agg"), &commitments, 16));
+        // 4 つの値でも内積引数の点は log2(64) = 6 組
+        assert_eq!(proof.ipp.l_vec.len(), 6);
+        let mut swapped = commitments.clone();
+        swapped.swap(0, 1);
+        assert!(!proof.verify_multiple(&gens, &mut Transcript::new(b"agg"), &swapped, 16));
+    }
+}
@@ -0,0 +1,298 @@
+// Blum のコイン投げ。2 者が電話越しに公平なコインを投げる最小のプロトコル:
+//   1. 発起側 (A) はビット a にコミットして送る
+//   2. 応答側 (B) はビット b をそのまま送る
+//   3. A が a を開示し、結果は a ⊕ b
+// コミットメントの秘匿性で B は a を知らずに b を選び、束縛性で A は b を見てから a を変えられない。
+// 残る不公平は A が結果を見て開示をやめることだけなので、期限までに正しい開示が届かなければ
+// A の棄権 (B の勝ち) として扱う。A にとっては B の応答が来なくても失うものはなく、単に打ち切ればよい
+use crate::commitment::{commit_random, Commitment, Opening};
+use crate::hash::Sha256;
+use crate::rand::RngCore;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Message {
+    Commit {
+        session: [u8; 16],
+        commitment: Commitment,
+    },
+    Respond {
+        session: [u8; 16],
+        bit: bool,
+    },
+    Reveal {
+        session: [u8; 16],
+        opening: Opening,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CoinFlipError {
+    // 今の状態では受け取れない種類のメッセージ
+    UnexpectedMessage,
+    WrongSession,
+    InvalidOpening,
+    Timeout,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Coin(bool),
+    // A が期限までに正しく開示しなかった
+    InitiatorAborted,
+}
+
+// 開示するのはセッション ID とビットの組。別のセッションのコミットメントを流用させない
+fn committed_value(session: &[u8; 16], bit: bool) -> Vec<u8> {
+    let mut value = session.to_vec();
+    value.push(bit as u8);
+    value
+}
+
+fn opened_bit(session: &[u8; 16], commitment: &Commitment, opening: &Opening) -> Option<bool> {
+    let value = commitment.open::<Sha256>(opening)?;
+    match value.split_last() {
+        Some((&bit @ (0 | 1), prefix)) if prefix == session => Some(bit == 1),
+        _ => None,
+    }
+}
+
+pub struct Initiator {
+    session: [u8; 16],
+    bit: bool,
+    opening: Opening,
+}
+
+impl Initiator {
+    pub fn new<R: RngCore>(rng: &mut R) -> (Self, Message) {
+        let mut session = [0u8; 16];
+        rng.fill_bytes(&mut session);
+        let bit = rng.next_u64() & 1 == 1;
+        let (commitment, opening) =
+            commit_random::<Sha256, R>(&committed_value(&session, bit), rng);
+        let message = Message::Commit {
+            session,
+            commitment,
+        };
+        (
+            Initiator {
+                session,
+                bit,
+                opening,
+            },
+            message,
+        )
+    }
+
+    // B のビットを受け取り、開示メッセージとコインの値を返す
+    pub fn receive_response(self, message: &Message) -> Result<(Message, bool), CoinFlipError> {
+        let Message::Respond { session, bit } = message else {
+            return Err(CoinFlipError::UnexpectedMessage);
+        };
+        if *session != self.session {
+            return Err(CoinFlipError::WrongSession);
+        }
+        let reveal = Message::Reveal {
+            session: self.session,
+            opening: self.opening,
+        };
+        Ok((reveal, self.bit ^ bit))
+    }
+}
+
+// 時刻は呼び出し側が与える論理時刻 (秒でもラウンド番号でもよい)
+pub struct Responder {
+    timeout: u64,
+    state: ResponderState,
+}
+
+enum ResponderState {
+    AwaitingCommit,
+    AwaitingReveal {
+        session: [u8; 16],
+        commitment: Commitment,
+        bit: bool,
+        deadline: u64,
+    },
+    Done(Outcome),
+}
+
+impl Responder {
+    pub fn new(timeout: u64) -> Self {
+        Responder {
+            timeout,
+            state: ResponderState::AwaitingCommit,
+        }
+    }
+
+    pub fn receive_commit<R: RngCore>(
+        &mut self,
+        message: &Message,
+        now: u64,
+        rng: &mut R,
+    ) -> Result<Message, CoinFlipError> {
+        let (
+            ResponderState::AwaitingCommit,
+            Message::Commit {
+                session,
+                commitment,
+            },
+        ) = (&self.state, message)
+        else {
+            return Err(CoinFlipError::UnexpectedMessage);
+        };
+        let bit = rng.next_u64() & 1 == 1;
+        self.state = ResponderState::AwaitingReveal {
+            session: *session,
+            commitment: commitment.clone(),
+            bit,
+            deadline: now.saturating_add(self.timeout),
+        };
+        Ok(Message::Respond {
+            session: *session,
+            bit,
+        })
+    }
+
+    // 不正な開示や期限切れは A の棄権として確定させる
+    pub fn receive_reveal(&mut self, message: &Message, now: u64) -> Result<bool, CoinFlipError> {
+        let ResponderState::AwaitingReveal {
+            session,
+            commitment,
+            bit,
+            deadline,
+        } = &self.state
+        else {
+            return Err(CoinFlipError::UnexpectedMessage);
+        };
+        let Message::Reveal {
+            session: received,
+            opening,
+        } = message
+        else {
+            return Err(CoinFlipError::UnexpectedMessage);
+        };
+        if received != session {
+            return Err(CoinFlipError::WrongSession);
+        }
+        if now > *deadline {
+            self.state = ResponderState::Done(Outcome::InitiatorAborted);
+            return Err(CoinFlipError::Timeout);
+        }
+        match opened_bit(session, commitment, opening) {
+            Some(a) => {
+                let coin = a ^ bit;
+                self.state = ResponderState::Done(Outcome::Coin(coin));
+                Ok(coin)
+            }
+            None => {
+                self.state = ResponderState::Done(Outcome::InitiatorAborted);
+                Err(CoinFlipError::InvalidOpening)
+            }
+        }
+    }
+
+    // 開示を待つ間に定期的に呼ぶ。期限を過ぎていれば棄権を確定する
+    pub fn poll(&mut self, now: u64) -> Option<Outcome> {
+        if let ResponderState::AwaitingReveal { deadline, .. } = self.state {
+            if now > deadline {
+                self.state = ResponderState::Done(Outcome::InitiatorAborted);
+            }
+        }
+        self.outcome()
+    }
+
+    pub fn outcome(&self) -> Option<Outcome> {
+        match self.state {
+            ResponderState::Done(outcome) => Some(outcome),
+            _ => None,
+        }
+    }
+}
+
+// 3 つのメッセージから第三者がコインの値を再計算する
+pub fn verify_transcript(messages: &[Message]) -> Result<bool, CoinFlipError> {
+    let [Message::Commit {
+        session,
+        commitment,
+    }, Message::Respond { session: s2, bit }, Message::Reveal {
+        session: s3,
+        opening,
+    }] = messages
+    else {
+        return Err(CoinFlipError::UnexpectedMessage);
+    };
+    if s2 != session || s3 != session {
+        return Err(CoinFlipError::WrongSession);
+    }
+    let a = opened_bit(session, commitment, opening).ok_or(CoinFlipError::InvalidOpening)?;
+    Ok(a ^ bit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::TestRng;
+
+    #[test]
+    fn test_honest_flips_agree() {
+        let mut rng = TestRng::from_label(b"coinflip");
+        let mut heads = 0;
+        for _ in 0..16 {
+            let (alice, commit) = Initiator::new(&mut rng);
+            let mut bob = Responder::new(10);
+            let respond = bob.receive_commit(&commit, 0, &mut rng).unwrap();
+            let (reveal, alice_coin) = alice.receive_response(&respond).unwrap();
+            let bob_coin = bob.receive_reveal(&reveal, 5).unwrap();
+            assert_eq!(alice_coin, bob_coin);
+            assert_eq!(bob.outcome(), Some(Outcome::Coin(bob_coin)));
+            assert_eq!(verify_transcript(&[commit, respond, reveal]), Ok(bob_coin));
+            heads += bob_coin as usize;
+        }
+        // 偏りがないことの粗い確認
+        assert!((3..=13).contains(&heads));
+    }
+
+    #[test]
+    fn test_abort_and_cheating() {
+        let mut rng = TestRng::from_label(b"coinflip-abort");
+        let (alice, commit) = Initiator::new(&mut rng);
+        let mut bob = Responder::new(10);
+        let respond = bob.receive_commit(&commit, 100, &mut rng).unwrap();
+        assert_eq!(bob.poll(110), None);
+        assert_eq!(bob.poll(111), Some(Outcome::InitiatorAborted));
+        // 期限後に届いた開示は受け付けない
+        let (reveal, _) = alice.receive_response(&respond).unwrap();
+        assert_eq!(
+            bob.receive_reveal(&reveal, 112),
+            Err(CoinFlipError::UnexpectedMessage)
+        );
+
+        // 開示を別のビットにすり替える
+        let (alice, commit) = Initiator::new(&mut rng);
+        let mut bob = Responder::new(10);
+        let respond = bob.receive_commit(&commit, 0, &mut rng).unwrap();
+        let (reveal, _) = alice.receive_response(&respond).unwrap();
+        let Message::Reveal {
+            session,
+            mut opening,
+        } = reveal
+        else {
+            unreachable!()
+        };
+        *opening.message.last_mut().unwrap() ^= 1;
+        let forged = Message::Reveal { session, opening };
+        assert_eq!(
+            bob.receive_reveal(&forged, 1),
+            Err(CoinFlipError::InvalidOpening)
+        );
+        assert_eq!(bob.outcome(), Some(Outcome::InitiatorAborted));
+        assert_eq!(
+            verify_transcript(&[commit.clone(), respond.clone(), forged]),
+            Err(CoinFlipError::InvalidOpening)
+        );
+        assert_eq!(
+            verify_transcript(&[respond, commit]),
+            Err(CoinFlipError::UnexpectedMessage)
+        );
+    }
+}
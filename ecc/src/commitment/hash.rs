@@ -0,0 +1,113 @@
+// ハッシュによるコミットメント C = H(tag || len(r) || r || m)。
+// 束縛性は衝突困難性から、秘匿性は十分長いランダムな r から来る
+use crate::hash::Digest;
+use crate::mac::ct_eq;
+use crate::rand::RngCore;
+
+const DOMAIN: &[u8] = b"fundamental-cryptography/hash-commitment";
+// 秘匿性のために要求する乱数の最小長
+pub const MIN_RANDOMNESS_LEN: usize = 16;
+const RANDOMNESS_LEN: usize = 32;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Commitment(Vec<u8>);
+
+// 公開時に相手へ渡す値
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Opening {
+    pub message: Vec<u8>,
+    pub randomness: Vec<u8>,
+}
+
+fn digest<D: Digest>(message: &[u8], randomness: &[u8]) -> Vec<u8> {
+    let mut h = D::new();
+    h.update(&(DOMAIN.len() as u64).to_be_bytes());
+    h.update(DOMAIN);
+    // r の長さを入れておかないと r と m の境界をずらした別の開示が作れる
+    h.update(&(randomness.len() as u64).to_be_bytes());
+    h.update(randomness);
+    h.update(message);
+    h.finalize()
+}
+
+pub fn commit<D: Digest>(message: &[u8], randomness: &[u8]) -> Commitment {
+    if randomness.len() < MIN_RANDOMNESS_LEN {
+        panic!(
+            "Commitment randomness must be at least {} bytes",
+            MIN_RANDOMNESS_LEN
+        );
+    }
+    Commitment(digest::<D>(message, randomness))
+}
+
+// 乱数を生成してコミットし、後で公開する値も返す
+pub fn commit_random<D: Digest, R: RngCore>(message: &[u8], rng: &mut R) -> (Commitment, Opening) {
+    let mut randomness = vec![0u8; RANDOMNESS_LEN];
+    rng.fill_bytes(&mut randomness);
+    let commitment = commit::<D>(message, &randomness);
+    let opening = Opening {
+        message: message.to_vec(),
+        randomness,
+    };
+    (commitment, opening)
+}
+
+impl Commitment {
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        Commitment(bytes.to_vec())
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn verify<D: Digest>(&self, message: &[u8], randomness: &[u8]) -> bool {
+        randomness.len() >= MIN_RANDOMNESS_LEN && ct_eq(&self.0, &digest::<D>(message, randomness))
+    }
+
+    // 開示が正しければコミットされていたメッセージを返す
+    pub fn open<'a, D: Digest>(&self, opening: &'a Opening) -> Option<&'a [u8]> {
+        if self.verify::<D>(&opening.message, &opening.randomness) {
+            Some(&opening.message)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::{Sha256, Sha3_256};
+    use crate::rand::OsRng;
+
+    #[test]
+    fn test_commit_and_open() {
+        let (c, opening) = commit_random::<Sha256, _>(b"bid: 42", &mut OsRng);
+        assert_eq!(c.open::<Sha256>(&opening), Some(&b"bid: 42"[..]));
+        assert!(c.verify::<Sha256>(b"bid: 42", &opening.randomness));
+        assert!(!c.verify::<Sha256>(b"bid: 43", &opening.randomness));
+        // 別のハッシュでは開けない
+        assert_eq!(c.open::<Sha3_256>(&opening), None);
+        // 同じメッセージでも乱数が違えばコミットメントは異なる
+        let (c2, _) = commit_random::<Sha256, _>(b"bid: 42", &mut OsRng);
+        assert_ne!(c, c2);
+    }
+
+    #[test]
+    fn test_boundary_shift_is_rejected() {
+        let r = [7u8; 32];
+        let c = commit::<Sha256>(b"message", &r);
+        // r の末尾をメッセージ側へ移しても同じ連結列だが、長さ付きなので一致しない
+        let shifted_message = [&r[31..], &b"message"[..]].concat();
+        assert!(!c.verify::<Sha256>(&shifted_message, &r[..31]));
+        assert!(!c.verify::<Sha256>(b"message", &r[..8]));
+        assert_eq!(Commitment::from_bytes(c.as_bytes()), c);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_short_randomness_panics() {
+        commit::<Sha256>(b"m", &[0u8; 8]);
+    }
+}
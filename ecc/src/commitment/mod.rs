@@ -0,0 +1,7 @@
+pub mod hash;
+pub mod pedersen;
+pub mod vector;
+
+pub use hash::{commit, commit_random, Commitment, Opening};
+pub use pedersen::{pedersen_g, pedersen_h, PedersenCommitment};
+pub use vector::{MerkleVector, MerkleVectorCommitment, VectorCommitment};
@@ -0,0 +1,94 @@
+// Pedersen コミットメント C = vG + rH。H は hash-to-curve で導くので G に対する離散対数を誰も知らず、
+// 束縛性は離散対数問題から、秘匿性は r の一様性から (情報理論的に) 来る。加法準同型でもある
+use crate::curve::{self, Scalar};
+use crate::rand::RngCore;
+use crate::Point;
+
+const H_DOMAIN: &[u8] = b"fundamental-cryptography/pedersen-h";
+
+// 値を掛ける生成元
+pub fn pedersen_g() -> Point {
+    curve::generator()
+}
+
+// ブラインドを掛ける生成元
+pub fn pedersen_h() -> Point {
+    curve::hash_to_curve(H_DOMAIN)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PedersenCommitment(Point);
+
+impl PedersenCommitment {
+    pub fn commit(value: &Scalar, blinding: &Scalar) -> Self {
+        PedersenCommitment(
+            pedersen_g()
+                .scalar_mul(value.value())
+                .add(&pedersen_h().scalar_mul(blinding.value())),
+        )
+    }
+
+    // ブラインドを一様に選んでコミットし、開示用にブラインドも返す
+    pub fn commit_random<R: RngCore>(value: &Scalar, rng: &mut R) -> (Self, Scalar) {
+        let blinding = Scalar::random(rng);
+        (PedersenCommitment::commit(value, &blinding), blinding)
+    }
+
+    pub fn from_point(point: Point) -> Self {
+        PedersenCommitment(point)
+    }
+
+    pub fn as_point(&self) -> &Point {
+        &self.0
+    }
+
+    // C(v1, r1) + C(v2, r2) = C(v1 + v2, r1 + r2)
+    pub fn add(&self, other: &PedersenCommitment) -> PedersenCommitment {
+        PedersenCommitment(self.0.add(&other.0))
+    }
+
+    pub fn sub(&self, other: &PedersenCommitment) -> PedersenCommitment {
+        PedersenCommitment(self.0.add(&other.0.neg()))
+    }
+
+    pub fn verify(&self, value: &Scalar, blinding: &Scalar) -> bool {
+        *self == PedersenCommitment::commit(value, blinding)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::TestRng;
+
+    #[test]
+    fn test_open_and_hide() {
+        let mut rng = TestRng::from_label(b"pedersen");
+        let v = Scalar::new(42);
+        let (c, r) = PedersenCommitment::commit_random(&v, &mut rng);
+        assert!(c.verify(&v, &r));
+        assert!(!c.verify(&Scalar::new(43), &r));
+        // 同じ値でもブラインドが違えば見分けがつかない
+        let (c2, r2) = PedersenCommitment::commit_random(&v, &mut rng);
+        assert_ne!(c, c2);
+        assert!(c2.verify(&v, &r2));
+        assert_ne!(pedersen_g(), pedersen_h());
+    }
+
+    #[test]
+    fn test_homomorphic_addition() {
+        let mut rng = TestRng::from_label(b"pedersen-sum");
+        let (a, b) = (Scalar::new(100), Scalar::new(250));
+        let (ca, ra) = PedersenCommitment::commit_random(&a, &mut rng);
+        let (cb, rb) = PedersenCommitment::commit_random(&b, &mut rng);
+        // 入力の和と出力が等しいことを、値を明かさずに確かめられる
+        let sum = ca.add(&cb);
+        assert!(sum.verify(&Scalar::new(350), &ra.add(&rb)));
+        assert!(sum.sub(&cb).verify(&a, &ra));
+        // 位数を超える和も mod N で折り返す
+        let big = Scalar::new(curve::N - 1);
+        let c = PedersenCommitment::commit(&big, &Scalar::ONE)
+            .add(&PedersenCommitment::commit(&Scalar::new(2), &Scalar::ONE));
+        assert!(c.verify(&Scalar::ONE, &Scalar::new(2)));
+    }
+}
@@ -0,0 +1,186 @@
+// ベクトルコミットメント: 順序付きの値の列に短くコミットし、i 番目の値だけを開示できる。
+// 位置の束縛 (i 番目として別の値を開けない) が要点で、ここでは Merkle 木で実装する。
+// KZG 版などを後から差し込めるよう操作はトレイトにまとめてある
+use crate::hash::Digest;
+use crate::merkle::{MerkleProof, MerkleTree, ProofNode};
+
+pub trait VectorCommitment: Sized {
+    type Commitment: Clone + PartialEq;
+    type Opening: Clone;
+
+    fn commit(values: &[Vec<u8>]) -> Self;
+
+    fn commitment(&self) -> Self::Commitment;
+
+    fn open(&self, index: usize) -> Self::Opening;
+
+    fn verify(
+        commitment: &Self::Commitment,
+        index: usize,
+        value: &[u8],
+        opening: &Self::Opening,
+    ) -> bool;
+
+    // 値を差し替え、新しいコミットメントとその位置の開示を返す
+    fn update(&mut self, index: usize, value: &[u8]) -> (Self::Commitment, Self::Opening);
+
+    // ベクトル全体を持たない側が、古い値の開示だけから新しいコミットメントを計算する。
+    // 開示が正しくなければ None
+    fn update_commitment(
+        commitment: &Self::Commitment,
+        index: usize,
+        old_value: &[u8],
+        new_value: &[u8],
+        opening: &Self::Opening,
+    ) -> Option<Self::Commitment>;
+}
+
+// 根と長さの組。長さがないと経路の形から位置を確かめられない
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerkleVectorCommitment {
+    pub root: Vec<u8>,
+    pub len: usize,
+}
+
+#[derive(Clone)]
+pub struct MerkleVector<D: Digest> {
+    values: Vec<Vec<u8>>,
+    tree: MerkleTree<D>,
+}
+
+// 長さ len の木で index の葉から根へ向かうとき、兄弟が左右どちらにあるかの列。
+// 奇数個の段で相方のいないノードはそのまま上がるので兄弟もない
+fn expected_sides(index: usize, len: usize) -> Vec<bool> {
+    let (mut i, mut width) = (index, len);
+    let mut sides = Vec::new();
+    while width > 1 {
+        let sibling = i ^ 1;
+        if sibling < width {
+            sides.push(sibling < i);
+        }
+        i /= 2;
+        width = width.div_ceil(2);
+    }
+    sides
+}
+
+fn is_well_formed(commitment: &MerkleVectorCommitment, index: usize, proof: &MerkleProof) -> bool {
+    index < commitment.len
+        && proof.index == index
+        && proof
+            .path
+            .iter()
+            .map(|node| matches!(node, ProofNode::Left(_)))
+            .eq(expected_sides(index, commitment.len))
+}
+
+impl<D: Digest> VectorCommitment for MerkleVector<D> {
+    type Commitment = MerkleVectorCommitment;
+    type Opening = MerkleProof;
+
+    fn commit(values: &[Vec<u8>]) -> Self {
+        MerkleVector {
+            values: values.to_vec(),
+            tree: MerkleTree::from_leaves(values),
+        }
+    }
+
+    fn commitment(&self) -> MerkleVectorCommitment {
+        MerkleVectorCommitment {
+            root: self.tree.root().to_vec(),
+            len: self.values.len(),
+        }
+    }
+
+    fn open(&self, index: usize) -> MerkleProof {
+        self.tree.prove(index)
+    }
+
+    fn verify(
+        commitment: &MerkleVectorCommitment,
+        index: usize,
+        value: &[u8],
+        opening: &MerkleProof,
+    ) -> bool {
+        is_well_formed(commitment, index, opening) && opening.verify::<D>(&commitment.root, value)
+    }
+
+    fn update(&mut self, index: usize, value: &[u8]) -> (MerkleVectorCommitment, MerkleProof) {
+        if index >= self.values.len() {
+            panic!(
+                "Index {} out of range for {} values",
+                index,
+                self.values.len()
+            );
+        }
+        self.values[index] = value.to_vec();
+        self.tree = MerkleTree::from_leaves(&self.values);
+        (self.commitment(), self.open(index))
+    }
+
+    // 兄弟ノードは変わらないので、同じ経路で新しい葉から根を計算し直せばよい
+    fn update_commitment(
+        commitment: &MerkleVectorCommitment,
+        index: usize,
+        old_value: &[u8],
+        new_value: &[u8],
+        opening: &MerkleProof,
+    ) -> Option<MerkleVectorCommitment> {
+        MerkleVector::<D>::verify(commitment, index, old_value, opening).then(|| {
+            MerkleVectorCommitment {
+                root: opening.root_from_leaf::<D>(new_value),
+                len: commitment.len,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::Sha256;
+
+    type Vc = MerkleVector<Sha256>;
+
+    fn values(n: usize) -> Vec<Vec<u8>> {
+        (0..n)
+            .map(|i| format!("value {}", i).into_bytes())
+            .collect()
+    }
+
+    #[test]
+    fn test_open_is_position_binding() {
+        let vc = Vc::commit(&values(5));
+        let c = vc.commitment();
+        for i in 0..5 {
+            assert!(Vc::verify(&c, i, &values(5)[i], &vc.open(i)));
+        }
+        // 正しい開示でも別の位置としては通らない
+        let proof = vc.open(2);
+        assert!(!Vc::verify(&c, 3, &values(5)[2], &proof));
+        let mut moved = proof.clone();
+        moved.index = 3;
+        assert!(!Vc::verify(&c, 3, &values(5)[2], &moved));
+        assert!(!Vc::verify(&c, 2, b"other", &proof));
+        // 相方のいない 5 番目の葉は 1 段目の兄弟を持たない
+        assert_eq!(expected_sides(4, 5), vec![true]);
+    }
+
+    #[test]
+    fn test_update_with_proof() {
+        let mut vc = Vc::commit(&values(6));
+        let old = vc.commitment();
+        let opening = vc.open(4);
+        // 値を持たない側は古い開示から新しいコミットメントを計算できる
+        let derived = Vc::update_commitment(&old, 4, b"value 4", b"new", &opening).unwrap();
+        let (updated, new_opening) = vc.update(4, b"new");
+        assert_eq!(derived, updated);
+        assert_ne!(old, updated);
+        assert!(Vc::verify(&updated, 4, b"new", &new_opening));
+        assert!(!Vc::verify(&updated, 4, b"value 4", &new_opening));
+        assert_eq!(
+            Vc::update_commitment(&old, 4, b"wrong", b"new", &opening),
+            None
+        );
+    }
+}
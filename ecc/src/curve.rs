@@ -0,0 +1,252 @@
+// y^2 = x^3 + 3 over F_p, 位数 N は素数 (余因子 1)
+use crate::hash::sha256;
+use crate::number_theory::mod_pow;
+use crate::rand::{uniform_below, RngCore};
+use crate::{mod_inverse_ct, FieldElement, Point};
+
+pub const P: u64 = 2305843009213693123;
+pub const A: u64 = 0;
+pub const B: u64 = 3;
+pub const N: u64 = 2305843007258120689;
+pub const GX: u64 = 1;
+pub const GY: u64 = 2;
+
+pub fn identity() -> Point {
+    Point::new(None, None, FieldElement::new(A, P), FieldElement::new(B, P))
+}
+
+pub fn generator() -> Point {
+    point(GX, GY)
+}
+
+pub fn point(x: u64, y: u64) -> Point {
+    Point::new(
+        Some(FieldElement::new(x, P)),
+        Some(FieldElement::new(y, P)),
+        FieldElement::new(A, P),
+        FieldElement::new(B, P),
+    )
+}
+
+// P ≡ 3 mod 4 なので平方根は r^((P+1)/4)
+fn sqrt(r: &FieldElement) -> Option<FieldElement> {
+    let y = r.pow((P + 1) / 4);
+    if y.mul(&y) == *r {
+        Some(y)
+    } else {
+        None
+    }
+}
+
+fn wide_mod(bytes: &[u8], modulus: u64) -> u64 {
+    let mut buf = [0u8; 16];
+    buf.copy_from_slice(&bytes[..16]);
+    (u128::from_be_bytes(buf) % modulus as u128) as u64
+}
+
+// Point::to_bytes の逆。座標が範囲外か曲線上にない点は拒否する
+pub fn decode_point(bytes: &[u8]) -> Option<Point> {
+    match bytes {
+        [0x00] => Some(identity()),
+        [0x04, rest @ ..] if rest.len() == 16 => {
+            let x = u64::from_be_bytes(rest[..8].try_into().unwrap());
+            let y = u64::from_be_bytes(rest[8..].try_into().unwrap());
+            if x >= P || y >= P {
+                return None;
+            }
+            let (fx, fy) = (FieldElement::new(x, P), FieldElement::new(y, P));
+            let rhs = fx
+                .pow(3)
+                .add(&FieldElement::new(A, P).mul(&fx))
+                .add(&FieldElement::new(B, P));
+            (fy.pow(2) == rhs).then(|| point(x, y))
+        }
+        _ => None,
+    }
+}
+
+// 位数 N を法とする整数。値は常に [0, N) に正規化されている
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Scalar(u64);
+
+impl Scalar {
+    pub const ZERO: Scalar = Scalar(0);
+    pub const ONE: Scalar = Scalar(1);
+
+    pub fn new(value: u64) -> Self {
+        Scalar(value % N)
+    }
+
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+
+    // 棄却サンプリングによる [0, N) の一様乱数
+    pub fn random<R: RngCore>(rng: &mut R) -> Self {
+        Scalar(uniform_below(N, rng))
+    }
+
+    // 秘密鍵や nonce 用の [1, N) の一様乱数
+    pub fn random_nonzero<R: RngCore>(rng: &mut R) -> Self {
+        Scalar(uniform_below(N - 1, rng) + 1)
+    }
+
+    // N の 2 倍以上の幅 (128 ビット) を剰余で縮める。偏りは 2^-67 以下で無視できる
+    pub fn from_bytes_mod_order_wide(bytes: &[u8; 16]) -> Self {
+        Scalar(wide_mod(bytes, N))
+    }
+
+    pub fn to_bytes(&self) -> [u8; 8] {
+        self.0.to_be_bytes()
+    }
+
+    // N 以上の値は非正規な符号化として拒否する
+    pub fn from_bytes(bytes: &[u8; 8]) -> Option<Self> {
+        let v = u64::from_be_bytes(*bytes);
+        (v < N).then_some(Scalar(v))
+    }
+
+    pub fn add(&self, other: &Scalar) -> Scalar {
+        Scalar(((self.0 as u128 + other.0 as u128) % N as u128) as u64)
+    }
+
+    pub fn sub(&self, other: &Scalar) -> Scalar {
+        self.add(&other.neg())
+    }
+
+    pub fn mul(&self, other: &Scalar) -> Scalar {
+        Scalar(((self.0 as u128 * other.0 as u128) % N as u128) as u64)
+    }
+
+    pub fn neg(&self) -> Scalar {
+        Scalar((N - self.0) % N)
+    }
+
+    pub fn inv(&self) -> Option<Scalar> {
+        mod_inverse_ct(self.0, N).map(Scalar)
+    }
+
+    pub fn pow(&self, exponent: u64) -> Scalar {
+        Scalar(mod_pow(self.0, exponent, N))
+    }
+}
+
+// マルチスカラー倍算 Σ s_i P_i。Straus 法で 2 倍算を全項で共有する
+pub fn msm(scalars: &[Scalar], points: &[Point]) -> Point {
+    if scalars.len() != points.len() {
+        panic!("MSM needs one scalar per point");
+    }
+    let bits = 64 - N.leading_zeros();
+    let mut acc = identity();
+    for bit in (0..bits).rev() {
+        acc = acc.add(&acc);
+        for (s, p) in scalars.iter().zip(points) {
+            if (s.value() >> bit) & 1 == 1 {
+                acc = acc.add(p);
+            }
+        }
+    }
+    acc
+}
+
+pub fn hash_to_scalar(msg: &[u8]) -> u64 {
+    Scalar::from_bytes_mod_order_wide(&sha256(msg)[..16].try_into().unwrap()).value()
+}
+
+// try-and-increment による決定的な点の導出 (離散対数は誰にも分からない)
+pub fn hash_to_curve(msg: &[u8]) -> Point {
+    let mut counter: u32 = 0;
+    loop {
+        let mut input = msg.to_vec();
+        input.extend_from_slice(&counter.to_be_bytes());
+        let digest = sha256(&input);
+        let x = FieldElement::new(wide_mod(&digest, P), P);
+        let rhs = x
+            .pow(3)
+            .add(&FieldElement::new(A, P).mul(&x))
+            .add(&FieldElement::new(B, P));
+        if let Some(y) = sqrt(&rhs) {
+            // y の偶奇はハッシュの最終ビットで選ぶ
+            let y = if (y.num & 1) as u8 == digest[31] & 1 {
+                y
+            } else {
+                FieldElement::new(0, P).sub(&y)
+            };
+            return point(x.num, y.num);
+        }
+        counter += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::OsRng;
+
+    #[test]
+    fn test_generator_has_order_n() {
+        let g = generator();
+        assert!(g.scalar_mul(N).is_infinity());
+        assert_eq!(g.scalar_mul(N + 1), g);
+    }
+
+    #[test]
+    fn test_scalar_arithmetic_and_sampling() {
+        let a = Scalar::new(N + 5);
+        assert_eq!(a, Scalar::new(5));
+        let b = Scalar::random_nonzero(&mut OsRng);
+        assert_ne!(b, Scalar::ZERO);
+        assert_eq!(b.mul(&b.inv().unwrap()), Scalar::ONE);
+        assert_eq!(a.sub(&b).add(&b), a);
+        assert_eq!(b.add(&b.neg()), Scalar::ZERO);
+        assert_eq!(Scalar::ZERO.inv(), None);
+        // [x + y]G = [x]G + [y]G
+        let (x, y) = (Scalar::random(&mut OsRng), Scalar::random(&mut OsRng));
+        let g = generator();
+        assert_eq!(
+            g.scalar_mul(x.add(&y).value()),
+            g.scalar_mul(x.value()).add(&g.scalar_mul(y.value()))
+        );
+        let wide = [0xff; 16];
+        assert_eq!(
+            Scalar::from_bytes_mod_order_wide(&wide).value() as u128,
+            u128::MAX % N as u128
+        );
+    }
+
+    #[test]
+    fn test_msm_matches_naive_sum() {
+        let mut rng = OsRng;
+        let points: Vec<Point> = (0..5).map(|i| hash_to_curve(&[i as u8])).collect();
+        let scalars: Vec<Scalar> = (0..5).map(|_| Scalar::random(&mut rng)).collect();
+        let naive = scalars
+            .iter()
+            .zip(&points)
+            .fold(identity(), |acc, (s, p)| acc.add(&p.scalar_mul(s.value())));
+        assert_eq!(msm(&scalars, &points), naive);
+        assert_eq!(msm(&[], &[]), identity());
+    }
+
+    #[test]
+    fn test_point_and_scalar_encoding() {
+        let p = generator().scalar_mul(12345);
+        assert_eq!(decode_point(&p.to_bytes()), Some(p.clone()));
+        assert_eq!(decode_point(&identity().to_bytes()), Some(identity()));
+        let mut bad = p.to_bytes();
+        bad[16] ^= 1;
+        assert_eq!(decode_point(&bad), None);
+        assert_eq!(decode_point(&[0x04; 3]), None);
+        let s = Scalar::new(987654321);
+        assert_eq!(Scalar::from_bytes(&s.to_bytes()), Some(s));
+        assert_eq!(Scalar::from_bytes(&N.to_be_bytes()), None);
+    }
+
+    #[test]
+    fn test_hash_to_curve_is_deterministic() {
+        let p1 = hash_to_curve(b"test");
+        let p2 = hash_to_curve(b"test");
+        assert_eq!(p1, p2);
+        assert_ne!(p1, hash_to_curve(b"other"));
+        assert!(p1.scalar_mul(N).is_infinity());
+    }
+}
@@ -0,0 +1,110 @@
+// 楕円曲線 ElGamal 暗号。公開鍵 Y = xG に対し、点 M を (rG, M + rY) と暗号化する。
+// 準同型なので、同じ公開鍵で 0 を暗号化したもの (sG, sY) を足せば、平文を変えずに
+// 暗号文だけを新しく作り直せる (再乱数化)。元の暗号文との対応は x を知らないと分からない
+use crate::curve::{self, Scalar};
+use crate::rand::RngCore;
+use crate::Point;
+
+pub struct ElGamalSecretKey(Scalar);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElGamalPublicKey(pub Point);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ciphertext {
+    pub c1: Point,
+    pub c2: Point,
+}
+
+pub fn keygen<R: RngCore>(rng: &mut R) -> (ElGamalSecretKey, ElGamalPublicKey) {
+    let x = Scalar::random_nonzero(rng);
+    let public = ElGamalPublicKey(curve::generator().scalar_mul(x.value()));
+    (ElGamalSecretKey(x), public)
+}
+
+impl ElGamalSecretKey {
+    pub fn public_key(&self) -> ElGamalPublicKey {
+        ElGamalPublicKey(curve::generator().scalar_mul(self.0.value()))
+    }
+
+    // M = c2 - x c1
+    pub fn decrypt(&self, ciphertext: &Ciphertext) -> Point {
+        ciphertext
+            .c2
+            .add(&ciphertext.c1.scalar_mul(self.0.value()).neg())
+    }
+}
+
+impl ElGamalPublicKey {
+    pub fn encrypt<R: RngCore>(&self, message: &Point, rng: &mut R) -> Ciphertext {
+        self.encrypt_with(message, &Scalar::random(rng))
+    }
+
+    pub fn encrypt_with(&self, message: &Point, r: &Scalar) -> Ciphertext {
+        Ciphertext {
+            c1: curve::generator().scalar_mul(r.value()),
+            c2: message.add(&self.0.scalar_mul(r.value())),
+        }
+    }
+
+    // 乱数 s で作り直す。s は再暗号化の正しさを証明するときに使う
+    pub fn rerandomize_with(&self, ciphertext: &Ciphertext, s: &Scalar) -> Ciphertext {
+        let zero = self.encrypt_with(&curve::identity(), s);
+        Ciphertext {
+            c1: ciphertext.c1.add(&zero.c1),
+            c2: ciphertext.c2.add(&zero.c2),
+        }
+    }
+
+    pub fn rerandomize<R: RngCore>(&self, ciphertext: &Ciphertext, rng: &mut R) -> Ciphertext {
+        self.rerandomize_with(ciphertext, &Scalar::random(rng))
+    }
+}
+
+// 小さな整数 v を vG として暗号化する (指数 ElGamal)。復号後は総当たりで v を戻す
+pub fn encode_small(value: u64) -> Point {
+    curve::generator().scalar_mul(value)
+}
+
+pub fn decode_small(point: &Point, max: u64) -> Option<u64> {
+    let g = curve::generator();
+    let mut acc = curve::identity();
+    for v in 0..=max {
+        if acc == *point {
+            return Some(v);
+        }
+        acc = acc.add(&g);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::TestRng;
+
+    #[test]
+    fn test_encrypt_decrypt_and_rerandomize() {
+        let mut rng = TestRng::from_label(b"elgamal");
+        let (sk, pk) = keygen(&mut rng);
+        assert_eq!(sk.public_key(), pk);
+        let m = curve::hash_to_curve(b"message");
+        let ct = pk.encrypt(&m, &mut rng);
+        assert_eq!(sk.decrypt(&ct), m);
+        let fresh = pk.rerandomize(&ct, &mut rng);
+        assert_ne!(fresh, ct);
+        assert_eq!(sk.decrypt(&fresh), m);
+        // 別の鍵では復号できない
+        let (other, _) = keygen(&mut rng);
+        assert_ne!(other.decrypt(&ct), m);
+    }
+
+    #[test]
+    fn test_small_values() {
+        let mut rng = TestRng::from_label(b"elgamal-small");
+        let (sk, pk) = keygen(&mut rng);
+        let ct = pk.encrypt(&encode_small(17), &mut rng);
+        assert_eq!(decode_small(&sk.decrypt(&ct), 100), Some(17));
+        assert_eq!(decode_small(&sk.decrypt(&ct), 10), None);
+    }
+}
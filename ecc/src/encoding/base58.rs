@@ -0,0 +1,146 @@
+// Base58 (Bitcoin のアルファベット)。0, O, I, l を除いた 58 文字でバイト列を大きな整数として
+// 表し、先頭の 0x00 は 1 文字ずつ '1' にする。Base58Check は version || payload に
+// SHA-256d の先頭 4 バイトを付けてから符号化し、写し間違いを検出できるようにしたもの
+use crate::hash::sha256d;
+
+const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const CHECKSUM_LEN: usize = 4;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Base58Error {
+    // アルファベットにない文字とその位置
+    InvalidCharacter(char, usize),
+    // version とチェックサムを入れる長さがない
+    TooShort,
+    InvalidChecksum,
+}
+
+pub fn encode(data: &[u8]) -> String {
+    let zeros = data.iter().take_while(|&&b| b == 0).count();
+    // 58 進の桁を下位から持つ。バイトを 1 つずつ 256 倍して足し込む
+    let mut digits: Vec<u8> = Vec::with_capacity(data.len() * 138 / 100 + 1);
+    for &byte in &data[zeros..] {
+        let mut carry = byte as u32;
+        for d in digits.iter_mut() {
+            carry += (*d as u32) << 8;
+            *d = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+    std::iter::repeat_n(ALPHABET[0], zeros)
+        .chain(digits.iter().rev().map(|&d| ALPHABET[d as usize]))
+        .map(char::from)
+        .collect()
+}
+
+pub fn decode(s: &str) -> Result<Vec<u8>, Base58Error> {
+    let mut bytes: Vec<u8> = Vec::with_capacity(s.len());
+    let mut zeros = 0;
+    let mut leading = true;
+    for (i, c) in s.chars().enumerate() {
+        let value = ALPHABET
+            .iter()
+            .position(|&a| a as char == c)
+            .ok_or(Base58Error::InvalidCharacter(c, i))? as u32;
+        if leading && value == 0 {
+            zeros += 1;
+            continue;
+        }
+        leading = false;
+        let mut carry = value;
+        for b in bytes.iter_mut() {
+            carry += (*b as u32) * 58;
+            *b = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+    bytes.extend(std::iter::repeat_n(0, zeros));
+    bytes.reverse();
+    Ok(bytes)
+}
+
+fn checksum(data: &[u8]) -> [u8; CHECKSUM_LEN] {
+    sha256d(data)[..CHECKSUM_LEN].try_into().unwrap()
+}
+
+// 任意のバイト列にチェックサムを付けて符号化する。BIP32 の拡張鍵のように 4 バイトの version を
+// 持つ形式はこちらを使う
+pub fn encode_checked(data: &[u8]) -> String {
+    let mut data = data.to_vec();
+    let sum = checksum(&data);
+    data.extend_from_slice(&sum);
+    encode(&data)
+}
+
+pub fn decode_checked(s: &str) -> Result<Vec<u8>, Base58Error> {
+    let data = decode(s)?;
+    if data.len() < 1 + CHECKSUM_LEN {
+        return Err(Base58Error::TooShort);
+    }
+    let (body, sum) = data.split_at(data.len() - CHECKSUM_LEN);
+    if checksum(body) != sum {
+        return Err(Base58Error::InvalidChecksum);
+    }
+    Ok(body.to_vec())
+}
+
+pub fn encode_check(version: u8, payload: &[u8]) -> String {
+    encode_checked(&[&[version][..], payload].concat())
+}
+
+// (version, payload) を返す
+pub fn decode_check(s: &str) -> Result<(u8, Vec<u8>), Base58Error> {
+    let body = decode_checked(s)?;
+    Ok((body[0], body[1..].to_vec()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::unhex;
+
+    #[test]
+    fn test_vectors() {
+        // Bitcoin Core の base58_encode_decode.json から
+        let cases = [
+            ("", ""),
+            ("61", "2g"),
+            ("626262", "a3gV"),
+            ("00000000000000000000", "1111111111"),
+            (
+                "00eb15231dfceb60925886b67d065299925915aeb172c06647",
+                "1NS17iag9jJgTHD1VXjvLCEnZuQ3rJDE9L",
+            ),
+            ("516b6fcd0f", "ABnLTmg"),
+            ("572e4794", "3EFU7m"),
+        ];
+        for (hex, b58) in cases {
+            assert_eq!(encode(&unhex(hex)), b58);
+            assert_eq!(decode(b58).unwrap(), unhex(hex));
+        }
+        assert_eq!(decode("3EF0"), Err(Base58Error::InvalidCharacter('0', 3)));
+    }
+
+    #[test]
+    fn test_base58check() {
+        // ジェネシスブロックの報酬アドレス
+        let hash = unhex("62e907b15cbf27d5425399ebf6f0fb50ebb88f18");
+        let address = encode_check(0x00, &hash);
+        assert_eq!(address, "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa");
+        assert_eq!(decode_check(&address), Ok((0x00, hash)));
+        // 1 文字変えるとチェックサムで弾かれる
+        assert_eq!(
+            decode_check("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNb"),
+            Err(Base58Error::InvalidChecksum)
+        );
+        assert_eq!(decode_check("1111"), Err(Base58Error::TooShort));
+    }
+}
@@ -0,0 +1,275 @@
+// Bech32 (BIP-173) と Bech32m (BIP-350)。人が読む接頭辞 (HRP)、区切りの '1'、
+// 5 ビットずつの値を 32 文字で表したデータ、GF(32) 上の BCH 符号による 6 文字のチェックサムからなる。
+// 2 つの違いはチェックサムの最後に XOR する定数だけで、Bech32m は末尾の文字の挿入・削除に対する
+// Bech32 の弱点を直したもの。segwit v0 は Bech32、v1 (taproot) 以降は Bech32m を使う
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+const CHECKSUM_LEN: usize = 6;
+const MAX_LEN: usize = 90;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Variant {
+    Bech32,
+    Bech32m,
+}
+
+impl Variant {
+    fn constant(self) -> u32 {
+        match self {
+            Variant::Bech32 => 1,
+            Variant::Bech32m => 0x2bc830a3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Bech32Error {
+    // データ部の文字が CHARSET にない、または HRP に使えない文字
+    InvalidCharacter(char),
+    MixedCase,
+    MissingSeparator,
+    InvalidLength,
+    InvalidChecksum,
+    // 8 ビットへの変換で余ったビットが 0 でない、または長すぎる
+    InvalidPadding,
+    // 5 ビットを超える値を符号化しようとした
+    InvalidData,
+    // segwit アドレスとしての制約違反
+    InvalidWitnessVersion,
+    InvalidProgramLength,
+    WrongVariant,
+    WrongHrp,
+}
+
+fn polymod(values: impl Iterator<Item = u8>) -> u32 {
+    values.fold(1u32, |chk, v| {
+        let top = chk >> 25;
+        let chk = ((chk & 0x1ffffff) << 5) ^ v as u32;
+        GENERATOR
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| (top >> i) & 1 == 1)
+            .fold(chk, |chk, (_, g)| chk ^ g)
+    })
+}
+
+// HRP は上位 3 ビット、0、下位 5 ビットの順に展開してチェックサムに混ぜる
+fn hrp_expand(hrp: &[u8]) -> Vec<u8> {
+    let mut out: Vec<u8> = hrp.iter().map(|c| c >> 5).collect();
+    out.push(0);
+    out.extend(hrp.iter().map(|c| c & 31));
+    out
+}
+
+fn checksum(hrp: &[u8], data: &[u8], variant: Variant) -> [u8; CHECKSUM_LEN] {
+    let values = hrp_expand(hrp)
+        .into_iter()
+        .chain(data.iter().copied())
+        .chain([0; CHECKSUM_LEN]);
+    let m = polymod(values) ^ variant.constant();
+    std::array::from_fn(|i| ((m >> (5 * (5 - i))) & 31) as u8)
+}
+
+// data は 5 ビットの値の列
+pub fn encode(hrp: &str, data: &[u8], variant: Variant) -> Result<String, Bech32Error> {
+    let hrp = hrp.to_ascii_lowercase();
+    check_hrp(&hrp)?;
+    if data.iter().any(|&v| v >= 32) {
+        return Err(Bech32Error::InvalidData);
+    }
+    if hrp.len() + 1 + data.len() + CHECKSUM_LEN > MAX_LEN {
+        return Err(Bech32Error::InvalidLength);
+    }
+    let sum = checksum(hrp.as_bytes(), data, variant);
+    let mut out = hrp;
+    out.push('1');
+    out.extend(
+        data.iter()
+            .chain(&sum)
+            .map(|&v| CHARSET[v as usize] as char),
+    );
+    Ok(out)
+}
+
+fn check_hrp(hrp: &str) -> Result<(), Bech32Error> {
+    if hrp.is_empty() || hrp.len() > MAX_LEN - 1 - CHECKSUM_LEN {
+        return Err(Bech32Error::InvalidLength);
+    }
+    match hrp.chars().find(|c| !(33..=126).contains(&(*c as u32))) {
+        Some(c) => Err(Bech32Error::InvalidCharacter(c)),
+        None => Ok(()),
+    }
+}
+
+// (HRP, 5 ビット値の列, 方式) を返す。文字列は全部大文字か全部小文字でなければならない
+pub fn decode(s: &str) -> Result<(String, Vec<u8>, Variant), Bech32Error> {
+    if s.len() > MAX_LEN {
+        return Err(Bech32Error::InvalidLength);
+    }
+    let has_lower = s.chars().any(|c| c.is_ascii_lowercase());
+    let has_upper = s.chars().any(|c| c.is_ascii_uppercase());
+    if has_lower && has_upper {
+        return Err(Bech32Error::MixedCase);
+    }
+    let s = s.to_ascii_lowercase();
+    let split = s.rfind('1').ok_or(Bech32Error::MissingSeparator)?;
+    let (hrp, rest) = (&s[..split], &s[split + 1..]);
+    check_hrp(hrp)?;
+    if rest.len() < CHECKSUM_LEN {
+        return Err(Bech32Error::InvalidLength);
+    }
+    let values = rest
+        .chars()
+        .map(|c| {
+            CHARSET
+                .iter()
+                .position(|&x| x as char == c)
+                .map(|v| v as u8)
+                .ok_or(Bech32Error::InvalidCharacter(c))
+        })
+        .collect::<Result<Vec<u8>, _>>()?;
+    let residue = polymod(
+        hrp_expand(hrp.as_bytes())
+            .into_iter()
+            .chain(values.iter().copied()),
+    );
+    let variant = [Variant::Bech32, Variant::Bech32m]
+        .into_iter()
+        .find(|v| v.constant() == residue)
+        .ok_or(Bech32Error::InvalidChecksum)?;
+    let data = values[..values.len() - CHECKSUM_LEN].to_vec();
+    Ok((hrp.to_string(), data, variant))
+}
+
+// from ビットずつの値の列を to ビットずつに詰め直す
+pub fn convert_bits(data: &[u8], from: u32, to: u32, pad: bool) -> Result<Vec<u8>, Bech32Error> {
+    let mut acc: u32 = 0;
+    let mut bits: u32 = 0;
+    let max = (1u32 << to) - 1;
+    let mut out = Vec::with_capacity(data.len() * from as usize / to as usize + 1);
+    for &value in data {
+        if (value as u32) >> from != 0 {
+            return Err(Bech32Error::InvalidData);
+        }
+        acc = (acc << from) | value as u32;
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            out.push(((acc >> bits) & max) as u8);
+        }
+    }
+    if pad {
+        if bits > 0 {
+            out.push(((acc << (to - bits)) & max) as u8);
+        }
+    } else if bits >= from || ((acc << (to - bits)) & max) != 0 {
+        return Err(Bech32Error::InvalidPadding);
+    }
+    Ok(out)
+}
+
+// segwit アドレス: 先頭の 5 ビット値が witness version、残りが 8 ビットに戻したプログラム
+pub fn encode_segwit(hrp: &str, version: u8, program: &[u8]) -> Result<String, Bech32Error> {
+    check_witness(version, program)?;
+    let variant = if version == 0 {
+        Variant::Bech32
+    } else {
+        Variant::Bech32m
+    };
+    let mut data = vec![version];
+    data.extend(convert_bits(program, 8, 5, true)?);
+    encode(hrp, &data, variant)
+}
+
+pub fn decode_segwit(hrp: &str, address: &str) -> Result<(u8, Vec<u8>), Bech32Error> {
+    let (decoded_hrp, data, variant) = decode(address)?;
+    if decoded_hrp != hrp.to_ascii_lowercase() {
+        return Err(Bech32Error::WrongHrp);
+    }
+    let (&version, rest) = data.split_first().ok_or(Bech32Error::InvalidLength)?;
+    let program = convert_bits(rest, 5, 8, false)?;
+    check_witness(version, &program)?;
+    let expected = if version == 0 {
+        Variant::Bech32
+    } else {
+        Variant::Bech32m
+    };
+    if variant != expected {
+        return Err(Bech32Error::WrongVariant);
+    }
+    Ok((version, program))
+}
+
+fn check_witness(version: u8, program: &[u8]) -> Result<(), Bech32Error> {
+    if version > 16 {
+        return Err(Bech32Error::InvalidWitnessVersion);
+    }
+    if !(2..=40).contains(&program.len()) || (version == 0 && ![20, 32].contains(&program.len())) {
+        return Err(Bech32Error::InvalidProgramLength);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{hex, unhex};
+
+    #[test]
+    fn test_checksum_vectors() {
+        // BIP-173 / BIP-350 の有効な文字列
+        for s in [
+            "A12UEL5L",
+            "a12uel5l",
+            "abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw",
+        ] {
+            assert_eq!(decode(s).unwrap().2, Variant::Bech32, "{}", s);
+        }
+        for s in [
+            "A1LQFN3A",
+            "a1lqfn3a",
+            "abcdef1l7aum6echk45nj3s0wdvt2fg8x9yrzpqzd3ryx",
+        ] {
+            assert_eq!(decode(s).unwrap().2, Variant::Bech32m, "{}", s);
+        }
+        let (hrp, data, variant) = decode("abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw").unwrap();
+        assert_eq!(
+            encode(&hrp, &data, variant).unwrap(),
+            "abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw"
+        );
+        assert_eq!(decode("a12uel5m"), Err(Bech32Error::InvalidChecksum));
+        assert_eq!(decode("A12uel5l"), Err(Bech32Error::MixedCase));
+        assert_eq!(decode("pzry9x0s0muk"), Err(Bech32Error::MissingSeparator));
+        assert_eq!(decode("x1b4n0q5v"), Err(Bech32Error::InvalidCharacter('b')));
+    }
+
+    #[test]
+    fn test_segwit_addresses() {
+        let (version, program) =
+            decode_segwit("bc", "BC1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4").unwrap();
+        assert_eq!(version, 0);
+        assert_eq!(hex(&program), "751e76e8199196d454941c45d1b3a323f1433bd6");
+        assert_eq!(
+            encode_segwit("bc", 0, &program).unwrap(),
+            "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+        );
+        // taproot (v1) は Bech32m
+        let key = unhex("79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798");
+        let address = encode_segwit("bc", 1, &key).unwrap();
+        assert_eq!(
+            address,
+            "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0"
+        );
+        assert_eq!(decode_segwit("bc", &address), Ok((1, key)));
+        assert_eq!(decode_segwit("tb", &address), Err(Bech32Error::WrongHrp));
+        // v1 のプログラムを Bech32 で符号化したものは受け付けない
+        let mut data = vec![1];
+        data.extend(convert_bits(&program, 8, 5, true).unwrap());
+        let wrong = encode("bc", &data, Variant::Bech32).unwrap();
+        assert_eq!(decode_segwit("bc", &wrong), Err(Bech32Error::WrongVariant));
+        assert_eq!(
+            encode_segwit("bc", 0, &[0; 21]),
+            Err(Bech32Error::InvalidProgramLength)
+        );
+    }
+}
@@ -0,0 +1,6 @@
+// バイト列を人が扱う文字列にする符号化
+pub mod base58;
+pub mod bech32;
+
+pub use base58::Base58Error;
+pub use bech32::{Bech32Error, Variant};
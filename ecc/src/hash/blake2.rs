@@ -0,0 +1,278 @@
+// BLAKE2b (64 ビット語) と BLAKE2s (32 ビット語) (RFC 7693)。
+// 鍵付きモード、ソルト、パーソナライズ、可変長出力に対応する
+const SIGMA: [[usize; 16]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+// 2 つの変種は語長・ラウンド数・回転量・初期値だけが異なるので同じ形で定義する
+macro_rules! blake2 {
+    ($name:ident, $word:ty, $bytes:expr, $rounds:expr, $rot:expr, $iv:expr) => {
+        #[derive(Clone)]
+        pub struct $name {
+            h: [$word; 8],
+            counter: u128,
+            buffer: [u8; 16 * $bytes],
+            buffer_len: usize,
+            out_len: usize,
+        }
+
+        impl $name {
+            pub const BLOCK_LEN: usize = 16 * $bytes;
+            pub const MAX_OUT_LEN: usize = 8 * $bytes;
+            pub const SALT_LEN: usize = 2 * $bytes;
+
+            pub fn new(out_len: usize) -> Self {
+                Self::with_params(out_len, &[], &[], &[])
+            }
+
+            pub fn new_keyed(key: &[u8], out_len: usize) -> Self {
+                Self::with_params(out_len, key, &[], &[])
+            }
+
+            // salt と personal は SALT_LEN バイト以下 (足りなければ 0 で埋める)
+            pub fn with_params(out_len: usize, key: &[u8], salt: &[u8], personal: &[u8]) -> Self {
+                if out_len == 0 || out_len > Self::MAX_OUT_LEN {
+                    panic!("Output length must be between 1 and {}", Self::MAX_OUT_LEN);
+                }
+                if key.len() > Self::MAX_OUT_LEN {
+                    panic!("Key must be at most {} bytes", Self::MAX_OUT_LEN);
+                }
+                if salt.len() > Self::SALT_LEN || personal.len() > Self::SALT_LEN {
+                    panic!(
+                        "Salt and personalization must be at most {} bytes",
+                        Self::SALT_LEN
+                    );
+                }
+                let mut h = $iv;
+                // パラメータブロックの先頭語: 出力長, 鍵長, fanout = 1, depth = 1
+                h[0] ^= 0x0101_0000 ^ ((key.len() as $word) << 8) ^ out_len as $word;
+                let load = |bytes: &[u8], i: usize| {
+                    let mut word = [0u8; $bytes];
+                    let start = (i * $bytes).min(bytes.len());
+                    let end = ((i + 1) * $bytes).min(bytes.len());
+                    word[..end - start].copy_from_slice(&bytes[start..end]);
+                    <$word>::from_le_bytes(word)
+                };
+                h[4] ^= load(salt, 0);
+                h[5] ^= load(salt, 1);
+                h[6] ^= load(personal, 0);
+                h[7] ^= load(personal, 1);
+                let mut state = $name {
+                    h,
+                    counter: 0,
+                    buffer: [0; 16 * $bytes],
+                    buffer_len: 0,
+                    out_len,
+                };
+                // 鍵は 0 埋めした 1 ブロックとして最初に吸収する
+                if !key.is_empty() {
+                    state.buffer[..key.len()].copy_from_slice(key);
+                    state.buffer_len = Self::BLOCK_LEN;
+                }
+                state
+            }
+
+            fn compress(&mut self, last: bool) {
+                let mut m = [0 as $word; 16];
+                for (i, chunk) in self.buffer.chunks($bytes).enumerate() {
+                    m[i] = <$word>::from_le_bytes(chunk.try_into().unwrap());
+                }
+                let mut v = [0 as $word; 16];
+                v[..8].copy_from_slice(&self.h);
+                v[8..].copy_from_slice(&$iv);
+                v[12] ^= self.counter as $word;
+                v[13] ^= (self.counter >> (8 * $bytes)) as $word;
+                if last {
+                    v[14] = !v[14];
+                }
+                let [r1, r2, r3, r4] = $rot;
+                let mut g = |a: usize, b: usize, c: usize, d: usize, x: $word, y: $word| {
+                    v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+                    v[d] = (v[d] ^ v[a]).rotate_right(r1);
+                    v[c] = v[c].wrapping_add(v[d]);
+                    v[b] = (v[b] ^ v[c]).rotate_right(r2);
+                    v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+                    v[d] = (v[d] ^ v[a]).rotate_right(r3);
+                    v[c] = v[c].wrapping_add(v[d]);
+                    v[b] = (v[b] ^ v[c]).rotate_right(r4);
+                };
+                for round in 0..$rounds {
+                    let s = &SIGMA[round % 10];
+                    g(0, 4, 8, 12, m[s[0]], m[s[1]]);
+                    g(1, 5, 9, 13, m[s[2]], m[s[3]]);
+                    g(2, 6, 10, 14, m[s[4]], m[s[5]]);
+                    g(3, 7, 11, 15, m[s[6]], m[s[7]]);
+                    g(0, 5, 10, 15, m[s[8]], m[s[9]]);
+                    g(1, 6, 11, 12, m[s[10]], m[s[11]]);
+                    g(2, 7, 8, 13, m[s[12]], m[s[13]]);
+                    g(3, 4, 9, 14, m[s[14]], m[s[15]]);
+                }
+                for i in 0..8 {
+                    self.h[i] ^= v[i] ^ v[i + 8];
+                }
+            }
+
+            pub fn update(&mut self, data: &[u8]) {
+                for &byte in data {
+                    // 最終ブロックにはフラグを立てるので、次の入力が来るまで圧縮しない
+                    if self.buffer_len == Self::BLOCK_LEN {
+                        self.counter += Self::BLOCK_LEN as u128;
+                        self.compress(false);
+                        self.buffer_len = 0;
+                    }
+                    self.buffer[self.buffer_len] = byte;
+                    self.buffer_len += 1;
+                }
+            }
+
+            pub fn finalize(mut self) -> Vec<u8> {
+                self.counter += self.buffer_len as u128;
+                self.buffer[self.buffer_len..].fill(0);
+                self.compress(true);
+                self.h
+                    .iter()
+                    .flat_map(|w| w.to_le_bytes())
+                    .take(self.out_len)
+                    .collect()
+            }
+        }
+    };
+}
+
+blake2!(
+    Blake2b,
+    u64,
+    8,
+    12,
+    [32, 24, 16, 63],
+    [
+        0x6a09e667f3bcc908u64,
+        0xbb67ae8584caa73b,
+        0x3c6ef372fe94f82b,
+        0xa54ff53a5f1d36f1,
+        0x510e527fade682d1,
+        0x9b05688c2b3e6c1f,
+        0x1f83d9abfb41bd6b,
+        0x5be0cd19137e2179,
+    ]
+);
+
+blake2!(
+    Blake2s,
+    u32,
+    4,
+    10,
+    [16, 12, 8, 7],
+    [
+        0x6a09e667u32,
+        0xbb67ae85,
+        0x3c6ef372,
+        0xa54ff53a,
+        0x510e527f,
+        0x9b05688c,
+        0x1f83d9ab,
+        0x5be0cd19,
+    ]
+);
+
+// Digest 用に出力長を最大に固定した版
+macro_rules! blake2_fixed {
+    ($name:ident, $inner:ident, $out:expr) => {
+        #[derive(Clone)]
+        pub struct $name($inner);
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl $name {
+            pub fn new() -> Self {
+                $name($inner::new($out))
+            }
+
+            pub fn update(&mut self, data: &[u8]) {
+                self.0.update(data);
+            }
+
+            pub fn finalize(self) -> [u8; $out] {
+                self.0.finalize().try_into().unwrap()
+            }
+        }
+    };
+}
+
+blake2_fixed!(Blake2b512, Blake2b, 64);
+blake2_fixed!(Blake2s256, Blake2s, 32);
+
+pub fn blake2b(data: &[u8], out_len: usize) -> Vec<u8> {
+    let mut hasher = Blake2b::new(out_len);
+    hasher.update(data);
+    hasher.finalize()
+}
+
+pub fn blake2s(data: &[u8], out_len: usize) -> Vec<u8> {
+    let mut hasher = Blake2s::new(out_len);
+    hasher.update(data);
+    hasher.finalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::hex;
+
+    #[test]
+    fn test_rfc7693_vectors() {
+        // RFC 7693 付録 A, B
+        assert_eq!(
+            hex(&blake2b(b"abc", 64)),
+            "ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d17d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923"
+        );
+        assert_eq!(
+            hex(&blake2s(b"abc", 32)),
+            "508c5e8c327c14e2e1a72ba34eeb452f37458b209ed63a294d999b4c86675982"
+        );
+        assert_eq!(
+            hex(&blake2b(b"", 32)),
+            "0e5751c026e543b2e8ab2eb06099daa1d1e5df47778f7787faab45cdf12fe3a8"
+        );
+    }
+
+    #[test]
+    fn test_keyed_salted_personalized() {
+        let data: Vec<u8> = (0..=255u8).collect();
+        let key: Vec<u8> = (0..64u8).collect();
+        let mut mac = Blake2b::new_keyed(&key, 64);
+        mac.update(&data);
+        assert_eq!(
+            hex(&mac.finalize()),
+            "b72071e096277edebb8ee5134dd3714996307ba3a55aa4733d412abbe28e909e10e57e6fbfb4ef53b3b960518294ff889a90829254412e2a60b85add07a3674f"
+        );
+        let mut h = Blake2s::with_params(20, b"secret", b"saltsalt", b"person");
+        for chunk in data.chunks(33) {
+            h.update(chunk);
+        }
+        assert_eq!(
+            hex(&h.finalize()),
+            "d16e750c93e9432ff2904e70088cdf1aac432f71"
+        );
+        // ブロック長ちょうどの入力
+        let mut h = Blake2b::with_params(32, &[], b"0123456789abcdef", b"blake2 personal");
+        h.update(&data[..128]);
+        assert_eq!(
+            hex(&h.finalize()),
+            "20ca10d6a51de62cff5d98a511b879b1f4aa7846840a9c33599c593a550b2080"
+        );
+    }
+}
@@ -0,0 +1,388 @@
+// BLAKE3: 1024 バイトのチャンクを葉とする二分木で、根の出力は任意長に伸ばせる (XOF)。
+// Blake3 は逐次版 (チェイニング値のスタック)、blake3_parallel は部分木をスレッドに分ける版
+const IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+const MSG_PERMUTATION: [usize; 16] = [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8];
+
+const BLOCK_LEN: usize = 64;
+const CHUNK_LEN: usize = 1024;
+
+const CHUNK_START: u32 = 1 << 0;
+const CHUNK_END: u32 = 1 << 1;
+const PARENT: u32 = 1 << 2;
+const ROOT: u32 = 1 << 3;
+const KEYED_HASH: u32 = 1 << 4;
+const DERIVE_KEY_CONTEXT: u32 = 1 << 5;
+const DERIVE_KEY_MATERIAL: u32 = 1 << 6;
+
+fn g(s: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize, x: u32, y: u32) {
+    s[a] = s[a].wrapping_add(s[b]).wrapping_add(x);
+    s[d] = (s[d] ^ s[a]).rotate_right(16);
+    s[c] = s[c].wrapping_add(s[d]);
+    s[b] = (s[b] ^ s[c]).rotate_right(12);
+    s[a] = s[a].wrapping_add(s[b]).wrapping_add(y);
+    s[d] = (s[d] ^ s[a]).rotate_right(8);
+    s[c] = s[c].wrapping_add(s[d]);
+    s[b] = (s[b] ^ s[c]).rotate_right(7);
+}
+
+fn compress(
+    cv: &[u32; 8],
+    block: &[u32; 16],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+) -> [u32; 16] {
+    let mut s = [
+        cv[0],
+        cv[1],
+        cv[2],
+        cv[3],
+        cv[4],
+        cv[5],
+        cv[6],
+        cv[7],
+        IV[0],
+        IV[1],
+        IV[2],
+        IV[3],
+        counter as u32,
+        (counter >> 32) as u32,
+        block_len,
+        flags,
+    ];
+    let mut m = *block;
+    for round in 0..7 {
+        g(&mut s, 0, 4, 8, 12, m[0], m[1]);
+        g(&mut s, 1, 5, 9, 13, m[2], m[3]);
+        g(&mut s, 2, 6, 10, 14, m[4], m[5]);
+        g(&mut s, 3, 7, 11, 15, m[6], m[7]);
+        g(&mut s, 0, 5, 10, 15, m[8], m[9]);
+        g(&mut s, 1, 6, 11, 12, m[10], m[11]);
+        g(&mut s, 2, 7, 8, 13, m[12], m[13]);
+        g(&mut s, 3, 4, 9, 14, m[14], m[15]);
+        if round < 6 {
+            m = MSG_PERMUTATION.map(|i| m[i]);
+        }
+    }
+    for i in 0..8 {
+        s[i] ^= s[i + 8];
+        s[i + 8] ^= cv[i];
+    }
+    s
+}
+
+fn words(block: &[u8; BLOCK_LEN]) -> [u32; 16] {
+    let mut w = [0u32; 16];
+    for (i, chunk) in block.chunks(4).enumerate() {
+        w[i] = u32::from_le_bytes(chunk.try_into().unwrap());
+    }
+    w
+}
+
+fn first_8(words: [u32; 16]) -> [u32; 8] {
+    words[..8].try_into().unwrap()
+}
+
+// 圧縮の直前の状態。ROOT フラグを立てるかどうかを後で決められるようにしておく
+#[derive(Clone)]
+struct Output {
+    cv: [u32; 8],
+    block: [u32; 16],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+}
+
+impl Output {
+    fn chaining_value(&self) -> [u32; 8] {
+        first_8(compress(
+            &self.cv,
+            &self.block,
+            self.counter,
+            self.block_len,
+            self.flags,
+        ))
+    }
+
+    // 出力ブロックのカウンタを進めながら 64 バイトずつ絞り出す
+    fn root_bytes(&self, out: &mut [u8]) {
+        for (i, out_block) in out.chunks_mut(2 * 32).enumerate() {
+            let w = compress(
+                &self.cv,
+                &self.block,
+                i as u64,
+                self.block_len,
+                self.flags | ROOT,
+            );
+            for (chunk, word) in out_block.chunks_mut(4).zip(w) {
+                chunk.copy_from_slice(&word.to_le_bytes()[..chunk.len()]);
+            }
+        }
+    }
+}
+
+fn parent_output(left: &[u32; 8], right: &[u32; 8], key: &[u32; 8], flags: u32) -> Output {
+    let mut block = [0u32; 16];
+    block[..8].copy_from_slice(left);
+    block[8..].copy_from_slice(right);
+    Output {
+        cv: *key,
+        block,
+        counter: 0,
+        block_len: BLOCK_LEN as u32,
+        flags: PARENT | flags,
+    }
+}
+
+#[derive(Clone)]
+struct ChunkState {
+    cv: [u32; 8],
+    chunk_counter: u64,
+    block: [u8; BLOCK_LEN],
+    block_len: usize,
+    blocks_compressed: usize,
+    flags: u32,
+}
+
+impl ChunkState {
+    fn new(key: &[u32; 8], chunk_counter: u64, flags: u32) -> Self {
+        ChunkState {
+            cv: *key,
+            chunk_counter,
+            block: [0; BLOCK_LEN],
+            block_len: 0,
+            blocks_compressed: 0,
+            flags,
+        }
+    }
+
+    fn len(&self) -> usize {
+        BLOCK_LEN * self.blocks_compressed + self.block_len
+    }
+
+    fn start_flag(&self) -> u32 {
+        if self.blocks_compressed == 0 {
+            CHUNK_START
+        } else {
+            0
+        }
+    }
+
+    fn update(&mut self, mut input: &[u8]) {
+        while !input.is_empty() {
+            // 最後のブロックには CHUNK_END を立てるので、埋まっても次の入力が来るまで圧縮しない
+            if self.block_len == BLOCK_LEN {
+                let flags = self.flags | self.start_flag();
+                self.cv = first_8(compress(
+                    &self.cv,
+                    &words(&self.block),
+                    self.chunk_counter,
+                    64,
+                    flags,
+                ));
+                self.blocks_compressed += 1;
+                self.block = [0; BLOCK_LEN];
+                self.block_len = 0;
+            }
+            let take = (BLOCK_LEN - self.block_len).min(input.len());
+            self.block[self.block_len..self.block_len + take].copy_from_slice(&input[..take]);
+            self.block_len += take;
+            input = &input[take..];
+        }
+    }
+
+    fn output(&self) -> Output {
+        Output {
+            cv: self.cv,
+            block: words(&self.block),
+            counter: self.chunk_counter,
+            block_len: self.block_len as u32,
+            flags: self.flags | self.start_flag() | CHUNK_END,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Blake3 {
+    chunk: ChunkState,
+    key: [u32; 8],
+    // 完成した部分木のチェイニング値
+    cv_stack: Vec<[u32; 8]>,
+    flags: u32,
+}
+
+impl Default for Blake3 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Blake3 {
+    fn with_key_words(key: [u32; 8], flags: u32) -> Self {
+        Blake3 {
+            chunk: ChunkState::new(&key, 0, flags),
+            key,
+            cv_stack: Vec::new(),
+            flags,
+        }
+    }
+
+    pub fn new() -> Self {
+        Self::with_key_words(IV, 0)
+    }
+
+    pub fn new_keyed(key: &[u8; 32]) -> Self {
+        Self::with_key_words(key_words(key), KEYED_HASH)
+    }
+
+    // context はアプリケーション固有の固定文字列
+    pub fn new_derive_key(context: &str) -> Self {
+        let mut context_hasher = Self::with_key_words(IV, DERIVE_KEY_CONTEXT);
+        context_hasher.update(context.as_bytes());
+        let mut context_key = [0u8; 32];
+        context_hasher.finalize_xof(&mut context_key);
+        Self::with_key_words(key_words(&context_key), DERIVE_KEY_MATERIAL)
+    }
+
+    fn push_chunk_cv(&mut self, mut cv: [u32; 8], mut total_chunks: u64) {
+        // チャンク数の末尾の 0 ビットの数だけ部分木が完成している
+        while total_chunks & 1 == 0 {
+            let left = self.cv_stack.pop().unwrap();
+            cv = parent_output(&left, &cv, &self.key, self.flags).chaining_value();
+            total_chunks >>= 1;
+        }
+        self.cv_stack.push(cv);
+    }
+
+    pub fn update(&mut self, mut input: &[u8]) {
+        while !input.is_empty() {
+            if self.chunk.len() == CHUNK_LEN {
+                let cv = self.chunk.output().chaining_value();
+                let total_chunks = self.chunk.chunk_counter + 1;
+                self.push_chunk_cv(cv, total_chunks);
+                self.chunk = ChunkState::new(&self.key, total_chunks, self.flags);
+            }
+            let take = (CHUNK_LEN - self.chunk.len()).min(input.len());
+            self.chunk.update(&input[..take]);
+            input = &input[take..];
+        }
+    }
+
+    pub fn finalize_xof(&self, out: &mut [u8]) {
+        let mut output = self.chunk.output();
+        for left in self.cv_stack.iter().rev() {
+            output = parent_output(left, &output.chaining_value(), &self.key, self.flags);
+        }
+        output.root_bytes(out);
+    }
+
+    pub fn finalize(&self) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        self.finalize_xof(&mut out);
+        out
+    }
+}
+
+fn key_words(key: &[u8; 32]) -> [u32; 8] {
+    let mut w = [0u32; 8];
+    for (i, chunk) in key.chunks(4).enumerate() {
+        w[i] = u32::from_le_bytes(chunk.try_into().unwrap());
+    }
+    w
+}
+
+pub fn blake3(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Blake3::new();
+    hasher.update(data);
+    hasher.finalize()
+}
+
+// 部分木の根の Output。左の部分木は全体より小さい最大の 2 冪個のチャンクを持つ
+fn subtree_output(data: &[u8], chunk_offset: u64, threads: usize) -> Output {
+    if data.len() <= CHUNK_LEN {
+        let mut chunk = ChunkState::new(&IV, chunk_offset, 0);
+        chunk.update(data);
+        return chunk.output();
+    }
+    let chunks = data.len().div_ceil(CHUNK_LEN);
+    let left_len = chunks.next_power_of_two() / 2 * CHUNK_LEN;
+    let (left, right) = data.split_at(left_len);
+    let right_offset = chunk_offset + (left_len / CHUNK_LEN) as u64;
+    let (left_cv, right_cv) = if threads > 1 {
+        std::thread::scope(|s| {
+            let handle =
+                s.spawn(|| subtree_output(left, chunk_offset, threads / 2).chaining_value());
+            let right_cv =
+                subtree_output(right, right_offset, threads - threads / 2).chaining_value();
+            (handle.join().unwrap(), right_cv)
+        })
+    } else {
+        (
+            subtree_output(left, chunk_offset, 1).chaining_value(),
+            subtree_output(right, right_offset, 1).chaining_value(),
+        )
+    };
+    parent_output(&left_cv, &right_cv, &IV, 0)
+}
+
+pub fn blake3_parallel(data: &[u8], threads: usize) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    subtree_output(data, 0, threads.max(1)).root_bytes(&mut out);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::hex;
+
+    #[test]
+    fn test_blake3_known_hashes() {
+        assert_eq!(
+            hex(&blake3(b"")),
+            "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"
+        );
+        assert_eq!(
+            hex(&blake3(b"abc")),
+            "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85"
+        );
+        // XOF の先頭 32 バイトは通常のハッシュと一致する
+        let mut hasher = Blake3::new();
+        hasher.update(b"abc");
+        let mut long = [0u8; 100];
+        hasher.finalize_xof(&mut long);
+        assert_eq!(long[..32], blake3(b"abc"));
+    }
+
+    #[test]
+    fn test_tree_hashing_matches_incremental() {
+        let data: Vec<u8> = (0..20_000u32).map(|i| (i % 251) as u8).collect();
+        for len in [0, 1, 1023, 1024, 1025, 2048, 3072, 5 * 1024 + 7, 20_000] {
+            let expected = blake3(&data[..len]);
+            let mut hasher = Blake3::new();
+            for piece in data[..len].chunks(333) {
+                hasher.update(piece);
+            }
+            assert_eq!(hasher.finalize(), expected, "len {}", len);
+            assert_eq!(blake3_parallel(&data[..len], 4), expected, "len {}", len);
+        }
+    }
+
+    #[test]
+    fn test_keyed_and_derive_key_vectors() {
+        // 公式テストベクタの空入力の場合
+        let keyed = Blake3::new_keyed(b"whats the Elvish word for friend");
+        assert_eq!(
+            hex(&keyed.finalize()),
+            "92b2b75604ed3c761f9d6f62392c8a9227ad0ea3f09573e783f1498a4ed60d26"
+        );
+        let derived = Blake3::new_derive_key("BLAKE3 2019-12-27 16:29:52 test vectors context");
+        assert_eq!(
+            hex(&derived.finalize()),
+            "2cc39783c223154fea8dfb7c1b1660f2ac2dcbd1c1de8277b0b0dd39b7e50d7d"
+        );
+    }
+}
@@ -0,0 +1,97 @@
+// MiMC (Albrecht ら 2016): E_k(x) は (x + k + c_i)^α を r 回繰り返し最後に k を足す。
+// r = ceil(log_α p) は補間攻撃を防ぐための最小ラウンド数。
+// ハッシュは Miyaguchi–Preneel 構成 h_i = E_{h_{i-1}}(m_i) + h_{i-1} + m_i
+use super::sha256;
+use crate::number_theory::gcd;
+use crate::FieldElement;
+
+#[derive(Debug, Clone)]
+pub struct Mimc {
+    pub prime: u64,
+    pub alpha: u64,
+    round_constants: Vec<FieldElement>,
+}
+
+impl Mimc {
+    pub fn new(prime: u64) -> Self {
+        let alpha = [3, 5, 7, 11, 13, 17]
+            .into_iter()
+            .find(|&a| gcd(a, prime - 1) == 1)
+            .expect("No small S-box exponent is coprime to p - 1");
+        let rounds = ((prime as f64).ln() / (alpha as f64).ln()).ceil() as usize;
+        // 最初のラウンド定数は 0
+        let round_constants = (0..rounds)
+            .map(|i| {
+                if i == 0 {
+                    return FieldElement::new(0, prime);
+                }
+                let mut input = b"MiMC".to_vec();
+                input.extend_from_slice(&prime.to_be_bytes());
+                input.extend_from_slice(&(i as u64).to_be_bytes());
+                let wide = u128::from_be_bytes(sha256(&input)[..16].try_into().unwrap());
+                FieldElement::new((wide % prime as u128) as u64, prime)
+            })
+            .collect();
+        Mimc {
+            prime,
+            alpha,
+            round_constants,
+        }
+    }
+
+    pub fn rounds(&self) -> usize {
+        self.round_constants.len()
+    }
+
+    pub fn round_constants(&self) -> &[FieldElement] {
+        &self.round_constants
+    }
+
+    pub fn encrypt(&self, x: &FieldElement, key: &FieldElement) -> FieldElement {
+        let x = self
+            .round_constants
+            .iter()
+            .fold(x.clone(), |x, c| x.add(key).add(c).pow(self.alpha));
+        x.add(key)
+    }
+
+    pub fn hash(&self, inputs: &[FieldElement]) -> FieldElement {
+        // 初期値に入力長を入れ、0 を後ろに足しただけの入力と区別する
+        let iv = FieldElement::new(inputs.len() as u64 % self.prime, self.prime);
+        inputs
+            .iter()
+            .fold(iv, |h, m| self.encrypt(m, &h).add(&h).add(m))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curve::N;
+
+    #[test]
+    fn test_mimc_rounds_and_permutation() {
+        let mimc = Mimc::new(N);
+        assert_eq!(mimc.alpha, 5);
+        // 5^27 > N > 5^26
+        assert_eq!(mimc.rounds(), 27);
+        // 小さな体で E_k が全単射であることを確かめる
+        let small = Mimc::new(101);
+        let key = FieldElement::new(42, 101);
+        let mut images: Vec<u64> = (0..101)
+            .map(|x| small.encrypt(&FieldElement::new(x, 101), &key).num)
+            .collect();
+        images.sort();
+        images.dedup();
+        assert_eq!(images.len(), 101);
+    }
+
+    #[test]
+    fn test_mimc_hash() {
+        let mimc = Mimc::new(N);
+        let fe = |x| FieldElement::new(x, N);
+        let h = mimc.hash(&[fe(1), fe(2)]);
+        assert_ne!(h, mimc.hash(&[fe(2), fe(1)]));
+        assert_ne!(h, mimc.hash(&[fe(1), fe(2), fe(0)]));
+    }
+}
@@ -0,0 +1,164 @@
+pub mod blake2;
+pub mod blake3;
+pub mod md5;
+pub mod mimc;
+pub mod pedersen;
+pub mod poseidon;
+pub mod ripemd160;
+pub mod sha1;
+pub mod sha256;
+pub mod sha3;
+pub mod sha512;
+
+pub use blake2::{blake2b, blake2s, Blake2b, Blake2b512, Blake2s, Blake2s256};
+pub use blake3::{blake3, blake3_parallel, Blake3};
+pub use md5::{md5, Md5};
+pub use mimc::Mimc;
+pub use pedersen::pedersen_hash;
+pub use poseidon::Poseidon;
+pub use ripemd160::{hash160, ripemd160, Ripemd160};
+pub use sha1::{sha1, Sha1};
+pub use sha256::{sha256, sha256d, Sha256};
+pub use sha3::{
+    keccak256, sha3_256, sha3_512, shake128, shake256, Keccak, Keccak256, Sha3_256, Sha3_512,
+};
+pub use sha512::{sha384, sha512, Sha384, Sha512};
+
+use std::io;
+
+// ストリーミングなハッシュ関数の共通インターフェース。
+// update を何度呼んでも一括で渡したのと同じ結果になる
+pub trait Digest: Clone {
+    const BLOCK_SIZE: usize;
+    const OUTPUT_SIZE: usize;
+
+    fn new() -> Self;
+    fn update(&mut self, data: &[u8]);
+    fn finalize(self) -> Vec<u8>;
+
+    fn digest(data: &[u8]) -> Vec<u8> {
+        let mut hasher = Self::new();
+        hasher.update(data);
+        hasher.finalize()
+    }
+}
+
+// io::copy でファイルなどをバッファリングせずに流し込めるようにする
+macro_rules! impl_write {
+    ($($name:ty),*) => {
+        $(
+            impl io::Write for $name {
+                fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                    <$name>::update(self, buf);
+                    Ok(buf.len())
+                }
+
+                fn flush(&mut self) -> io::Result<()> {
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_digest {
+    ($name:ty, $block:expr, $output:expr) => {
+        impl Digest for $name {
+            const BLOCK_SIZE: usize = $block;
+            const OUTPUT_SIZE: usize = $output;
+
+            fn new() -> Self {
+                <$name>::new()
+            }
+
+            fn update(&mut self, data: &[u8]) {
+                <$name>::update(self, data)
+            }
+
+            fn finalize(self) -> Vec<u8> {
+                <$name>::finalize(self).to_vec()
+            }
+        }
+    };
+}
+
+impl_digest!(Md5, 64, 16);
+impl_digest!(Sha1, 64, 20);
+impl_digest!(Ripemd160, 64, 20);
+impl_digest!(Sha256, 64, 32);
+impl_digest!(Sha384, 128, 48);
+impl_digest!(Sha512, 128, 64);
+impl_digest!(Sha3_256, 136, 32);
+impl_digest!(Sha3_512, 72, 64);
+impl_digest!(Keccak256, 136, 32);
+impl_digest!(Blake2b512, 128, 64);
+impl_digest!(Blake2s256, 64, 32);
+
+// Blake3 の finalize は &self を取るので個別に実装する
+impl Digest for Blake3 {
+    const BLOCK_SIZE: usize = 64;
+    const OUTPUT_SIZE: usize = 32;
+
+    fn new() -> Self {
+        Blake3::new()
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        Blake3::update(self, data)
+    }
+
+    fn finalize(self) -> Vec<u8> {
+        Blake3::finalize(&self).to_vec()
+    }
+}
+
+impl_write!(
+    Md5, Sha1, Ripemd160, Sha256, Sha384, Sha512, Sha3_256, Sha3_512, Keccak256, Blake2b, Blake2s,
+    Blake2b512, Blake2s256, Blake3, Keccak
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_chunked<D: Digest>(one_shot: &[u8]) {
+        let data: Vec<u8> = (0..1000u32).map(|i| (i * 7 + 3) as u8).collect();
+        let expected = D::digest(&data);
+        assert_eq!(expected.len(), D::OUTPUT_SIZE);
+        // ブロック境界の前後を含むいろいろな分割で同じ値になる
+        for chunk in [1, D::BLOCK_SIZE - 1, D::BLOCK_SIZE, D::BLOCK_SIZE + 1, 333] {
+            let mut hasher = D::new();
+            for piece in data.chunks(chunk) {
+                hasher.update(piece);
+            }
+            assert_eq!(hasher.finalize(), expected);
+        }
+        assert_eq!(D::digest(b"abc"), one_shot);
+    }
+
+    #[test]
+    fn test_all_digests_agree_with_one_shot() {
+        check_chunked::<Md5>(&md5(b"abc"));
+        check_chunked::<Sha1>(&sha1(b"abc"));
+        check_chunked::<Ripemd160>(&ripemd160(b"abc"));
+        check_chunked::<Sha256>(&sha256(b"abc"));
+        check_chunked::<Sha384>(&sha384(b"abc"));
+        check_chunked::<Sha512>(&sha512(b"abc"));
+        check_chunked::<Sha3_256>(&sha3_256(b"abc"));
+        check_chunked::<Sha3_512>(&sha3_512(b"abc"));
+        check_chunked::<Keccak256>(&keccak256(b"abc"));
+        check_chunked::<Blake2b512>(&blake2b(b"abc", 64));
+        check_chunked::<Blake2s256>(&blake2s(b"abc", 32));
+        check_chunked::<Blake3>(&blake3(b"abc"));
+    }
+
+    #[test]
+    fn test_io_copy_into_hasher() {
+        let data = vec![0x5au8; 100_000];
+        let mut reader = io::Cursor::new(&data);
+        let mut hasher = Sha256::new();
+        let copied = io::copy(&mut reader, &mut hasher).unwrap();
+        assert_eq!(copied, data.len() as u64);
+        assert_eq!(hasher.finalize(), sha256(&data));
+    }
+}
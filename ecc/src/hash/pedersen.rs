@@ -0,0 +1,48 @@
+// Pedersen ハッシュ: 入力を 56 ビットずつ区切った m_i に対して Σ m_i G_i。
+// G_i は互いの離散対数が分からないように hash_to_curve で作るので、
+// 衝突を見つけることは離散対数問題を解くことと同じくらい難しい
+use crate::curve;
+use crate::Point;
+
+const CHUNK_BYTES: usize = 7;
+
+pub fn pedersen_generator(i: usize) -> Point {
+    let mut label = b"PedersenHash".to_vec();
+    label.extend_from_slice(&(i as u64).to_be_bytes());
+    curve::hash_to_curve(&label)
+}
+
+pub fn pedersen_hash(data: &[u8]) -> Point {
+    // 最初の生成元にはバイト長を掛け、長さの違う入力が同じ和にならないようにする
+    let length_term = pedersen_generator(0).scalar_mul(data.len() as u64);
+    data.chunks(CHUNK_BYTES)
+        .enumerate()
+        .fold(length_term, |acc, (i, chunk)| {
+            let mut bytes = [0u8; 8];
+            bytes[8 - chunk.len()..].copy_from_slice(chunk);
+            let m = u64::from_be_bytes(bytes);
+            acc.add(&pedersen_generator(i + 1).scalar_mul(m))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pedersen_hash_is_additive_per_chunk() {
+        // 同じ長さの入力では、チャンクごとの差がそのまま生成元の倍数の差になる
+        let a = pedersen_hash(&[0, 0, 0, 0, 0, 0, 5]);
+        let b = pedersen_hash(&[0, 0, 0, 0, 0, 0, 3]);
+        assert_eq!(a, b.add(&pedersen_generator(1).scalar_mul(2)));
+    }
+
+    #[test]
+    fn test_pedersen_hash_separates_inputs() {
+        let h = pedersen_hash(b"hello world");
+        assert_eq!(h, pedersen_hash(b"hello world"));
+        assert_ne!(h, pedersen_hash(b"hello worle"));
+        assert_ne!(pedersen_hash(&[]), pedersen_hash(&[0]));
+        assert!(!h.is_infinity());
+    }
+}
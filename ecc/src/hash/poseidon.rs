@@ -0,0 +1,151 @@
+// Poseidon 置換 (Grassi ら 2019) とスポンジ。S-box x^α を全ラウンドでは全要素に、
+// 部分ラウンドでは先頭要素だけにかけることで、回路上の乗算数を抑える。
+// ラウンド定数は Grain LFSR の代わりに SHA-256 のカウンタモードから生成している
+use super::sha256;
+use crate::number_theory::gcd;
+use crate::FieldElement;
+
+#[derive(Debug, Clone)]
+pub struct Poseidon {
+    pub prime: u64,
+    pub width: usize,
+    pub full_rounds: usize,
+    pub partial_rounds: usize,
+    pub alpha: u64,
+    round_constants: Vec<FieldElement>,
+    mds: Vec<Vec<FieldElement>>,
+}
+
+impl Poseidon {
+    pub fn new(prime: u64, width: usize, full_rounds: usize, partial_rounds: usize) -> Self {
+        if width < 2 || !full_rounds.is_multiple_of(2) {
+            panic!("Poseidon needs width >= 2 and an even number of full rounds");
+        }
+        // x^α が置換になる最小の α (gcd(α, p - 1) = 1)
+        let alpha = [3, 5, 7, 11, 13, 17]
+            .into_iter()
+            .find(|&a| gcd(a, prime - 1) == 1)
+            .expect("No small S-box exponent is coprime to p - 1");
+        let rounds = full_rounds + partial_rounds;
+        let round_constants = (0..rounds * width)
+            .map(|i| {
+                let mut input = b"Poseidon".to_vec();
+                input.extend_from_slice(&prime.to_be_bytes());
+                input.extend_from_slice(&(width as u64).to_be_bytes());
+                input.extend_from_slice(&(i as u64).to_be_bytes());
+                let h = sha256(&input);
+                let wide = u128::from_be_bytes(h[..16].try_into().unwrap());
+                FieldElement::new((wide % prime as u128) as u64, prime)
+            })
+            .collect();
+        // Cauchy 行列 M[i][j] = 1 / (x_i + y_j) は MDS (x_i = i, y_j = width + j)
+        let mds = (0..width)
+            .map(|i| {
+                (0..width)
+                    .map(|j| {
+                        let one = FieldElement::new(1, prime);
+                        one.div(&FieldElement::new((i + width + j) as u64 % prime, prime))
+                    })
+                    .collect()
+            })
+            .collect();
+        Poseidon {
+            prime,
+            width,
+            full_rounds,
+            partial_rounds,
+            alpha,
+            round_constants,
+            mds,
+        }
+    }
+
+    pub fn permute(&self, state: &mut [FieldElement]) {
+        if state.len() != self.width {
+            panic!("State must have {} elements", self.width);
+        }
+        let half = self.full_rounds / 2;
+        for round in 0..self.full_rounds + self.partial_rounds {
+            for (s, c) in state
+                .iter_mut()
+                .zip(&self.round_constants[round * self.width..])
+            {
+                *s = s.add(c);
+            }
+            let full = round < half || round >= half + self.partial_rounds;
+            if full {
+                for s in state.iter_mut() {
+                    *s = s.pow(self.alpha);
+                }
+            } else {
+                state[0] = state[0].pow(self.alpha);
+            }
+            let mixed: Vec<FieldElement> = self
+                .mds
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .zip(state.iter())
+                        .fold(FieldElement::new(0, self.prime), |acc, (m, s)| {
+                            acc.add(&m.mul(s))
+                        })
+                })
+                .collect();
+            state.clone_from_slice(&mixed);
+        }
+    }
+
+    // 容量 1、レート width - 1 のスポンジ。容量要素に入力長を入れて長さの違う入力を区別する
+    pub fn hash(&self, inputs: &[FieldElement]) -> FieldElement {
+        let mut state = vec![FieldElement::new(0, self.prime); self.width];
+        state[0] = FieldElement::new(inputs.len() as u64 % self.prime, self.prime);
+        for chunk in inputs.chunks(self.width - 1) {
+            for (s, x) in state[1..].iter_mut().zip(chunk) {
+                *s = s.add(x);
+            }
+            self.permute(&mut state);
+        }
+        if inputs.is_empty() {
+            self.permute(&mut state);
+        }
+        state[1].clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curve::N;
+
+    fn fe(x: u64) -> FieldElement {
+        FieldElement::new(x, N)
+    }
+
+    #[test]
+    fn test_poseidon_parameters() {
+        let poseidon = Poseidon::new(N, 3, 8, 56);
+        // N - 1 = 2^4 * 3^2 * ... なので α = 3 は使えず 5 になる
+        assert_eq!(poseidon.alpha, 5);
+        assert_eq!(poseidon.round_constants.len(), 64 * 3);
+        // MDS 行列は可逆 (3x3 の行列式が 0 でない)
+        let m = &poseidon.mds;
+        let det = m[0][0]
+            .mul(&m[1][1].mul(&m[2][2]).sub(&m[1][2].mul(&m[2][1])))
+            .sub(&m[0][1].mul(&m[1][0].mul(&m[2][2]).sub(&m[1][2].mul(&m[2][0]))))
+            .add(&m[0][2].mul(&m[1][0].mul(&m[2][1]).sub(&m[1][1].mul(&m[2][0]))));
+        assert_ne!(det, fe(0));
+    }
+
+    #[test]
+    fn test_poseidon_hash_separates_inputs() {
+        let poseidon = Poseidon::new(N, 3, 8, 56);
+        let h = poseidon.hash(&[fe(1), fe(2)]);
+        assert_eq!(h, poseidon.hash(&[fe(1), fe(2)]));
+        assert_ne!(h, poseidon.hash(&[fe(2), fe(1)]));
+        assert_ne!(poseidon.hash(&[fe(1)]), poseidon.hash(&[fe(1), fe(0)]));
+        assert_ne!(poseidon.hash(&[]), poseidon.hash(&[fe(0)]));
+        // 3 要素以上は複数回の置換で吸収する
+        let long: Vec<FieldElement> = (0..7).map(fe).collect();
+        assert_ne!(poseidon.hash(&long), poseidon.hash(&long[..6]));
+    }
+}
@@ -0,0 +1,243 @@
+// Keccak-f[1600] とスポンジ構成。SHA-3 と Keccak-256 はパディングの先頭バイトだけが違う
+const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808a,
+    0x8000000080008000,
+    0x000000000000808b,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008a,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000a,
+    0x000000008000808b,
+    0x800000000000008b,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800a,
+    0x800000008000000a,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+// ρ の回転量 (レーン x + 5y)
+const RHO: [u32; 25] = [
+    0, 1, 62, 28, 27, 36, 44, 6, 55, 20, 3, 10, 43, 25, 39, 41, 45, 15, 21, 8, 18, 2, 61, 56, 14,
+];
+
+pub fn keccak_f1600(a: &mut [u64; 25]) {
+    for rc in ROUND_CONSTANTS {
+        // θ
+        let mut c = [0u64; 5];
+        for x in 0..5 {
+            c[x] = a[x] ^ a[x + 5] ^ a[x + 10] ^ a[x + 15] ^ a[x + 20];
+        }
+        for x in 0..5 {
+            let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                a[x + 5 * y] ^= d;
+            }
+        }
+        // ρ と π: (x, y) -> (y, 2x + 3y)
+        let mut b = [0u64; 25];
+        for x in 0..5 {
+            for y in 0..5 {
+                b[y + 5 * ((2 * x + 3 * y) % 5)] = a[x + 5 * y].rotate_left(RHO[x + 5 * y]);
+            }
+        }
+        // χ
+        for y in 0..5 {
+            for x in 0..5 {
+                a[x + 5 * y] = b[x + 5 * y] ^ (!b[(x + 1) % 5 + 5 * y] & b[(x + 2) % 5 + 5 * y]);
+            }
+        }
+        // ι
+        a[0] ^= rc;
+    }
+}
+
+#[derive(Clone)]
+pub struct Keccak {
+    state: [u64; 25],
+    rate: usize,
+    pad: u8,
+    pos: usize,
+    squeezing: bool,
+}
+
+impl Keccak {
+    // rate はバイト単位。pad は SHA-3 なら 0x06、SHAKE なら 0x1f、Keccak なら 0x01
+    pub fn new(rate: usize, pad: u8) -> Self {
+        if rate == 0 || rate >= 200 {
+            panic!("Invalid sponge rate {}", rate);
+        }
+        Keccak {
+            state: [0; 25],
+            rate,
+            pad,
+            pos: 0,
+            squeezing: false,
+        }
+    }
+
+    fn xor_byte(&mut self, i: usize, byte: u8) {
+        self.state[i / 8] ^= (byte as u64) << (8 * (i % 8));
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        if self.squeezing {
+            panic!("Cannot absorb after squeezing has started");
+        }
+        for &byte in data {
+            self.xor_byte(self.pos, byte);
+            self.pos += 1;
+            if self.pos == self.rate {
+                keccak_f1600(&mut self.state);
+                self.pos = 0;
+            }
+        }
+    }
+
+    // 最初の呼び出しでパディングして吸収を閉じ、以降は続きを絞り出す
+    pub fn squeeze(&mut self, out: &mut [u8]) {
+        if !self.squeezing {
+            self.xor_byte(self.pos, self.pad);
+            self.xor_byte(self.rate - 1, 0x80);
+            keccak_f1600(&mut self.state);
+            self.pos = 0;
+            self.squeezing = true;
+        }
+        for byte in out.iter_mut() {
+            if self.pos == self.rate {
+                keccak_f1600(&mut self.state);
+                self.pos = 0;
+            }
+            *byte = (self.state[self.pos / 8] >> (8 * (self.pos % 8))) as u8;
+            self.pos += 1;
+        }
+    }
+}
+
+// 出力長固定のストリーミング版
+macro_rules! fixed_sponge {
+    ($name:ident, $rate:expr, $pad:expr, $out:expr) => {
+        #[derive(Clone)]
+        pub struct $name(Keccak);
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl $name {
+            pub fn new() -> Self {
+                $name(Keccak::new($rate, $pad))
+            }
+
+            pub fn update(&mut self, data: &[u8]) {
+                self.0.update(data);
+            }
+
+            pub fn finalize(mut self) -> [u8; $out] {
+                let mut out = [0u8; $out];
+                self.0.squeeze(&mut out);
+                out
+            }
+        }
+    };
+}
+
+fixed_sponge!(Sha3_256, 136, 0x06, 32);
+fixed_sponge!(Sha3_512, 72, 0x06, 64);
+// Ethereum の Keccak-256 (FIPS 202 以前のパディング)
+fixed_sponge!(Keccak256, 136, 0x01, 32);
+
+pub fn sha3_256(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha3_256::new();
+    hasher.update(data);
+    hasher.finalize()
+}
+
+pub fn sha3_512(data: &[u8]) -> [u8; 64] {
+    let mut hasher = Sha3_512::new();
+    hasher.update(data);
+    hasher.finalize()
+}
+
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update(data);
+    hasher.finalize()
+}
+
+pub fn shake128() -> Keccak {
+    Keccak::new(168, 0x1f)
+}
+
+pub fn shake256() -> Keccak {
+    Keccak::new(136, 0x1f)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::hex;
+
+    #[test]
+    fn test_sha3_and_keccak_vectors() {
+        assert_eq!(
+            hex(&sha3_256(b"")),
+            "a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a"
+        );
+        assert_eq!(
+            hex(&sha3_256(b"abc")),
+            "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532"
+        );
+        assert_eq!(
+            hex(&sha3_512(b"abc")),
+            "b751850b1a57168a5693cd924b6b096e08f621827444f70d884f5d0240d2712e10e116e9192af3c91a7ec57647e3934057340b4cf408d5a56592f8274eec53f0"
+        );
+        assert_eq!(
+            hex(&keccak256(b"")),
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+        // 1 ブロック (136 バイト) をまたぐ入力
+        assert_eq!(
+            hex(&sha3_256(&[0xa3; 200])),
+            "79f38adec5c20307a98ef76e8324afbfd46cfd81b22e3973c65fa1bd9de31787"
+        );
+    }
+
+    #[test]
+    fn test_shake_xof() {
+        let mut xof = shake128();
+        xof.update(b"");
+        let mut out = [0u8; 32];
+        xof.squeeze(&mut out);
+        assert_eq!(
+            hex(&out),
+            "7f9c2ba4e88f827d616045507605853ed73b8093f6efbc88eb1a6eacfa66ef26"
+        );
+        // 分割して絞り出しても同じ列になる
+        let mut long = vec![0u8; 400];
+        let mut xof = shake256();
+        xof.update(b"abc");
+        xof.squeeze(&mut long);
+        let mut pieces = shake256();
+        pieces.update(b"a");
+        pieces.update(b"bc");
+        let mut first = vec![0u8; 137];
+        let mut second = vec![0u8; 263];
+        pieces.squeeze(&mut first);
+        pieces.squeeze(&mut second);
+        assert_eq!([first, second].concat(), long);
+        assert_eq!(hex(&long[..16]), "483366601360a8771c6863080cc4114d");
+    }
+}
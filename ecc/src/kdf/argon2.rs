@@ -0,0 +1,376 @@
+// Argon2id (RFC 9106, バージョン 0x13)。1 KiB ブロックの行列をメモリに確保し、
+// 最初の半パスはデータ非依存 (Argon2i)、以降はデータ依存 (Argon2d) の参照で埋める
+use crate::hash::Blake2b;
+
+const VERSION: u32 = 0x13;
+const ARGON2ID: u32 = 2;
+const BLOCK_WORDS: usize = 128;
+const SYNC_POINTS: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Argon2Params {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+    pub tag_len: usize,
+}
+
+impl Default for Argon2Params {
+    // RFC 9106 の 2 番目の推奨値 (64 MiB, t = 3, p = 4)
+    fn default() -> Self {
+        Argon2Params {
+            memory_kib: 64 * 1024,
+            iterations: 3,
+            parallelism: 4,
+            tag_len: 32,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Argon2Error {
+    InvalidEncoding,
+}
+
+type Block = [u64; BLOCK_WORDS];
+
+fn blake2b_512(parts: &[&[u8]]) -> Vec<u8> {
+    let mut h = Blake2b::new(64);
+    for part in parts {
+        h.update(part);
+    }
+    h.finalize()
+}
+
+fn blake2b_long(out_len: usize, parts: &[&[u8]]) -> Vec<u8> {
+    let hash = |len: usize, data: &[&[u8]]| {
+        let mut h = Blake2b::new(len);
+        for part in data {
+            h.update(part);
+        }
+        h.finalize()
+    };
+    let len_prefix = (out_len as u32).to_le_bytes();
+    let mut input: Vec<&[u8]> = vec![&len_prefix];
+    input.extend_from_slice(parts);
+    if out_len <= 64 {
+        return hash(out_len, &input);
+    }
+    // 64 バイトずつ連鎖させ、各段の前半 32 バイトを出力に使う
+    let r = out_len.div_ceil(32) - 2;
+    let mut v = hash(64, &input);
+    let mut out = v[..32].to_vec();
+    for _ in 1..r {
+        v = hash(64, &[&v]);
+        out.extend_from_slice(&v[..32]);
+    }
+    out.extend_from_slice(&hash(out_len - 32 * r, &[&v]));
+    out
+}
+
+// BlaMka: 加算に 2 * lo(a) * lo(b) を足して拡散を強めた BLAKE2b の G
+fn gb(v: &mut Block, a: usize, b: usize, c: usize, d: usize) {
+    let mul = |x: u64, y: u64| {
+        2u64.wrapping_mul(x & 0xffff_ffff)
+            .wrapping_mul(y & 0xffff_ffff)
+    };
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(mul(v[a], v[b]));
+    v[d] = (v[d] ^ v[a]).rotate_right(32);
+    v[c] = v[c].wrapping_add(v[d]).wrapping_add(mul(v[c], v[d]));
+    v[b] = (v[b] ^ v[c]).rotate_right(24);
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(mul(v[a], v[b]));
+    v[d] = (v[d] ^ v[a]).rotate_right(16);
+    v[c] = v[c].wrapping_add(v[d]).wrapping_add(mul(v[c], v[d]));
+    v[b] = (v[b] ^ v[c]).rotate_right(63);
+}
+
+// 16 語に対する置換 P。語の位置は idx で与える
+fn permute(v: &mut Block, idx: [usize; 16]) {
+    let [i0, i1, i2, i3, i4, i5, i6, i7, i8, i9, i10, i11, i12, i13, i14, i15] = idx;
+    gb(v, i0, i4, i8, i12);
+    gb(v, i1, i5, i9, i13);
+    gb(v, i2, i6, i10, i14);
+    gb(v, i3, i7, i11, i15);
+    gb(v, i0, i5, i10, i15);
+    gb(v, i1, i6, i11, i12);
+    gb(v, i2, i7, i8, i13);
+    gb(v, i3, i4, i9, i14);
+}
+
+// 圧縮関数 G(X, Y): R = X ^ Y の各行、各列に P をかけて R と XOR する
+fn compress(x: &Block, y: &Block) -> Block {
+    let mut r = [0u64; BLOCK_WORDS];
+    for i in 0..BLOCK_WORDS {
+        r[i] = x[i] ^ y[i];
+    }
+    let mut z = r;
+    for row in 0..8 {
+        permute(&mut z, std::array::from_fn(|i| 16 * row + i));
+    }
+    for col in 0..8 {
+        permute(
+            &mut z,
+            std::array::from_fn(|i| 16 * (i / 2) + 2 * col + i % 2),
+        );
+    }
+    for i in 0..BLOCK_WORDS {
+        z[i] ^= r[i];
+    }
+    z
+}
+
+fn block_from_bytes(bytes: &[u8]) -> Block {
+    let mut b = [0u64; BLOCK_WORDS];
+    for (w, chunk) in b.iter_mut().zip(bytes.chunks(8)) {
+        *w = u64::from_le_bytes(chunk.try_into().unwrap());
+    }
+    b
+}
+
+pub fn argon2id_with_secret(
+    password: &[u8],
+    salt: &[u8],
+    secret: &[u8],
+    associated_data: &[u8],
+    params: &Argon2Params,
+) -> Vec<u8> {
+    let p = params.parallelism as usize;
+    if p == 0 || params.iterations == 0 || params.tag_len < 4 {
+        panic!("Invalid Argon2 parameters {:?}", params);
+    }
+    if (params.memory_kib as usize) < 8 * p {
+        panic!("Argon2 memory must be at least 8 * parallelism KiB");
+    }
+    let le = |x: u32| x.to_le_bytes();
+    let len = |x: &[u8]| (x.len() as u32).to_le_bytes();
+    let h0 = blake2b_512(&[
+        &le(params.parallelism),
+        &le(params.tag_len as u32),
+        &le(params.memory_kib),
+        &le(params.iterations),
+        &le(VERSION),
+        &le(ARGON2ID),
+        &len(password),
+        password,
+        &len(salt),
+        salt,
+        &len(secret),
+        secret,
+        &len(associated_data),
+        associated_data,
+    ]);
+
+    // 各レーンの長さは同期点の数 (4) の倍数に切り下げる
+    let segment_len = params.memory_kib as usize / (SYNC_POINTS * p);
+    let lane_len = segment_len * SYNC_POINTS;
+    let total_blocks = lane_len * p;
+    let mut memory: Vec<Block> = vec![[0; BLOCK_WORDS]; total_blocks];
+    for lane in 0..p {
+        for i in 0..2 {
+            let bytes = blake2b_long(1024, &[&h0, &le(i as u32), &le(lane as u32)]);
+            memory[lane * lane_len + i] = block_from_bytes(&bytes);
+        }
+    }
+
+    let zero = [0u64; BLOCK_WORDS];
+    for pass in 0..params.iterations as usize {
+        for slice in 0..SYNC_POINTS {
+            for lane in 0..p {
+                let data_independent = pass == 0 && slice < SYNC_POINTS / 2;
+                let mut input = [0u64; BLOCK_WORDS];
+                input[..6].copy_from_slice(&[
+                    pass as u64,
+                    lane as u64,
+                    slice as u64,
+                    total_blocks as u64,
+                    params.iterations as u64,
+                    ARGON2ID as u64,
+                ]);
+                let mut addresses = zero;
+                let next_addresses = |input: &mut Block| {
+                    input[6] += 1;
+                    compress(&zero, &compress(&zero, input))
+                };
+                let start = if pass == 0 && slice == 0 { 2 } else { 0 };
+                if data_independent && start == 2 {
+                    addresses = next_addresses(&mut input);
+                }
+                for index in start..segment_len {
+                    let column = slice * segment_len + index;
+                    let curr = lane * lane_len + column;
+                    let prev = if column == 0 {
+                        curr + lane_len - 1
+                    } else {
+                        curr - 1
+                    };
+                    let pseudo_rand = if data_independent {
+                        if index % BLOCK_WORDS == 0 {
+                            addresses = next_addresses(&mut input);
+                        }
+                        addresses[index % BLOCK_WORDS]
+                    } else {
+                        memory[prev][0]
+                    };
+                    let j1 = pseudo_rand & 0xffff_ffff;
+                    let j2 = pseudo_rand >> 32;
+                    let ref_lane = if pass == 0 && slice == 0 {
+                        lane
+                    } else {
+                        (j2 % p as u64) as usize
+                    };
+                    let same_lane = ref_lane == lane;
+                    // 参照できるのは完成した区間と、同じレーンなら現在の区間の直前まで
+                    let area = match (pass, same_lane) {
+                        (0, true) => slice * segment_len + index - 1,
+                        (0, false) => slice * segment_len - (index == 0) as usize,
+                        (_, true) => lane_len - segment_len + index - 1,
+                        (_, false) => lane_len - segment_len - (index == 0) as usize,
+                    } as u64;
+                    let x = (j1 * j1) >> 32;
+                    let relative = area - 1 - ((area * x) >> 32);
+                    let start_pos = if pass == 0 || slice == SYNC_POINTS - 1 {
+                        0
+                    } else {
+                        (slice + 1) * segment_len
+                    };
+                    let ref_index = (start_pos + relative as usize) % lane_len;
+                    let block = compress(&memory[prev], &memory[ref_lane * lane_len + ref_index]);
+                    if pass == 0 {
+                        memory[curr] = block;
+                    } else {
+                        for (m, b) in memory[curr].iter_mut().zip(block) {
+                            *m ^= b;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // 各レーンの最後のブロックの XOR から可変長ハッシュでタグを作る
+    let mut last = zero;
+    for lane in 0..p {
+        for (c, m) in last.iter_mut().zip(memory[lane * lane_len + lane_len - 1]) {
+            *c ^= m;
+        }
+    }
+    let bytes: Vec<u8> = last.iter().flat_map(|w| w.to_le_bytes()).collect();
+    blake2b_long(params.tag_len, &[&bytes])
+}
+
+pub fn argon2id(password: &[u8], salt: &[u8], params: &Argon2Params) -> Vec<u8> {
+    argon2id_with_secret(password, salt, &[], &[], params)
+}
+
+// PHC 文字列形式で使う、パディングなしの標準 Base64
+const B64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn b64_encode(data: &[u8]) -> String {
+    let mut out = String::new();
+    for chunk in data.chunks(3) {
+        let n = chunk.iter().fold(0u32, |acc, &b| acc << 8 | b as u32) << (8 * (3 - chunk.len()));
+        for i in 0..=chunk.len() {
+            out.push(B64[(n >> (18 - 6 * i)) as usize & 63] as char);
+        }
+    }
+    out
+}
+
+fn b64_decode(s: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    let (mut acc, mut bits) = (0u32, 0);
+    for c in s.bytes() {
+        acc = acc << 6 | B64.iter().position(|&b| b == c)? as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+        }
+    }
+    Some(out)
+}
+
+// $argon2id$v=19$m=<KiB>,t=<反復>,p=<並列度>$<ソルト>$<タグ>
+pub fn hash_encoded(password: &[u8], salt: &[u8], params: &Argon2Params) -> String {
+    format!(
+        "$argon2id$v={}$m={},t={},p={}${}${}",
+        VERSION,
+        params.memory_kib,
+        params.iterations,
+        params.parallelism,
+        b64_encode(salt),
+        b64_encode(&argon2id(password, salt, params))
+    )
+}
+
+pub fn verify_encoded(encoded: &str, password: &[u8]) -> Result<bool, Argon2Error> {
+    let parts: Vec<&str> = encoded.split('$').collect();
+    let [_, "argon2id", version, settings, salt, tag] = parts[..] else {
+        return Err(Argon2Error::InvalidEncoding);
+    };
+    if version != format!("v={}", VERSION) {
+        return Err(Argon2Error::InvalidEncoding);
+    }
+    let mut values = [0u32; 3];
+    for (value, (field, key)) in values
+        .iter_mut()
+        .zip(settings.split(',').zip(["m=", "t=", "p="]))
+    {
+        *value = field
+            .strip_prefix(key)
+            .and_then(|v| v.parse().ok())
+            .ok_or(Argon2Error::InvalidEncoding)?;
+    }
+    let salt = b64_decode(salt).ok_or(Argon2Error::InvalidEncoding)?;
+    let tag = b64_decode(tag).ok_or(Argon2Error::InvalidEncoding)?;
+    let [memory_kib, iterations, parallelism] = values;
+    if tag.len() < 4 || parallelism == 0 || iterations == 0 || memory_kib < 8 * parallelism {
+        return Err(Argon2Error::InvalidEncoding);
+    }
+    let params = Argon2Params {
+        memory_kib,
+        iterations,
+        parallelism,
+        tag_len: tag.len(),
+    };
+    Ok(crate::mac::ct_eq(&argon2id(password, &salt, &params), &tag))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::hex;
+
+    #[test]
+    fn test_rfc9106_argon2id_vector() {
+        let params = Argon2Params {
+            memory_kib: 32,
+            iterations: 3,
+            parallelism: 4,
+            tag_len: 32,
+        };
+        let tag = argon2id_with_secret(&[1; 32], &[2; 16], &[3; 8], &[4; 12], &params);
+        assert_eq!(
+            hex(&tag),
+            "0d640df58d78766c08c037a34a8b53c9d01ef0452d75b65eb52520e96b01e659"
+        );
+    }
+
+    #[test]
+    fn test_encoded_roundtrip() {
+        let params = Argon2Params {
+            memory_kib: 256,
+            iterations: 2,
+            parallelism: 2,
+            tag_len: 24,
+        };
+        let encoded = hash_encoded(b"hunter2", b"somesaltsomesalt", &params);
+        assert!(encoded.starts_with("$argon2id$v=19$m=256,t=2,p=2$c29tZXNhbHRzb21lc2FsdA$"));
+        assert_eq!(verify_encoded(&encoded, b"hunter2"), Ok(true));
+        assert_eq!(verify_encoded(&encoded, b"hunter3"), Ok(false));
+        assert_eq!(
+            verify_encoded("$argon2i$v=19$m=256,t=2,p=2$c2FsdA$AAAA", b"x"),
+            Err(Argon2Error::InvalidEncoding)
+        );
+    }
+}
@@ -0,0 +1,75 @@
+// HKDF (RFC 5869): 抽出 (extract) で一様な PRK を作り、展開 (expand) で必要な長さに伸ばす
+use crate::hash::Digest;
+use crate::mac::Hmac;
+
+// salt が空なら HashLen バイトの 0 を使う
+pub fn extract<D: Digest>(salt: &[u8], ikm: &[u8]) -> Vec<u8> {
+    let zeros = vec![0u8; D::OUTPUT_SIZE];
+    let salt = if salt.is_empty() { &zeros[..] } else { salt };
+    let mut mac = Hmac::<D>::new(salt);
+    mac.update(ikm);
+    mac.finalize()
+}
+
+// T(i) = HMAC(PRK, T(i-1) || info || i)
+pub fn expand<D: Digest>(prk: &[u8], info: &[u8], len: usize) -> Vec<u8> {
+    if len > 255 * D::OUTPUT_SIZE {
+        panic!("HKDF output length {} exceeds 255 * HashLen", len);
+    }
+    let mut okm = Vec::with_capacity(len);
+    let mut t = Vec::new();
+    let mut counter = 1u8;
+    while okm.len() < len {
+        let mut mac = Hmac::<D>::new(prk);
+        mac.update(&t);
+        mac.update(info);
+        mac.update(&[counter]);
+        t = mac.finalize();
+        okm.extend_from_slice(&t);
+        counter = counter.wrapping_add(1);
+    }
+    okm.truncate(len);
+    okm
+}
+
+pub fn hkdf<D: Digest>(salt: &[u8], ikm: &[u8], info: &[u8], len: usize) -> Vec<u8> {
+    expand::<D>(&extract::<D>(salt, ikm), info, len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::{Sha1, Sha256};
+    use crate::test_util::{hex, unhex};
+
+    #[test]
+    fn test_rfc5869_case_1() {
+        let ikm = [0x0b; 22];
+        let salt = unhex("000102030405060708090a0b0c");
+        let info = unhex("f0f1f2f3f4f5f6f7f8f9");
+        let prk = extract::<Sha256>(&salt, &ikm);
+        assert_eq!(
+            hex(&prk),
+            "077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5"
+        );
+        assert_eq!(
+            hex(&expand::<Sha256>(&prk, &info, 42)),
+            "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865"
+        );
+    }
+
+    #[test]
+    fn test_rfc5869_empty_salt_and_info() {
+        // テストケース 3 (SHA-256) と 7 (SHA-1)
+        let ikm = [0x0b; 22];
+        assert_eq!(
+            hex(&hkdf::<Sha256>(&[], &ikm, &[], 42)),
+            "8da4e775a563c18f715f802a063c5a31b8a11f5c5ee1879ec3454e5f3c738d2d9d201395faa4b61a96c8"
+        );
+        let ikm = [0x0c; 22];
+        assert_eq!(
+            hex(&hkdf::<Sha1>(&[], &ikm, &[], 42)),
+            "2c91117204d745f3500d636a62f64f0ab3bae548aa53d423b0d1f27ebba6f5e5673a081d70cce7acfc48"
+        );
+    }
+}
@@ -0,0 +1,9 @@
+pub mod argon2;
+pub mod hkdf;
+pub mod pbkdf2;
+pub mod scrypt;
+
+pub use argon2::{argon2id, Argon2Params};
+pub use hkdf::hkdf;
+pub use pbkdf2::pbkdf2;
+pub use scrypt::scrypt;
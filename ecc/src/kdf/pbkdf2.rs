@@ -0,0 +1,87 @@
+// PBKDF2 (RFC 8018): T_i = U_1 ^ U_2 ^ ... ^ U_c, U_1 = PRF(P, S || i), U_j = PRF(P, U_{j-1})
+use crate::hash::Digest;
+use crate::mac::Hmac;
+
+pub fn pbkdf2<D: Digest>(password: &[u8], salt: &[u8], iterations: u32, len: usize) -> Vec<u8> {
+    if iterations == 0 {
+        panic!("PBKDF2 requires at least one iteration");
+    }
+    // パスワードを鍵にした HMAC の内外の初期状態は使い回せる
+    let prf = Hmac::<D>::new(password);
+    let mut out = Vec::with_capacity(len);
+    let mut block_index = 1u32;
+    while out.len() < len {
+        let mut mac = prf.clone();
+        mac.update(salt);
+        mac.update(&block_index.to_be_bytes());
+        let mut u = mac.finalize();
+        let mut t = u.clone();
+        for _ in 1..iterations {
+            let mut mac = prf.clone();
+            mac.update(&u);
+            u = mac.finalize();
+            for (x, y) in t.iter_mut().zip(&u) {
+                *x ^= y;
+            }
+        }
+        out.extend_from_slice(&t);
+        block_index += 1;
+    }
+    out.truncate(len);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::{Sha1, Sha256};
+    use crate::test_util::hex;
+
+    #[test]
+    fn test_rfc6070_vectors() {
+        let cases: [(&[u8], &[u8], u32, &str); 5] = [
+            (
+                b"password",
+                b"salt",
+                1,
+                "0c60c80f961f0e71f3a9b524af6012062fe037a6",
+            ),
+            (
+                b"password",
+                b"salt",
+                2,
+                "ea6c014dc72d6f8ccd1ed92ace1d41f0d8de8957",
+            ),
+            (
+                b"password",
+                b"salt",
+                4096,
+                "4b007901b765489abead49d926f721d065a429c1",
+            ),
+            (
+                b"passwordPASSWORDpassword",
+                b"saltSALTsaltSALTsaltSALTsaltSALTsalt",
+                4096,
+                "3d2eec4fe41c849b80c8d83662c0e44a8b291a964cf2f07038",
+            ),
+            (
+                b"pass\0word",
+                b"sa\0lt",
+                4096,
+                "56fa6aa75548099dcc37d7f03425e0c3",
+            ),
+        ];
+        for (password, salt, c, expected) in cases {
+            let len = expected.len() / 2;
+            assert_eq!(hex(&pbkdf2::<Sha1>(password, salt, c, len)), expected);
+        }
+    }
+
+    #[test]
+    fn test_pbkdf2_sha256_multi_block() {
+        assert_eq!(
+            hex(&pbkdf2::<Sha256>(b"password", b"salt", 2, 40)),
+            "ae4d0c95af6b46d32d0adff928f06dd02a303f8ef3c251dfd6e2d85a95474c43830651afcb5c862f"
+        );
+    }
+}
@@ -0,0 +1,103 @@
+// scrypt (RFC 7914): PBKDF2 で広げたブロックを ROMix に通し、N * 128r バイトのメモリを強制する
+use super::pbkdf2;
+use crate::hash::Sha256;
+use crate::symmetric::salsa20::salsa20_core;
+
+// 2r 個の 64 バイトブロックを Salsa20/8 で連鎖させ、偶数番目・奇数番目の順に並べ替える
+fn block_mix(input: &[[u32; 16]], output: &mut [[u32; 16]]) {
+    let r = input.len() / 2;
+    let mut x = input[2 * r - 1];
+    for (i, block) in input.iter().enumerate() {
+        for (xj, bj) in x.iter_mut().zip(block) {
+            *xj ^= bj;
+        }
+        // Salsa20/8 コア
+        x = salsa20_core(&x, 8);
+        output[(i % 2) * r + i / 2] = x;
+    }
+}
+
+fn ro_mix(block: &mut [[u32; 16]], n: usize) {
+    let len = block.len();
+    let mut v = Vec::with_capacity(n * len);
+    let mut x = block.to_vec();
+    let mut y = vec![[0u32; 16]; len];
+    for _ in 0..n {
+        v.extend_from_slice(&x);
+        block_mix(&x, &mut y);
+        std::mem::swap(&mut x, &mut y);
+    }
+    for _ in 0..n {
+        // Integerify: 最後のブロックの先頭語
+        let j = x[len - 1][0] as usize % n;
+        for (xi, vi) in x.iter_mut().zip(&v[j * len..(j + 1) * len]) {
+            for (a, b) in xi.iter_mut().zip(vi) {
+                *a ^= b;
+            }
+        }
+        block_mix(&x, &mut y);
+        std::mem::swap(&mut x, &mut y);
+    }
+    block.copy_from_slice(&x);
+}
+
+// n は 2 の冪、r はブロックサイズ、p は並列度
+pub fn scrypt(password: &[u8], salt: &[u8], n: usize, r: usize, p: usize, len: usize) -> Vec<u8> {
+    if n < 2 || !n.is_power_of_two() {
+        panic!("scrypt N must be a power of two greater than 1, got {}", n);
+    }
+    if r == 0 || p == 0 {
+        panic!("scrypt r and p must be positive");
+    }
+    let block_len = 128 * r;
+    let mut b = pbkdf2::<Sha256>(password, salt, 1, p * block_len);
+    for chunk in b.chunks_mut(block_len) {
+        let mut words: Vec<[u32; 16]> = chunk
+            .chunks(64)
+            .map(|blk| {
+                let mut w = [0u32; 16];
+                for (wi, bytes) in w.iter_mut().zip(blk.chunks(4)) {
+                    *wi = u32::from_le_bytes(bytes.try_into().unwrap());
+                }
+                w
+            })
+            .collect();
+        ro_mix(&mut words, n);
+        for (bytes, w) in chunk.chunks_mut(4).zip(words.iter().flatten()) {
+            bytes.copy_from_slice(&w.to_le_bytes());
+        }
+    }
+    pbkdf2::<Sha256>(password, &b, 1, len)
+}
+
+// Litecoin のプルーフ・オブ・ワーク: ヘッダ自身をパスワードとソルトにした scrypt(1024, 1, 1)
+pub fn litecoin_pow_hash(header: &[u8; 80]) -> [u8; 32] {
+    scrypt(header, header, 1024, 1, 1, 32).try_into().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::hex;
+
+    #[test]
+    fn test_rfc7914_vectors() {
+        assert_eq!(
+            hex(&scrypt(b"", b"", 16, 1, 1, 64)),
+            "77d6576238657b203b19ca42c18a0497f16b4844e3074ae8dfdffa3fede21442fcd0069ded0948f8326a753a0fc81f17e8d3e0fb2e0d3628cf35e20c38d18906"
+        );
+        assert_eq!(
+            hex(&scrypt(b"password", b"NaCl", 1024, 8, 16, 64)),
+            "fdbabe1c9d3472007856e7190d01e9fe7c6ad7cbc8237830e77376634b3731622eaf30d92e22a3886ff109279d9830dac727afb94a83ee6d8360cbdfa2cc0640"
+        );
+    }
+
+    #[test]
+    fn test_litecoin_pow_hash() {
+        let header: [u8; 80] = std::array::from_fn(|i| i as u8);
+        assert_eq!(
+            hex(&litecoin_pow_hash(&header)),
+            "bc540a1a801df96e493005c71e010e2d387607fbf0fec416fd3c2645aa1ba9d2"
+        );
+    }
+}
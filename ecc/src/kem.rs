@@ -0,0 +1,212 @@
+use crate::bignum::BigUint;
+use crate::curve::{self, Scalar};
+use crate::hash::sha256;
+use crate::lattice::kyber;
+use crate::rand::RngCore;
+use crate::rsa::{self, RsaPrivateKey, RsaPublicKey};
+use crate::Point;
+use std::marker::PhantomData;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KemError {
+    InvalidCiphertext,
+}
+
+pub trait Kem {
+    type PublicKey;
+    type SecretKey;
+    type Ciphertext;
+
+    fn keygen<R: RngCore>(rng: &mut R) -> (Self::PublicKey, Self::SecretKey);
+
+    fn encaps<R: RngCore>(pk: &Self::PublicKey, rng: &mut R) -> (Self::Ciphertext, [u8; 32]);
+
+    fn decaps(sk: &Self::SecretKey, ct: &Self::Ciphertext) -> Result<[u8; 32], KemError>;
+}
+
+fn derive(label: &[u8], parts: &[&[u8]]) -> [u8; 32] {
+    let mut input = label.to_vec();
+    for part in parts {
+        input.extend_from_slice(part);
+    }
+    sha256(&input)
+}
+
+// ECIES 型の DH-KEM: ct = e*G, 共有秘密 = H(ct || pk || e*pk)
+pub struct DhKem;
+
+impl Kem for DhKem {
+    type PublicKey = Point;
+    type SecretKey = u64;
+    type Ciphertext = Point;
+
+    fn keygen<R: RngCore>(rng: &mut R) -> (Point, u64) {
+        let sk = Scalar::random_nonzero(rng).value();
+        (curve::generator().scalar_mul(sk), sk)
+    }
+
+    fn encaps<R: RngCore>(pk: &Point, rng: &mut R) -> (Point, [u8; 32]) {
+        let (ct, eph) = Self::keygen(rng);
+        let dh = pk.scalar_mul(eph);
+        let shared = derive(b"DHKEM", &[&ct.to_bytes(), &pk.to_bytes(), &dh.to_bytes()]);
+        (ct, shared)
+    }
+
+    fn decaps(sk: &u64, ct: &Point) -> Result<[u8; 32], KemError> {
+        if ct.is_infinity() {
+            return Err(KemError::InvalidCiphertext);
+        }
+        let pk = curve::generator().scalar_mul(*sk);
+        let dh = ct.scalar_mul(*sk);
+        Ok(derive(
+            b"DHKEM",
+            &[&ct.to_bytes(), &pk.to_bytes(), &dh.to_bytes()],
+        ))
+    }
+}
+
+pub const RSA_KEM_BITS: usize = 1024;
+
+// RSA-KEM: ランダムな r を暗号化し、共有秘密は H(r)。r は渡された鍵の法の長さで直列化する
+pub struct RsaKem;
+
+fn modulus_len(n: &BigUint) -> usize {
+    n.bits().div_ceil(8)
+}
+
+impl Kem for RsaKem {
+    type PublicKey = RsaPublicKey;
+    type SecretKey = RsaPrivateKey;
+    type Ciphertext = BigUint;
+
+    fn keygen<R: RngCore>(rng: &mut R) -> (RsaPublicKey, RsaPrivateKey) {
+        rsa::keygen(RSA_KEM_BITS, rng)
+    }
+
+    fn encaps<R: RngCore>(pk: &RsaPublicKey, rng: &mut R) -> (BigUint, [u8; 32]) {
+        let r = BigUint::random_below(&pk.n, rng);
+        let shared = derive(b"RSAKEM", &[&r.to_bytes_be_padded(modulus_len(&pk.n))]);
+        (pk.encrypt(&r), shared)
+    }
+
+    fn decaps(sk: &RsaPrivateKey, ct: &BigUint) -> Result<[u8; 32], KemError> {
+        if *ct >= sk.n {
+            return Err(KemError::InvalidCiphertext);
+        }
+        let r = sk.decrypt(ct);
+        Ok(derive(
+            b"RSAKEM",
+            &[&r.to_bytes_be_padded(modulus_len(&sk.n))],
+        ))
+    }
+}
+
+pub struct KyberKem;
+
+impl Kem for KyberKem {
+    type PublicKey = kyber::PublicKey;
+    type SecretKey = kyber::SecretKey;
+    type Ciphertext = kyber::Ciphertext;
+
+    fn keygen<R: RngCore>(rng: &mut R) -> (kyber::PublicKey, kyber::SecretKey) {
+        kyber::keygen(rng)
+    }
+
+    fn encaps<R: RngCore>(pk: &kyber::PublicKey, rng: &mut R) -> (kyber::Ciphertext, [u8; 32]) {
+        kyber::encaps(pk, rng)
+    }
+
+    fn decaps(sk: &kyber::SecretKey, ct: &kyber::Ciphertext) -> Result<[u8; 32], KemError> {
+        Ok(kyber::decaps(sk, ct))
+    }
+}
+
+// 古典 KEM と耐量子 KEM を連結するハイブリッド KEM。
+// どちらか一方が安全なら共有秘密 H(ss1 || ss2) も安全に保たれる。
+pub struct HybridKem<A, B>(PhantomData<(A, B)>);
+
+impl<A: Kem, B: Kem> Kem for HybridKem<A, B> {
+    type PublicKey = (A::PublicKey, B::PublicKey);
+    type SecretKey = (A::SecretKey, B::SecretKey);
+    type Ciphertext = (A::Ciphertext, B::Ciphertext);
+
+    fn keygen<R: RngCore>(rng: &mut R) -> (Self::PublicKey, Self::SecretKey) {
+        let (pk_a, sk_a) = A::keygen(rng);
+        let (pk_b, sk_b) = B::keygen(rng);
+        ((pk_a, pk_b), (sk_a, sk_b))
+    }
+
+    fn encaps<R: RngCore>(pk: &Self::PublicKey, rng: &mut R) -> (Self::Ciphertext, [u8; 32]) {
+        let (ct_a, ss_a) = A::encaps(&pk.0, rng);
+        let (ct_b, ss_b) = B::encaps(&pk.1, rng);
+        ((ct_a, ct_b), derive(b"HybridKEM", &[&ss_a, &ss_b]))
+    }
+
+    fn decaps(sk: &Self::SecretKey, ct: &Self::Ciphertext) -> Result<[u8; 32], KemError> {
+        let ss_a = A::decaps(&sk.0, &ct.0)?;
+        let ss_b = B::decaps(&sk.1, &ct.1)?;
+        Ok(derive(b"HybridKEM", &[&ss_a, &ss_b]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::{OsRng, TestRng};
+
+    fn roundtrip<K: Kem>() {
+        let (pk, sk) = K::keygen(&mut OsRng);
+        let (ct, shared) = K::encaps(&pk, &mut OsRng);
+        assert_eq!(K::decaps(&sk, &ct), Ok(shared));
+    }
+
+    #[test]
+    fn test_kem_roundtrips() {
+        roundtrip::<DhKem>();
+        roundtrip::<RsaKem>();
+        roundtrip::<KyberKem>();
+    }
+
+    #[test]
+    fn test_injected_rng_reproduces_keys_and_secrets() {
+        fn run<K: Kem>() -> [u8; 32] {
+            let mut rng = TestRng::from_label(b"kem");
+            let (pk, _) = K::keygen(&mut rng);
+            K::encaps(&pk, &mut rng).1
+        }
+        assert_eq!(run::<DhKem>(), run::<DhKem>());
+        assert_eq!(run::<KyberKem>(), run::<KyberKem>());
+        assert_eq!(
+            run::<HybridKem<DhKem, KyberKem>>(),
+            run::<HybridKem<DhKem, KyberKem>>()
+        );
+    }
+
+    #[test]
+    fn test_rsa_kem_pads_to_key_size() {
+        // RSA_KEM_BITS 以外の大きさの鍵でも panic せず、法の長さで r を直列化する
+        for bits in [512, 1152] {
+            let (pk, sk) = rsa::keygen(bits, &mut OsRng);
+            let (ct, shared) = RsaKem::encaps(&pk, &mut OsRng);
+            assert_eq!(RsaKem::decaps(&sk, &ct), Ok(shared));
+            let r = sk.decrypt(&ct);
+            assert_eq!(
+                shared,
+                derive(b"RSAKEM", &[&r.to_bytes_be_padded(bits / 8)])
+            );
+        }
+    }
+
+    #[test]
+    fn test_hybrid_kem() {
+        roundtrip::<HybridKem<DhKem, KyberKem>>();
+
+        let (pk, sk) = HybridKem::<DhKem, KyberKem>::keygen(&mut OsRng);
+        let (mut ct, _) = HybridKem::<DhKem, KyberKem>::encaps(&pk, &mut OsRng);
+        ct.0 = curve::identity();
+        assert_eq!(
+            HybridKem::<DhKem, KyberKem>::decaps(&sk, &ct),
+            Err(KemError::InvalidCiphertext)
+        );
+    }
+}
@@ -0,0 +1,275 @@
+// ML-KEM (Kyber) を模した簡略版 KEM。
+// k = 2, η = 2, d_u = 10, d_v = 4。XOF/PRF は SHA-256 のカウンタモードで代用している。
+use super::ring::{Rq, N, Q};
+use crate::hash::sha256;
+use crate::rand::RngCore;
+
+pub const K: usize = 2;
+pub const ETA: usize = 2;
+pub const DU: u32 = 10;
+pub const DV: u32 = 4;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicKey {
+    pub t: Vec<Rq>,
+    pub rho: [u8; 32],
+}
+
+#[derive(Debug, Clone)]
+pub struct SecretKey {
+    s: Vec<Rq>,
+    pk: PublicKey,
+    pk_hash: [u8; 32],
+    z: [u8; 32],
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ciphertext {
+    pub u: Vec<Vec<u16>>,
+    pub v: Vec<u16>,
+}
+
+fn expand(seed: &[u8], domain: &[u8], len: usize) -> Vec<u8> {
+    let mut out = Vec::with_capacity(len + 32);
+    let mut counter: u32 = 0;
+    while out.len() < len {
+        let mut input = seed.to_vec();
+        input.extend_from_slice(domain);
+        input.extend_from_slice(&counter.to_be_bytes());
+        out.extend_from_slice(&sha256(&input));
+        counter += 1;
+    }
+    out.truncate(len);
+    out
+}
+
+fn hash_pair(a: &[u8], b: &[u8]) -> ([u8; 32], [u8; 32]) {
+    let mut input = a.to_vec();
+    input.extend_from_slice(b);
+    let out = expand(&input, b"G", 64);
+    let mut first = [0u8; 32];
+    let mut second = [0u8; 32];
+    first.copy_from_slice(&out[..32]);
+    second.copy_from_slice(&out[32..]);
+    (first, second)
+}
+
+// 12 ビットずつ取り出し q 未満のものだけを採用する棄却サンプリング
+fn sample_uniform(rho: &[u8; 32], i: u8, j: u8) -> Rq {
+    let mut poly = Rq::zero();
+    let mut filled = 0;
+    let mut block: u32 = 0;
+    while filled < N {
+        let bytes = expand(rho, &[b'A', j, i, (block >> 8) as u8, block as u8], 168);
+        for chunk in bytes.chunks(3) {
+            let d1 = chunk[0] as u32 | ((chunk[1] as u32 & 0x0f) << 8);
+            let d2 = (chunk[1] as u32 >> 4) | ((chunk[2] as u32) << 4);
+            for d in [d1, d2] {
+                if d < Q && filled < N {
+                    poly.coeffs[filled] = d;
+                    filled += 1;
+                }
+            }
+        }
+        block += 1;
+    }
+    poly
+}
+
+// 中心二項分布 CBD_η: (a_1 + ... + a_η) - (b_1 + ... + b_η)
+fn sample_cbd(sigma: &[u8; 32], nonce: u8) -> Rq {
+    let bytes = expand(sigma, &[b'P', nonce], 64 * ETA);
+    let bit = |k: usize| ((bytes[k / 8] >> (k % 8)) & 1) as i64;
+    let mut coeffs = [0i64; N];
+    for (i, c) in coeffs.iter_mut().enumerate() {
+        let a: i64 = (0..ETA).map(|j| bit(2 * i * ETA + j)).sum();
+        let b: i64 = (0..ETA).map(|j| bit(2 * i * ETA + ETA + j)).sum();
+        *c = a - b;
+    }
+    Rq::from_coeffs(&coeffs)
+}
+
+fn matrix(rho: &[u8; 32], transpose: bool) -> Vec<Vec<Rq>> {
+    (0..K)
+        .map(|i| {
+            (0..K)
+                .map(|j| {
+                    if transpose {
+                        sample_uniform(rho, j as u8, i as u8)
+                    } else {
+                        sample_uniform(rho, i as u8, j as u8)
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn mat_vec(a: &[Vec<Rq>], v: &[Rq]) -> Vec<Rq> {
+    a.iter().map(|row| dot(row, v)).collect()
+}
+
+fn dot(a: &[Rq], b: &[Rq]) -> Rq {
+    a.iter()
+        .zip(b)
+        .fold(Rq::zero(), |acc, (x, y)| acc.add(&x.mul(y)))
+}
+
+// Compress_d(x) = round(2^d / q * x) mod 2^d
+pub fn compress(poly: &Rq, d: u32) -> Vec<u16> {
+    poly.coeffs
+        .iter()
+        .map(|&x| ((((x << d) + Q / 2) / Q) & ((1 << d) - 1)) as u16)
+        .collect()
+}
+
+// Decompress_d(y) = round(q / 2^d * y)
+pub fn decompress(values: &[u16], d: u32) -> Rq {
+    let mut poly = Rq::zero();
+    for (c, &y) in poly.coeffs.iter_mut().zip(values) {
+        *c = (y as u32 * Q + (1 << (d - 1))) >> d;
+    }
+    poly
+}
+
+fn encode_message(m: &[u8; 32]) -> Rq {
+    let bits: Vec<u16> = (0..N).map(|i| ((m[i / 8] >> (i % 8)) & 1) as u16).collect();
+    decompress(&bits, 1)
+}
+
+fn decode_message(poly: &Rq) -> [u8; 32] {
+    let mut m = [0u8; 32];
+    for (i, bit) in compress(poly, 1).into_iter().enumerate() {
+        m[i / 8] |= (bit as u8) << (i % 8);
+    }
+    m
+}
+
+impl PublicKey {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        for poly in &self.t {
+            for &c in poly.coeffs.iter() {
+                out.extend_from_slice(&(c as u16).to_le_bytes());
+            }
+        }
+        out.extend_from_slice(&self.rho);
+        out
+    }
+}
+
+impl Ciphertext {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        for values in self.u.iter().chain(std::iter::once(&self.v)) {
+            for &c in values {
+                out.extend_from_slice(&c.to_le_bytes());
+            }
+        }
+        out
+    }
+}
+
+fn pke_encrypt(pk: &PublicKey, m: &[u8; 32], coins: &[u8; 32]) -> Ciphertext {
+    let at = matrix(&pk.rho, true);
+    let r: Vec<Rq> = (0..K).map(|i| sample_cbd(coins, i as u8)).collect();
+    let e1: Vec<Rq> = (0..K).map(|i| sample_cbd(coins, (K + i) as u8)).collect();
+    let e2 = sample_cbd(coins, (2 * K) as u8);
+
+    // u = A^T r + e1, v = t^T r + e2 + Decompress_1(m)
+    let u: Vec<Rq> = mat_vec(&at, &r)
+        .iter()
+        .zip(&e1)
+        .map(|(x, e)| x.add(e))
+        .collect();
+    let v = dot(&pk.t, &r).add(&e2).add(&encode_message(m));
+    Ciphertext {
+        u: u.iter().map(|p| compress(p, DU)).collect(),
+        v: compress(&v, DV),
+    }
+}
+
+fn pke_decrypt(s: &[Rq], ct: &Ciphertext) -> [u8; 32] {
+    let u: Vec<Rq> = ct.u.iter().map(|c| decompress(c, DU)).collect();
+    let v = decompress(&ct.v, DV);
+    decode_message(&v.sub(&dot(s, &u)))
+}
+
+pub fn keygen<R: RngCore>(rng: &mut R) -> (PublicKey, SecretKey) {
+    let mut d = [0u8; 32];
+    let mut z = [0u8; 32];
+    rng.fill_bytes(&mut d);
+    rng.fill_bytes(&mut z);
+
+    let (rho, sigma) = hash_pair(&d, &[]);
+    let a = matrix(&rho, false);
+    let s: Vec<Rq> = (0..K).map(|i| sample_cbd(&sigma, i as u8)).collect();
+    let e: Vec<Rq> = (0..K).map(|i| sample_cbd(&sigma, (K + i) as u8)).collect();
+
+    // t = A s + e
+    let t = mat_vec(&a, &s)
+        .iter()
+        .zip(&e)
+        .map(|(x, e)| x.add(e))
+        .collect();
+    let pk = PublicKey { t, rho };
+    let pk_hash = sha256(&pk.to_bytes());
+    let sk = SecretKey {
+        s,
+        pk: pk.clone(),
+        pk_hash,
+        z,
+    };
+    (pk, sk)
+}
+
+pub fn encaps<R: RngCore>(pk: &PublicKey, rng: &mut R) -> (Ciphertext, [u8; 32]) {
+    let mut m = [0u8; 32];
+    rng.fill_bytes(&mut m);
+    let (shared, coins) = hash_pair(&m, &sha256(&pk.to_bytes()));
+    (pke_encrypt(pk, &m, &coins), shared)
+}
+
+// 再暗号化で検証し、不一致なら z から導いた擬似乱数鍵を返す (暗黙的拒否)
+pub fn decaps(sk: &SecretKey, ct: &Ciphertext) -> [u8; 32] {
+    let m = pke_decrypt(&sk.s, ct);
+    let (shared, coins) = hash_pair(&m, &sk.pk_hash);
+    if pke_encrypt(&sk.pk, &m, &coins) == *ct {
+        shared
+    } else {
+        let mut input = sk.z.to_vec();
+        input.extend_from_slice(&ct.to_bytes());
+        sha256(&input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::OsRng;
+
+    #[test]
+    fn test_encaps_decaps_roundtrip() {
+        let (pk, sk) = keygen(&mut OsRng);
+        let (ct, shared) = encaps(&pk, &mut OsRng);
+        assert_eq!(decaps(&sk, &ct), shared);
+    }
+
+    #[test]
+    fn test_tampered_ciphertext_is_rejected() {
+        let (pk, sk) = keygen(&mut OsRng);
+        let (mut ct, shared) = encaps(&pk, &mut OsRng);
+        ct.v[0] ^= 1 << (DV - 1);
+        assert_ne!(decaps(&sk, &ct), shared);
+    }
+
+    #[test]
+    fn test_compress_roundtrip_error_is_small() {
+        let poly = Rq::from_coeffs(&(0..N as i64).map(|i| i * 13).collect::<Vec<_>>());
+        let back = decompress(&compress(&poly, DU), DU);
+        for (a, b) in poly.coeffs.iter().zip(back.coeffs.iter()) {
+            let diff = (*a as i64 - *b as i64).rem_euclid(Q as i64);
+            assert!(diff.min(Q as i64 - diff) <= (Q as i64 >> (DU + 1)) + 1);
+        }
+    }
+}
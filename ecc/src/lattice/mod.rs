@@ -0,0 +1,3 @@
+pub mod kyber;
+pub mod ntru;
+pub mod ring;
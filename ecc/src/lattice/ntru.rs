@@ -0,0 +1,259 @@
+// 小さいパラメータの NTRU 暗号 (Z[X] / (X^N - 1) 上の畳み込み環)
+use crate::rand::RngCore;
+
+pub const N: usize = 107;
+pub const P: i64 = 3;
+pub const Q: i64 = 64;
+pub const DF: usize = 15;
+pub const DG: usize = 12;
+pub const DR: usize = 5;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TruncPoly {
+    pub coeffs: Vec<i64>,
+}
+
+impl TruncPoly {
+    pub fn zero() -> Self {
+        TruncPoly { coeffs: vec![0; N] }
+    }
+
+    pub fn from_coeffs(coeffs: &[i64]) -> Self {
+        if coeffs.len() > N {
+            panic!("Polynomial degree must be less than {}", N);
+        }
+        let mut out = TruncPoly::zero();
+        out.coeffs[..coeffs.len()].copy_from_slice(coeffs);
+        out
+    }
+
+    pub fn add(&self, other: &TruncPoly) -> TruncPoly {
+        let coeffs = self
+            .coeffs
+            .iter()
+            .zip(&other.coeffs)
+            .map(|(a, b)| a + b)
+            .collect();
+        TruncPoly { coeffs }
+    }
+
+    // X^N = 1 による巡回畳み込み
+    pub fn mul(&self, other: &TruncPoly) -> TruncPoly {
+        let mut out = TruncPoly::zero();
+        for (i, a) in self.coeffs.iter().enumerate() {
+            if *a == 0 {
+                continue;
+            }
+            for (j, b) in other.coeffs.iter().enumerate() {
+                out.coeffs[(i + j) % N] += a * b;
+            }
+        }
+        out
+    }
+
+    pub fn scale(&self, k: i64) -> TruncPoly {
+        TruncPoly {
+            coeffs: self.coeffs.iter().map(|c| c * k).collect(),
+        }
+    }
+
+    pub fn reduce(&self, modulus: i64) -> TruncPoly {
+        TruncPoly {
+            coeffs: self.coeffs.iter().map(|c| c.rem_euclid(modulus)).collect(),
+        }
+    }
+
+    // 係数を (-m/2, m/2] に持ち上げる
+    pub fn center_lift(&self, modulus: i64) -> TruncPoly {
+        TruncPoly {
+            coeffs: self
+                .coeffs
+                .iter()
+                .map(|c| {
+                    let r = c.rem_euclid(modulus);
+                    if r > modulus / 2 {
+                        r - modulus
+                    } else {
+                        r
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    // 素数 p を法とした逆元 (X^N - 1 との拡張ユークリッド互除法)
+    pub fn inverse_mod_prime(&self, p: i64) -> Option<TruncPoly> {
+        let mut modulus = vec![0i64; N + 1];
+        modulus[0] = p - 1;
+        modulus[N] = 1;
+
+        let (mut r0, mut r1) = (modulus, trim(self.reduce(p).coeffs));
+        let (mut t0, mut t1) = (vec![], vec![1i64]);
+        while !r1.is_empty() {
+            let (quot, rem) = poly_divmod(&r0, &r1, p);
+            let t2 = poly_sub(&t0, &poly_mul(&quot, &t1, p), p);
+            r0 = r1;
+            r1 = rem;
+            t0 = t1;
+            t1 = t2;
+        }
+        // gcd が定数でなければ逆元は存在しない
+        if r0.len() != 1 {
+            return None;
+        }
+        let scale = mod_inverse(r0[0], p);
+        let mut out = TruncPoly::zero();
+        for (i, c) in t0.iter().enumerate() {
+            out.coeffs[i % N] = (out.coeffs[i % N] + c * scale).rem_euclid(p);
+        }
+        Some(out)
+    }
+
+    // q = 2^k を法とした逆元 (mod 2 の逆元からのニュートン反復)
+    pub fn inverse_mod_power_of_two(&self, q: i64) -> Option<TruncPoly> {
+        let mut inv = self.inverse_mod_prime(2)?;
+        let two = TruncPoly::from_coeffs(&[2]);
+        let mut precision = 2;
+        while precision < q {
+            precision *= precision;
+            let correction = two.add(&self.mul(&inv).scale(-1));
+            inv = inv.mul(&correction).reduce(q);
+        }
+        Some(inv.reduce(q))
+    }
+}
+
+fn trim(mut v: Vec<i64>) -> Vec<i64> {
+    while v.last() == Some(&0) {
+        v.pop();
+    }
+    v
+}
+
+fn mod_inverse(a: i64, p: i64) -> i64 {
+    (1..p).find(|x| (a * x).rem_euclid(p) == 1).unwrap()
+}
+
+fn poly_sub(a: &[i64], b: &[i64], p: i64) -> Vec<i64> {
+    let len = a.len().max(b.len());
+    let out = (0..len)
+        .map(|i| (a.get(i).unwrap_or(&0) - b.get(i).unwrap_or(&0)).rem_euclid(p))
+        .collect();
+    trim(out)
+}
+
+fn poly_mul(a: &[i64], b: &[i64], p: i64) -> Vec<i64> {
+    if a.is_empty() || b.is_empty() {
+        return vec![];
+    }
+    let mut out = vec![0i64; a.len() + b.len() - 1];
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            out[i + j] = (out[i + j] + x * y).rem_euclid(p);
+        }
+    }
+    trim(out)
+}
+
+fn poly_divmod(a: &[i64], b: &[i64], p: i64) -> (Vec<i64>, Vec<i64>) {
+    let mut rem = trim(a.to_vec());
+    if rem.len() < b.len() {
+        return (vec![], rem);
+    }
+    let mut quot = vec![0i64; rem.len() - b.len() + 1];
+    let lead_inv = mod_inverse(*b.last().unwrap(), p);
+    while rem.len() >= b.len() {
+        let shift = rem.len() - b.len();
+        let coef = (rem.last().unwrap() * lead_inv).rem_euclid(p);
+        quot[shift] = coef;
+        for (i, c) in b.iter().enumerate() {
+            rem[shift + i] = (rem[shift + i] - coef * c).rem_euclid(p);
+        }
+        rem = trim(rem);
+    }
+    (trim(quot), rem)
+}
+
+// +1 を ones 個、-1 を minus_ones 個持つ三値多項式
+pub fn random_ternary<R: RngCore>(ones: usize, minus_ones: usize, rng: &mut R) -> TruncPoly {
+    let mut coeffs = vec![0i64; N];
+    for c in coeffs.iter_mut().take(ones) {
+        *c = 1;
+    }
+    for c in coeffs.iter_mut().skip(ones).take(minus_ones) {
+        *c = -1;
+    }
+    // Fisher-Yates シャッフル
+    for i in (1..N).rev() {
+        let j = (rng.next_u64() % (i as u64 + 1)) as usize;
+        coeffs.swap(i, j);
+    }
+    TruncPoly { coeffs }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicKey {
+    pub h: TruncPoly,
+}
+
+#[derive(Debug, Clone)]
+pub struct SecretKey {
+    f: TruncPoly,
+    f_p: TruncPoly,
+}
+
+pub fn keygen<R: RngCore>(rng: &mut R) -> (PublicKey, SecretKey) {
+    loop {
+        let f = random_ternary(DF, DF - 1, rng);
+        // f は p と q の両方で可逆でなければならない
+        let (f_p, f_q) = match (f.inverse_mod_prime(P), f.inverse_mod_power_of_two(Q)) {
+            (Some(f_p), Some(f_q)) => (f_p, f_q),
+            _ => continue,
+        };
+        let g = random_ternary(DG, DG, rng);
+        // h = p * F_q * g mod q
+        let h = f_q.mul(&g).scale(P).reduce(Q);
+        return (PublicKey { h }, SecretKey { f, f_p });
+    }
+}
+
+// m は {-1, 0, 1} の係数を持つ多項式
+pub fn encrypt<R: RngCore>(pk: &PublicKey, m: &TruncPoly, rng: &mut R) -> TruncPoly {
+    let r = random_ternary(DR, DR, rng);
+    r.mul(&pk.h).add(m).reduce(Q)
+}
+
+pub fn decrypt(sk: &SecretKey, e: &TruncPoly) -> TruncPoly {
+    let a = sk.f.mul(e).center_lift(Q);
+    sk.f_p.mul(&a).center_lift(P)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::OsRng;
+
+    #[test]
+    fn test_inverse_mod_prime_and_power_of_two() {
+        let f = random_ternary(DF, DF - 1, &mut OsRng);
+        let one = TruncPoly::from_coeffs(&[1]);
+        if let Some(f_p) = f.inverse_mod_prime(P) {
+            assert_eq!(f.mul(&f_p).reduce(P), one);
+        }
+        if let Some(f_q) = f.inverse_mod_power_of_two(Q) {
+            assert_eq!(f.mul(&f_q).reduce(Q), one);
+        }
+        // X - 1 は X^N - 1 の因子なので可逆でない
+        assert!(TruncPoly::from_coeffs(&[-1, 1])
+            .inverse_mod_prime(P)
+            .is_none());
+    }
+
+    #[test]
+    fn test_encrypt_decrypt_roundtrip() {
+        let (pk, sk) = keygen(&mut OsRng);
+        let m = random_ternary(20, 20, &mut OsRng);
+        let e = encrypt(&pk, &m, &mut OsRng);
+        assert_eq!(decrypt(&sk, &e), m);
+    }
+}
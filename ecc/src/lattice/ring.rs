@@ -0,0 +1,84 @@
+// R_q = Z_q[X] / (X^N + 1)
+pub const N: usize = 256;
+pub const Q: u32 = 3329;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rq {
+    pub coeffs: [u32; N],
+}
+
+impl Rq {
+    pub fn zero() -> Self {
+        Rq { coeffs: [0; N] }
+    }
+
+    pub fn from_coeffs(coeffs: &[i64]) -> Self {
+        if coeffs.len() > N {
+            panic!("Polynomial degree must be less than {}", N);
+        }
+        let mut out = Rq::zero();
+        for (c, &v) in out.coeffs.iter_mut().zip(coeffs) {
+            *c = v.rem_euclid(Q as i64) as u32;
+        }
+        out
+    }
+
+    pub fn add(&self, other: &Rq) -> Rq {
+        let mut out = Rq::zero();
+        for i in 0..N {
+            out.coeffs[i] = (self.coeffs[i] + other.coeffs[i]) % Q;
+        }
+        out
+    }
+
+    pub fn sub(&self, other: &Rq) -> Rq {
+        let mut out = Rq::zero();
+        for i in 0..N {
+            out.coeffs[i] = (self.coeffs[i] + Q - other.coeffs[i]) % Q;
+        }
+        out
+    }
+
+    // X^N = -1 を使った素朴な負巡回畳み込み
+    pub fn mul(&self, other: &Rq) -> Rq {
+        let mut acc = [0i64; N];
+        for i in 0..N {
+            for j in 0..N {
+                let prod = self.coeffs[i] as i64 * other.coeffs[j] as i64;
+                if i + j < N {
+                    acc[i + j] += prod;
+                } else {
+                    acc[i + j - N] -= prod;
+                }
+            }
+        }
+        let mut out = Rq::zero();
+        for (c, v) in out.coeffs.iter_mut().zip(acc) {
+            *c = v.rem_euclid(Q as i64) as u32;
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negacyclic_wraparound() {
+        // X^(N-1) * X = X^N = -1
+        let mut x_top = [0i64; N];
+        x_top[N - 1] = 1;
+        let a = Rq::from_coeffs(&x_top);
+        let b = Rq::from_coeffs(&[0, 1]);
+        assert_eq!(a.mul(&b), Rq::from_coeffs(&[-1]));
+    }
+
+    #[test]
+    fn test_mul_distributes_over_add() {
+        let a = Rq::from_coeffs(&[1, 2, 3, 4]);
+        let b = Rq::from_coeffs(&[5, -6, 7]);
+        let c = Rq::from_coeffs(&[3328, 0, 0, 12]);
+        assert_eq!(a.mul(&b.add(&c)), a.mul(&b).add(&a.mul(&c)));
+    }
+}
@@ -1,26 +1,65 @@
-#[derive(Debug, Clone, PartialEq, Eq)]
-struct FieldElement {
-    num: u64,
-    prime: u64,
+pub mod accumulator;
+pub mod attacks;
+pub mod beacon;
+pub mod bignum;
+pub mod binary_field;
+pub mod bitcoin;
+pub mod bulletproofs;
+pub mod coinflip;
+pub mod commitment;
+pub mod curve;
+pub mod elgamal;
+pub mod encoding;
+pub mod hash;
+pub mod kdf;
+pub mod kem;
+pub mod lattice;
+pub mod mac;
+pub mod merkle;
+pub mod mixnet;
+pub mod mpc;
+pub mod number_theory;
+pub mod plonk;
+pub mod r1cs;
+pub mod rand;
+pub mod rsa;
+pub mod secp256k1;
+pub mod sigma;
+pub mod spake2;
+pub mod symmetric;
+pub mod timelock;
+pub mod transcript;
+pub mod vdf;
+pub mod vrf;
+
+use number_theory::{mod_inverse, mod_inverse_ct};
+
+#[cfg(test)]
+mod test_util;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FieldElement {
+    pub num: u64,
+    pub prime: u64,
 }
 
 impl FieldElement {
-    fn new(num: u64, prime: u64) -> Self {
+    pub fn new(num: u64, prime: u64) -> Self {
         if num >= prime {
             panic!("Num {} not in field range 0 to {}", num, prime - 1);
         }
         FieldElement { num, prime }
     }
 
-    fn add(&self, other: &FieldElement) -> FieldElement {
+    pub fn add(&self, other: &FieldElement) -> FieldElement {
         if self.prime != other.prime {
             panic!("Cannot add two numbers in different Fields");
         }
-        let num = (self.num + other.num) % self.prime;
+        let num = ((self.num as u128 + other.num as u128) % self.prime as u128) as u64;
         FieldElement::new(num, self.prime)
     }
 
-    fn sub(&self, other: &FieldElement) -> FieldElement {
+    pub fn sub(&self, other: &FieldElement) -> FieldElement {
         if self.prime != other.prime {
             panic!("Cannot subtract two numbers in different Fields");
         }
@@ -28,56 +67,72 @@ impl FieldElement {
         FieldElement::new(num, self.prime)
     }
 
-    fn mul(&self, other: &FieldElement) -> FieldElement {
+    pub fn mul(&self, other: &FieldElement) -> FieldElement {
         if self.prime != other.prime {
             panic!("Cannot multiply two numbers in different Fields");
         }
-        let num = (self.num * other.num) % self.prime;
+        let num = ((self.num as u128 * other.num as u128) % self.prime as u128) as u64;
         FieldElement::new(num, self.prime)
     }
 
-    fn pow(&self, exponent: u64) -> FieldElement {
-        let exp = exponent % (self.prime - 1);
-        let num = self.num.pow(exp as u32) % self.prime;
-        FieldElement::new(num, self.prime)
+    pub fn pow(&self, exponent: u64) -> FieldElement {
+        let mut exp = exponent % (self.prime - 1);
+        let mut base = self.clone();
+        let mut result = FieldElement::new(1, self.prime);
+        // 二乗と乗算による冪乗
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result.mul(&base);
+            }
+            base = base.mul(&base);
+            exp >>= 1;
+        }
+        result
     }
 
-    fn div(&self, other: &FieldElement) -> FieldElement {
+    pub fn div(&self, other: &FieldElement) -> FieldElement {
         if self.prime != other.prime {
             panic!("Cannot divide two numbers in different Fields");
         }
-        let num = (self.num * other.pow(self.prime - 2).num) % self.prime;
-        FieldElement::new(num, self.prime)
+        // 秘密値の逆元になりうるので、奇素数では定数時間版を使う
+        let inv = if self.prime % 2 == 1 {
+            mod_inverse_ct(other.num, self.prime)
+        } else {
+            mod_inverse(other.num, self.prime)
+        }
+        .expect("Cannot divide by zero");
+        self.mul(&FieldElement::new(inv, self.prime))
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-struct Point {
-    x: Option<FieldElement>,
-    y: Option<FieldElement>,
-    a: FieldElement,
-    b: FieldElement,
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Point {
+    pub x: Option<FieldElement>,
+    pub y: Option<FieldElement>,
+    pub a: FieldElement,
+    pub b: FieldElement,
 }
 
 impl Point {
-    fn new(
+    pub fn new(
         x: Option<FieldElement>,
         y: Option<FieldElement>,
         a: FieldElement,
         b: FieldElement,
     ) -> Self {
-        match (&x, &y) {
-            (Some(x), Some(y)) => {
-                if y.pow(2) != x.pow(3).add(&a.mul(x).add(&b)) {
-                    panic!("({},,{} is not on the curve", x.num, y.num);
-                }
+        if let (Some(x), Some(y)) = (&x, &y) {
+            if y.pow(2) != x.pow(3).add(&a.mul(x).add(&b)) {
+                panic!("({}, {}) is not on the curve", x.num, y.num);
             }
-            _ => {}
         }
         Point { x, y, a, b }
     }
 
-    fn add(&self, other: &Point) -> Point {
+    pub fn is_infinity(&self) -> bool {
+        self.x.is_none()
+    }
+
+    pub fn add(&self, other: &Point) -> Point {
         if self.a != other.a || self.b != other.b {
             panic!("Points are not on the same curve");
         }
@@ -95,7 +150,8 @@ impl Point {
         let x2 = other.x.as_ref().unwrap();
         let y2 = other.y.as_ref().unwrap();
 
-        if x1 == x2 && y1 != y2 {
+        // P + (-P) および y = 0 の点の 2 倍は無限遠点
+        if x1 == x2 && (y1 != y2 || y1.num == 0) {
             return Point::new(None, None, self.a.clone(), self.b.clone());
         }
 
@@ -117,7 +173,22 @@ impl Point {
         Point::new(Some(x3), Some(y3), self.a.clone(), self.b.clone())
     }
 
-    fn scalar_mul(&self, coefficient: u64) -> Point {
+    pub fn neg(&self) -> Point {
+        match (&self.x, &self.y) {
+            (Some(x), Some(y)) => {
+                let zero = FieldElement::new(0, y.prime);
+                Point::new(
+                    Some(x.clone()),
+                    Some(zero.sub(y)),
+                    self.a.clone(),
+                    self.b.clone(),
+                )
+            }
+            _ => self.clone(),
+        }
+    }
+
+    pub fn scalar_mul(&self, coefficient: u64) -> Point {
         let mut coef = coefficient;
         let mut current = self.clone();
         let mut result = Point::new(None, None, self.a.clone(), self.b.clone());
@@ -131,6 +202,19 @@ impl Point {
         }
         result
     }
+
+    // 無限遠点は 0x00、それ以外は 0x04 || x || y (ビッグエンディアン)
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match (&self.x, &self.y) {
+            (Some(x), Some(y)) => {
+                let mut out = vec![0x04];
+                out.extend_from_slice(&x.num.to_be_bytes());
+                out.extend_from_slice(&y.num.to_be_bytes());
+                out
+            }
+            _ => vec![0x00],
+        }
+    }
 }
 
 #[cfg(test)]
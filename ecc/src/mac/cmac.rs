@@ -0,0 +1,130 @@
+// CMAC (RFC 4493, NIST SP 800-38B)。CBC-MAC の最後のブロックを、暗号化した 0 から導く
+// 部分鍵でマスクして任意長のメッセージに安全に使えるようにしたもの
+use super::ct_eq;
+use crate::symmetric::BlockCipher;
+
+// GF(2^n) で x を掛ける。ブロックを最上位ビット先頭の整数とみなす
+pub(crate) fn dbl(block: &[u8]) -> Vec<u8> {
+    let rb = match block.len() {
+        16 => 0x87,
+        8 => 0x1b,
+        n => panic!("CMAC is not defined for {}-byte blocks", n),
+    };
+    let mut out = vec![0u8; block.len()];
+    let mut carry = 0;
+    for (o, &b) in out.iter_mut().zip(block).rev() {
+        *o = (b << 1) | carry;
+        carry = b >> 7;
+    }
+    *out.last_mut().unwrap() ^= rb * carry;
+    out
+}
+
+#[derive(Clone)]
+pub struct Cmac<C: BlockCipher> {
+    cipher: C,
+    k1: Vec<u8>,
+    k2: Vec<u8>,
+    state: Vec<u8>,
+    // 最後のブロックは finalize まで処理せずに持っておく
+    buffer: Vec<u8>,
+}
+
+impl<C: BlockCipher> Cmac<C> {
+    pub fn new(cipher: C) -> Self {
+        let mut l = vec![0u8; C::BLOCK_SIZE];
+        cipher.encrypt_block(&mut l);
+        let k1 = dbl(&l);
+        let k2 = dbl(&k1);
+        Cmac {
+            cipher,
+            k1,
+            k2,
+            state: vec![0; C::BLOCK_SIZE],
+            buffer: Vec::with_capacity(C::BLOCK_SIZE),
+        }
+    }
+
+    fn absorb(&mut self, block: &[u8]) {
+        for (s, b) in self.state.iter_mut().zip(block) {
+            *s ^= b;
+        }
+        self.cipher.encrypt_block(&mut self.state);
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            if self.buffer.len() == C::BLOCK_SIZE {
+                let block = std::mem::take(&mut self.buffer);
+                self.absorb(&block);
+            }
+            let take = (C::BLOCK_SIZE - self.buffer.len()).min(data.len());
+            self.buffer.extend_from_slice(&data[..take]);
+            data = &data[take..];
+        }
+    }
+
+    pub fn finalize(mut self) -> Vec<u8> {
+        // 完全なブロックなら K1、そうでなければ 10..0 で埋めて K2 を XOR する
+        let mut last = std::mem::take(&mut self.buffer);
+        let mask = if last.len() == C::BLOCK_SIZE {
+            &self.k1
+        } else {
+            last.push(0x80);
+            last.resize(C::BLOCK_SIZE, 0);
+            &self.k2
+        };
+        for (b, k) in last.iter_mut().zip(mask) {
+            *b ^= k;
+        }
+        self.absorb(&last);
+        self.state
+    }
+
+    pub fn verify(self, tag: &[u8]) -> bool {
+        ct_eq(&self.finalize(), tag)
+    }
+}
+
+pub fn cmac<C: BlockCipher + Clone>(cipher: &C, data: &[u8]) -> Vec<u8> {
+    let mut mac = Cmac::new(cipher.clone());
+    mac.update(data);
+    mac.finalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::symmetric::{Aes, TripleDes};
+    use crate::test_util::{hex, unhex};
+
+    #[test]
+    fn test_rfc4493_vectors() {
+        let aes = Aes::new(&unhex("2b7e151628aed2a6abf7158809cf4f3c"));
+        let msg = unhex("6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e5130c81c46a35ce411e5fbc1191a0a52eff69f2445df4f9b17ad2b417be66c3710");
+        let cases = [
+            (0, "bb1d6929e95937287fa37d129b756746"),
+            (16, "070a16b46b4d4144f79bdd9dd04a287c"),
+            (40, "dfa66747de9ae63030ca32611497c827"),
+            (64, "51f0bebf7e3b9d92fc49741779363cfe"),
+        ];
+        for (len, expected) in cases {
+            assert_eq!(hex(&cmac(&aes, &msg[..len])), expected);
+            let mut mac = Cmac::new(aes.clone());
+            for chunk in msg[..len].chunks(7) {
+                mac.update(chunk);
+            }
+            assert!(mac.verify(&unhex(expected)));
+        }
+        // 部分鍵 (RFC 4493 2.3 の K1, K2)
+        let mac = Cmac::new(aes);
+        assert_eq!(hex(&mac.k1), "fbeed618357133667c85e08f7236a8de");
+        assert_eq!(hex(&mac.k2), "f7ddac306ae266ccf90bc11ee46d513b");
+    }
+
+    #[test]
+    fn test_cmac_with_64_bit_blocks() {
+        let tdes = TripleDes::new(&unhex("0123456789abcdef23456789abcdef01456789abcdef0123"));
+        assert_eq!(hex(&cmac(&tdes, b"hello cmac")), "174d95a1818c3d11");
+    }
+}
@@ -0,0 +1,109 @@
+// HMAC (RFC 2104): H((K ^ opad) || H((K ^ ipad) || m))
+use super::ct_eq;
+use crate::hash::Digest;
+
+#[derive(Clone)]
+pub struct Hmac<D: Digest> {
+    inner: D,
+    outer: D,
+}
+
+impl<D: Digest> Hmac<D> {
+    pub fn new(key: &[u8]) -> Self {
+        // ブロック長より長い鍵は先にハッシュする
+        let mut block = if key.len() > D::BLOCK_SIZE {
+            let mut h = D::new();
+            h.update(key);
+            h.finalize()
+        } else {
+            key.to_vec()
+        };
+        block.resize(D::BLOCK_SIZE, 0);
+
+        let mut inner = D::new();
+        inner.update(&block.iter().map(|b| b ^ 0x36).collect::<Vec<u8>>());
+        let mut outer = D::new();
+        outer.update(&block.iter().map(|b| b ^ 0x5c).collect::<Vec<u8>>());
+        Hmac { inner, outer }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    pub fn finalize(self) -> Vec<u8> {
+        let mut outer = self.outer;
+        outer.update(&self.inner.finalize());
+        outer.finalize()
+    }
+
+    pub fn verify(self, tag: &[u8]) -> bool {
+        ct_eq(&self.finalize(), tag)
+    }
+}
+
+pub fn hmac<D: Digest>(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<D>::new(key);
+    mac.update(data);
+    mac.finalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::{Sha1, Sha256, Sha384, Sha512};
+    use crate::test_util::hex;
+
+    #[test]
+    fn test_rfc4231_vectors() {
+        let cases: [(&[u8], &[u8], &str, &str); 3] = [
+            (
+                &[0x0b; 20],
+                b"Hi There",
+                "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7",
+                "87aa7cdea5ef619d4ff0b4241a1d6cb02379f4e2ce4ec2787ad0b30545e17cdedaa833b7d6b8a702038b274eaea3f4e4be9d914eeb61f1702e696c203a126854",
+            ),
+            (
+                b"Jefe",
+                b"what do ya want for nothing?",
+                "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+                "164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea2505549758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737",
+            ),
+            // ブロック長より長い鍵
+            (
+                &[0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First",
+                "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54",
+                "80b24263c7c1a3ebb71493c1dd7be8b49b46d1f41b4aeec1121b013783f8f3526b56d037e05f2598bd0fd2215d6a1e5295e64f73f63f0aec8b915a985d786598",
+            ),
+        ];
+        for (key, data, tag256, tag512) in cases {
+            assert_eq!(hex(&hmac::<Sha256>(key, data)), tag256);
+            assert_eq!(hex(&hmac::<Sha512>(key, data)), tag512);
+        }
+        assert_eq!(
+            hex(&hmac::<Sha384>(b"Jefe", b"what do ya want for nothing?")),
+            "af45d2e376484031617f78d2b58a6b1b9c7ef464f5a01b47e42ec3736322445e8e2240ca5e69e2c78b3239ecfab21649"
+        );
+        assert_eq!(
+            hex(&hmac::<Sha1>(
+                b"key",
+                b"The quick brown fox jumps over the lazy dog"
+            )),
+            "de7c9b85b8b78aa6bc8a7a36f70a90701c9db4d9"
+        );
+    }
+
+    #[test]
+    fn test_verify() {
+        let tag = hmac::<Sha256>(b"key", b"message");
+        let mut mac = Hmac::<Sha256>::new(b"key");
+        mac.update(b"mess");
+        mac.update(b"age");
+        assert!(mac.clone().verify(&tag));
+        let mut wrong = tag.clone();
+        wrong[31] ^= 1;
+        assert!(!mac.clone().verify(&wrong));
+        assert!(!mac.verify(&tag[..16]));
+    }
+}
@@ -0,0 +1,12 @@
+pub mod cmac;
+pub mod hmac;
+pub mod poly1305;
+
+pub use cmac::{cmac, Cmac};
+pub use hmac::{hmac, Hmac};
+pub use poly1305::{poly1305, Poly1305};
+
+// 長さ以外の情報を実行時間から漏らさない比較
+pub fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
@@ -0,0 +1,197 @@
+// Poly1305 (RFC 8439)。p = 2^130 - 5 上で、16 バイトずつの多項式を r で評価し s を足す。
+// 1 つの鍵は 1 つのメッセージにしか使えない
+use super::ct_eq;
+
+const MASK26: u32 = (1 << 26) - 1;
+
+#[derive(Clone)]
+pub struct Poly1305 {
+    // r と累積値 h を 26 ビット × 5 の limb で持つ
+    r: [u32; 5],
+    h: [u32; 5],
+    s: [u32; 4],
+    buffer: [u8; 16],
+    buffer_len: usize,
+}
+
+fn le32(b: &[u8]) -> u32 {
+    u32::from_le_bytes(b.try_into().unwrap())
+}
+
+impl Poly1305 {
+    pub fn new(key: &[u8; 32]) -> Self {
+        // r のクランプ: 特定のビットを 0 にして乗算で桁あふれしないようにする
+        let r = [
+            le32(&key[0..4]) & 0x3ffffff,
+            (le32(&key[3..7]) >> 2) & 0x3ffff03,
+            (le32(&key[6..10]) >> 4) & 0x3ffc0ff,
+            (le32(&key[9..13]) >> 6) & 0x3f03fff,
+            (le32(&key[12..16]) >> 8) & 0x00fffff,
+        ];
+        let s = [0, 1, 2, 3].map(|i| le32(&key[16 + 4 * i..20 + 4 * i]));
+        Poly1305 {
+            r,
+            h: [0; 5],
+            s,
+            buffer: [0; 16],
+            buffer_len: 0,
+        }
+    }
+
+    // hibit は完全なブロックでは 2^128 のビット、最後の端数ブロックでは 0
+    fn block(&mut self, m: &[u8; 16], hibit: u32) {
+        let r = self.r;
+        let h = &mut self.h;
+        h[0] += le32(&m[0..4]) & MASK26;
+        h[1] += (le32(&m[3..7]) >> 2) & MASK26;
+        h[2] += (le32(&m[6..10]) >> 4) & MASK26;
+        h[3] += (le32(&m[9..13]) >> 6) & MASK26;
+        h[4] += (le32(&m[12..16]) >> 8) | hibit;
+
+        // 2^130 ≡ 5 なので、桁が 5 を超える項は 5 倍して下に回す
+        let s = [0, r[1] * 5, r[2] * 5, r[3] * 5, r[4] * 5];
+        let mut d = [0u64; 5];
+        for (i, di) in d.iter_mut().enumerate() {
+            for (j, &hj) in h.iter().enumerate() {
+                let coeff = if j <= i { r[i - j] } else { s[5 + i - j] };
+                *di += hj as u64 * coeff as u64;
+            }
+        }
+
+        let mut carry = 0u64;
+        for i in 0..5 {
+            d[i] += carry;
+            h[i] = (d[i] as u32) & MASK26;
+            carry = d[i] >> 26;
+        }
+        h[0] += (carry * 5) as u32;
+        h[1] += h[0] >> 26;
+        h[0] &= MASK26;
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        if self.buffer_len > 0 {
+            let take = (16 - self.buffer_len).min(data.len());
+            self.buffer[self.buffer_len..self.buffer_len + take].copy_from_slice(&data[..take]);
+            self.buffer_len += take;
+            data = &data[take..];
+            if self.buffer_len < 16 {
+                return;
+            }
+            let block = self.buffer;
+            self.block(&block, 1 << 24);
+            self.buffer_len = 0;
+        }
+        let mut blocks = data.chunks_exact(16);
+        for block in &mut blocks {
+            self.block(block.try_into().unwrap(), 1 << 24);
+        }
+        let rest = blocks.remainder();
+        self.buffer[..rest.len()].copy_from_slice(rest);
+        self.buffer_len = rest.len();
+    }
+
+    pub fn finalize(mut self) -> [u8; 16] {
+        if self.buffer_len > 0 {
+            // 端数ブロックは 0x01 を付けてから 0 で埋める
+            let mut block = [0u8; 16];
+            block[..self.buffer_len].copy_from_slice(&self.buffer[..self.buffer_len]);
+            block[self.buffer_len] = 1;
+            self.block(&block, 0);
+        }
+
+        // h を完全に繰り上げてから、h - p が非負ならそちらを選ぶ
+        let mut h = self.h;
+        for i in 1..5 {
+            h[i] += h[i - 1] >> 26;
+            h[i - 1] &= MASK26;
+        }
+        h[0] += (h[4] >> 26) * 5;
+        h[4] &= MASK26;
+        h[1] += h[0] >> 26;
+        h[0] &= MASK26;
+
+        let mut g = [0u32; 5];
+        let mut carry = 5;
+        for i in 0..4 {
+            g[i] = h[i] + carry;
+            carry = g[i] >> 26;
+            g[i] &= MASK26;
+        }
+        g[4] = (h[4] + carry).wrapping_sub(1 << 26);
+        // g[4] の最上位ビットが立っていれば h < p
+        let mask = (g[4] >> 31).wrapping_sub(1);
+        for i in 0..5 {
+            h[i] = (h[i] & !mask) | (g[i] & mask);
+        }
+
+        // 2^128 を法として s を足す
+        let words = [
+            h[0] | (h[1] << 26),
+            (h[1] >> 6) | (h[2] << 20),
+            (h[2] >> 12) | (h[3] << 14),
+            (h[3] >> 18) | (h[4] << 8),
+        ];
+        let mut out = [0u8; 16];
+        let mut carry = 0u64;
+        for (i, chunk) in out.chunks_mut(4).enumerate() {
+            let sum = words[i] as u64 + self.s[i] as u64 + carry;
+            chunk.copy_from_slice(&(sum as u32).to_le_bytes());
+            carry = sum >> 32;
+        }
+        out
+    }
+
+    pub fn verify(self, tag: &[u8]) -> bool {
+        ct_eq(&self.finalize(), tag)
+    }
+}
+
+pub fn poly1305(key: &[u8; 32], data: &[u8]) -> [u8; 16] {
+    let mut mac = Poly1305::new(key);
+    mac.update(data);
+    mac.finalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{hex, unhex};
+
+    #[test]
+    fn test_rfc8439_vector() {
+        // 2.5.2
+        let key = unhex("85d6be7857556d337f4452fe42d506a80103808afb0db2fd4abff6af4149f51b")
+            .try_into()
+            .unwrap();
+        let msg = b"Cryptographic Forum Research Group";
+        assert_eq!(
+            hex(&poly1305(&key, msg)),
+            "a8061dc1305136c6c22b8baf0c0127a9"
+        );
+        let mut mac = Poly1305::new(&key);
+        for chunk in msg.chunks(5) {
+            mac.update(chunk);
+        }
+        assert!(mac
+            .clone()
+            .verify(&unhex("a8061dc1305136c6c22b8baf0c0127a9")));
+        assert!(!mac.verify(&[0u8; 16]));
+    }
+
+    #[test]
+    fn test_final_reduction_edge_cases() {
+        // r = 2, s = 0 で h = 2(2^129 - 1) = 2^130 - 2 ≡ 3
+        let mut key = [0u8; 32];
+        key[0] = 2;
+        assert_eq!(
+            hex(&poly1305(&key, &[0xff; 16])),
+            "03000000000000000000000000000000"
+        );
+        // 全ビットが立った鍵 (クランプ後の r が最大) と長いメッセージ
+        assert_eq!(
+            hex(&poly1305(&[0xff; 32], &[0xff; 100])),
+            "b99c030d7ce939bb6607393e68656f22"
+        );
+    }
+}
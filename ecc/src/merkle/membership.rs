@@ -0,0 +1,465 @@
+// Merkle 木への所属証明。木は MiMC で作り (葉 = MiMC(x)、節 = MiMC(左, 右))、証明者は
+// 葉から根までの経路上の値をすべて Pedersen コミットメントで隠したまま
+//   - 秘密 x から葉を計算したこと
+//   - 各段で (左, 右) が (今の節, 兄弟) か (兄弟, 今の節) であること (OR 証明で左右を隠す)
+//   - 親がその 2 つの MiMC ハッシュであること
+//   - 最後の節が公開された根であること
+// を 1 つの Fiat–Shamir チャレンジで示す。MiMC の加算はコミットメントのまま計算でき、
+// S-box の乗算ごとに Product の証明を付ける。検証者は根と深さだけを使い、葉の集合も位置も知らない。
+// 証明の大きさは深さに比例する (1 段あたり MiMC の暗号化 2 回分)
+use crate::commitment::{pedersen_g, PedersenCommitment};
+use crate::curve::{self, Scalar, N};
+use crate::hash::mimc::Mimc;
+use crate::rand::RngCore;
+use crate::sigma::{
+    self, AllOf, And, Or, OrWitness, Product, ProductWitness, SameValue, SigmaProtocol,
+};
+use crate::transcript::Transcript;
+use crate::{FieldElement, Point};
+
+type Position = Or<And<SameValue, SameValue>, And<SameValue, SameValue>>;
+type Statement = And<And<AllOf<Product>, AllOf<Position>>, SameValue>;
+
+// 1 段分のコミットメント
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathCommitment {
+    pub sibling: Point,
+    pub left: Point,
+    pub right: Point,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MembershipProof {
+    pub secret: Point,
+    // 葉に近い段から順に
+    pub path: Vec<PathCommitment>,
+    // S-box の積へのコミットメント (回路の計算順)
+    pub products: Vec<Point>,
+    pub commitment: <Statement as SigmaProtocol>::Commitment,
+    pub response: <Statement as SigmaProtocol>::Response,
+}
+
+fn mimc() -> Mimc {
+    Mimc::new(N)
+}
+
+fn fe(x: &Scalar) -> FieldElement {
+    FieldElement::new(x.value(), N)
+}
+
+fn scalar(x: &FieldElement) -> Scalar {
+    Scalar::new(x.num)
+}
+
+pub fn membership_leaf(secret: &Scalar) -> Scalar {
+    scalar(&mimc().hash(&[fe(secret)]))
+}
+
+// levels[0] が葉、最後が根だけの段
+fn tree_levels(leaves: &[Scalar]) -> Vec<Vec<Scalar>> {
+    if !leaves.len().is_power_of_two() {
+        panic!(
+            "Number of leaves must be a power of two, got {}",
+            leaves.len()
+        );
+    }
+    let mimc = mimc();
+    let mut levels = vec![leaves.to_vec()];
+    while levels.last().unwrap().len() > 1 {
+        let next = levels
+            .last()
+            .unwrap()
+            .chunks(2)
+            .map(|pair| scalar(&mimc.hash(&[fe(&pair[0]), fe(&pair[1])])))
+            .collect();
+        levels.push(next);
+    }
+    levels
+}
+
+pub fn membership_root(leaves: &[Scalar]) -> Scalar {
+    tree_levels(leaves).last().unwrap()[0]
+}
+
+// MiMC をコミットメントの上で計算する。証明者は値とブラインドも持ち、検証者は点だけを持つ
+trait Circuit {
+    type Wire: Clone;
+    fn constant(&mut self, value: &Scalar) -> Self::Wire;
+    fn add(&mut self, a: &Self::Wire, b: &Self::Wire) -> Self::Wire;
+    fn mul(&mut self, a: &Self::Wire, b: &Self::Wire) -> Self::Wire;
+}
+
+// 上の桁から 2 乗と乗算を繰り返す
+fn power<C: Circuit>(c: &mut C, base: &C::Wire, exponent: u64) -> C::Wire {
+    let mut acc = base.clone();
+    for i in (0..63 - exponent.leading_zeros()).rev() {
+        acc = c.mul(&acc, &acc);
+        if (exponent >> i) & 1 == 1 {
+            acc = c.mul(&acc, base);
+        }
+    }
+    acc
+}
+
+fn encrypt<C: Circuit>(c: &mut C, mimc: &Mimc, x: &C::Wire, key: &C::Wire) -> C::Wire {
+    let mut x = x.clone();
+    for rc in mimc.round_constants() {
+        let rc = c.constant(&scalar(rc));
+        let keyed = c.add(&x, key);
+        let u = c.add(&keyed, &rc);
+        x = power(c, &u, mimc.alpha);
+    }
+    c.add(&x, key)
+}
+
+fn hash<C: Circuit>(c: &mut C, mimc: &Mimc, inputs: &[C::Wire]) -> C::Wire {
+    let mut h = c.constant(&Scalar::new(inputs.len() as u64));
+    for m in inputs {
+        let e = encrypt(c, mimc, m, &h);
+        let e = c.add(&e, &h);
+        h = c.add(&e, m);
+    }
+    h
+}
+
+// 葉と、各段の (左, 右) から作った親。最後が根になるはずの節
+fn path_nodes<C: Circuit>(
+    c: &mut C,
+    mimc: &Mimc,
+    secret: &C::Wire,
+    children: &[(C::Wire, C::Wire)],
+) -> Vec<C::Wire> {
+    let mut nodes = vec![hash(c, mimc, std::slice::from_ref(secret))];
+    for (left, right) in children {
+        nodes.push(hash(c, mimc, &[left.clone(), right.clone()]));
+    }
+    nodes
+}
+
+#[derive(Debug, Clone)]
+struct Opened {
+    point: Point,
+    value: Scalar,
+    blinding: Scalar,
+}
+
+impl Opened {
+    fn commit(value: Scalar, blinding: Scalar) -> Self {
+        let point = PedersenCommitment::commit(&value, &blinding)
+            .as_point()
+            .clone();
+        Opened {
+            point,
+            value,
+            blinding,
+        }
+    }
+
+    fn random<R: RngCore>(value: Scalar, rng: &mut R) -> Self {
+        Opened::commit(value, Scalar::random(rng))
+    }
+}
+
+struct Prover<'a, R> {
+    rng: &'a mut R,
+    products: Vec<Product>,
+    witnesses: Vec<ProductWitness>,
+}
+
+impl<R: RngCore> Circuit for Prover<'_, R> {
+    type Wire = Opened;
+
+    fn constant(&mut self, value: &Scalar) -> Opened {
+        Opened::commit(*value, Scalar::ZERO)
+    }
+
+    fn add(&mut self, a: &Opened, b: &Opened) -> Opened {
+        Opened {
+            point: a.point.add(&b.point),
+            value: a.value.add(&b.value),
+            blinding: a.blinding.add(&b.blinding),
+        }
+    }
+
+    fn mul(&mut self, a: &Opened, b: &Opened) -> Opened {
+        let z = Opened::random(a.value.mul(&b.value), self.rng);
+        self.products.push(Product::new(
+            a.point.clone(),
+            b.point.clone(),
+            z.point.clone(),
+        ));
+        self.witnesses.push(ProductWitness::new(
+            &a.value,
+            &a.blinding,
+            &b.blinding,
+            &z.blinding,
+        ));
+        z
+    }
+}
+
+struct Verifier<'a> {
+    outputs: std::slice::Iter<'a, Point>,
+    products: Vec<Product>,
+    // 証明の積が足りなかった
+    short: bool,
+}
+
+impl Circuit for Verifier<'_> {
+    type Wire = Point;
+
+    fn constant(&mut self, value: &Scalar) -> Point {
+        pedersen_g().scalar_mul(value.value())
+    }
+
+    fn add(&mut self, a: &Point, b: &Point) -> Point {
+        a.add(b)
+    }
+
+    fn mul(&mut self, a: &Point, b: &Point) -> Point {
+        let z = self.outputs.next().cloned().unwrap_or_else(|| {
+            self.short = true;
+            curve::identity()
+        });
+        self.products
+            .push(Product::new(a.clone(), b.clone(), z.clone()));
+        z
+    }
+}
+
+fn position(current: &Point, level: &PathCommitment) -> Position {
+    let same = |a: &Point, b: &Point| SameValue::new(a.clone(), b.clone());
+    Or(
+        And(
+            same(&level.left, current),
+            same(&level.right, &level.sibling),
+        ),
+        And(
+            same(&level.left, &level.sibling),
+            same(&level.right, current),
+        ),
+    )
+}
+
+fn statement(
+    root: &Scalar,
+    nodes: &[Point],
+    path: &[PathCommitment],
+    products: Vec<Product>,
+) -> Statement {
+    let positions = nodes
+        .iter()
+        .zip(path)
+        .map(|(node, level)| position(node, level))
+        .collect();
+    let top = SameValue::new(
+        nodes.last().unwrap().clone(),
+        pedersen_g().scalar_mul(root.value()),
+    );
+    And(And(AllOf(products), AllOf(positions)), top)
+}
+
+// 証明を木 (根と深さ) に束縛する。文脈 (メッセージなど) は呼び出し側がトランスクリプトに入れておく
+fn bind_tree(transcript: &mut Transcript, root: &Scalar, depth: usize) {
+    transcript.append_message(b"dom-sep", b"merkle-membership");
+    transcript.append_scalar(b"root", root);
+    transcript.append_u64(b"depth", depth as u64);
+}
+
+pub fn prove_membership<R: RngCore>(
+    leaves: &[Scalar],
+    secret: &Scalar,
+    transcript: &mut Transcript,
+    rng: &mut R,
+) -> MembershipProof {
+    let levels = tree_levels(leaves);
+    let leaf = membership_leaf(secret);
+    let index = leaves
+        .iter()
+        .position(|l| *l == leaf)
+        .expect("Secret is not the preimage of a leaf of the tree");
+    let depth = levels.len() - 1;
+    let root = levels[depth][0];
+    let x = Opened::random(*secret, rng);
+    let mut openings = Vec::with_capacity(depth);
+    for (i, level) in levels[..depth].iter().enumerate() {
+        let pos = index >> i;
+        let (current, sibling) = (level[pos], level[pos ^ 1]);
+        let is_right = pos & 1 == 1;
+        let (left, right) = if is_right {
+            (sibling, current)
+        } else {
+            (current, sibling)
+        };
+        openings.push((
+            is_right,
+            Opened::random(sibling, rng),
+            Opened::random(left, rng),
+            Opened::random(right, rng),
+        ));
+    }
+    let children: Vec<(Opened, Opened)> = openings
+        .iter()
+        .map(|(_, _, l, r)| (l.clone(), r.clone()))
+        .collect();
+
+    let mimc = mimc();
+    let mut prover = Prover {
+        rng,
+        products: Vec::new(),
+        witnesses: Vec::new(),
+    };
+    let nodes = path_nodes(&mut prover, &mimc, &x, &children);
+    let Prover {
+        rng,
+        products,
+        witnesses,
+    } = prover;
+
+    let diff = |a: &Opened, b: &Opened| a.blinding.sub(&b.blinding);
+    let positions = openings
+        .iter()
+        .zip(&nodes)
+        .map(|((is_right, sibling, left, right), current)| {
+            if *is_right {
+                OrWitness::Right((diff(left, sibling), diff(right, current)))
+            } else {
+                OrWitness::Left((diff(left, current), diff(right, sibling)))
+            }
+        })
+        .collect();
+    let path: Vec<PathCommitment> = openings
+        .iter()
+        .map(|(_, sibling, left, right)| PathCommitment {
+            sibling: sibling.point.clone(),
+            left: left.point.clone(),
+            right: right.point.clone(),
+        })
+        .collect();
+    let outputs = products.iter().map(|p| p.z.clone()).collect();
+    let node_points: Vec<Point> = nodes.iter().map(|n| n.point.clone()).collect();
+    let statement = statement(&root, &node_points, &path, products);
+    let witness = ((witnesses, positions), nodes[depth].blinding);
+
+    bind_tree(transcript, &root, depth);
+    let (commitment, response) = sigma::prove(&statement, &witness, transcript, rng);
+    MembershipProof {
+        secret: x.point,
+        path,
+        products: outputs,
+        commitment,
+        response,
+    }
+}
+
+pub fn verify_membership(
+    root: &Scalar,
+    depth: usize,
+    proof: &MembershipProof,
+    transcript: &mut Transcript,
+) -> bool {
+    if proof.path.len() != depth {
+        return false;
+    }
+    let children: Vec<(Point, Point)> = proof
+        .path
+        .iter()
+        .map(|level| (level.left.clone(), level.right.clone()))
+        .collect();
+    let mut verifier = Verifier {
+        outputs: proof.products.iter(),
+        products: Vec::new(),
+        short: false,
+    };
+    let nodes = path_nodes(&mut verifier, &mimc(), &proof.secret, &children);
+    if verifier.short || verifier.outputs.next().is_some() {
+        return false;
+    }
+    let statement = statement(root, &nodes, &proof.path, verifier.products);
+    bind_tree(transcript, root, depth);
+    let sigma_proof = (proof.commitment.clone(), proof.response.clone());
+    sigma::verify(&statement, &sigma_proof, transcript)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::TestRng;
+
+    fn tree(n: usize, rng: &mut TestRng) -> (Vec<Scalar>, Vec<Scalar>) {
+        let secrets: Vec<Scalar> = (0..n).map(|_| Scalar::random(rng)).collect();
+        let leaves = secrets.iter().map(membership_leaf).collect();
+        (secrets, leaves)
+    }
+
+    fn transcript(msg: &[u8]) -> Transcript {
+        let mut t = Transcript::new(b"merkle-membership-test");
+        t.append_message(b"msg", msg);
+        t
+    }
+
+    #[test]
+    fn test_any_member_can_prove_against_the_root() {
+        let mut rng = TestRng::from_label(b"merkle-membership");
+        let (secrets, leaves) = tree(4, &mut rng);
+        let root = membership_root(&leaves);
+        let mimc = mimc();
+        let node = |a: &Scalar, b: &Scalar| scalar(&mimc.hash(&[fe(a), fe(b)]));
+        assert_eq!(
+            root,
+            node(&node(&leaves[0], &leaves[1]), &node(&leaves[2], &leaves[3]))
+        );
+        // 左端と右端の葉: 各段の左右が違っても証明の形は同じ
+        let proofs: Vec<MembershipProof> = [0, 3]
+            .iter()
+            .map(|&i| prove_membership(&leaves, &secrets[i], &mut transcript(b"hi"), &mut rng))
+            .collect();
+        for proof in &proofs {
+            assert!(verify_membership(&root, 2, proof, &mut transcript(b"hi")));
+            assert!(!verify_membership(&root, 2, proof, &mut transcript(b"bye")));
+            assert_eq!(proof.path.len(), 2);
+        }
+        assert_eq!(proofs[0].products.len(), proofs[1].products.len());
+    }
+
+    #[test]
+    fn test_rejects_other_roots_and_tampering() {
+        let mut rng = TestRng::from_label(b"merkle-membership-bad");
+        let (secrets, leaves) = tree(4, &mut rng);
+        let root = membership_root(&leaves);
+        let proof = prove_membership(&leaves, &secrets[2], &mut transcript(b"m"), &mut rng);
+        let check =
+            |proof: &MembershipProof| verify_membership(&root, 2, proof, &mut transcript(b"m"));
+        assert!(check(&proof));
+        // 別の木の根、違う深さ
+        let (_, others) = tree(4, &mut rng);
+        let other_root = membership_root(&others);
+        assert!(!verify_membership(
+            &other_root,
+            2,
+            &proof,
+            &mut transcript(b"m")
+        ));
+        assert!(!verify_membership(&root, 3, &proof, &mut transcript(b"m")));
+        // 左右の入れ替え、積の差し替えと欠落
+        let mut swapped = proof.clone();
+        let level = &mut swapped.path[1];
+        std::mem::swap(&mut level.left, &mut level.right);
+        assert!(!check(&swapped));
+        let mut forged = proof.clone();
+        forged.products[10] = forged.products[10].add(&pedersen_g());
+        assert!(!check(&forged));
+        let mut short = proof.clone();
+        short.products.pop();
+        assert!(!check(&short));
+    }
+
+    #[test]
+    #[should_panic(expected = "not the preimage of a leaf")]
+    fn test_outsider_cannot_prove() {
+        let mut rng = TestRng::from_label(b"merkle-membership-outsider");
+        let (_, leaves) = tree(2, &mut rng);
+        let outsider = Scalar::random(&mut rng);
+        prove_membership(&leaves, &outsider, &mut transcript(b"m"), &mut rng);
+    }
+}
@@ -0,0 +1,10 @@
+pub mod membership;
+pub mod sparse;
+pub mod tree;
+
+pub use membership::{
+    membership_leaf, membership_root, prove_membership, verify_membership, MembershipProof,
+    PathCommitment,
+};
+pub use sparse::{SparseMerkleProof, SparseMerkleTree};
+pub use tree::{MerkleProof, MerkleTree, ProofNode};
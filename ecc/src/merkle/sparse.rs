@@ -0,0 +1,223 @@
+// 256 ビットの鍵で引く疎な Merkle 木。空の部分木の値は高さごとに決まっているので、
+// 既定値と異なるノードだけを保持する
+use super::tree::node_hash;
+use crate::hash::Digest;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+const DEPTH: usize = 256;
+
+// 鍵は 256 ビットの整数 (ビッグエンディアン) として扱い、ビット h が高さ h での左右を決める
+fn bit(key: &[u8; 32], h: usize) -> bool {
+    (key[31 - h / 8] >> (h % 8)) & 1 == 1
+}
+
+// 下位 h ビットを 0 にしたもの = 高さ h のノードの識別子
+fn prefix(key: &[u8; 32], h: usize) -> [u8; 32] {
+    let mut p = *key;
+    for i in 0..h.min(DEPTH) {
+        p[31 - i / 8] &= !(1 << (i % 8));
+    }
+    p
+}
+
+fn sibling(key: &[u8; 32], h: usize) -> [u8; 32] {
+    let mut p = prefix(key, h);
+    p[31 - h / 8] ^= 1 << (h % 8);
+    p
+}
+
+// 鍵も葉のハッシュに含め、別の位置への付け替えを防ぐ
+pub fn sparse_leaf_hash<D: Digest>(key: &[u8; 32], value: &[u8]) -> Vec<u8> {
+    let mut h = D::new();
+    h.update(&[0x00]);
+    h.update(key);
+    h.update(value);
+    h.finalize()
+}
+
+// 空の葉を 0 とした、高さごとの空の部分木の根
+fn default_nodes<D: Digest>() -> Vec<Vec<u8>> {
+    let mut defaults = vec![vec![0u8; D::OUTPUT_SIZE]];
+    for h in 0..DEPTH {
+        defaults.push(node_hash::<D>(&defaults[h], &defaults[h]));
+    }
+    defaults
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseMerkleProof {
+    // ビット h が立っていれば高さ h の兄弟は既定値ではなく siblings に入っている
+    pub bitmap: [u8; 32],
+    pub siblings: Vec<Vec<u8>>,
+}
+
+impl SparseMerkleProof {
+    // value が None なら非包含 (その鍵の葉が空であること) を検証する
+    pub fn verify<D: Digest>(&self, root: &[u8], key: &[u8; 32], value: Option<&[u8]>) -> bool {
+        let defaults = default_nodes::<D>();
+        let mut acc = match value {
+            Some(v) => sparse_leaf_hash::<D>(key, v),
+            None => defaults[0].clone(),
+        };
+        let mut siblings = self.siblings.iter();
+        for (h, default) in defaults.iter().enumerate().take(DEPTH) {
+            let sib = if bit(&self.bitmap, h) {
+                match siblings.next() {
+                    Some(s) => s,
+                    None => return false,
+                }
+            } else {
+                default
+            };
+            acc = if bit(key, h) {
+                node_hash::<D>(sib, &acc)
+            } else {
+                node_hash::<D>(&acc, sib)
+            };
+        }
+        siblings.next().is_none() && acc == root
+    }
+}
+
+#[derive(Clone)]
+pub struct SparseMerkleTree<D: Digest> {
+    nodes: HashMap<(usize, [u8; 32]), Vec<u8>>,
+    values: HashMap<[u8; 32], Vec<u8>>,
+    defaults: Vec<Vec<u8>>,
+    _digest: PhantomData<D>,
+}
+
+impl<D: Digest> Default for SparseMerkleTree<D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D: Digest> SparseMerkleTree<D> {
+    pub fn new() -> Self {
+        SparseMerkleTree {
+            nodes: HashMap::new(),
+            values: HashMap::new(),
+            defaults: default_nodes::<D>(),
+            _digest: PhantomData,
+        }
+    }
+
+    fn node(&self, h: usize, id: &[u8; 32]) -> &[u8] {
+        self.nodes.get(&(h, *id)).unwrap_or(&self.defaults[h])
+    }
+
+    pub fn root(&self) -> &[u8] {
+        self.node(DEPTH, &[0; 32])
+    }
+
+    pub fn get(&self, key: &[u8; 32]) -> Option<&[u8]> {
+        self.values.get(key).map(|v| v.as_slice())
+    }
+
+    // 葉から根までの 256 ノードだけを計算し直す
+    fn set_leaf(&mut self, key: &[u8; 32], leaf: Vec<u8>) {
+        let mut acc = leaf;
+        for h in 0..=DEPTH {
+            let id = prefix(key, h);
+            if acc == self.defaults[h] {
+                self.nodes.remove(&(h, id));
+            } else {
+                self.nodes.insert((h, id), acc.clone());
+            }
+            if h == DEPTH {
+                break;
+            }
+            let sib = self.node(h, &sibling(key, h)).to_vec();
+            acc = if bit(key, h) {
+                node_hash::<D>(&sib, &acc)
+            } else {
+                node_hash::<D>(&acc, &sib)
+            };
+        }
+    }
+
+    pub fn insert(&mut self, key: [u8; 32], value: &[u8]) {
+        self.set_leaf(&key, sparse_leaf_hash::<D>(&key, value));
+        self.values.insert(key, value.to_vec());
+    }
+
+    pub fn remove(&mut self, key: &[u8; 32]) -> Option<Vec<u8>> {
+        let old = self.values.remove(key)?;
+        self.set_leaf(key, self.defaults[0].clone());
+        Some(old)
+    }
+
+    // 鍵が存在すれば包含証明、なければ非包含証明になる
+    pub fn prove(&self, key: &[u8; 32]) -> SparseMerkleProof {
+        let mut bitmap = [0u8; 32];
+        let mut siblings = Vec::new();
+        for h in 0..DEPTH {
+            if let Some(s) = self.nodes.get(&(h, sibling(key, h))) {
+                bitmap[31 - h / 8] |= 1 << (h % 8);
+                siblings.push(s.clone());
+            }
+        }
+        SparseMerkleProof { bitmap, siblings }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::{sha256, Sha256};
+
+    #[test]
+    fn test_membership_and_non_membership() {
+        let mut tree = SparseMerkleTree::<Sha256>::new();
+        let empty_root = tree.root().to_vec();
+        let keys: Vec<[u8; 32]> = (0..10u8).map(|i| sha256(&[i])).collect();
+        for (i, key) in keys.iter().enumerate() {
+            tree.insert(*key, format!("value {}", i).as_bytes());
+        }
+        for (i, key) in keys.iter().enumerate() {
+            let proof = tree.prove(key);
+            let value = format!("value {}", i);
+            assert!(proof.verify::<Sha256>(tree.root(), key, Some(value.as_bytes())));
+            assert!(!proof.verify::<Sha256>(tree.root(), key, None));
+            // 既定値の兄弟は省略されるので証明は短い
+            assert!(proof.siblings.len() < 20);
+        }
+        let absent = sha256(b"absent");
+        let proof = tree.prove(&absent);
+        assert!(proof.verify::<Sha256>(tree.root(), &absent, None));
+        assert!(!proof.verify::<Sha256>(tree.root(), &absent, Some(b"value 0")));
+
+        // 全て消すと空の木の根に戻る
+        for key in &keys {
+            assert!(tree.remove(key).is_some());
+        }
+        assert_eq!(tree.root(), &empty_root[..]);
+        assert!(tree.nodes.is_empty());
+    }
+
+    #[test]
+    fn test_incremental_update_changes_root() {
+        let mut tree = SparseMerkleTree::<Sha256>::new();
+        let key = [0xffu8; 32];
+        tree.insert(key, b"v1");
+        let root1 = tree.root().to_vec();
+        let old_proof = tree.prove(&key);
+        tree.insert(key, b"v2");
+        assert_ne!(tree.root(), &root1[..]);
+        assert_eq!(tree.get(&key), Some(&b"v2"[..]));
+        assert!(tree
+            .prove(&key)
+            .verify::<Sha256>(tree.root(), &key, Some(b"v2")));
+        assert!(!old_proof.verify::<Sha256>(tree.root(), &key, Some(b"v1")));
+        // 隣接する鍵 (最後のビットだけ違う)
+        let mut neighbour = key;
+        neighbour[31] ^= 1;
+        tree.insert(neighbour, b"n");
+        assert_eq!(tree.prove(&key).siblings.len(), 1);
+        assert!(tree
+            .prove(&key)
+            .verify::<Sha256>(tree.root(), &key, Some(b"v2")));
+    }
+}
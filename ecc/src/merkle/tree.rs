@@ -0,0 +1,162 @@
+// 二分 Merkle 木。葉と内部ノードで接頭辞を変え (0x00 / 0x01)、
+// 内部ノードの値を葉として通す第二原像攻撃を防ぐ
+use crate::hash::Digest;
+use std::marker::PhantomData;
+
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+pub fn leaf_hash<D: Digest>(data: &[u8]) -> Vec<u8> {
+    let mut h = D::new();
+    h.update(&[LEAF_PREFIX]);
+    h.update(data);
+    h.finalize()
+}
+
+pub fn node_hash<D: Digest>(left: &[u8], right: &[u8]) -> Vec<u8> {
+    let mut h = D::new();
+    h.update(&[NODE_PREFIX]);
+    h.update(left);
+    h.update(right);
+    h.finalize()
+}
+
+// 兄弟ノードがどちら側にあるか
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProofNode {
+    Left(Vec<u8>),
+    Right(Vec<u8>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerkleProof {
+    pub index: usize,
+    pub path: Vec<ProofNode>,
+}
+
+impl MerkleProof {
+    pub fn root_from_leaf<D: Digest>(&self, leaf: &[u8]) -> Vec<u8> {
+        self.path
+            .iter()
+            .fold(leaf_hash::<D>(leaf), |acc, node| match node {
+                ProofNode::Left(sibling) => node_hash::<D>(sibling, &acc),
+                ProofNode::Right(sibling) => node_hash::<D>(&acc, sibling),
+            })
+    }
+
+    pub fn verify<D: Digest>(&self, root: &[u8], leaf: &[u8]) -> bool {
+        self.root_from_leaf::<D>(leaf) == root
+    }
+}
+
+#[derive(Clone)]
+pub struct MerkleTree<D: Digest> {
+    // levels[0] が葉のハッシュ、最後の段が根
+    levels: Vec<Vec<Vec<u8>>>,
+    _digest: PhantomData<D>,
+}
+
+impl<D: Digest> MerkleTree<D> {
+    pub fn from_leaves<T: AsRef<[u8]>>(leaves: &[T]) -> Self {
+        if leaves.is_empty() {
+            panic!("Merkle tree needs at least one leaf");
+        }
+        let mut levels = vec![leaves
+            .iter()
+            .map(|leaf| leaf_hash::<D>(leaf.as_ref()))
+            .collect::<Vec<_>>()];
+        while levels.last().unwrap().len() > 1 {
+            // 奇数個のときは最後のノードをそのまま上の段に上げる (複製はしない)
+            let next = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => node_hash::<D>(left, right),
+                    [single] => single.clone(),
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(next);
+        }
+        MerkleTree {
+            levels,
+            _digest: PhantomData,
+        }
+    }
+
+    pub fn root(&self) -> &[u8] {
+        &self.levels.last().unwrap()[0]
+    }
+
+    pub fn len(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    // 空の木は作れない
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    pub fn prove(&self, index: usize) -> MerkleProof {
+        if index >= self.len() {
+            panic!(
+                "Leaf index {} out of range for {} leaves",
+                index,
+                self.len()
+            );
+        }
+        let mut path = Vec::new();
+        let mut i = index;
+        for level in &self.levels[..self.levels.len() - 1] {
+            let sibling = i ^ 1;
+            if sibling < level.len() {
+                path.push(if sibling < i {
+                    ProofNode::Left(level[sibling].clone())
+                } else {
+                    ProofNode::Right(level[sibling].clone())
+                });
+            }
+            i /= 2;
+        }
+        MerkleProof { index, path }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::Sha256;
+
+    #[test]
+    fn test_inclusion_proofs_for_all_leaves() {
+        for n in [1, 2, 3, 5, 8, 13] {
+            let leaves: Vec<Vec<u8>> = (0..n).map(|i| format!("leaf {}", i).into_bytes()).collect();
+            let tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+            for (i, leaf) in leaves.iter().enumerate() {
+                let proof = tree.prove(i);
+                assert!(proof.verify::<Sha256>(tree.root(), leaf));
+                assert!(!proof.verify::<Sha256>(tree.root(), b"forged leaf"));
+            }
+        }
+    }
+
+    #[test]
+    fn test_internal_node_is_not_a_valid_leaf() {
+        let leaves = [b"a", b"b", b"c", b"d"];
+        let tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+        // 左の部分木の 2 つの子の連結を葉と偽っても、接頭辞が違うので根は一致しない
+        let left = leaf_hash::<Sha256>(b"a");
+        let right = leaf_hash::<Sha256>(b"b");
+        let fake_leaf = [left, right].concat();
+        let proof = MerkleProof {
+            index: 0,
+            path: vec![ProofNode::Right(node_hash::<Sha256>(
+                &leaf_hash::<Sha256>(b"c"),
+                &leaf_hash::<Sha256>(b"d"),
+            ))],
+        };
+        assert!(!proof.verify::<Sha256>(tree.root(), &fake_leaf));
+        assert_eq!(tree.prove(1).path.len(), 2);
+    }
+}
@@ -0,0 +1,192 @@
+// 再暗号化型ミックスネット (投票の匿名化など)。各ミックスノードは受け取った ElGamal 暗号文を
+// 並べ替え、それぞれを再乱数化して次へ渡す。ノードのうち 1 つでも正直なら、
+// 出力と入力の対応 (誰の票か) は分からない。
+// 出力 (c1', c2') が入力 (c1, c2) の再暗号化であることは、c1' - c1 = sG と c2' - c2 = sY の
+// Chaum–Pedersen 証明で示せる。並べ替えを隠したまま示すため、各出力について
+// 「いずれかの入力の再暗号化」を OR 証明にする。ただしこれは同じ入力を 2 回使う不正を防げない
+// (置換であることまで示すには Neff や Bayer–Groth のシャッフル証明が必要)
+use crate::curve::{self, Scalar};
+use crate::elgamal::{Ciphertext, ElGamalPublicKey};
+use crate::rand::{uniform_below, RngCore};
+use crate::sigma::{self, AnyOf, AnyOfResponse, Dleq};
+use crate::transcript::Transcript;
+use crate::Point;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReEncryptionProof {
+    pub commitments: Vec<(Point, Point)>,
+    pub response: AnyOfResponse<Scalar>,
+}
+
+// 出力ごとの証明
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShuffleProof(pub Vec<ReEncryptionProof>);
+
+fn reencryption(pk: &ElGamalPublicKey, input: &Ciphertext, output: &Ciphertext) -> Dleq {
+    Dleq::new(
+        curve::generator(),
+        output.c1.add(&input.c1.neg()),
+        pk.0.clone(),
+        output.c2.add(&input.c2.neg()),
+    )
+}
+
+fn statement(pk: &ElGamalPublicKey, inputs: &[Ciphertext], output: &Ciphertext) -> AnyOf<Dleq> {
+    AnyOf(inputs.iter().map(|c| reencryption(pk, c, output)).collect())
+}
+
+fn bind(
+    transcript: &mut Transcript,
+    pk: &ElGamalPublicKey,
+    inputs: &[Ciphertext],
+    outputs: &[Ciphertext],
+) {
+    transcript.append_message(b"dom-sep", b"mixnet-shuffle");
+    transcript.append_point(b"Y", &pk.0);
+    for c in inputs.iter().chain(outputs) {
+        transcript.append_point(b"c1", &c.c1);
+        transcript.append_point(b"c2", &c.c2);
+    }
+}
+
+// Fisher–Yates。permutation[j] は j 番目の出力の元になった入力の番号
+fn random_permutation<R: RngCore>(n: usize, rng: &mut R) -> Vec<usize> {
+    let mut permutation: Vec<usize> = (0..n).collect();
+    for i in (1..n).rev() {
+        let j = uniform_below(i as u64 + 1, rng) as usize;
+        permutation.swap(i, j);
+    }
+    permutation
+}
+
+pub struct MixNode {
+    pk: ElGamalPublicKey,
+}
+
+impl MixNode {
+    pub fn new(pk: ElGamalPublicKey) -> Self {
+        MixNode { pk }
+    }
+
+    pub fn mix<R: RngCore>(&self, inputs: &[Ciphertext], rng: &mut R) -> Vec<Ciphertext> {
+        random_permutation(inputs.len(), rng)
+            .into_iter()
+            .map(|i| self.pk.rerandomize(&inputs[i], rng))
+            .collect()
+    }
+
+    pub fn mix_with_proof<R: RngCore>(
+        &self,
+        inputs: &[Ciphertext],
+        transcript: &mut Transcript,
+        rng: &mut R,
+    ) -> (Vec<Ciphertext>, ShuffleProof) {
+        let permutation = random_permutation(inputs.len(), rng);
+        let randomness: Vec<Scalar> = permutation.iter().map(|_| Scalar::random(rng)).collect();
+        let outputs: Vec<Ciphertext> = permutation
+            .iter()
+            .zip(&randomness)
+            .map(|(&i, s)| self.pk.rerandomize_with(&inputs[i], s))
+            .collect();
+        bind(transcript, &self.pk, inputs, &outputs);
+        let proofs = outputs
+            .iter()
+            .zip(permutation.iter().zip(&randomness))
+            .map(|(output, (&i, s))| {
+                let (commitments, response) = sigma::prove(
+                    &statement(&self.pk, inputs, output),
+                    &(i, *s),
+                    transcript,
+                    rng,
+                );
+                ReEncryptionProof {
+                    commitments,
+                    response,
+                }
+            })
+            .collect();
+        (outputs, ShuffleProof(proofs))
+    }
+}
+
+pub fn verify_shuffle(
+    pk: &ElGamalPublicKey,
+    inputs: &[Ciphertext],
+    outputs: &[Ciphertext],
+    proof: &ShuffleProof,
+    transcript: &mut Transcript,
+) -> bool {
+    if inputs.len() != outputs.len() || proof.0.len() != outputs.len() {
+        return false;
+    }
+    bind(transcript, pk, inputs, outputs);
+    outputs.iter().zip(&proof.0).all(|(output, p)| {
+        let p = (p.commitments.clone(), p.response.clone());
+        sigma::verify(&statement(pk, inputs, output), &p, transcript)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elgamal::{self, decode_small, encode_small};
+    use crate::rand::TestRng;
+
+    #[test]
+    fn test_mix_cascade_preserves_votes() {
+        let mut rng = TestRng::from_label(b"mixnet");
+        let (sk, pk) = elgamal::keygen(&mut rng);
+        let votes = [3u64, 1, 4, 1, 5];
+        let ballots: Vec<Ciphertext> = votes
+            .iter()
+            .map(|&v| pk.encrypt(&encode_small(v), &mut rng))
+            .collect();
+        let nodes = [MixNode::new(pk.clone()), MixNode::new(pk.clone())];
+        let mixed = nodes
+            .iter()
+            .fold(ballots.clone(), |batch, node| node.mix(&batch, &mut rng));
+        // 暗号文としてはどれも入力と一致しない
+        assert!(mixed.iter().all(|c| !ballots.contains(c)));
+        let mut tally: Vec<u64> = mixed
+            .iter()
+            .map(|c| decode_small(&sk.decrypt(c), 10).unwrap())
+            .collect();
+        tally.sort();
+        assert_eq!(tally, vec![1, 1, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_shuffle_proof() {
+        let mut rng = TestRng::from_label(b"mixnet-proof");
+        let (_, pk) = elgamal::keygen(&mut rng);
+        let inputs: Vec<Ciphertext> = (0..4)
+            .map(|v| pk.encrypt(&encode_small(v), &mut rng))
+            .collect();
+        let node = MixNode::new(pk.clone());
+        let (outputs, proof) = node.mix_with_proof(&inputs, &mut Transcript::new(b"mix"), &mut rng);
+        assert!(verify_shuffle(
+            &pk,
+            &inputs,
+            &outputs,
+            &proof,
+            &mut Transcript::new(b"mix")
+        ));
+        // 平文をすり替えた出力は、どの入力の再暗号化にもならない
+        let mut bad = outputs.clone();
+        bad[1] = pk.encrypt(&encode_small(9), &mut rng);
+        assert!(!verify_shuffle(
+            &pk,
+            &inputs,
+            &bad,
+            &proof,
+            &mut Transcript::new(b"mix")
+        ));
+        assert!(!verify_shuffle(
+            &pk,
+            &inputs,
+            &outputs[..3],
+            &proof,
+            &mut Transcript::new(b"mix")
+        ));
+    }
+}
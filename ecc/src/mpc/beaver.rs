@@ -0,0 +1,222 @@
+// 加法的秘密分散と Beaver の三つ組による乗算。x は x = x_0 + ... + x_{n-1} (mod p) と
+// 分けて各参加者に配り、足し算と定数倍は各自の手元で済む。掛け算は信頼できる
+// ディーラーが事前に配った c = ab の三つ組を使い、d = x - a と e = y - b だけを公開して
+// xy = c + d b + e a + d e を分散したまま組み立てる (a, b が一様なので d, e は何も漏らさない)
+use crate::rand::{uniform_below, RngCore};
+use crate::FieldElement;
+
+fn random_element<R: RngCore>(prime: u64, rng: &mut R) -> FieldElement {
+    FieldElement::new(uniform_below(prime, rng), prime)
+}
+
+// 最後の 1 つ以外を一様に選び、最後で帳尻を合わせる
+pub fn share<R: RngCore>(secret: &FieldElement, parties: usize, rng: &mut R) -> Vec<FieldElement> {
+    if parties == 0 {
+        panic!("Need at least one party");
+    }
+    let mut shares: Vec<FieldElement> = (0..parties - 1)
+        .map(|_| random_element(secret.prime, rng))
+        .collect();
+    let sum = sum(&shares, secret.prime);
+    shares.push(secret.sub(&sum));
+    shares
+}
+
+fn sum(values: &[FieldElement], prime: u64) -> FieldElement {
+    values
+        .iter()
+        .fold(FieldElement::new(0, prime), |acc, v| acc.add(v))
+}
+
+pub fn reconstruct(shares: &[FieldElement]) -> FieldElement {
+    let prime = shares.first().expect("No shares to reconstruct").prime;
+    sum(shares, prime)
+}
+
+// ある参加者が持つ三つ組の分け前
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BeaverTriple {
+    pub a: FieldElement,
+    pub b: FieldElement,
+    pub c: FieldElement,
+}
+
+// 信頼できるディーラー。a, b を選んで c = ab とし、それぞれを分散して配る
+pub struct Dealer {
+    prime: u64,
+    parties: usize,
+}
+
+impl Dealer {
+    pub fn new(prime: u64, parties: usize) -> Self {
+        Dealer { prime, parties }
+    }
+
+    pub fn triple<R: RngCore>(&self, rng: &mut R) -> Vec<BeaverTriple> {
+        let a = random_element(self.prime, rng);
+        let b = random_element(self.prime, rng);
+        let c = a.mul(&b);
+        let (a, b, c) = (
+            share(&a, self.parties, rng),
+            share(&b, self.parties, rng),
+            share(&c, self.parties, rng),
+        );
+        (0..self.parties)
+            .map(|i| BeaverTriple {
+                a: a[i].clone(),
+                b: b[i].clone(),
+                c: c[i].clone(),
+            })
+            .collect()
+    }
+}
+
+// 乗算の第 1 段で全員に公開する (x_i - a_i, y_i - b_i)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MaskedShares {
+    pub d: FieldElement,
+    pub e: FieldElement,
+}
+
+// 公開された分け前を合計した d, e
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Opened {
+    pub d: FieldElement,
+    pub e: FieldElement,
+}
+
+pub fn open(messages: &[MaskedShares]) -> Opened {
+    let d: Vec<FieldElement> = messages.iter().map(|m| m.d.clone()).collect();
+    let e: Vec<FieldElement> = messages.iter().map(|m| m.e.clone()).collect();
+    Opened {
+        d: reconstruct(&d),
+        e: reconstruct(&e),
+    }
+}
+
+// 参加者は自分の番号だけを知っていれば、あとは分け前の局所計算で済む
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Party {
+    pub index: usize,
+}
+
+impl Party {
+    pub fn new(index: usize) -> Self {
+        Party { index }
+    }
+
+    pub fn add(&self, x: &FieldElement, y: &FieldElement) -> FieldElement {
+        x.add(y)
+    }
+
+    pub fn mul_constant(&self, x: &FieldElement, k: &FieldElement) -> FieldElement {
+        x.mul(k)
+    }
+
+    // 定数は 1 人だけが足す
+    pub fn add_constant(&self, x: &FieldElement, k: &FieldElement) -> FieldElement {
+        if self.index == 0 {
+            x.add(k)
+        } else {
+            x.clone()
+        }
+    }
+
+    pub fn mask(&self, x: &FieldElement, y: &FieldElement, triple: &BeaverTriple) -> MaskedShares {
+        MaskedShares {
+            d: x.sub(&triple.a),
+            e: y.sub(&triple.b),
+        }
+    }
+
+    // z_i = c_i + d b_i + e a_i (+ d e は 1 人だけ)
+    pub fn finish_mul(&self, triple: &BeaverTriple, opened: &Opened) -> FieldElement {
+        let z = triple
+            .c
+            .add(&opened.d.mul(&triple.b))
+            .add(&opened.e.mul(&triple.a));
+        self.add_constant(&z, &opened.d.mul(&opened.e))
+    }
+}
+
+// 全員分の分け前をまとめて 1 回の乗算を進める。通信を省いたシミュレーション
+pub fn multiply(
+    parties: &[Party],
+    x: &[FieldElement],
+    y: &[FieldElement],
+    triples: &[BeaverTriple],
+) -> Vec<FieldElement> {
+    let messages: Vec<MaskedShares> = parties
+        .iter()
+        .zip(x.iter().zip(y))
+        .zip(triples)
+        .map(|((p, (xi, yi)), t)| p.mask(xi, yi, t))
+        .collect();
+    let opened = open(&messages);
+    parties
+        .iter()
+        .zip(triples)
+        .map(|(p, t)| p.finish_mul(t, &opened))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::TestRng;
+
+    const P: u64 = 2_147_483_647;
+
+    fn fe(x: u64) -> FieldElement {
+        FieldElement::new(x, P)
+    }
+
+    #[test]
+    fn test_sharing_roundtrip() {
+        let mut rng = TestRng::from_label(b"beaver-share");
+        let shares = share(&fe(42), 3, &mut rng);
+        assert_eq!(shares.len(), 3);
+        assert_eq!(reconstruct(&shares), fe(42));
+        // 2 つだけでは秘密は決まらない (ここでは一致しないことだけ確かめる)
+        assert_ne!(reconstruct(&shares[..2]), fe(42));
+        let triple = Dealer::new(P, 3).triple(&mut rng);
+        let get = |f: fn(&BeaverTriple) -> FieldElement| -> Vec<FieldElement> {
+            triple.iter().map(f).collect()
+        };
+        let (a, b, c) = (
+            reconstruct(&get(|t| t.a.clone())),
+            reconstruct(&get(|t| t.b.clone())),
+            reconstruct(&get(|t| t.c.clone())),
+        );
+        assert_eq!(a.mul(&b), c);
+    }
+
+    #[test]
+    fn test_two_party_arithmetic() {
+        // x * y * z + 7 を 2 者で分散したまま計算する
+        let mut rng = TestRng::from_label(b"beaver-mul");
+        let parties = [Party::new(0), Party::new(1)];
+        let dealer = Dealer::new(P, 2);
+        let (x, y, z) = (
+            share(&fe(1234), 2, &mut rng),
+            share(&fe(5678), 2, &mut rng),
+            share(&fe(P - 3), 2, &mut rng),
+        );
+        let xy = multiply(&parties, &x, &y, &dealer.triple(&mut rng));
+        let xyz = multiply(&parties, &xy, &z, &dealer.triple(&mut rng));
+        let result: Vec<FieldElement> = parties
+            .iter()
+            .zip(&xyz)
+            .map(|(p, s)| p.add_constant(s, &fe(7)))
+            .collect();
+        let expected = fe(1234).mul(&fe(5678)).mul(&fe(P - 3)).add(&fe(7));
+        assert_eq!(reconstruct(&result), expected);
+        // 線形演算は通信なしで済む
+        let lin: Vec<FieldElement> = parties
+            .iter()
+            .zip(x.iter().zip(&y))
+            .map(|(p, (a, b))| p.mul_constant(&p.add(a, b), &fe(3)))
+            .collect();
+        assert_eq!(reconstruct(&lin), fe(3 * (1234 + 5678)));
+    }
+}
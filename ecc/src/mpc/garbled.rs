@@ -0,0 +1,358 @@
+// Yao の garbled circuit。ワイヤごとに 0/1 を表す 16 バイトのラベルを乱数で選び、
+// AND ゲートは入力ラベルから出力ラベルを取り出す 4 行の暗号表にする。
+// Free-XOR (全ワイヤで label_1 = label_0 ⊕ R) で XOR と NOT は表なしで評価でき、
+// point-and-permute (ラベルの最下位ビットで行を選ぶ) で評価者は 1 行だけ復号する。
+// 評価者は自分の入力ラベルを OT で受け取るので、入力も中間値も互いに知られない
+use super::ot::{OtError, OtReceiver, OtSender};
+use crate::hash::sha256;
+use crate::rand::RngCore;
+
+pub type Label = [u8; 16];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Wire(usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gate {
+    And(Wire, Wire, Wire),
+    Xor(Wire, Wire, Wire),
+    Not(Wire, Wire),
+}
+
+// ゲートはワイヤを作った順に並ぶので、先頭から評価すればよい
+#[derive(Debug, Clone, Default)]
+pub struct BooleanCircuit {
+    num_wires: usize,
+    garbler_inputs: Vec<Wire>,
+    evaluator_inputs: Vec<Wire>,
+    gates: Vec<Gate>,
+    outputs: Vec<Wire>,
+}
+
+impl BooleanCircuit {
+    pub fn new() -> Self {
+        BooleanCircuit::default()
+    }
+
+    fn wire(&mut self) -> Wire {
+        self.num_wires += 1;
+        Wire(self.num_wires - 1)
+    }
+
+    pub fn garbler_input(&mut self) -> Wire {
+        let w = self.wire();
+        self.garbler_inputs.push(w);
+        w
+    }
+
+    pub fn evaluator_input(&mut self) -> Wire {
+        let w = self.wire();
+        self.evaluator_inputs.push(w);
+        w
+    }
+
+    pub fn and(&mut self, a: Wire, b: Wire) -> Wire {
+        let out = self.wire();
+        self.gates.push(Gate::And(a, b, out));
+        out
+    }
+
+    pub fn xor(&mut self, a: Wire, b: Wire) -> Wire {
+        let out = self.wire();
+        self.gates.push(Gate::Xor(a, b, out));
+        out
+    }
+
+    pub fn not(&mut self, a: Wire) -> Wire {
+        let out = self.wire();
+        self.gates.push(Gate::Not(a, out));
+        out
+    }
+
+    // a ∨ b = (a ⊕ b) ⊕ (a ∧ b)
+    pub fn or(&mut self, a: Wire, b: Wire) -> Wire {
+        let x = self.xor(a, b);
+        let y = self.and(a, b);
+        self.xor(x, y)
+    }
+
+    pub fn output(&mut self, w: Wire) {
+        self.outputs.push(w);
+    }
+
+    pub fn num_and_gates(&self) -> usize {
+        self.gates
+            .iter()
+            .filter(|g| matches!(g, Gate::And(..)))
+            .count()
+    }
+
+    // 暗号化せずに評価する。garbled 版の答え合わせ用
+    pub fn evaluate_plain(&self, garbler: &[bool], evaluator: &[bool]) -> Vec<bool> {
+        self.check_inputs(garbler.len(), evaluator.len());
+        let mut values = vec![false; self.num_wires];
+        for (w, &v) in self.garbler_inputs.iter().zip(garbler) {
+            values[w.0] = v;
+        }
+        for (w, &v) in self.evaluator_inputs.iter().zip(evaluator) {
+            values[w.0] = v;
+        }
+        for gate in &self.gates {
+            match *gate {
+                Gate::And(a, b, out) => values[out.0] = values[a.0] & values[b.0],
+                Gate::Xor(a, b, out) => values[out.0] = values[a.0] ^ values[b.0],
+                Gate::Not(a, out) => values[out.0] = !values[a.0],
+            }
+        }
+        self.outputs.iter().map(|w| values[w.0]).collect()
+    }
+
+    fn check_inputs(&self, garbler: usize, evaluator: usize) {
+        if garbler != self.garbler_inputs.len() || evaluator != self.evaluator_inputs.len() {
+            panic!("Wrong number of circuit inputs");
+        }
+    }
+}
+
+fn xor(a: &Label, b: &Label) -> Label {
+    std::array::from_fn(|i| a[i] ^ b[i])
+}
+
+fn permute_bit(label: &Label) -> usize {
+    (label[15] & 1) as usize
+}
+
+// 行の鍵 H(la || lb || gate)
+fn row_key(a: &Label, b: &Label, gate: usize) -> Label {
+    let mut input = a.to_vec();
+    input.extend_from_slice(b);
+    input.extend_from_slice(&(gate as u64).to_le_bytes());
+    sha256(&input)[..16].try_into().unwrap()
+}
+
+// 評価者に送るもの: AND ゲートの表と出力の復号ビット
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GarbledCircuit {
+    pub tables: Vec<[Label; 4]>,
+    pub output_decoding: Vec<bool>,
+}
+
+// 入力ワイヤの 0 ラベルと大域オフセット R。garbler だけが持つ
+pub struct InputEncoding {
+    garbler: Vec<Label>,
+    evaluator: Vec<Label>,
+    offset: Label,
+}
+
+impl InputEncoding {
+    pub fn encode_garbler(&self, bits: &[bool]) -> Vec<Label> {
+        if bits.len() != self.garbler.len() {
+            panic!("Wrong number of garbler inputs");
+        }
+        self.garbler
+            .iter()
+            .zip(bits)
+            .map(|(l, &b)| if b { xor(l, &self.offset) } else { *l })
+            .collect()
+    }
+
+    // 評価者の入力ワイヤごとの (0 ラベル, 1 ラベル)。OT で片方だけ渡す
+    pub fn evaluator_labels(&self) -> Vec<(Label, Label)> {
+        self.evaluator
+            .iter()
+            .map(|l| (*l, xor(l, &self.offset)))
+            .collect()
+    }
+}
+
+pub fn garble<R: RngCore>(
+    circuit: &BooleanCircuit,
+    rng: &mut R,
+) -> (GarbledCircuit, InputEncoding) {
+    let mut offset = [0u8; 16];
+    rng.fill_bytes(&mut offset);
+    // R の最下位ビットを 1 にして、0 ラベルと 1 ラベルの permute ビットを逆にする
+    offset[15] |= 1;
+    let mut zero = vec![[0u8; 16]; circuit.num_wires];
+    for w in circuit
+        .garbler_inputs
+        .iter()
+        .chain(&circuit.evaluator_inputs)
+    {
+        rng.fill_bytes(&mut zero[w.0]);
+    }
+    let mut tables = Vec::new();
+    for (i, gate) in circuit.gates.iter().enumerate() {
+        match *gate {
+            Gate::Xor(a, b, out) => zero[out.0] = xor(&zero[a.0], &zero[b.0]),
+            Gate::Not(a, out) => zero[out.0] = xor(&zero[a.0], &offset),
+            Gate::And(a, b, out) => {
+                rng.fill_bytes(&mut zero[out.0]);
+                let label = |w: Wire, v: bool| {
+                    if v {
+                        xor(&zero[w.0], &offset)
+                    } else {
+                        zero[w.0]
+                    }
+                };
+                let mut table = [[0u8; 16]; 4];
+                for (va, vb) in [(false, false), (false, true), (true, false), (true, true)] {
+                    let (la, lb) = (label(a, va), label(b, vb));
+                    let row = 2 * permute_bit(&la) + permute_bit(&lb);
+                    table[row] = xor(&row_key(&la, &lb, i), &label(out, va & vb));
+                }
+                tables.push(table);
+            }
+        }
+    }
+    let output_decoding = circuit
+        .outputs
+        .iter()
+        .map(|w| permute_bit(&zero[w.0]) == 1)
+        .collect();
+    let encoding = InputEncoding {
+        garbler: circuit.garbler_inputs.iter().map(|w| zero[w.0]).collect(),
+        evaluator: circuit.evaluator_inputs.iter().map(|w| zero[w.0]).collect(),
+        offset,
+    };
+    (
+        GarbledCircuit {
+            tables,
+            output_decoding,
+        },
+        encoding,
+    )
+}
+
+// 入力ラベルは garbler 分、評価者分の順
+pub fn evaluate(
+    circuit: &BooleanCircuit,
+    garbled: &GarbledCircuit,
+    garbler: &[Label],
+    evaluator: &[Label],
+) -> Vec<bool> {
+    circuit.check_inputs(garbler.len(), evaluator.len());
+    let mut labels = vec![[0u8; 16]; circuit.num_wires];
+    for (w, l) in circuit.garbler_inputs.iter().zip(garbler) {
+        labels[w.0] = *l;
+    }
+    for (w, l) in circuit.evaluator_inputs.iter().zip(evaluator) {
+        labels[w.0] = *l;
+    }
+    let mut tables = garbled.tables.iter();
+    for (i, gate) in circuit.gates.iter().enumerate() {
+        match *gate {
+            Gate::Xor(a, b, out) => labels[out.0] = xor(&labels[a.0], &labels[b.0]),
+            // NOT は 0 ラベル側をずらしてあるのでラベルはそのまま
+            Gate::Not(a, out) => labels[out.0] = labels[a.0],
+            Gate::And(a, b, out) => {
+                let table = tables.next().expect("missing garbled table");
+                let (la, lb) = (labels[a.0], labels[b.0]);
+                let row = 2 * permute_bit(&la) + permute_bit(&lb);
+                labels[out.0] = xor(&row_key(&la, &lb, i), &table[row]);
+            }
+        }
+    }
+    circuit
+        .outputs
+        .iter()
+        .zip(&garbled.output_decoding)
+        .map(|(w, &d)| (permute_bit(&labels[w.0]) == 1) ^ d)
+        .collect()
+}
+
+// a > b を下位ビットから順に比べる: gt ← (a ∧ ¬b) ⊕ (¬(a ⊕ b) ∧ gt)。
+// 2 つの項は同時に真にならないので ⊕ は ∨ と同じ
+pub fn greater_than_circuit(bits: usize) -> BooleanCircuit {
+    let mut c = BooleanCircuit::new();
+    let a: Vec<Wire> = (0..bits).map(|_| c.garbler_input()).collect();
+    let b: Vec<Wire> = (0..bits).map(|_| c.evaluator_input()).collect();
+    let mut gt = None;
+    for i in 0..bits {
+        let not_b = c.not(b[i]);
+        let a_wins = c.and(a[i], not_b);
+        gt = Some(match gt {
+            None => a_wins,
+            Some(prev) => {
+                let diff = c.xor(a[i], b[i]);
+                let eq = c.not(diff);
+                let keep = c.and(eq, prev);
+                c.xor(a_wins, keep)
+            }
+        });
+    }
+    c.output(gt.expect("comparison needs at least one bit"));
+    c
+}
+
+fn to_bits(x: u64, bits: usize) -> Vec<bool> {
+    (0..bits).map(|i| (x >> i) & 1 == 1).collect()
+}
+
+// 大富豪問題: Alice (garbler) と Bob (評価者) が互いの資産を明かさずに
+// Alice の方が多いかだけを知る。Bob の入力ラベルは 1 ビットずつ OT で受け取る
+pub fn millionaires<R: RngCore>(
+    alice: u64,
+    bob: u64,
+    bits: usize,
+    rng: &mut R,
+) -> Result<bool, OtError> {
+    let circuit = greater_than_circuit(bits);
+    let (garbled, encoding) = garble(&circuit, rng);
+    let alice_labels = encoding.encode_garbler(&to_bits(alice, bits));
+    let mut bob_labels = Vec::with_capacity(bits);
+    for ((l0, l1), choice) in encoding.evaluator_labels().iter().zip(to_bits(bob, bits)) {
+        let (sender, setup) = OtSender::new(rng);
+        let (receiver, reply) = OtReceiver::new(&setup, choice, rng)?;
+        let transfer = sender.transfer(&reply, l0, l1)?;
+        let label = receiver.receive(&transfer)?;
+        bob_labels.push(label.try_into().map_err(|_| OtError::InvalidMessage)?);
+    }
+    Ok(evaluate(&circuit, &garbled, &alice_labels, &bob_labels)[0])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::TestRng;
+
+    #[test]
+    fn test_garbled_matches_plain_evaluation() {
+        // 出力: (a ∧ b) ⊕ ¬c, a ∨ c
+        let mut circuit = BooleanCircuit::new();
+        let a = circuit.garbler_input();
+        let b = circuit.evaluator_input();
+        let c = circuit.evaluator_input();
+        let ab = circuit.and(a, b);
+        let nc = circuit.not(c);
+        let x = circuit.xor(ab, nc);
+        let y = circuit.or(a, c);
+        circuit.output(x);
+        circuit.output(y);
+        let mut rng = TestRng::from_label(b"garble");
+        for bits in 0..8u8 {
+            let (g, e) = ([bits & 1 == 1], [bits & 2 != 0, bits & 4 != 0]);
+            let (garbled, encoding) = garble(&circuit, &mut rng);
+            let labels: Vec<Label> = encoding
+                .evaluator_labels()
+                .iter()
+                .zip(e)
+                .map(|(&(l0, l1), v)| if v { l1 } else { l0 })
+                .collect();
+            let out = evaluate(&circuit, &garbled, &encoding.encode_garbler(&g), &labels);
+            assert_eq!(out, circuit.evaluate_plain(&g, &e));
+        }
+        assert_eq!(circuit.num_and_gates(), 2);
+    }
+
+    #[test]
+    fn test_millionaires_problem() {
+        let mut rng = TestRng::from_label(b"millionaires");
+        assert!(millionaires(1_000_000, 999_999, 20, &mut rng).unwrap());
+        assert!(!millionaires(5, 5, 8, &mut rng).unwrap());
+        assert!(!millionaires(3, 200, 8, &mut rng).unwrap());
+        assert!(millionaires(255, 0, 8, &mut rng).unwrap());
+        let circuit = greater_than_circuit(8);
+        assert_eq!(circuit.num_and_gates(), 15);
+    }
+}
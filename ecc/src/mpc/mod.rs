@@ -0,0 +1,8 @@
+// 秘密計算 (MPC) の基本部品
+pub mod beaver;
+pub mod garbled;
+pub mod ot;
+
+pub use beaver::{multiply, reconstruct, share, BeaverTriple, Dealer, Party};
+pub use garbled::{evaluate, garble, millionaires, BooleanCircuit, GarbledCircuit, InputEncoding};
+pub use ot::{OtError, OtReceiver, OtSender, ReceiverChoice, SenderSetup, SenderTransfer};
@@ -0,0 +1,162 @@
+// 1-out-of-2 紛失通信 (Chou–Orlandi, 2015 "The Simplest Protocol for Oblivious Transfer")。
+// 送信者は A = aG を送り、受信者は選択ビット c に応じて B = bG か B = A + bG を返す。
+// 受信者は k_c = H(bA) だけを、送信者は k_0 = H(aB), k_1 = H(a(B - A)) の両方を得るので、
+// 送信者は c を知らず、受信者は選ばなかった方のメッセージを復号できない
+use crate::curve::{self, Scalar};
+use crate::hash::sha256;
+use crate::rand::RngCore;
+use crate::symmetric::ChaCha20Poly1305;
+use crate::Point;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OtError {
+    InvalidMessage,
+    DecryptionFailed,
+}
+
+// 送信者 → 受信者: A
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SenderSetup(pub Point);
+
+// 受信者 → 送信者: B
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReceiverChoice(pub Point);
+
+// 送信者 → 受信者: 2 つの鍵で暗号化したメッセージ
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SenderTransfer {
+    pub e0: Vec<u8>,
+    pub e1: Vec<u8>,
+}
+
+fn derive_key(a: &Point, b: &Point, shared: &Point) -> [u8; 32] {
+    let mut input = b"chou-orlandi-ot".to_vec();
+    for p in [a, b, shared] {
+        input.extend_from_slice(&p.to_bytes());
+    }
+    sha256(&input)
+}
+
+// 鍵は 1 度しか使わないので nonce は固定でよい
+fn seal(key: &[u8; 32], message: &[u8]) -> Vec<u8> {
+    ChaCha20Poly1305::new(key).encrypt(&[0; 12], b"", message)
+}
+
+pub struct OtSender {
+    secret: Scalar,
+    setup: Point,
+}
+
+impl OtSender {
+    pub fn new<R: RngCore>(rng: &mut R) -> (OtSender, SenderSetup) {
+        let secret = Scalar::random_nonzero(rng);
+        let setup = curve::generator().scalar_mul(secret.value());
+        let msg = SenderSetup(setup.clone());
+        (OtSender { secret, setup }, msg)
+    }
+
+    // 受信者の B から 2 つの鍵を導く。OT 拡張などで鍵だけ使いたいとき用
+    pub fn keys(&self, choice: &ReceiverChoice) -> Result<([u8; 32], [u8; 32]), OtError> {
+        let b = &choice.0;
+        if b.is_infinity() {
+            return Err(OtError::InvalidMessage);
+        }
+        let k0 = b.scalar_mul(self.secret.value());
+        let k1 = b.add(&self.setup.neg()).scalar_mul(self.secret.value());
+        Ok((
+            derive_key(&self.setup, b, &k0),
+            derive_key(&self.setup, b, &k1),
+        ))
+    }
+
+    pub fn transfer(
+        self,
+        choice: &ReceiverChoice,
+        m0: &[u8],
+        m1: &[u8],
+    ) -> Result<SenderTransfer, OtError> {
+        let (k0, k1) = self.keys(choice)?;
+        Ok(SenderTransfer {
+            e0: seal(&k0, m0),
+            e1: seal(&k1, m1),
+        })
+    }
+}
+
+pub struct OtReceiver {
+    choice: bool,
+    key: [u8; 32],
+}
+
+impl OtReceiver {
+    pub fn new<R: RngCore>(
+        setup: &SenderSetup,
+        choice: bool,
+        rng: &mut R,
+    ) -> Result<(OtReceiver, ReceiverChoice), OtError> {
+        let a = &setup.0;
+        if a.is_infinity() {
+            return Err(OtError::InvalidMessage);
+        }
+        let secret = Scalar::random_nonzero(rng);
+        let bg = curve::generator().scalar_mul(secret.value());
+        let b = if choice { a.add(&bg) } else { bg };
+        let key = derive_key(a, &b, &a.scalar_mul(secret.value()));
+        Ok((OtReceiver { choice, key }, ReceiverChoice(b)))
+    }
+
+    pub fn key(&self) -> [u8; 32] {
+        self.key
+    }
+
+    pub fn receive(self, transfer: &SenderTransfer) -> Result<Vec<u8>, OtError> {
+        let e = if self.choice {
+            &transfer.e1
+        } else {
+            &transfer.e0
+        };
+        ChaCha20Poly1305::new(&self.key)
+            .decrypt(&[0; 12], b"", e)
+            .map_err(|_| OtError::DecryptionFailed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::TestRng;
+
+    fn run(choice: bool) -> (SenderTransfer, OtReceiver) {
+        let mut rng = TestRng::from_label(b"ot");
+        let (sender, setup) = OtSender::new(&mut rng);
+        let (receiver, reply) = OtReceiver::new(&setup, choice, &mut rng).unwrap();
+        let transfer = sender.transfer(&reply, b"zero", b"one").unwrap();
+        (transfer, receiver)
+    }
+
+    #[test]
+    fn test_receiver_gets_chosen_message() {
+        let (transfer, receiver) = run(false);
+        assert_eq!(receiver.receive(&transfer).unwrap(), b"zero");
+        let (transfer, receiver) = run(true);
+        assert_eq!(receiver.receive(&transfer).unwrap(), b"one");
+    }
+
+    #[test]
+    fn test_other_message_stays_hidden() {
+        let (transfer, receiver) = run(true);
+        // 受信者の鍵では選ばなかった方の暗号文を開けない
+        let key = receiver.key();
+        assert_eq!(
+            ChaCha20Poly1305::new(&key).decrypt(&[0; 12], b"", &transfer.e0),
+            Err(crate::symmetric::AeadError::InvalidTag)
+        );
+        let mut rng = TestRng::from_label(b"ot-bad");
+        let (sender, _) = OtSender::new(&mut rng);
+        let bad = ReceiverChoice(curve::identity());
+        assert_eq!(
+            sender.transfer(&bad, b"", b"").err(),
+            Some(OtError::InvalidMessage)
+        );
+    }
+}
@@ -0,0 +1,216 @@
+// Stein の二進 GCD: 除算を使わずシフトと減算だけで計算する。
+// *_ct 版は反復回数と演算列を入力によらず固定し、分岐の代わりにマスクを使う
+use crate::bignum::BigUint;
+
+pub fn binary_gcd(a: u64, b: u64) -> u64 {
+    if a == 0 || b == 0 {
+        return a | b;
+    }
+    let shift = (a | b).trailing_zeros();
+    let (mut a, mut b) = (a >> a.trailing_zeros(), b);
+    // a は常に奇数
+    while b != 0 {
+        b >>= b.trailing_zeros();
+        if a > b {
+            std::mem::swap(&mut a, &mut b);
+        }
+        b -= a;
+    }
+    a << shift
+}
+
+pub fn binary_gcd_big(a: &BigUint, b: &BigUint) -> BigUint {
+    if a.is_zero() || b.is_zero() {
+        return a.add(b);
+    }
+    let shift = a.trailing_zeros().min(b.trailing_zeros());
+    let mut a = a.shr(a.trailing_zeros());
+    let mut b = b.clone();
+    while !b.is_zero() {
+        b = b.shr(b.trailing_zeros());
+        if a > b {
+            std::mem::swap(&mut a, &mut b);
+        }
+        b = b.sub(&a);
+    }
+    a.shl(shift)
+}
+
+fn mask(bit: u64) -> u64 {
+    bit.wrapping_neg()
+}
+
+// 少なくとも一方が奇数であれば、2 * 64 回の反復で必ず a = 0 に到達する
+pub fn binary_gcd_ct(a: u64, b: u64) -> u64 {
+    let shift = (a | b).trailing_zeros().min(63);
+    let (mut a, mut b) = (a >> shift, b >> shift);
+    // b を奇数にしておく
+    let swap = mask(1 ^ (b & 1)) & (a ^ b);
+    a ^= swap;
+    b ^= swap;
+    for _ in 0..128 {
+        let a_odd = mask(a & 1);
+        let lt = mask((a < b) as u64) & a_odd;
+        let swap = lt & (a ^ b);
+        a ^= swap;
+        b ^= swap;
+        a = a.wrapping_sub(b & a_odd);
+        a >>= 1;
+    }
+    // a = b = 0 のときは 0 を返す
+    b << shift
+}
+
+// 奇数の法 m に対する逆元。a ≡ u * x, b ≡ v * x (mod m) を保ちながら二進 GCD を回す
+pub fn mod_inverse_ct(x: u64, m: u64) -> Option<u64> {
+    if m.is_multiple_of(2) {
+        panic!("Constant-time inverse requires an odd modulus");
+    }
+    let m128 = m as u128;
+    let (mut a, mut b) = (x % m, m);
+    let (mut u, mut v) = (1u64 % m, 0u64);
+    for _ in 0..128 {
+        let a_odd = mask(a & 1);
+        let lt = mask((a < b) as u64) & a_odd;
+        let swap_ab = lt & (a ^ b);
+        a ^= swap_ab;
+        b ^= swap_ab;
+        let swap_uv = lt & (u ^ v);
+        u ^= swap_uv;
+        v ^= swap_uv;
+        a = a.wrapping_sub(b & a_odd);
+        // u = u - v (mod m)
+        let (diff, borrow) = u.overflowing_sub(v & a_odd);
+        u = diff.wrapping_add(m & mask(borrow as u64));
+        a >>= 1;
+        // u = u / 2 (mod m)
+        u = ((u as u128 + (m128 & mask(u & 1) as u128)) >> 1) as u64;
+    }
+    if b == 1 {
+        Some(v)
+    } else {
+        None
+    }
+}
+
+// 固定長のリム列に対するマスク付き演算
+fn sub_masked(a: &mut [u64], b: &[u64], m: u64) -> u64 {
+    let mut borrow = 0u64;
+    for (x, &y) in a.iter_mut().zip(b) {
+        let (d1, b1) = x.overflowing_sub(y & m);
+        let (d2, b2) = d1.overflowing_sub(borrow);
+        *x = d2;
+        borrow = (b1 | b2) as u64;
+    }
+    borrow
+}
+
+fn add_masked(a: &mut [u64], b: &[u64], m: u64) {
+    let mut carry = 0u64;
+    for (x, &y) in a.iter_mut().zip(b) {
+        let (s1, c1) = x.overflowing_add(y & m);
+        let (s2, c2) = s1.overflowing_add(carry);
+        *x = s2;
+        carry = (c1 | c2) as u64;
+    }
+}
+
+fn lt_mask(a: &[u64], b: &[u64]) -> u64 {
+    let mut t = a.to_vec();
+    mask(sub_masked(&mut t, b, u64::MAX))
+}
+
+fn swap_masked(a: &mut [u64], b: &mut [u64], m: u64) {
+    for (x, y) in a.iter_mut().zip(b.iter_mut()) {
+        let t = (*x ^ *y) & m;
+        *x ^= t;
+        *y ^= t;
+    }
+}
+
+fn shr1(a: &mut [u64]) {
+    for i in 0..a.len() {
+        let high = a.get(i + 1).map_or(0, |&w| w << 63);
+        a[i] = (a[i] >> 1) | high;
+    }
+}
+
+// mod_inverse_ct と同じ手順を、法のリム数 + 1 の固定幅で行う
+pub fn mod_inverse_ct_big(x: &BigUint, m: &BigUint) -> Option<BigUint> {
+    if m.is_even() {
+        panic!("Constant-time inverse requires an odd modulus");
+    }
+    let width = m.limbs().len() + 1;
+    let pad = |n: &BigUint| {
+        let mut v = n.limbs().to_vec();
+        v.resize(width, 0);
+        v
+    };
+    let modulus = pad(m);
+    let (mut a, mut b) = (pad(&x.rem(m)), modulus.clone());
+    let (mut u, mut v) = (pad(&BigUint::one().rem(m)), vec![0u64; width]);
+    for _ in 0..2 * 64 * m.limbs().len() {
+        let a_odd = mask(a[0] & 1);
+        let lt = lt_mask(&a, &b) & a_odd;
+        swap_masked(&mut a, &mut b, lt);
+        swap_masked(&mut u, &mut v, lt);
+        sub_masked(&mut a, &b, a_odd);
+        let borrow = sub_masked(&mut u, &v, a_odd);
+        add_masked(&mut u, &modulus, mask(borrow));
+        shr1(&mut a);
+        let u_odd = mask(u[0] & 1);
+        add_masked(&mut u, &modulus, u_odd);
+        shr1(&mut u);
+    }
+    if BigUint::from_limbs(b).is_one() {
+        Some(BigUint::from_limbs(v))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::number_theory::{gcd, gcd_big, mod_inverse, mod_inverse_big};
+    use crate::rand::{OsRng, RngCore};
+
+    #[test]
+    fn test_binary_gcd_matches_euclid() {
+        let mut rng = OsRng;
+        for i in 0..500 {
+            let shift = i % 20;
+            let (a, b) = (rng.next_u64() >> shift << shift, rng.next_u64() >> (i % 7));
+            assert_eq!(binary_gcd(a, b), gcd(a, b));
+            assert_eq!(binary_gcd_ct(a, b), gcd(a, b));
+        }
+        for (a, b) in [(0, 0), (0, 12), (12, 0), (1 << 40, 1 << 20), (u64::MAX, 3)] {
+            assert_eq!(binary_gcd(a, b), gcd(a, b));
+            assert_eq!(binary_gcd_ct(a, b), gcd(a, b));
+        }
+        let a = BigUint::from_hex("fedcba9876543210fedcba98765432100").unwrap();
+        let b = BigUint::from_hex("123456789abcdef0123456789000").unwrap();
+        assert_eq!(binary_gcd_big(&a, &b), gcd_big(&a, &b));
+    }
+
+    #[test]
+    fn test_constant_time_inverse() {
+        let mut rng = OsRng;
+        for m in [3u64, 3233, 1_000_003, u64::MAX, 2305843009213693951] {
+            for _ in 0..50 {
+                let x = rng.next_u64();
+                assert_eq!(mod_inverse_ct(x, m), mod_inverse(x, m), "{} {}", x, m);
+            }
+        }
+        assert_eq!(mod_inverse_ct(0, 7), None);
+        let m =
+            BigUint::from_hex("c90fdaa22168c234c4c6628b80dc1cd129024e088a67cc74020bbea63b139b23")
+                .unwrap();
+        for _ in 0..20 {
+            let x = BigUint::random_bits(300, &mut rng);
+            assert_eq!(mod_inverse_ct_big(&x, &m), mod_inverse_big(&x, &m));
+        }
+        let m = BigUint::from_u64(3 * 5 * 7 * 11);
+        assert_eq!(mod_inverse_ct_big(&BigUint::from_u64(21), &m), None);
+    }
+}
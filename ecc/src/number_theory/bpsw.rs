@@ -0,0 +1,287 @@
+// Baillie–PSW 判定: 底 2 の強擬素数判定と強 Lucas 擬素数判定の組み合わせ。
+// 両方をすり抜ける合成数は (64 ビットの範囲では) 見つかっていない。
+use super::{jacobi, jacobi_big, mod_mul, primes_up_to};
+use crate::bignum::BigUint;
+use crate::number_theory::primality::{miller_rabin_big, miller_rabin_u64};
+
+// どの段階で合成数と判定されたか
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BpswResult {
+    ProbablePrime,
+    LessThanTwo,
+    SmallFactor(u64),
+    PerfectSquare,
+    StrongFermat,
+    StrongLucas,
+}
+
+fn isqrt_u64(n: u64) -> u64 {
+    let mut x = (n as f64).sqrt() as u64;
+    while x as u128 * x as u128 > n as u128 {
+        x -= 1;
+    }
+    while (x as u128 + 1) * (x as u128 + 1) <= n as u128 {
+        x += 1;
+    }
+    x
+}
+
+fn sub_mod(a: u64, b: u64, n: u64) -> u64 {
+    ((a as u128 + n as u128 - b as u128) % n as u128) as u64
+}
+
+fn half_mod(a: u64, n: u64) -> u64 {
+    if a.is_multiple_of(2) {
+        a / 2
+    } else {
+        ((a as u128 + n as u128) / 2) as u64
+    }
+}
+
+// Selfridge の方法 A: D = 5, -7, 9, -11, ... で (D / n) = -1 となる最初の D
+fn selfridge_d(n: u64) -> Option<i64> {
+    let mut d: i64 = 5;
+    loop {
+        match jacobi(d, n) {
+            -1 => return Some(d),
+            0 if d.unsigned_abs() != n => return None,
+            _ => {}
+        }
+        d = if d > 0 { -(d + 2) } else { -d + 2 };
+    }
+}
+
+// P = 1, Q = (1 - D) / 4 の Lucas 列で強 Lucas 擬素数かを判定する
+pub fn is_strong_lucas_prp(n: u64) -> bool {
+    if n == 2 {
+        return true;
+    }
+    if n < 2 || n.is_multiple_of(2) || isqrt_u64(n).pow(2) == n {
+        return false;
+    }
+    let Some(d) = selfridge_d(n) else {
+        return false;
+    };
+    let to_mod = |x: i64| (x as i128).rem_euclid(n as i128) as u64;
+    let (p, q, dm) = (1u64, to_mod((1 - d) / 4), to_mod(d));
+
+    // n + 1 = k * 2^s
+    let s = (n as u128 + 1).trailing_zeros();
+    let k = (n as u128 + 1) >> s;
+
+    // 二進展開の上位ビットから U_k, V_k, Q^k を計算する
+    let (mut u, mut v, mut qk) = (1u64, p, q);
+    for i in (0..127 - k.leading_zeros()).rev() {
+        u = mod_mul(u, v, n);
+        v = sub_mod(mod_mul(v, v, n), mod_mul(2, qk, n), n);
+        qk = mod_mul(qk, qk, n);
+        if (k >> i) & 1 == 1 {
+            let next_u = half_mod(
+                ((mod_mul(p, u, n) as u128 + v as u128) % n as u128) as u64,
+                n,
+            );
+            let next_v = half_mod(
+                ((mod_mul(dm, u, n) as u128 + mod_mul(p, v, n) as u128) % n as u128) as u64,
+                n,
+            );
+            u = next_u;
+            v = next_v;
+            qk = mod_mul(qk, q, n);
+        }
+    }
+
+    if u == 0 || v == 0 {
+        return true;
+    }
+    for _ in 1..s {
+        v = sub_mod(mod_mul(v, v, n), mod_mul(2, qk, n), n);
+        qk = mod_mul(qk, qk, n);
+        if v == 0 {
+            return true;
+        }
+    }
+    false
+}
+
+pub fn baillie_psw_check(n: u64) -> BpswResult {
+    if n < 2 {
+        return BpswResult::LessThanTwo;
+    }
+    for p in primes_up_to(100) {
+        if n == p {
+            return BpswResult::ProbablePrime;
+        }
+        if n.is_multiple_of(p) {
+            return BpswResult::SmallFactor(p);
+        }
+    }
+    if !miller_rabin_u64(n, 2) {
+        return BpswResult::StrongFermat;
+    }
+    if isqrt_u64(n).pow(2) == n {
+        return BpswResult::PerfectSquare;
+    }
+    if !is_strong_lucas_prp(n) {
+        return BpswResult::StrongLucas;
+    }
+    BpswResult::ProbablePrime
+}
+
+pub fn is_prime_bpsw(n: u64) -> bool {
+    baillie_psw_check(n) == BpswResult::ProbablePrime
+}
+
+fn selfridge_d_big(n: &BigUint) -> Option<i64> {
+    let mut d: i64 = 5;
+    loop {
+        let d_mod_n = if d > 0 {
+            BigUint::from_u64(d as u64)
+        } else {
+            n.sub(&BigUint::from_u64(d.unsigned_abs()))
+        };
+        match jacobi_big(&d_mod_n, n) {
+            -1 => return Some(d),
+            0 => return None,
+            _ => {}
+        }
+        d = if d > 0 { -(d + 2) } else { -d + 2 };
+    }
+}
+
+// is_strong_lucas_prp と同じ計算を BigUint で行う
+pub fn is_strong_lucas_prp_big(n: &BigUint) -> bool {
+    if let Some(small) = n.to_u64() {
+        return is_strong_lucas_prp(small);
+    }
+    if n.is_even() || n.is_perfect_square() {
+        return false;
+    }
+    let Some(d) = selfridge_d_big(n) else {
+        return false;
+    };
+    let to_mod = |x: i64| {
+        if x >= 0 {
+            BigUint::from_u64(x as u64)
+        } else {
+            n.sub(&BigUint::from_u64(x.unsigned_abs()))
+        }
+    };
+    let (q, dm) = (to_mod((1 - d) / 4), to_mod(d));
+    let sub_mod = |a: &BigUint, b: &BigUint| a.add(n).sub(b).rem(n);
+    let half_mod = |a: BigUint| if a.is_even() { a } else { a.add(n) }.shr(1);
+
+    let n_plus_1 = n.add(&BigUint::one());
+    let s = n_plus_1.trailing_zeros();
+    let k = n_plus_1.shr(s);
+
+    // P = 1
+    let (mut u, mut v, mut qk) = (BigUint::one(), BigUint::one(), q.clone());
+    for i in (0..k.bits() - 1).rev() {
+        u = u.mul(&v).rem(n);
+        v = sub_mod(&v.mul(&v).rem(n), &qk.shl(1).rem(n));
+        qk = qk.mul(&qk).rem(n);
+        if k.bit(i) {
+            let next_u = half_mod(u.add(&v).rem(n));
+            let next_v = half_mod(dm.mul(&u).add(&v).rem(n));
+            u = next_u;
+            v = next_v;
+            qk = qk.mul(&q).rem(n);
+        }
+    }
+
+    if u.is_zero() || v.is_zero() {
+        return true;
+    }
+    for _ in 1..s {
+        v = sub_mod(&v.mul(&v).rem(n), &qk.shl(1).rem(n));
+        qk = qk.mul(&qk).rem(n);
+        if v.is_zero() {
+            return true;
+        }
+    }
+    false
+}
+
+pub fn baillie_psw_check_big(n: &BigUint) -> BpswResult {
+    if let Some(small) = n.to_u64() {
+        return baillie_psw_check(small);
+    }
+    for p in primes_up_to(100) {
+        if n.rem_u64(p) == 0 {
+            return BpswResult::SmallFactor(p);
+        }
+    }
+    if !miller_rabin_big(n, &BigUint::from_u64(2)) {
+        return BpswResult::StrongFermat;
+    }
+    if n.is_perfect_square() {
+        return BpswResult::PerfectSquare;
+    }
+    if !is_strong_lucas_prp_big(n) {
+        return BpswResult::StrongLucas;
+    }
+    BpswResult::ProbablePrime
+}
+
+pub fn is_prime_bpsw_big(n: &BigUint) -> bool {
+    baillie_psw_check_big(n) == BpswResult::ProbablePrime
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pseudoprimes_are_caught_by_the_other_test() {
+        // 底 2 の強擬素数は Lucas 判定で落ちる
+        for n in [2047, 3277, 4033, 4681, 8321] {
+            assert!(!is_strong_lucas_prp(n), "{}", n);
+        }
+        for n in [1373653, 25326001, 3825123056546413051] {
+            assert_eq!(baillie_psw_check(n), BpswResult::StrongLucas, "{}", n);
+        }
+        // 強 Lucas 擬素数は底 2 の判定で落ちる
+        for n in [5459, 5777, 10877, 16109, 18971] {
+            assert!(is_strong_lucas_prp(n), "{}", n);
+        }
+        for n in [22499, 25199, 40309, 58519] {
+            assert!(is_strong_lucas_prp(n), "{}", n);
+            assert_eq!(baillie_psw_check(n), BpswResult::StrongFermat, "{}", n);
+        }
+    }
+
+    #[test]
+    fn test_bpsw_primes_and_small_cases() {
+        for p in [
+            2,
+            97,
+            101,
+            1_000_003,
+            2305843009213693951,
+            18446744073709551557,
+        ] {
+            assert!(is_prime_bpsw(p), "{}", p);
+        }
+        assert_eq!(baillie_psw_check(1), BpswResult::LessThanTwo);
+        assert_eq!(baillie_psw_check(91), BpswResult::SmallFactor(7));
+        assert!(!is_prime_bpsw(u64::MAX));
+    }
+
+    #[test]
+    fn test_bpsw_big() {
+        let one = BigUint::one();
+        let m127 = one.shl(127).sub(&one);
+        assert!(is_prime_bpsw_big(&m127));
+        assert!(is_strong_lucas_prp_big(&m127));
+        let m521 = one.shl(521).sub(&one);
+        assert!(is_prime_bpsw_big(&m521));
+        // フェルマー数 2^128 + 1 = 59649589127497217 * 5704689200685129054721 は
+        // 底 2 の強擬素数なので Lucas 判定で初めて落ちる
+        let f7 = one.shl(128).add(&one);
+        assert_eq!(baillie_psw_check_big(&f7), BpswResult::StrongLucas);
+        assert!(!is_strong_lucas_prp_big(&f7));
+        let square = m127.mul(&m127);
+        assert_eq!(baillie_psw_check_big(&square), BpswResult::StrongFermat);
+        assert!(!is_strong_lucas_prp_big(&square));
+    }
+}
@@ -0,0 +1,51 @@
+use crate::bignum::BigUint;
+
+// num / den の連分数展開 [a0; a1, a2, ...]
+pub fn continued_fraction(num: &BigUint, den: &BigUint) -> Vec<BigUint> {
+    if den.is_zero() {
+        panic!("Continued fraction of {}/0 is undefined", num);
+    }
+    let mut terms = Vec::new();
+    let (mut a, mut b) = (num.clone(), den.clone());
+    while !b.is_zero() {
+        let (q, r) = a.divrem(&b);
+        terms.push(q);
+        a = b;
+        b = r;
+    }
+    terms
+}
+
+// 各近似分数 h_i / k_i を (h_i, k_i) として返す
+pub fn convergents(terms: &[BigUint]) -> Vec<(BigUint, BigUint)> {
+    // h_{-1} = 1, h_{-2} = 0, k_{-1} = 0, k_{-2} = 1
+    let (mut h_prev, mut h) = (BigUint::zero(), BigUint::one());
+    let (mut k_prev, mut k) = (BigUint::one(), BigUint::zero());
+    let mut result = Vec::with_capacity(terms.len());
+    for a in terms {
+        let h_next = a.mul(&h).add(&h_prev);
+        let k_next = a.mul(&k).add(&k_prev);
+        h_prev = std::mem::replace(&mut h, h_next);
+        k_prev = std::mem::replace(&mut k, k_next);
+        result.push((h.clone(), k.clone()));
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_continued_fraction_and_convergents() {
+        // 415 / 93 = [4; 2, 6, 7]
+        let terms = continued_fraction(&BigUint::from_u64(415), &BigUint::from_u64(93));
+        let expected: Vec<BigUint> = [4, 2, 6, 7].map(BigUint::from_u64).to_vec();
+        assert_eq!(terms, expected);
+        let conv: Vec<(u64, u64)> = convergents(&terms)
+            .iter()
+            .map(|(h, k)| (h.to_u64().unwrap(), k.to_u64().unwrap()))
+            .collect();
+        assert_eq!(conv, vec![(4, 1), (9, 2), (58, 13), (415, 93)]);
+    }
+}
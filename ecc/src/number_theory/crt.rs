@@ -0,0 +1,50 @@
+use super::{gcd_big, mod_inverse_big};
+use crate::bignum::{BigInt, BigUint};
+
+// x ≡ r_i (mod m_i) を満たす最小の非負整数 x。法が互いに素でなければ None。
+pub fn crt(congruences: &[(BigUint, BigUint)]) -> Option<BigUint> {
+    for (i, (_, m_i)) in congruences.iter().enumerate() {
+        if m_i.is_zero() {
+            return None;
+        }
+        for (_, m_j) in &congruences[i + 1..] {
+            if !gcd_big(m_i, m_j).is_one() {
+                return None;
+            }
+        }
+    }
+
+    let mut x = BigUint::zero();
+    let mut modulus = BigUint::one();
+    for (r, m) in congruences {
+        // x + modulus * k ≡ r (mod m) を k について解く
+        let diff = BigInt::from_biguint(r.clone()).sub(&BigInt::from_biguint(x.clone()));
+        let inv = mod_inverse_big(&modulus, m)?;
+        let k = diff.mod_floor(m).mul(&inv).rem(m);
+        x = x.add(&modulus.mul(&k));
+        modulus = modulus.mul(m);
+    }
+    Some(x.rem(&modulus))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(n: u64) -> BigUint {
+        BigUint::from_u64(n)
+    }
+
+    #[test]
+    fn test_crt_classic_example() {
+        // 孫子算経: 3 で割ると 2 余り、5 で割ると 3 余り、7 で割ると 2 余る数
+        let x = crt(&[(big(2), big(3)), (big(3), big(5)), (big(2), big(7))]);
+        assert_eq!(x, Some(big(23)));
+    }
+
+    #[test]
+    fn test_crt_rejects_non_coprime_moduli() {
+        assert_eq!(crt(&[(big(1), big(4)), (big(3), big(6))]), None);
+        assert_eq!(crt(&[(big(1), big(0))]), None);
+    }
+}
@@ -0,0 +1,124 @@
+// 離散対数問題: base^x = target となる x を求める
+pub mod pohlig_hellman;
+pub mod rho;
+
+pub use pohlig_hellman::{pohlig_hellman, pohlig_hellman_work};
+pub use rho::{pollard_rho, pollard_rho_parallel};
+
+use crate::{FieldElement, Point};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+// Z_p^* (乗法) と楕円曲線上の点 (加法) を同じように扱うための巡回群の抽象
+pub trait CyclicGroup: Clone + Eq + Hash {
+    fn identity(&self) -> Self;
+    fn combine(&self, other: &Self) -> Self;
+    fn inverse(&self) -> Self;
+    fn exp(&self, k: u64) -> Self;
+}
+
+impl CyclicGroup for FieldElement {
+    fn identity(&self) -> Self {
+        FieldElement::new(1, self.prime)
+    }
+
+    fn combine(&self, other: &Self) -> Self {
+        self.mul(other)
+    }
+
+    fn inverse(&self) -> Self {
+        self.identity().div(self)
+    }
+
+    fn exp(&self, k: u64) -> Self {
+        self.pow(k)
+    }
+}
+
+impl CyclicGroup for Point {
+    fn identity(&self) -> Self {
+        Point::new(None, None, self.a.clone(), self.b.clone())
+    }
+
+    fn combine(&self, other: &Self) -> Self {
+        self.add(other)
+    }
+
+    fn inverse(&self) -> Self {
+        self.neg()
+    }
+
+    fn exp(&self, k: u64) -> Self {
+        self.scalar_mul(k)
+    }
+}
+
+fn ceil_sqrt(n: u64) -> u64 {
+    let mut m = (n as f64).sqrt() as u64;
+    while (m as u128) * (m as u128) < n as u128 {
+        m += 1;
+    }
+    m
+}
+
+// Baby-step giant-step: 時間も空間も O(√order)
+pub fn bsgs<G: CyclicGroup>(base: &G, target: &G, order: u64) -> Option<u64> {
+    let m = ceil_sqrt(order);
+
+    // baby steps: base^j (0 <= j < m)
+    let mut table = HashMap::with_capacity(m as usize);
+    let mut current = base.identity();
+    for j in 0..m {
+        table.entry(current.clone()).or_insert(j);
+        current = current.combine(base);
+    }
+
+    // giant steps: target * base^(-i*m)
+    let giant = base.exp(m).inverse();
+    let mut gamma = target.clone();
+    for i in 0..m {
+        if let Some(&j) = table.get(&gamma) {
+            return Some((i * m + j) % order);
+        }
+        gamma = gamma.combine(&giant);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bsgs_multiplicative_group() {
+        // 5 は p = 1000003 の原始根
+        let p = 1_000_003;
+        let g = FieldElement::new(5, p);
+        let x = 777_777;
+        assert_eq!(bsgs(&g, &g.pow(x), p - 1), Some(x));
+    }
+
+    #[test]
+    fn test_bsgs_elliptic_curve_group() {
+        // y^2 = x^3 + 7 over F_223 上の位数 7 の点 (15, 86)
+        let a = FieldElement::new(0, 223);
+        let b = FieldElement::new(7, 223);
+        let g = Point::new(
+            Some(FieldElement::new(15, 223)),
+            Some(FieldElement::new(86, 223)),
+            a,
+            b,
+        );
+        for k in 0..7 {
+            assert_eq!(bsgs(&g, &g.scalar_mul(k), 7), Some(k));
+        }
+        // 生成されない点には解がない
+        let other = Point::new(
+            Some(FieldElement::new(47, 223)),
+            Some(FieldElement::new(71, 223)),
+            g.a.clone(),
+            g.b.clone(),
+        );
+        assert_eq!(bsgs(&g, &other, 7), None);
+    }
+}
@@ -0,0 +1,85 @@
+// Pohlig–Hellman: 群の位数 n = Π p_i^e_i の各素数冪部分群で離散対数を解き、CRT で結合する。
+// 計算量は Σ e_i·√p_i 程度なので、n の最大素因数が小さいと離散対数は簡単に解けてしまう。
+use super::{bsgs, CyclicGroup};
+use crate::bignum::BigUint;
+use crate::number_theory::{crt, factorize};
+
+// 位数 p^e の部分群で x mod p^e を p 進の桁ごとに求める
+fn solve_prime_power<G: CyclicGroup>(
+    base: &G,
+    target: &G,
+    order: u64,
+    p: u64,
+    e: u32,
+) -> Option<u64> {
+    // gamma は位数 p の元
+    let gamma = base.exp(order / p);
+    let mut x = 0u64;
+    let mut p_k = 1u64;
+    for k in 0..e {
+        // (target * base^(-x))^(n / p^(k+1)) = gamma^(d_k)
+        let reduced = target.combine(&base.exp(x).inverse());
+        let h = reduced.exp(order / p_k / p);
+        let d = bsgs(&gamma, &h, p)?;
+        x += d * p_k;
+        if k + 1 < e {
+            p_k *= p;
+        }
+    }
+    Some(x)
+}
+
+pub fn pohlig_hellman<G: CyclicGroup>(base: &G, target: &G, order: u64) -> Option<u64> {
+    let mut congruences = Vec::new();
+    for (p, e) in factorize(order) {
+        let x = solve_prime_power(base, target, order, p, e)?;
+        congruences.push((BigUint::from_u64(x), BigUint::from_u64(p.pow(e))));
+    }
+    let x = crt(&congruences)?.to_u64()?;
+    // target が base の生成する群に入っていなければ検算で弾かれる
+    if base.exp(x) == *target {
+        Some(x)
+    } else {
+        None
+    }
+}
+
+// 群演算のおおよその回数 Σ e_i·⌈√p_i⌉ (素数位数の群ではこれが √n になる)
+pub fn pohlig_hellman_work(order: u64) -> u64 {
+    factorize(order)
+        .into_iter()
+        .map(|(p, e)| e as u64 * ((p as f64).sqrt().ceil() as u64))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FieldElement, Point};
+
+    #[test]
+    fn test_smooth_order_multiplicative_group() {
+        // p - 1 = 2^41 * 3 なので 42 ビット強の群でも一瞬で解ける
+        let p = 6_597_069_766_657;
+        let g = FieldElement::new(5, p);
+        let x = 4_398_046_511_104 + 12345;
+        assert_eq!(pohlig_hellman(&g, &g.pow(x), p - 1), Some(x));
+        assert!(pohlig_hellman_work(p - 1) < 100);
+        assert!(pohlig_hellman_work(1_000_003) > 1000);
+    }
+
+    #[test]
+    fn test_composite_order_curve_group() {
+        // y^2 = x^3 + 7 over F_10079 の位数は 10080 = 2^5 * 3^2 * 5 * 7 で、(6250, 5842) が生成元
+        let prime = 10079;
+        let g = Point::new(
+            Some(FieldElement::new(6250, prime)),
+            Some(FieldElement::new(5842, prime)),
+            FieldElement::new(0, prime),
+            FieldElement::new(7, prime),
+        );
+        for x in [0, 1, 4321, 10079] {
+            assert_eq!(pohlig_hellman(&g, &g.scalar_mul(x), 10080), Some(x));
+        }
+    }
+}
@@ -0,0 +1,190 @@
+// 区別点 (distinguished points) を使った Pollard ρ 法による離散対数。
+// メモリは区別点の数だけで済み、複数スレッドのランダムウォークで並列化できる。
+use super::CyclicGroup;
+use crate::number_theory::{gcd, mod_inverse, mod_mul};
+use crate::rand::RngCore;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+
+const PARTITIONS: usize = 20;
+
+struct Walk<G> {
+    multipliers: Vec<(G, u64, u64)>,
+    dp_mask: u64,
+    max_len: u64,
+}
+
+struct Shared<'a, G, R> {
+    table: Mutex<HashMap<G, (u64, u64)>>,
+    rng: Mutex<&'a mut R>,
+    result: Mutex<Option<u64>>,
+    done: AtomicBool,
+    steps: AtomicU64,
+}
+
+fn element_hash<G: Hash>(g: &G) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    g.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn add_mod(a: u64, b: u64, n: u64) -> u64 {
+    ((a as u128 + b as u128) % n as u128) as u64
+}
+
+// a1 + b1*x ≡ a2 + b2*x (mod n) を解き、実際に base^x = target となる x を返す
+fn solve_collision<G: CyclicGroup>(
+    base: &G,
+    target: &G,
+    order: u64,
+    (a1, b1): (u64, u64),
+    (a2, b2): (u64, u64),
+) -> Option<u64> {
+    let db = (b2 + order - b1) % order;
+    let da = (a1 + order - a2) % order;
+    let d = gcd(db, order);
+    if db == 0 || !da.is_multiple_of(d) || d > 1 << 16 {
+        return None;
+    }
+    let reduced = order / d;
+    let x0 = mod_mul(da / d, mod_inverse(db / d, reduced)?, reduced);
+    (0..d)
+        .map(|k| x0 + k * reduced)
+        .find(|&x| base.exp(x) == *target)
+}
+
+fn worker<G: CyclicGroup, R: RngCore>(
+    base: &G,
+    target: &G,
+    order: u64,
+    walk: &Walk<G>,
+    shared: &Shared<G, R>,
+    budget: u64,
+) {
+    while !shared.done.load(Ordering::Relaxed) {
+        let (mut a, mut b) = {
+            let mut rng = shared.rng.lock().unwrap();
+            (rng.next_u64() % order, rng.next_u64() % order)
+        };
+        let mut y = base.exp(a).combine(&target.exp(b));
+        for _ in 0..walk.max_len {
+            let h = element_hash(&y);
+            if (h >> 8) & walk.dp_mask == 0 {
+                let mut table = shared.table.lock().unwrap();
+                match table.get(&y) {
+                    Some(&prev) if prev != (a, b) => {
+                        if let Some(x) = solve_collision(base, target, order, prev, (a, b)) {
+                            *shared.result.lock().unwrap() = Some(x);
+                            shared.done.store(true, Ordering::Relaxed);
+                        }
+                    }
+                    Some(_) => {}
+                    None => {
+                        table.insert(y.clone(), (a, b));
+                    }
+                }
+                break;
+            }
+            // r-adding walk: 分割ごとに決めた乗数を掛ける
+            let (m, c, d) = &walk.multipliers[(h % PARTITIONS as u64) as usize];
+            y = y.combine(m);
+            a = add_mod(a, *c, order);
+            b = add_mod(b, *d, order);
+        }
+        if shared.steps.fetch_add(walk.max_len, Ordering::Relaxed) > budget {
+            shared.done.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+pub fn pollard_rho_parallel<G, R>(
+    base: &G,
+    target: &G,
+    order: u64,
+    threads: usize,
+    rng: &mut R,
+) -> Option<u64>
+where
+    G: CyclicGroup + Send + Sync,
+    R: RngCore + Send,
+{
+    if order < 2 {
+        return Some(0);
+    }
+    let multipliers = (0..PARTITIONS)
+        .map(|_| {
+            let (c, d) = (rng.next_u64() % order, rng.next_u64() % order);
+            (base.exp(c).combine(&target.exp(d)), c, d)
+        })
+        .collect();
+    // √order 回程度のステップで衝突するので、区別点はその 1/4 乗程度の頻度にする
+    let bits = 64 - order.leading_zeros() as u64;
+    let dp_bits = (bits / 4).min(16);
+    let walk = Walk {
+        multipliers,
+        dp_mask: (1 << dp_bits) - 1,
+        max_len: 20 << dp_bits,
+    };
+    let budget = 64 * (order as f64).sqrt() as u64 + 100 * walk.max_len;
+    let shared = Shared {
+        table: Mutex::new(HashMap::new()),
+        rng: Mutex::new(rng),
+        result: Mutex::new(None),
+        done: AtomicBool::new(false),
+        steps: AtomicU64::new(0),
+    };
+
+    std::thread::scope(|s| {
+        for _ in 1..threads.max(1) {
+            s.spawn(|| worker(base, target, order, &walk, &shared, budget));
+        }
+        worker(base, target, order, &walk, &shared, budget);
+    });
+    shared.result.into_inner().unwrap()
+}
+
+pub fn pollard_rho<G, R>(base: &G, target: &G, order: u64, rng: &mut R) -> Option<u64>
+where
+    G: CyclicGroup + Send + Sync,
+    R: RngCore + Send,
+{
+    pollard_rho_parallel(base, target, order, 1, rng)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::OsRng;
+    use crate::{FieldElement, Point};
+
+    #[test]
+    fn test_rho_prime_order_subgroup() {
+        // p = 2q + 1 の安全素数で、4 = 2^2 は位数 q の部分群を生成する
+        let p = 2_000_000_579;
+        let q = (p - 1) / 2;
+        let g = FieldElement::new(4, p);
+        let x = 123_456_789;
+        assert_eq!(pollard_rho(&g, &g.pow(x), q, &mut OsRng), Some(x));
+    }
+
+    #[test]
+    fn test_rho_parallel_elliptic_curve() {
+        // y^2 = x^3 + 7 over F_1000003 は素数位数 999007
+        let prime = 1_000_003;
+        let g = Point::new(
+            Some(FieldElement::new(76347, prime)),
+            Some(FieldElement::new(606316, prime)),
+            FieldElement::new(0, prime),
+            FieldElement::new(7, prime),
+        );
+        let order = 999_007;
+        let x = 424_242;
+        assert_eq!(
+            pollard_rho_parallel(&g, &g.scalar_mul(x), order, 4, &mut OsRng),
+            Some(x)
+        );
+    }
+}
@@ -0,0 +1,254 @@
+use super::{gcd, gcd_big, is_prime, is_prime_big, mod_mul, primes_up_to};
+use crate::bignum::BigUint;
+use crate::rand::{default_rng, RngCore};
+
+const TRIAL_DIVISION_BOUND: usize = 1000;
+const PRIMALITY_ROUNDS: usize = 32;
+
+fn collect<T: Ord + Clone>(mut factors: Vec<T>) -> Vec<(T, u32)> {
+    factors.sort();
+    let mut out: Vec<(T, u32)> = Vec::new();
+    for f in factors {
+        match out.last_mut() {
+            Some((p, e)) if *p == f => *e += 1,
+            _ => out.push((f, 1)),
+        }
+    }
+    out
+}
+
+// Brent の改良版 Pollard ρ 法。f(x) = x^2 + c で周期を探し、gcd をまとめて取る
+fn brent_rho(n: u64, c: u64) -> Option<u64> {
+    let batch = 128;
+    // n が 2^64 に近いと x^2 + c が u64 からあふれるので u128 で足す
+    let f = |x: u64| ((mod_mul(x, x, n) as u128 + c as u128) % n as u128) as u64;
+    let (mut y, mut r, mut q, mut g) = (2u64, 1u64, 1u64, 1u64);
+    let mut x = y;
+    let mut ys = y;
+    while g == 1 {
+        x = y;
+        for _ in 0..r {
+            y = f(y);
+        }
+        let mut k = 0;
+        while k < r && g == 1 {
+            ys = y;
+            for _ in 0..batch.min(r - k) {
+                y = f(y);
+                q = mod_mul(q, x.abs_diff(y), n);
+            }
+            g = gcd(q, n);
+            k += batch;
+        }
+        r *= 2;
+    }
+    // まとめた積が n になってしまったら 1 歩ずつやり直す
+    if g == n {
+        loop {
+            ys = f(ys);
+            g = gcd(x.abs_diff(ys), n);
+            if g > 1 {
+                break;
+            }
+        }
+    }
+    if g == n {
+        None
+    } else {
+        Some(g)
+    }
+}
+
+fn split<R: RngCore>(n: u64, out: &mut Vec<u64>, rng: &mut R) {
+    if n == 1 {
+        return;
+    }
+    if is_prime(n, PRIMALITY_ROUNDS, rng) {
+        out.push(n);
+        return;
+    }
+    let d = (1..).find_map(|c| brent_rho(n, c)).unwrap();
+    split(d, out, rng);
+    split(n / d, out, rng);
+}
+
+pub fn factorize(n: u64) -> Vec<(u64, u32)> {
+    factorize_with_rng(n, &mut default_rng())
+}
+
+// 素数判定 (Miller–Rabin) の乱数源を呼び出し側が渡す版
+pub fn factorize_with_rng<R: RngCore>(n: u64, rng: &mut R) -> Vec<(u64, u32)> {
+    if n == 0 {
+        panic!("Cannot factorize zero");
+    }
+    let mut n = n;
+    let mut factors = Vec::new();
+    for p in primes_up_to(TRIAL_DIVISION_BOUND) {
+        while n.is_multiple_of(p) {
+            factors.push(p);
+            n /= p;
+        }
+    }
+    split(n, &mut factors, rng);
+    collect(factors)
+}
+
+fn brent_rho_big(n: &BigUint, c: u64) -> Option<BigUint> {
+    let batch = 128;
+    let c = BigUint::from_u64(c);
+    let f = |x: &BigUint| x.mul(x).add(&c).rem(n);
+    let diff = |a: &BigUint, b: &BigUint| if a > b { a.sub(b) } else { b.sub(a) };
+    let mut y = BigUint::from_u64(2);
+    let mut x = y.clone();
+    let mut ys = y.clone();
+    let mut q = BigUint::one();
+    let mut g = BigUint::one();
+    let mut r = 1u64;
+    while g.is_one() {
+        x = y.clone();
+        for _ in 0..r {
+            y = f(&y);
+        }
+        let mut k = 0;
+        while k < r && g.is_one() {
+            ys = y.clone();
+            for _ in 0..batch.min(r - k) {
+                y = f(&y);
+                q = q.mul(&diff(&x, &y)).rem(n);
+            }
+            g = gcd_big(&q, n);
+            k += batch;
+        }
+        r *= 2;
+    }
+    if g == *n {
+        loop {
+            ys = f(&ys);
+            g = gcd_big(&diff(&x, &ys), n);
+            if !g.is_one() {
+                break;
+            }
+        }
+    }
+    if g == *n {
+        None
+    } else {
+        Some(g)
+    }
+}
+
+fn split_big<R: RngCore>(n: BigUint, out: &mut Vec<BigUint>, rng: &mut R) {
+    if n.is_one() {
+        return;
+    }
+    if let Some(small) = n.to_u64() {
+        let mut factors = Vec::new();
+        split(small, &mut factors, rng);
+        out.extend(factors.into_iter().map(BigUint::from_u64));
+        return;
+    }
+    if is_prime_big(&n, PRIMALITY_ROUNDS, rng) {
+        out.push(n);
+        return;
+    }
+    let d = (1..).find_map(|c| brent_rho_big(&n, c)).unwrap();
+    let rest = n.div(&d);
+    split_big(d, out, rng);
+    split_big(rest, out, rng);
+}
+
+pub fn factorize_big(n: &BigUint) -> Vec<(BigUint, u32)> {
+    factorize_big_with_rng(n, &mut default_rng())
+}
+
+pub fn factorize_big_with_rng<R: RngCore>(n: &BigUint, rng: &mut R) -> Vec<(BigUint, u32)> {
+    if n.is_zero() {
+        panic!("Cannot factorize zero");
+    }
+    let mut n = n.clone();
+    let mut factors = Vec::new();
+    for p in primes_up_to(TRIAL_DIVISION_BOUND) {
+        while n.rem_u64(p) == 0 {
+            factors.push(BigUint::from_u64(p));
+            n = n.div(&BigUint::from_u64(p));
+        }
+    }
+    split_big(n, &mut factors, rng);
+    collect(factors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::TestRng;
+
+    #[test]
+    fn test_factorize_u64() {
+        assert_eq!(factorize(1), vec![]);
+        assert_eq!(factorize(360), vec![(2, 3), (3, 2), (5, 1)]);
+        // 2^64 - 1 = 3 * 5 * 17 * 257 * 641 * 65537 * 6700417
+        assert_eq!(
+            factorize(u64::MAX),
+            vec![
+                (3, 1),
+                (5, 1),
+                (17, 1),
+                (257, 1),
+                (641, 1),
+                (65537, 1),
+                (6700417, 1)
+            ]
+        );
+        // 32 ビット素数 2 つの積 (小さい RSA 法)
+        assert_eq!(
+            factorize(4294967291 * 4294967279),
+            vec![(4294967279, 1), (4294967291, 1)]
+        );
+    }
+
+    #[test]
+    fn test_factorize_near_u64_max() {
+        // 2^64 - 39 = 139646831 * 132095686967
+        let n = u64::MAX - 38;
+        assert_eq!(factorize(n), vec![(139646831, 1), (132095686967, 1)]);
+        // c が大きくても x^2 + c はあふれない
+        if let Some(d) = brent_rho(n, u64::MAX - 1) {
+            assert_eq!(n % d, 0);
+        }
+    }
+
+    #[test]
+    fn test_factorize_big() {
+        let n = BigUint::from_str_radix("38687433415368767705353687", 10).unwrap();
+        assert_eq!(
+            factorize_big(&n),
+            vec![
+                (BigUint::from_u64(67108879), 1),
+                (BigUint::from_u64(268447807), 1),
+                (BigUint::from_u64(2147484679), 1),
+            ]
+        );
+        let n = BigUint::from_u64(1 << 40).mul(&BigUint::from_u64(1_000_003));
+        assert_eq!(
+            factorize_big(&n),
+            vec![
+                (BigUint::from_u64(2), 40),
+                (BigUint::from_u64(1_000_003), 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_factorize_with_injected_rng() {
+        // 素数判定の乱数を固定しても結果は同じ
+        let n = BigUint::from_u64(1 << 40).mul(&BigUint::from_u64(1_000_003));
+        assert_eq!(
+            factorize_big_with_rng(&n, &mut TestRng::new()),
+            factorize_big(&n)
+        );
+        assert_eq!(
+            factorize_with_rng(4294967291 * 4294967279, &mut TestRng::new()),
+            vec![(4294967279, 1), (4294967291, 1)]
+        );
+    }
+}
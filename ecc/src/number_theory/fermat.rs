@@ -0,0 +1,68 @@
+// フェルマー法: n = a^2 - b^2 = (a - b)(a + b) となる a を sqrt(n) から順に探す。
+// p と q が近いほど早く見つかる
+use crate::bignum::BigUint;
+
+// 平方数かどうかを isqrt の前に剰余で大半ふるい落とす
+const SIEVE_MODULI: [u64; 4] = [64, 63, 65, 11];
+const SIEVE_MODULUS: u64 = 64 * 63 * 65 * 11;
+
+pub fn fermat_factor(n: &BigUint, max_steps: usize) -> Option<(BigUint, BigUint)> {
+    if n.is_even() {
+        panic!("Fermat factorization requires an odd modulus");
+    }
+    let mut a = n.isqrt();
+    if a.mul(&a) < *n {
+        a = a.add(&BigUint::one());
+    }
+    // b2 = a^2 - n を差分 2a + 1 で更新する
+    let mut b2 = a.mul(&a).sub(n);
+    let squares: Vec<Vec<bool>> = SIEVE_MODULI
+        .iter()
+        .map(|&m| {
+            let mut table = vec![false; m as usize];
+            for r in 0..m {
+                table[(r * r % m) as usize] = true;
+            }
+            table
+        })
+        .collect();
+    let maybe_square = |x: &BigUint| {
+        let r = x.rem_u64(SIEVE_MODULUS);
+        SIEVE_MODULI
+            .iter()
+            .zip(&squares)
+            .all(|(&m, table)| table[(r % m) as usize])
+    };
+    for _ in 0..max_steps {
+        if maybe_square(&b2) && b2.is_perfect_square() {
+            let b = b2.isqrt();
+            let p = a.sub(&b);
+            if p.is_one() {
+                return None;
+            }
+            return Some((p, a.add(&b)));
+        }
+        b2 = b2.add(&a.shl(1)).add(&BigUint::one());
+        a = a.add(&BigUint::one());
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fermat_factor() {
+        let n = BigUint::from_u64(5959);
+        assert_eq!(
+            fermat_factor(&n, 10),
+            Some((BigUint::from_u64(59), BigUint::from_u64(101)))
+        );
+        // 素数なら 1 * n にしか到達しない
+        assert_eq!(
+            fermat_factor(&BigUint::from_u64(1_000_003), 1_000_000),
+            None
+        );
+    }
+}
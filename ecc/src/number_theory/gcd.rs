@@ -0,0 +1,108 @@
+use crate::bignum::{BigInt, BigUint};
+
+pub fn gcd(a: u64, b: u64) -> u64 {
+    let (mut a, mut b) = (a, b);
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+pub fn gcd_big(a: &BigUint, b: &BigUint) -> BigUint {
+    let (mut a, mut b) = (a.clone(), b.clone());
+    while !b.is_zero() {
+        let r = a.rem(&b);
+        a = b;
+        b = r;
+    }
+    a
+}
+
+// a*x + b*y = g = gcd(a, b) となる (g, x, y)
+pub fn ext_gcd(a: u64, b: u64) -> (u64, i128, i128) {
+    let (mut old_r, mut r) = (a as i128, b as i128);
+    let (mut old_s, mut s) = (1i128, 0i128);
+    let (mut old_t, mut t) = (0i128, 1i128);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_s, s) = (s, old_s - q * s);
+        (old_t, t) = (t, old_t - q * t);
+    }
+    (old_r as u64, old_s, old_t)
+}
+
+pub fn ext_gcd_big(a: &BigUint, b: &BigUint) -> (BigUint, BigInt, BigInt) {
+    let (mut old_r, mut r) = (a.clone(), b.clone());
+    let (mut old_s, mut s) = (BigInt::from_i128(1), BigInt::zero());
+    let (mut old_t, mut t) = (BigInt::zero(), BigInt::from_i128(1));
+    while !r.is_zero() {
+        let (q, rem) = old_r.divrem(&r);
+        let q = BigInt::from_biguint(q);
+        old_r = r;
+        r = rem;
+        let next_s = old_s.sub(&q.mul(&s));
+        old_s = s;
+        s = next_s;
+        let next_t = old_t.sub(&q.mul(&t));
+        old_t = t;
+        t = next_t;
+    }
+    (old_r, old_s, old_t)
+}
+
+// 法 m が素数でなくても gcd(a, m) = 1 なら逆元が存在する
+pub fn mod_inverse(a: u64, m: u64) -> Option<u64> {
+    if m == 0 {
+        return None;
+    }
+    let (g, x, _) = ext_gcd(a % m, m);
+    if g != 1 {
+        return None;
+    }
+    Some(x.rem_euclid(m as i128) as u64)
+}
+
+pub fn mod_inverse_big(a: &BigUint, m: &BigUint) -> Option<BigUint> {
+    if m.is_zero() {
+        return None;
+    }
+    let (g, x, _) = ext_gcd_big(&a.rem(m), m);
+    if !g.is_one() {
+        return None;
+    }
+    Some(x.mod_floor(m))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ext_gcd_bezout() {
+        let (g, x, y) = ext_gcd(240, 46);
+        assert_eq!(g, 2);
+        assert_eq!(240 * x + 46 * y, 2);
+
+        let a = BigUint::from_hex("fedcba9876543210fedcba9876543210").unwrap();
+        let b = BigUint::from_hex("123456789abcdef0123456789").unwrap();
+        let (g, x, y) = ext_gcd_big(&a, &b);
+        let lhs = BigInt::from_biguint(a.clone())
+            .mul(&x)
+            .add(&BigInt::from_biguint(b.clone()).mul(&y));
+        assert_eq!(lhs, BigInt::from_biguint(g.clone()));
+        assert_eq!(g, gcd_big(&a, &b));
+    }
+
+    #[test]
+    fn test_mod_inverse_composite_modulus() {
+        assert_eq!(mod_inverse(7, 40), Some(23));
+        assert_eq!(mod_inverse(6, 40), None);
+
+        // φ(n) のような合成数の法でも逆元が求まる
+        let m = BigUint::from_u64(3120);
+        let e = BigUint::from_u64(17);
+        assert_eq!(mod_inverse_big(&e, &m), Some(BigUint::from_u64(2753)));
+        assert_eq!(mod_inverse_big(&BigUint::from_u64(15), &m), None);
+    }
+}
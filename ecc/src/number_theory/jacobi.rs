@@ -0,0 +1,95 @@
+use crate::bignum::BigUint;
+
+// ヤコビ記号 (a / n)、n は正の奇数
+pub fn jacobi(a: i64, n: u64) -> i32 {
+    if n == 0 || n.is_multiple_of(2) {
+        panic!("Jacobi symbol requires an odd positive modulus, got {}", n);
+    }
+    let mut a = (a as i128).rem_euclid(n as i128) as u64;
+    let mut n = n;
+    let mut result = 1;
+    while a != 0 {
+        // (2 / n) = -1 となるのは n ≡ 3, 5 (mod 8)
+        while a.is_multiple_of(2) {
+            a /= 2;
+            if n % 8 == 3 || n % 8 == 5 {
+                result = -result;
+            }
+        }
+        // 平方剰余の相互法則
+        std::mem::swap(&mut a, &mut n);
+        if a % 4 == 3 && n % 4 == 3 {
+            result = -result;
+        }
+        a %= n;
+    }
+    if n == 1 {
+        result
+    } else {
+        0
+    }
+}
+
+// 二進アルゴリズム: 除算を使わず、2 の因子の除去と減算だけで求める
+pub fn jacobi_big(a: &BigUint, n: &BigUint) -> i32 {
+    if n.is_zero() || n.is_even() {
+        panic!("Jacobi symbol requires an odd positive modulus, got {}", n);
+    }
+    let mut a = if a >= n { a.rem(n) } else { a.clone() };
+    let mut n = n.clone();
+    let mut result = 1;
+    let mod8 = |x: &BigUint| x.limbs().first().map_or(0, |l| l & 7);
+    while !a.is_zero() {
+        let tz = a.trailing_zeros();
+        a = a.shr(tz);
+        if tz % 2 == 1 && matches!(mod8(&n), 3 | 5) {
+            result = -result;
+        }
+        // a, n ともに奇数。a < n なら相互法則で入れ替える
+        if a < n {
+            std::mem::swap(&mut a, &mut n);
+            if mod8(&a) % 4 == 3 && mod8(&n) % 4 == 3 {
+                result = -result;
+            }
+        }
+        // (a / n) = ((a - n) / n)
+        a = a.sub(&n);
+    }
+    if n.is_one() {
+        result
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jacobi_small_values() {
+        assert_eq!(jacobi(1001, 9907), -1);
+        assert_eq!(jacobi(19, 45), 1);
+        assert_eq!(jacobi(8, 21), -1);
+        assert_eq!(jacobi(5, 21), 1);
+        assert_eq!(jacobi(-1, 7), -1);
+        assert_eq!(jacobi(21, 21), 0);
+    }
+
+    #[test]
+    fn test_jacobi_big_matches_u64() {
+        for n in (1..200u64).step_by(2) {
+            for a in 0..60u64 {
+                let expected = jacobi(a as i64, n);
+                let got = jacobi_big(&BigUint::from_u64(a), &BigUint::from_u64(n));
+                assert_eq!(got, expected, "({} / {})", a, n);
+            }
+        }
+        // M127 は素数なので (a / M127) はルジャンドル記号 a^((p-1)/2) と一致する
+        let p = BigUint::one().shl(127).sub(&BigUint::one());
+        let a = BigUint::from_u128(0x1234_5678_9abc_def0_1122_3344_5566_7788);
+        let euler = a.modpow(&p.shr(1), &p);
+        let expected = if euler.is_one() { 1 } else { -1 };
+        assert_eq!(jacobi_big(&a, &p), expected);
+    }
+}
@@ -0,0 +1,129 @@
+pub mod binary_gcd;
+pub mod bpsw;
+pub mod continued_fraction;
+pub mod crt;
+pub mod dlog;
+pub mod factor;
+pub mod fermat;
+pub mod gcd;
+pub mod jacobi;
+pub mod primality;
+pub mod prime_gen;
+pub mod primitive_root;
+pub mod quadratic_sieve;
+pub mod totient;
+
+pub use binary_gcd::{
+    binary_gcd, binary_gcd_big, binary_gcd_ct, mod_inverse_ct, mod_inverse_ct_big,
+};
+pub use bpsw::{
+    baillie_psw_check, baillie_psw_check_big, is_prime_bpsw, is_prime_bpsw_big, BpswResult,
+};
+pub use continued_fraction::{continued_fraction, convergents};
+pub use crt::crt;
+pub use dlog::{bsgs, pohlig_hellman, pollard_rho, pollard_rho_parallel};
+pub use factor::{factorize, factorize_big, factorize_big_with_rng, factorize_with_rng};
+pub use fermat::fermat_factor;
+pub use gcd::{ext_gcd, ext_gcd_big, gcd, gcd_big, mod_inverse, mod_inverse_big};
+pub use jacobi::{jacobi, jacobi_big};
+pub use primality::{is_prime, is_prime_big};
+pub use prime_gen::{
+    generate_prime, generate_prime_with_condition, generate_safe_prime,
+    generate_sophie_germain_prime, hash_to_prime,
+};
+pub use primitive_root::{order_of_element, primitive_root, primitive_root_big};
+pub use quadratic_sieve::quadratic_sieve;
+pub use totient::{
+    carmichael_lambda, carmichael_lambda_big, carmichael_lambda_from_factors, euler_phi,
+    euler_phi_big, euler_phi_from_factors,
+};
+
+pub fn mod_mul(a: u64, b: u64, m: u64) -> u64 {
+    ((a as u128 * b as u128) % m as u128) as u64
+}
+
+pub fn mod_pow(base: u64, exponent: u64, m: u64) -> u64 {
+    if m == 1 {
+        return 0;
+    }
+    let mut result = 1;
+    let mut base = base % m;
+    let mut exp = exponent;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mod_mul(result, base, m);
+        }
+        base = mod_mul(base, base, m);
+        exp >>= 1;
+    }
+    result
+}
+
+// Tonelli–Shanks 法による素数 p を法とした平方根
+pub fn sqrt_mod(a: u64, p: u64) -> Option<u64> {
+    let a = a % p;
+    if a == 0 || p == 2 {
+        return Some(a);
+    }
+    if mod_pow(a, (p - 1) / 2, p) != 1 {
+        return None;
+    }
+    // p - 1 = q * 2^s
+    let s = (p - 1).trailing_zeros();
+    let q = (p - 1) >> s;
+    let z = (2..p)
+        .find(|&z| mod_pow(z, (p - 1) / 2, p) == p - 1)
+        .unwrap();
+    let mut m = s;
+    let mut c = mod_pow(z, q, p);
+    let mut t = mod_pow(a, q, p);
+    let mut r = mod_pow(a, q.div_ceil(2), p);
+    while t != 1 {
+        let mut i = 0;
+        let mut t2 = t;
+        while t2 != 1 {
+            t2 = mod_mul(t2, t2, p);
+            i += 1;
+        }
+        let b = mod_pow(c, 1 << (m - i - 1), p);
+        m = i;
+        c = mod_mul(b, b, p);
+        t = mod_mul(t, c, p);
+        r = mod_mul(r, b, p);
+    }
+    Some(r)
+}
+
+// エラトステネスの篩
+pub fn primes_up_to(limit: usize) -> Vec<u64> {
+    let mut is_composite = vec![false; limit + 1];
+    let mut primes = Vec::new();
+    for i in 2..=limit {
+        if is_composite[i] {
+            continue;
+        }
+        primes.push(i as u64);
+        for j in (i * i..=limit).step_by(i) {
+            is_composite[j] = true;
+        }
+    }
+    primes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sqrt_mod() {
+        // 17 ≡ 1 (mod 16) なので Tonelli–Shanks のループを何度か回る
+        for p in [17, 1_000_003, 2_305_843_009_213_693_951] {
+            for a in [2u64, 3, 10, 13] {
+                if let Some(r) = sqrt_mod(a, p) {
+                    assert_eq!(mod_mul(r, r, p), a % p);
+                }
+            }
+        }
+        assert_eq!(sqrt_mod(3, 17), None);
+    }
+}
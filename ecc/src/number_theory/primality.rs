@@ -0,0 +1,121 @@
+use super::{mod_mul, mod_pow};
+use crate::bignum::BigUint;
+use crate::rand::RngCore;
+
+pub const SMALL_PRIMES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+// n がこの値未満なら、対応する個数の小さい素数を証人にすれば判定は決定的になる
+const DETERMINISTIC_BOUNDS: [(u64, usize); 8] = [
+    (2_047, 1),
+    (1_373_653, 2),
+    (25_326_001, 3),
+    (3_215_031_751, 4),
+    (2_152_302_898_747, 5),
+    (3_474_749_660_383, 6),
+    (341_550_071_728_321, 7),
+    (3_825_123_056_546_413_051, 9),
+];
+
+// n - 1 = d * 2^s
+pub(crate) fn miller_rabin_u64(n: u64, a: u64) -> bool {
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    let mut x = mod_pow(a, d, n);
+    if x == 1 || x == n - 1 {
+        return true;
+    }
+    for _ in 1..s {
+        x = mod_mul(x, x, n);
+        if x == n - 1 {
+            return true;
+        }
+    }
+    false
+}
+
+pub(crate) fn miller_rabin_big(n: &BigUint, a: &BigUint) -> bool {
+    let one = BigUint::one();
+    let n_minus_1 = n.sub(&one);
+    let s = n_minus_1.trailing_zeros();
+    let d = n_minus_1.shr(s);
+    let mut x = a.modpow(&d, n);
+    if x.is_one() || x == n_minus_1 {
+        return true;
+    }
+    for _ in 1..s {
+        x = x.mul(&x).rem(n);
+        if x == n_minus_1 {
+            return true;
+        }
+    }
+    false
+}
+
+pub fn is_prime<R: RngCore>(n: u64, rounds: usize, rng: &mut R) -> bool {
+    if n < 2 {
+        return false;
+    }
+    for &p in SMALL_PRIMES.iter() {
+        if n == p {
+            return true;
+        }
+        if n.is_multiple_of(p) {
+            return false;
+        }
+    }
+    if let Some(&(_, count)) = DETERMINISTIC_BOUNDS.iter().find(|(bound, _)| n < *bound) {
+        return SMALL_PRIMES[..count]
+            .iter()
+            .all(|&a| miller_rabin_u64(n, a));
+    }
+    miller_rabin_u64(n, 2)
+        && (0..rounds).all(|_| {
+            let a = rng.next_u64() % (n - 3) + 2;
+            miller_rabin_u64(n, a)
+        })
+}
+
+pub fn is_prime_big<R: RngCore>(n: &BigUint, rounds: usize, rng: &mut R) -> bool {
+    if let Some(small) = n.to_u64() {
+        return is_prime(small, rounds, rng);
+    }
+    if n.is_even() {
+        return false;
+    }
+    let three = BigUint::from_u64(3);
+    let bound = n.sub(&three);
+    miller_rabin_big(n, &BigUint::from_u64(2))
+        && (0..rounds).all(|_| {
+            // 証人は [2, n-2] から一様に選ぶ
+            let a = BigUint::random_below(&bound, rng).add(&BigUint::from_u64(2));
+            miller_rabin_big(n, &a)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::OsRng;
+
+    #[test]
+    fn test_is_prime_u64() {
+        let primes = [2, 3, 65537, 2305843009213693951, 18446744073709551557];
+        let composites = [0, 1, 561, 3215031751, 3825123056546413051, u64::MAX];
+        for p in primes {
+            assert!(is_prime(p, 10, &mut OsRng), "{} is prime", p);
+        }
+        for c in composites {
+            assert!(!is_prime(c, 10, &mut OsRng), "{} is composite", c);
+        }
+    }
+
+    #[test]
+    fn test_is_prime_big() {
+        // 2^127 - 1 は素数, 2^128 + 1 は合成数 (59649589127497217 * 5704689200685129054721)
+        let m127 = BigUint::one().shl(127).sub(&BigUint::one());
+        let f7 = BigUint::one().shl(128).add(&BigUint::one());
+        assert!(is_prime_big(&m127, 20, &mut OsRng));
+        assert!(!is_prime_big(&f7, 20, &mut OsRng));
+        assert!(!is_prime_big(&m127.mul(&m127), 20, &mut OsRng));
+    }
+}
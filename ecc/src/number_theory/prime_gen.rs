@@ -0,0 +1,140 @@
+use super::is_prime_big;
+use super::{is_prime_bpsw_big, primes_up_to};
+use crate::bignum::BigUint;
+use crate::hash::sha256;
+use crate::rand::RngCore;
+
+const TRIAL_DIVISION_BOUND: usize = 2000;
+const MILLER_RABIN_ROUNDS: usize = 32;
+
+pub fn generate_prime<R: RngCore>(bits: usize, rng: &mut R) -> BigUint {
+    generate_prime_with_condition(bits, rng, |_| true)
+}
+
+// condition を満たす素数が見つかるまで候補を引き直す (例: p ≡ 3 mod 4)
+pub fn generate_prime_with_condition<R, F>(bits: usize, rng: &mut R, condition: F) -> BigUint
+where
+    R: RngCore,
+    F: Fn(&BigUint) -> bool,
+{
+    if bits < 2 {
+        panic!("Cannot generate a prime with fewer than 2 bits");
+    }
+    let small_primes = primes_up_to(TRIAL_DIVISION_BOUND);
+    loop {
+        // 最上位ビットを立ててちょうど bits ビットにし、奇数にする
+        let mut candidate = BigUint::random_bits(bits, rng);
+        candidate.set_bit(bits - 1);
+        candidate.set_bit(0);
+        if !condition(&candidate) {
+            continue;
+        }
+        if let Some(small) = candidate.to_u64() {
+            if small_primes.contains(&small) {
+                return candidate;
+            }
+        }
+        if small_primes.iter().any(|&p| candidate.rem_u64(p) == 0) {
+            continue;
+        }
+        if is_prime_big(&candidate, MILLER_RABIN_ROUNDS, rng) {
+            return candidate;
+        }
+    }
+}
+
+// p = 2q + 1 (q も素数) となる bits ビットの安全素数 p を返す。
+// Z_p^* の位数は 2q なので、部分群の位数は 1, 2, q, 2q に限られ
+// 小さい部分群への閉じ込め攻撃が効かない。
+pub fn generate_safe_prime<R: RngCore>(bits: usize, rng: &mut R) -> BigUint {
+    let q = generate_sophie_germain_prime(bits - 1, rng);
+    q.shl(1).add(&BigUint::one())
+}
+
+// 2q + 1 も素数となる素数 q
+pub fn generate_sophie_germain_prime<R: RngCore>(bits: usize, rng: &mut R) -> BigUint {
+    if bits < 2 {
+        panic!("Cannot generate a prime with fewer than 2 bits");
+    }
+    let small_primes = primes_up_to(TRIAL_DIVISION_BOUND);
+    let survives_trial_division = |n: &BigUint| match n.to_u64() {
+        Some(small) if small <= TRIAL_DIVISION_BOUND as u64 => small_primes.contains(&small),
+        _ => small_primes.iter().all(|&p| n.rem_u64(p) != 0),
+    };
+    loop {
+        let mut q = BigUint::random_bits(bits, rng);
+        q.set_bit(bits - 1);
+        q.set_bit(0);
+        let p = q.shl(1).add(&BigUint::one());
+        // q と p の両方を篩ってから Miller-Rabin にかける
+        if !survives_trial_division(&q) || !survives_trial_division(&p) {
+            continue;
+        }
+        if is_prime_big(&q, MILLER_RABIN_ROUNDS, rng) && is_prime_big(&p, MILLER_RABIN_ROUNDS, rng)
+        {
+            return q;
+        }
+    }
+}
+
+// データを bits ビットの素数に写す。H(data || counter) を候補とし、素数になるまで
+// counter を進める。誰が計算しても同じ素数になるよう判定は決定的な BPSW で行う
+pub fn hash_to_prime(data: &[u8], bits: usize) -> BigUint {
+    if !(2..=256).contains(&bits) {
+        panic!("hash_to_prime supports 2 to 256 bits, got {}", bits);
+    }
+    for counter in 0u64.. {
+        let digest = sha256(&[data, &counter.to_be_bytes()].concat());
+        let mut candidate = BigUint::from_bytes_be(&digest).shr(256 - bits);
+        candidate.set_bit(bits - 1);
+        candidate.set_bit(0);
+        if is_prime_bpsw_big(&candidate) {
+            return candidate;
+        }
+    }
+    unreachable!()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::OsRng;
+
+    #[test]
+    fn test_generate_prime_has_exact_bit_length() {
+        for bits in [2, 8, 64, 256] {
+            let p = generate_prime(bits, &mut OsRng);
+            assert_eq!(p.bits(), bits);
+            assert!(is_prime_big(&p, 20, &mut OsRng));
+        }
+    }
+
+    #[test]
+    fn test_generate_safe_prime() {
+        let p = generate_safe_prime(64, &mut OsRng);
+        let q = p.shr(1);
+        assert_eq!(p.bits(), 64);
+        assert!(is_prime_big(&p, 20, &mut OsRng));
+        assert!(is_prime_big(&q, 20, &mut OsRng));
+
+        // 平方剰余 g^2 は位数 q の部分群に入る
+        let g = BigUint::from_u64(5).modpow(&BigUint::from_u64(2), &p);
+        assert!(g.modpow(&q, &p).is_one());
+    }
+
+    #[test]
+    fn test_hash_to_prime_is_deterministic() {
+        let p = hash_to_prime(b"alice", 128);
+        assert_eq!(p.bits(), 128);
+        assert!(is_prime_big(&p, 20, &mut OsRng));
+        assert_eq!(p, hash_to_prime(b"alice", 128));
+        assert_ne!(p, hash_to_prime(b"bob", 128));
+    }
+
+    #[test]
+    fn test_generate_prime_with_condition() {
+        let p = generate_prime_with_condition(128, &mut OsRng, |p| p.rem_u64(4) == 3);
+        assert_eq!(p.rem_u64(4), 3);
+        assert!(is_prime_big(&p, 20, &mut OsRng));
+    }
+}
@@ -0,0 +1,87 @@
+// 元の位数と原始根。群の位数を素因数分解し、各素因数で割れるだけ割って位数を絞る
+use super::{carmichael_lambda, euler_phi, factorize, gcd, mod_pow};
+use crate::bignum::BigUint;
+
+// (Z/nZ)^* における g の位数。g と n が互いに素でなければ None
+pub fn order_of_element(g: u64, n: u64) -> Option<u64> {
+    if n == 0 {
+        panic!("Modulus must be positive");
+    }
+    if n == 1 {
+        return Some(1);
+    }
+    if gcd(g % n, n) != 1 {
+        return None;
+    }
+    // 位数は λ(n) の約数
+    let mut order = carmichael_lambda(n);
+    for (q, _) in factorize(order) {
+        while order.is_multiple_of(q) && mod_pow(g, order / q, n) == 1 {
+            order /= q;
+        }
+    }
+    Some(order)
+}
+
+// 最小の原始根。(Z/nZ)^* が巡回群 (n = 1, 2, 4, p^k, 2p^k) でなければ None
+pub fn primitive_root(n: u64) -> Option<u64> {
+    if n == 0 {
+        panic!("Modulus must be positive");
+    }
+    let phi = euler_phi(n);
+    if carmichael_lambda(n) != phi {
+        return None;
+    }
+    if n <= 2 {
+        return Some(n - 1);
+    }
+    let factors = factorize(phi);
+    (2..n).find(|&g| gcd(g, n) == 1 && factors.iter().all(|&(q, _)| mod_pow(g, phi / q, n) != 1))
+}
+
+// p - 1 の素因数分解が既知の素数 p (安全素数など) の最小の原始根
+pub fn primitive_root_big(p: &BigUint, p_minus_1_factors: &[(BigUint, u32)]) -> BigUint {
+    let one = BigUint::one();
+    let p1 = p.sub(&one);
+    let mut g = BigUint::from_u64(2);
+    loop {
+        if p_minus_1_factors
+            .iter()
+            .all(|(q, _)| !g.modpow(&p1.div(q), p).is_one())
+        {
+            return g;
+        }
+        g = g.add(&one);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_order_and_primitive_root() {
+        assert_eq!(primitive_root(7), Some(3));
+        assert_eq!(primitive_root(23), Some(5));
+        assert_eq!(primitive_root(6597069766657), Some(5));
+        assert_eq!(primitive_root(2 * 25), Some(3));
+        // (Z/8Z)^* = {1, 3, 5, 7} は巡回群ではない
+        assert_eq!(primitive_root(8), None);
+        assert_eq!(primitive_root(15), None);
+        assert_eq!(order_of_element(2, 7), Some(3));
+        assert_eq!(order_of_element(3, 8), Some(2));
+        assert_eq!(order_of_element(6, 9), None);
+        assert_eq!(order_of_element(2, 1_000_003), Some(1_000_002));
+        assert_eq!(order_of_element(4, 1_000_003), Some(1_000_002 / 2));
+    }
+
+    #[test]
+    fn test_primitive_root_of_safe_prime() {
+        // p = 2q + 1
+        let p = BigUint::from_u64(2000000579);
+        let q = p.shr(1);
+        let factors = [(BigUint::from_u64(2), 1), (q.clone(), 1)];
+        let g = primitive_root_big(&p, &factors);
+        assert_eq!(g.to_u64(), primitive_root(2000000579));
+    }
+}
@@ -0,0 +1,210 @@
+// 小さな二次篩法: x^2 - n が因子基底上で滑らかになる x を集め、
+// GF(2) 上のガウス消去で X^2 ≡ Y^2 (mod n) を作って gcd(X - Y, n) を取る。
+use super::{gcd_big, mod_pow, primes_up_to, sqrt_mod};
+use crate::bignum::BigUint;
+
+const BLOCK: usize = 1 << 16;
+const EXTRA_RELATIONS: usize = 16;
+// x^2 - n を u128 で計算するので、x ≈ √n が 2^63 を大きく超えない範囲に限る
+const MAX_BITS: u32 = 126;
+
+struct Relation {
+    x: u128,
+    exponents: Vec<u32>,
+}
+
+fn isqrt_u128(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    let mut x = (n as f64).sqrt() as u128;
+    while x * x > n {
+        x -= 1;
+    }
+    while (x + 1) * (x + 1) <= n {
+        x += 1;
+    }
+    x
+}
+
+// L(n)^(1/2) 程度の因子基底上限
+fn smoothness_bound(n: u128) -> u64 {
+    let ln = (n as f64).ln();
+    let b = (0.5 * (ln * ln.ln()).sqrt()).exp() * 1.5;
+    (b as u64).max(200)
+}
+
+fn factor_base(n: u128, bound: u64) -> Vec<(u64, u64)> {
+    primes_up_to(bound as usize)
+        .into_iter()
+        .filter(|&p| p == 2 || mod_pow((n % p as u128) as u64, (p - 1) / 2, p) == 1)
+        .map(|p| (p, sqrt_mod((n % p as u128) as u64, p).unwrap()))
+        .collect()
+}
+
+fn trial_divide(mut value: u128, base: &[(u64, u64)]) -> Option<Vec<u32>> {
+    let mut exponents = vec![0u32; base.len()];
+    for (i, &(p, _)) in base.iter().enumerate() {
+        while value.is_multiple_of(p as u128) {
+            value /= p as u128;
+            exponents[i] += 1;
+        }
+    }
+    if value == 1 {
+        Some(exponents)
+    } else {
+        None
+    }
+}
+
+fn collect_relations(n: u128, base: &[(u64, u64)]) -> Vec<Relation> {
+    let start = isqrt_u128(n) + 1;
+    let largest = base.last().unwrap().0 as f32;
+    let mut relations = Vec::new();
+    let mut offset = 0u128;
+    while relations.len() < base.len() + EXTRA_RELATIONS {
+        let x0 = start + offset;
+        let mut sieve = vec![0f32; BLOCK];
+        for &(p, r) in base.iter().skip(1) {
+            let logp = (p as f32).log2();
+            // x ≡ ±r (mod p) となる位置に log p を足す
+            for root in [r, p - r] {
+                let mut i = ((root as u128 + p as u128 - x0 % p as u128) % p as u128) as usize;
+                while i < BLOCK {
+                    sieve[i] += logp;
+                    i += p as usize;
+                }
+            }
+        }
+        for (i, &score) in sieve.iter().enumerate() {
+            let x = x0 + i as u128;
+            let q = x * x - n;
+            let threshold = (q as f32).log2() - 2.0 * largest.log2();
+            if score < threshold {
+                continue;
+            }
+            if let Some(exponents) = trial_divide(q, base) {
+                relations.push(Relation { x, exponents });
+            }
+        }
+        offset += BLOCK as u128;
+    }
+    relations
+}
+
+// 指数ベクトルの偶奇を GF(2) 上で消去し、積が平方数になる関係式の組を返す
+fn find_dependencies(relations: &[Relation], columns: usize) -> Vec<Vec<usize>> {
+    let words = relations.len().div_ceil(64);
+    let mut rows: Vec<(Vec<bool>, Vec<u64>)> = relations
+        .iter()
+        .enumerate()
+        .map(|(i, rel)| {
+            let bits = rel.exponents.iter().map(|e| e % 2 == 1).collect();
+            let mut history = vec![0u64; words];
+            history[i / 64] |= 1 << (i % 64);
+            (bits, history)
+        })
+        .collect();
+
+    let mut pivot_row = 0;
+    for col in 0..columns {
+        let Some(pivot) = (pivot_row..rows.len()).find(|&r| rows[r].0[col]) else {
+            continue;
+        };
+        rows.swap(pivot_row, pivot);
+        let (pivot_bits, pivot_history) = rows[pivot_row].clone();
+        for (r, row) in rows.iter_mut().enumerate() {
+            if r != pivot_row && row.0[col] {
+                for (a, b) in row.0.iter_mut().zip(&pivot_bits) {
+                    *a ^= b;
+                }
+                for (a, b) in row.1.iter_mut().zip(&pivot_history) {
+                    *a ^= b;
+                }
+            }
+        }
+        pivot_row += 1;
+    }
+
+    rows[pivot_row..]
+        .iter()
+        .map(|(_, history)| {
+            (0..relations.len())
+                .filter(|&i| (history[i / 64] >> (i % 64)) & 1 == 1)
+                .collect()
+        })
+        .collect()
+}
+
+// n が 2^126 以上なら篩わずに None を返す (この大きさはどのみち現実的に分解できない)
+pub fn quadratic_sieve(n: u128) -> Option<u128> {
+    if n < 4 || n >> MAX_BITS != 0 {
+        return None;
+    }
+    let root = isqrt_u128(n);
+    if root * root == n {
+        return Some(root);
+    }
+    let bound = smoothness_bound(n);
+    for p in primes_up_to(bound as usize) {
+        if n.is_multiple_of(p as u128) {
+            return (p as u128 != n).then_some(p as u128);
+        }
+    }
+
+    let base = factor_base(n, bound);
+    let relations = collect_relations(n, &base);
+    let big_n = BigUint::from_u128(n);
+    for dependency in find_dependencies(&relations, base.len()) {
+        // X = Π x_i, Y = Π p_j^(e_j / 2)
+        let mut x = BigUint::one();
+        let mut exponents = vec![0u32; base.len()];
+        for &i in &dependency {
+            x = x.mul(&BigUint::from_u128(relations[i].x)).rem(&big_n);
+            for (e, r) in exponents.iter_mut().zip(&relations[i].exponents) {
+                *e += r;
+            }
+        }
+        let mut y = BigUint::one();
+        for (&(p, _), &e) in base.iter().zip(&exponents) {
+            let factor = BigUint::from_u64(p).modpow(&BigUint::from_u64(e as u64 / 2), &big_n);
+            y = y.mul(&factor).rem(&big_n);
+        }
+        let diff = if x >= y { x.sub(&y) } else { y.sub(&x) };
+        let g = gcd_big(&diff, &big_n);
+        if !g.is_one() && g != big_n {
+            return g.to_u128();
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quadratic_sieve_60_bit() {
+        let p: u128 = 1_073_741_827;
+        let q: u128 = 1_073_741_831;
+        let f = quadratic_sieve(p * q).unwrap();
+        assert!(f == p || f == q);
+    }
+
+    #[test]
+    fn test_quadratic_sieve_80_bit() {
+        let p: u128 = 1_099_511_627_791;
+        let q: u128 = 1_099_511_628_401;
+        let f = quadratic_sieve(p * q).unwrap();
+        assert!(f == p || f == q);
+    }
+
+    #[test]
+    fn test_quadratic_sieve_rejects_oversized_input() {
+        // √n ≈ 2^64 で x^2 が u128 からあふれる大きさ
+        let n = u128::MAX - 2;
+        assert!(!n.is_multiple_of(3));
+        assert_eq!(quadratic_sieve(n), None);
+        assert_eq!(quadratic_sieve(1 << MAX_BITS), None);
+    }
+}
@@ -0,0 +1,104 @@
+// オイラーの φ 関数とカーマイケルの λ 関数 (どちらも素因数分解から求める)
+use super::{factorize, factorize_big, gcd, gcd_big};
+use crate::bignum::BigUint;
+
+pub fn euler_phi(n: u64) -> u64 {
+    if n == 0 {
+        panic!("Euler's totient is undefined for 0");
+    }
+    // φ(p^k) = p^(k-1) (p - 1)
+    factorize(n)
+        .iter()
+        .map(|&(p, k)| p.pow(k - 1) * (p - 1))
+        .product()
+}
+
+// λ(n): (Z/nZ)^* の全ての元 a について a^λ(n) ≡ 1 となる最小の正整数
+pub fn carmichael_lambda(n: u64) -> u64 {
+    if n == 0 {
+        panic!("Carmichael function is undefined for 0");
+    }
+    factorize(n).iter().fold(1, |acc, &(p, k)| {
+        let l = match (p, k) {
+            (2, 1) => 1,
+            (2, 2) => 2,
+            // (Z/2^kZ)^* は巡回群ではないので λ(2^k) = 2^(k-2)
+            (2, _) => 1 << (k - 2),
+            _ => p.pow(k - 1) * (p - 1),
+        };
+        acc / gcd(acc, l) * l
+    })
+}
+
+pub fn euler_phi_from_factors(factors: &[(BigUint, u32)]) -> BigUint {
+    let one = BigUint::one();
+    factors.iter().fold(BigUint::one(), |acc, (p, k)| {
+        let pk1 = (1..*k).fold(BigUint::one(), |x, _| x.mul(p));
+        acc.mul(&pk1).mul(&p.sub(&one))
+    })
+}
+
+pub fn carmichael_lambda_from_factors(factors: &[(BigUint, u32)]) -> BigUint {
+    let one = BigUint::one();
+    let two = BigUint::from_u64(2);
+    factors.iter().fold(BigUint::one(), |acc, (p, k)| {
+        let l = if *p == two {
+            match k {
+                1 => BigUint::one(),
+                2 => two.clone(),
+                _ => BigUint::one().shl(*k as usize - 2),
+            }
+        } else {
+            (1..*k).fold(p.sub(&one), |x, _| x.mul(p))
+        };
+        acc.div(&gcd_big(&acc, &l)).mul(&l)
+    })
+}
+
+pub fn euler_phi_big(n: &BigUint) -> BigUint {
+    if n.is_zero() {
+        panic!("Euler's totient is undefined for 0");
+    }
+    euler_phi_from_factors(&factorize_big(n))
+}
+
+pub fn carmichael_lambda_big(n: &BigUint) -> BigUint {
+    if n.is_zero() {
+        panic!("Carmichael function is undefined for 0");
+    }
+    carmichael_lambda_from_factors(&factorize_big(n))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phi_and_lambda_small_values() {
+        let phi: Vec<u64> = (1..=12).map(euler_phi).collect();
+        assert_eq!(phi, vec![1, 1, 2, 2, 4, 2, 6, 4, 6, 4, 10, 4]);
+        let lambda: Vec<u64> = (1..=12).map(carmichael_lambda).collect();
+        assert_eq!(lambda, vec![1, 1, 2, 2, 4, 2, 6, 2, 6, 4, 10, 2]);
+        // カーマイケル数 561 = 3 * 11 * 17
+        assert_eq!(carmichael_lambda(561), 80);
+        assert_eq!(euler_phi(561), 320);
+    }
+
+    #[test]
+    fn test_big_variants_match_u64() {
+        for n in [
+            1u64,
+            32,
+            561,
+            1_000_003 * 999_983,
+            2u64.pow(20) * 3u64.pow(7),
+        ] {
+            let b = BigUint::from_u64(n);
+            assert_eq!(euler_phi_big(&b).to_u64(), Some(euler_phi(n)));
+            assert_eq!(
+                carmichael_lambda_big(&b).to_u64(),
+                Some(carmichael_lambda(n))
+            );
+        }
+    }
+}
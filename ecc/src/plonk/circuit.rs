@@ -0,0 +1,277 @@
+// PLONK の回路: 各ゲートは q_L a + q_R b + q_O c + q_M ab + q_C = 0 を課し、
+// 同じ変数を指すワイヤ同士が等しいこと (コピー制約) は置換 σ で表す。
+// ゲート i の 3 本のワイヤを ω^i, k1 ω^i, k2 ω^i でラベル付けし、σ は
+// 同じ変数のスロットを巡回させる
+use super::domain::Domain;
+use super::poly::Polynomial;
+use crate::curve::Scalar;
+
+// 剰余類 H, k1 H, k2 H が互いに交わらないよう、16 乗しても 1 にならない値を選ぶ
+pub const K1: u64 = 2;
+pub const K2: u64 = 3;
+const MIN_GATES: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Var(usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Selectors {
+    pub q_l: Scalar,
+    pub q_r: Scalar,
+    pub q_o: Scalar,
+    pub q_m: Scalar,
+    pub q_c: Scalar,
+}
+
+#[derive(Debug, Clone)]
+struct Gate {
+    wires: [Var; 3],
+    selectors: Selectors,
+}
+
+// 変数の値を記録しながらゲートを積む
+#[derive(Debug, Clone, Default)]
+pub struct CircuitBuilder {
+    values: Vec<Scalar>,
+    gates: Vec<Gate>,
+}
+
+// 検証者も知っている前処理済みの回路
+#[derive(Debug, Clone)]
+pub struct Circuit {
+    pub domain: Domain,
+    pub q_l: Polynomial,
+    pub q_r: Polynomial,
+    pub q_o: Polynomial,
+    pub q_m: Polynomial,
+    pub q_c: Polynomial,
+    pub sigma: [Polynomial; 3],
+    // 置換を H 上の値で持ったもの。証明者が Z を組み立てるのに使う
+    pub sigma_evals: [Vec<Scalar>; 3],
+}
+
+// 各ゲートの a, b, c ワイヤの値
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Witness {
+    pub a: Vec<Scalar>,
+    pub b: Vec<Scalar>,
+    pub c: Vec<Scalar>,
+}
+
+impl CircuitBuilder {
+    pub fn new() -> Self {
+        CircuitBuilder::default()
+    }
+
+    pub fn variable(&mut self, value: Scalar) -> Var {
+        self.values.push(value);
+        Var(self.values.len() - 1)
+    }
+
+    pub fn value(&self, var: Var) -> Scalar {
+        self.values[var.0]
+    }
+
+    pub fn add_gate(&mut self, a: Var, b: Var, c: Var, selectors: Selectors) {
+        self.gates.push(Gate {
+            wires: [a, b, c],
+            selectors,
+        });
+    }
+
+    // c = a + b
+    pub fn add(&mut self, a: Var, b: Var) -> Var {
+        let c = self.variable(self.value(a).add(&self.value(b)));
+        self.add_gate(
+            a,
+            b,
+            c,
+            Selectors {
+                q_l: Scalar::ONE,
+                q_r: Scalar::ONE,
+                q_o: Scalar::ONE.neg(),
+                ..Selectors::default()
+            },
+        );
+        c
+    }
+
+    // c = a * b
+    pub fn mul(&mut self, a: Var, b: Var) -> Var {
+        let c = self.variable(self.value(a).mul(&self.value(b)));
+        self.add_gate(
+            a,
+            b,
+            c,
+            Selectors {
+                q_m: Scalar::ONE,
+                q_o: Scalar::ONE.neg(),
+                ..Selectors::default()
+            },
+        );
+        c
+    }
+
+    // c = a + k。b ワイヤは使わないので a を繋いでおく
+    pub fn add_constant(&mut self, a: Var, k: Scalar) -> Var {
+        let c = self.variable(self.value(a).add(&k));
+        self.add_gate(
+            a,
+            a,
+            c,
+            Selectors {
+                q_l: Scalar::ONE,
+                q_o: Scalar::ONE.neg(),
+                q_c: k,
+                ..Selectors::default()
+            },
+        );
+        c
+    }
+
+    // a = k を公開の制約として課す
+    pub fn assert_constant(&mut self, a: Var, k: Scalar) {
+        self.add_gate(
+            a,
+            a,
+            a,
+            Selectors {
+                q_l: Scalar::ONE,
+                q_c: k.neg(),
+                ..Selectors::default()
+            },
+        );
+    }
+
+    pub fn num_gates(&self) -> usize {
+        self.gates.len()
+    }
+
+    // ゲート数を 2 冪に切り上げ、残りは全セレクタ 0 のゲートで埋める
+    pub fn build(mut self) -> (Circuit, Witness) {
+        if self.values.is_empty() {
+            self.variable(Scalar::ZERO);
+        }
+        let n = self.gates.len().max(MIN_GATES).next_power_of_two();
+        let domain = Domain::new(n);
+        let pad = Gate {
+            wires: [Var(0); 3],
+            selectors: Selectors::default(),
+        };
+        self.gates.resize(n, pad);
+
+        let column = |f: fn(&Selectors) -> Scalar| {
+            let evals: Vec<Scalar> = self.gates.iter().map(|g| f(&g.selectors)).collect();
+            domain.ifft(&evals)
+        };
+        let (q_l, q_r, q_o) = (column(|s| s.q_l), column(|s| s.q_r), column(|s| s.q_o));
+        let (q_m, q_c) = (column(|s| s.q_m), column(|s| s.q_c));
+
+        // スロット j * n + i (列 j, ゲート i) を変数ごとに集めて巡回させる
+        let mut slots: Vec<Vec<usize>> = vec![vec![]; self.values.len()];
+        for (i, gate) in self.gates.iter().enumerate() {
+            for (j, w) in gate.wires.iter().enumerate() {
+                slots[w.0].push(j * n + i);
+            }
+        }
+        let ks = [Scalar::ONE, Scalar::new(K1), Scalar::new(K2)];
+        let label = |slot: usize| ks[slot / n].mul(&domain.element(slot % n));
+        let mut sigma_evals: [Vec<Scalar>; 3] = std::array::from_fn(|_| vec![Scalar::ZERO; n]);
+        for cycle in &slots {
+            for (t, &slot) in cycle.iter().enumerate() {
+                let next = cycle[(t + 1) % cycle.len()];
+                sigma_evals[slot / n][slot % n] = label(next);
+            }
+        }
+        let sigma = std::array::from_fn(|j| domain.ifft(&sigma_evals[j]));
+
+        let wire = |j: usize| -> Vec<Scalar> {
+            self.gates
+                .iter()
+                .map(|g| self.values[g.wires[j].0])
+                .collect()
+        };
+        let witness = Witness {
+            a: wire(0),
+            b: wire(1),
+            c: wire(2),
+        };
+        let circuit = Circuit {
+            domain,
+            q_l,
+            q_r,
+            q_o,
+            q_m,
+            q_c,
+            sigma,
+            sigma_evals,
+        };
+        (circuit, witness)
+    }
+}
+
+impl Circuit {
+    // 証明を経ずに割り当てを直接確かめる。ゲート制約とコピー制約の両方
+    pub fn is_satisfied(&self, w: &Witness) -> bool {
+        let n = self.domain.n;
+        let xs = self.domain.elements();
+        let gates_ok = (0..n).all(|i| {
+            let x = &xs[i];
+            let (a, b, c) = (w.a[i], w.b[i], w.c[i]);
+            self.q_l
+                .evaluate(x)
+                .mul(&a)
+                .add(&self.q_r.evaluate(x).mul(&b))
+                .add(&self.q_o.evaluate(x).mul(&c))
+                .add(&self.q_m.evaluate(x).mul(&a).mul(&b))
+                .add(&self.q_c.evaluate(x))
+                == Scalar::ZERO
+        });
+        // σ が指す先のスロットと値が一致すること
+        let ks = [Scalar::ONE, Scalar::new(K1), Scalar::new(K2)];
+        let columns = [&w.a, &w.b, &w.c];
+        let copies_ok = (0..3).all(|j| {
+            (0..n).all(|i| {
+                let target = self.sigma_evals[j][i];
+                (0..3).any(|k| {
+                    (0..n).any(|l| ks[k].mul(&xs[l]) == target && columns[k][l] == columns[j][i])
+                })
+            })
+        });
+        gates_ok && copies_ok
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_cubic_circuit() {
+        // x^3 + x + 5 = 35
+        let mut cs = CircuitBuilder::new();
+        let x = cs.variable(Scalar::new(3));
+        let x2 = cs.mul(x, x);
+        let x3 = cs.mul(x2, x);
+        let sum = cs.add(x3, x);
+        let out = cs.add_constant(sum, Scalar::new(5));
+        cs.assert_constant(out, Scalar::new(35));
+        assert_eq!(cs.num_gates(), 5);
+        let (circuit, witness) = cs.build();
+        assert_eq!(circuit.domain.n, 8);
+        assert!(circuit.is_satisfied(&witness));
+        // ゲートは満たしたままコピー制約だけ破る: 2 番目の乗算の x を 4 に
+        let mut bad = witness.clone();
+        bad.b[1] = Scalar::new(4);
+        bad.c[1] = Scalar::new(36);
+        assert!(!circuit.is_satisfied(&bad));
+    }
+
+    #[test]
+    fn test_cosets_are_disjoint() {
+        let (k1, k2) = (Scalar::new(K1), Scalar::new(K2));
+        assert_ne!(k1.pow(16), Scalar::ONE);
+        assert_ne!(k2.pow(16), Scalar::ONE);
+        assert_ne!(k2.mul(&k1.inv().unwrap()).pow(16), Scalar::ONE);
+    }
+}
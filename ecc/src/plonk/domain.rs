@@ -0,0 +1,126 @@
+// 評価領域 H = {1, ω, ..., ω^{n-1}} と基数 2 の FFT。
+// N - 1 = 2^4 · 3^2 · 67 · 10687 · 22363338163 なので 2 冪の領域は 16 点まで
+use super::poly::Polynomial;
+use crate::curve::Scalar;
+
+// 原始根 11 の (N - 1) / 16 乗。位数 16 の 1 の冪根
+const ROOT_OF_UNITY_16: u64 = 369148622375815297;
+pub const MAX_DOMAIN_SIZE: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Domain {
+    pub n: usize,
+    pub omega: Scalar,
+}
+
+impl Domain {
+    pub fn new(n: usize) -> Self {
+        if !n.is_power_of_two() || n > MAX_DOMAIN_SIZE {
+            panic!(
+                "Domain size must be a power of two up to {}, got {}",
+                MAX_DOMAIN_SIZE, n
+            );
+        }
+        let omega = Scalar::new(ROOT_OF_UNITY_16).pow((MAX_DOMAIN_SIZE / n) as u64);
+        Domain { n, omega }
+    }
+
+    pub fn element(&self, i: usize) -> Scalar {
+        self.omega.pow(i as u64)
+    }
+
+    pub fn elements(&self) -> Vec<Scalar> {
+        (0..self.n).map(|i| self.element(i)).collect()
+    }
+
+    // 係数から H 上の値へ。次数は n 未満であること
+    pub fn fft(&self, poly: &Polynomial) -> Vec<Scalar> {
+        if poly.coeffs().len() > self.n {
+            panic!("Polynomial degree too large for the domain");
+        }
+        let mut coeffs = poly.coeffs().to_vec();
+        coeffs.resize(self.n, Scalar::ZERO);
+        fft(&coeffs, &self.omega)
+    }
+
+    // H 上の値から係数へ。ω^{-1} で FFT して n で割る
+    pub fn ifft(&self, evals: &[Scalar]) -> Polynomial {
+        if evals.len() != self.n {
+            panic!("Expected {} evaluations, got {}", self.n, evals.len());
+        }
+        let omega_inv = self.omega.inv().unwrap();
+        let n_inv = Scalar::new(self.n as u64).inv().unwrap();
+        Polynomial::new(
+            fft(evals, &omega_inv)
+                .iter()
+                .map(|c| c.mul(&n_inv))
+                .collect(),
+        )
+    }
+
+    // Z_H(x) = x^n - 1
+    pub fn vanishing_eval(&self, x: &Scalar) -> Scalar {
+        x.pow(self.n as u64).sub(&Scalar::ONE)
+    }
+
+    // ω^0 で 1、他の点で 0 になる Lagrange 基底 L_1(x) = (x^n - 1) / (n (x - 1))
+    pub fn first_lagrange_eval(&self, x: &Scalar) -> Scalar {
+        if *x == Scalar::ONE {
+            return Scalar::ONE;
+        }
+        let denom = Scalar::new(self.n as u64).mul(&x.sub(&Scalar::ONE));
+        self.vanishing_eval(x).mul(&denom.inv().unwrap())
+    }
+}
+
+// 偶数番目と奇数番目に分けて再帰する Cooley–Tukey
+fn fft(values: &[Scalar], omega: &Scalar) -> Vec<Scalar> {
+    let n = values.len();
+    if n == 1 {
+        return values.to_vec();
+    }
+    let omega_sq = omega.mul(omega);
+    let even: Vec<Scalar> = values.iter().step_by(2).copied().collect();
+    let odd: Vec<Scalar> = values.iter().skip(1).step_by(2).copied().collect();
+    let (e, o) = (fft(&even, &omega_sq), fft(&odd, &omega_sq));
+    let mut out = vec![Scalar::ZERO; n];
+    let mut w = Scalar::ONE;
+    for i in 0..n / 2 {
+        let t = w.mul(&o[i]);
+        out[i] = e[i].add(&t);
+        out[i + n / 2] = e[i].sub(&t);
+        w = w.mul(omega);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_root_of_unity() {
+        let omega = Scalar::new(ROOT_OF_UNITY_16);
+        assert_eq!(omega.pow(16), Scalar::ONE);
+        assert_eq!(omega.pow(8), Scalar::ONE.neg());
+        let d = Domain::new(4);
+        assert_eq!(d.omega.pow(4), Scalar::ONE);
+        assert_ne!(d.omega.pow(2), Scalar::ONE);
+    }
+
+    #[test]
+    fn test_fft_roundtrip_and_lagrange() {
+        let d = Domain::new(8);
+        let p = Polynomial::new((1..=8).map(Scalar::new).collect());
+        let evals = d.fft(&p);
+        for (x, y) in d.elements().iter().zip(&evals) {
+            assert_eq!(p.evaluate(x), *y);
+        }
+        assert_eq!(d.ifft(&evals), p);
+        let x = Scalar::new(12345);
+        let mut l1 = vec![Scalar::ZERO; 8];
+        l1[0] = Scalar::ONE;
+        assert_eq!(d.ifft(&l1).evaluate(&x), d.first_lagrange_eval(&x));
+        assert_eq!(d.vanishing_eval(&d.element(3)), Scalar::ZERO);
+    }
+}
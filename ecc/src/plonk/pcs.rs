@@ -0,0 +1,113 @@
+// 内積引数による多項式コミットメント (Halo 流)。KZG と違いペアリングも信頼できる
+// セットアップも要らないが、検証には次数に比例する時間がかかる。
+// 係数ベクトルを C = <p, G> とコミットし、p(z) = <p, (1, z, z^2, ...)> を内積引数で示す。
+// ブラインドしないので秘匿性はない
+use super::poly::Polynomial;
+use crate::bulletproofs::{prove_ipa, verify_ipa, BulletproofGens, InnerProductProof};
+use crate::curve::{msm, Scalar};
+use crate::transcript::Transcript;
+use crate::Point;
+
+#[derive(Debug, Clone)]
+pub struct CommitKey {
+    gens: BulletproofGens,
+}
+
+impl CommitKey {
+    // 次数 max_degree までの多項式を扱える鍵。生成元の数は 2 冪に切り上げる
+    pub fn new(max_degree: usize) -> Self {
+        CommitKey {
+            gens: BulletproofGens::new((max_degree + 1).next_power_of_two()),
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.gens.g.len()
+    }
+
+    pub fn commit(&self, poly: &Polynomial) -> Point {
+        let coeffs = poly.coeffs();
+        if coeffs.len() > self.size() {
+            panic!("Polynomial of degree {} exceeds commit key", poly.degree());
+        }
+        msm(coeffs, &self.gens.g[..coeffs.len()])
+    }
+
+    // <b, H> + v Q を足した点に対する内積引数。Q はチャレンジで毎回ずらす
+    fn statement(
+        &self,
+        transcript: &mut Transcript,
+        commitment: &Point,
+        z: &Scalar,
+        value: &Scalar,
+    ) -> (Vec<Scalar>, Point, Point) {
+        transcript.append_message(b"dom-sep", b"ipa-pcs");
+        transcript.append_point(b"C", commitment);
+        transcript.append_scalar(b"z", z);
+        transcript.append_scalar(b"v", value);
+        let w = transcript.challenge_scalar(b"w");
+        let q = self.gens.q.scalar_mul(w.value());
+        let mut b = Vec::with_capacity(self.size());
+        let mut power = Scalar::ONE;
+        for _ in 0..self.size() {
+            b.push(power);
+            power = power.mul(z);
+        }
+        let p = msm(
+            &[&b[..], &[Scalar::ONE, *value]].concat(),
+            &[&self.gens.h[..], &[commitment.clone(), q.clone()]].concat(),
+        );
+        (b, q, p)
+    }
+
+    pub fn open(
+        &self,
+        transcript: &mut Transcript,
+        poly: &Polynomial,
+        z: &Scalar,
+    ) -> (Scalar, InnerProductProof) {
+        let value = poly.evaluate(z);
+        let (b, q, _) = self.statement(transcript, &self.commit(poly), z, &value);
+        let mut a = poly.coeffs().to_vec();
+        a.resize(self.size(), Scalar::ZERO);
+        let (_, proof) = prove_ipa(transcript, &q, &self.gens.g, &self.gens.h, &a, &b);
+        (value, proof)
+    }
+
+    pub fn verify(
+        &self,
+        transcript: &mut Transcript,
+        commitment: &Point,
+        z: &Scalar,
+        value: &Scalar,
+        proof: &InnerProductProof,
+    ) -> bool {
+        let (_, q, p) = self.statement(transcript, commitment, z, value);
+        verify_ipa(transcript, &q, &self.gens.g, &self.gens.h, &p, proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open_and_verify() {
+        let ck = CommitKey::new(7);
+        assert_eq!(ck.size(), 8);
+        let p = Polynomial::new((1..=6).map(Scalar::new).collect());
+        let c = ck.commit(&p);
+        let z = Scalar::new(99);
+        let (v, proof) = ck.open(&mut Transcript::new(b"pcs"), &p, &z);
+        assert_eq!(v, p.evaluate(&z));
+        assert!(ck.verify(&mut Transcript::new(b"pcs"), &c, &z, &v, &proof));
+        // 別の値や別の点では通らない
+        let wrong = v.add(&Scalar::ONE);
+        assert!(!ck.verify(&mut Transcript::new(b"pcs"), &c, &z, &wrong, &proof));
+        let other = Scalar::new(100);
+        assert!(!ck.verify(&mut Transcript::new(b"pcs"), &c, &other, &v, &proof));
+        // コミットメントは加法的に準同型
+        let q = Polynomial::new(vec![Scalar::new(5); 3]);
+        assert_eq!(ck.commit(&p.add(&q)), c.add(&ck.commit(&q)));
+    }
+}
@@ -0,0 +1,147 @@
+// Scalar 係数の一変数多項式。係数は低次から並べ、末尾の 0 は取り除いておく
+use crate::curve::Scalar;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polynomial {
+    coeffs: Vec<Scalar>,
+}
+
+impl Polynomial {
+    pub fn new(mut coeffs: Vec<Scalar>) -> Self {
+        while coeffs.last() == Some(&Scalar::ZERO) {
+            coeffs.pop();
+        }
+        Polynomial { coeffs }
+    }
+
+    pub fn zero() -> Self {
+        Polynomial { coeffs: vec![] }
+    }
+
+    pub fn constant(c: Scalar) -> Self {
+        Polynomial::new(vec![c])
+    }
+
+    // X^n - 1
+    pub fn vanishing(n: usize) -> Self {
+        let mut coeffs = vec![Scalar::ZERO; n + 1];
+        coeffs[0] = Scalar::ONE.neg();
+        coeffs[n] = Scalar::ONE;
+        Polynomial { coeffs }
+    }
+
+    pub fn coeffs(&self) -> &[Scalar] {
+        &self.coeffs
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coeffs.is_empty()
+    }
+
+    // 零多項式の次数は 0 として扱う
+    pub fn degree(&self) -> usize {
+        self.coeffs.len().saturating_sub(1)
+    }
+
+    // Horner 法
+    pub fn evaluate(&self, x: &Scalar) -> Scalar {
+        self.coeffs
+            .iter()
+            .rev()
+            .fold(Scalar::ZERO, |acc, c| acc.mul(x).add(c))
+    }
+
+    pub fn add(&self, other: &Polynomial) -> Polynomial {
+        let len = self.coeffs.len().max(other.coeffs.len());
+        let get = |p: &Polynomial, i: usize| p.coeffs.get(i).copied().unwrap_or(Scalar::ZERO);
+        Polynomial::new((0..len).map(|i| get(self, i).add(&get(other, i))).collect())
+    }
+
+    pub fn sub(&self, other: &Polynomial) -> Polynomial {
+        self.add(&other.scale(&Scalar::ONE.neg()))
+    }
+
+    pub fn scale(&self, c: &Scalar) -> Polynomial {
+        Polynomial::new(self.coeffs.iter().map(|x| x.mul(c)).collect())
+    }
+
+    // 次数が小さいので素朴な畳み込みで十分
+    pub fn mul(&self, other: &Polynomial) -> Polynomial {
+        if self.is_zero() || other.is_zero() {
+            return Polynomial::zero();
+        }
+        let mut out = vec![Scalar::ZERO; self.coeffs.len() + other.coeffs.len() - 1];
+        for (i, a) in self.coeffs.iter().enumerate() {
+            for (j, b) in other.coeffs.iter().enumerate() {
+                out[i + j] = out[i + j].add(&a.mul(b));
+            }
+        }
+        Polynomial::new(out)
+    }
+
+    // p(cX)。置換引数で Z(ωX) を作るのに使う
+    pub fn scale_input(&self, c: &Scalar) -> Polynomial {
+        let mut power = Scalar::ONE;
+        let mut out = Vec::with_capacity(self.coeffs.len());
+        for coeff in &self.coeffs {
+            out.push(coeff.mul(&power));
+            power = power.mul(c);
+        }
+        Polynomial::new(out)
+    }
+
+    // 商と余りを返す長除法
+    pub fn div_rem(&self, divisor: &Polynomial) -> (Polynomial, Polynomial) {
+        if divisor.is_zero() {
+            panic!("Polynomial division by zero");
+        }
+        if self.coeffs.len() < divisor.coeffs.len() {
+            return (Polynomial::zero(), self.clone());
+        }
+        let lead_inv = divisor.coeffs.last().unwrap().inv().unwrap();
+        let mut rem = self.coeffs.clone();
+        let mut quot = vec![Scalar::ZERO; rem.len() - divisor.coeffs.len() + 1];
+        for i in (0..quot.len()).rev() {
+            let q = rem[i + divisor.coeffs.len() - 1].mul(&lead_inv);
+            quot[i] = q;
+            for (j, d) in divisor.coeffs.iter().enumerate() {
+                rem[i + j] = rem[i + j].sub(&q.mul(d));
+            }
+        }
+        (Polynomial::new(quot), Polynomial::new(rem))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn poly(coeffs: &[u64]) -> Polynomial {
+        Polynomial::new(coeffs.iter().map(|&c| Scalar::new(c)).collect())
+    }
+
+    #[test]
+    fn test_arithmetic_and_evaluation() {
+        // (1 + X)(2 + X) = 2 + 3X + X^2
+        let p = poly(&[1, 1]).mul(&poly(&[2, 1]));
+        assert_eq!(p, poly(&[2, 3, 1]));
+        assert_eq!(p.evaluate(&Scalar::new(5)), Scalar::new(42));
+        assert_eq!(p.sub(&p), Polynomial::zero());
+        assert_eq!(p.add(&poly(&[0, 0, Scalar::ONE.neg().value()])).degree(), 1);
+        // p(2X) = 2 + 6X + 4X^2
+        assert_eq!(p.scale_input(&Scalar::new(2)), poly(&[2, 6, 4]));
+    }
+
+    #[test]
+    fn test_div_rem() {
+        let p = poly(&[7, 0, 3, 1, 9]);
+        let d = poly(&[4, 1, 2]);
+        let (q, r) = p.div_rem(&d);
+        assert!(r.degree() < d.degree());
+        assert_eq!(q.mul(&d).add(&r), p);
+        // X^4 - 1 は X^2 - 1 で割り切れる
+        let (q, r) = Polynomial::vanishing(4).div_rem(&Polynomial::vanishing(2));
+        assert!(r.is_zero());
+        assert_eq!(q, poly(&[1, 0, 1]));
+    }
+}
@@ -0,0 +1,335 @@
+// PLONK の証明と検証。ワイヤ多項式 a, b, c と置換の累積積 Z をコミットし、
+// gate(X) + α perm(X) + α^2 L_1(X)(Z(X) - 1) が Z_H(X) で割り切れることを
+// 商 t(X) を通じてランダムな点 ζ で確かめる。開示は KZG の代わりに内積引数で行う
+use super::circuit::{Circuit, Witness, K1, K2};
+use super::pcs::CommitKey;
+use super::poly::Polynomial;
+use crate::bulletproofs::InnerProductProof;
+use crate::curve::{msm, Scalar};
+use crate::transcript::Transcript;
+use crate::Point;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlonkProof {
+    pub a: Point,
+    pub b: Point,
+    pub c: Point,
+    pub z: Point,
+    pub t: Point,
+    pub a_eval: Scalar,
+    pub b_eval: Scalar,
+    pub c_eval: Scalar,
+    pub z_eval: Scalar,
+    pub z_omega_eval: Scalar,
+    pub t_eval: Scalar,
+    // a, b, c, Z, t をまとめた ζ での開示と、Z の ωζ での開示
+    pub opening: InnerProductProof,
+    pub opening_omega: InnerProductProof,
+}
+
+// t の次数は 3n - 4 以下
+pub fn commit_key(circuit: &Circuit) -> CommitKey {
+    CommitKey::new(3 * circuit.domain.n)
+}
+
+fn start(ck: &CommitKey, circuit: &Circuit, transcript: &mut Transcript) {
+    transcript.append_message(b"dom-sep", b"plonk");
+    transcript.append_u64(b"n", circuit.domain.n as u64);
+    let fixed = [
+        &circuit.q_l,
+        &circuit.q_r,
+        &circuit.q_o,
+        &circuit.q_m,
+        &circuit.q_c,
+        &circuit.sigma[0],
+        &circuit.sigma[1],
+        &circuit.sigma[2],
+    ];
+    for p in fixed {
+        transcript.append_point(b"fixed", &ck.commit(p));
+    }
+}
+
+fn batch(v: &Scalar, len: usize) -> Vec<Scalar> {
+    let mut out = vec![Scalar::ONE];
+    for i in 1..len {
+        out.push(out[i - 1].mul(v));
+    }
+    out
+}
+
+pub fn prove(
+    ck: &CommitKey,
+    circuit: &Circuit,
+    witness: &Witness,
+    transcript: &mut Transcript,
+) -> PlonkProof {
+    let domain = &circuit.domain;
+    let n = domain.n;
+    start(ck, circuit, transcript);
+
+    // 1. ワイヤ
+    let a = domain.ifft(&witness.a);
+    let b = domain.ifft(&witness.b);
+    let c = domain.ifft(&witness.c);
+    let (a_comm, b_comm, c_comm) = (ck.commit(&a), ck.commit(&b), ck.commit(&c));
+    transcript.append_point(b"a", &a_comm);
+    transcript.append_point(b"b", &b_comm);
+    transcript.append_point(b"c", &c_comm);
+    let beta = transcript.challenge_scalar(b"beta");
+    let gamma = transcript.challenge_scalar(b"gamma");
+
+    // 2. 置換の累積積 Z(ω^{i+1}) = Z(ω^i) Π (w + β id + γ) / (w + β σ + γ)
+    let ks = [Scalar::ONE, Scalar::new(K1), Scalar::new(K2)];
+    let columns = [&witness.a, &witness.b, &witness.c];
+    let mut z_evals = vec![Scalar::ONE];
+    for i in 0..n - 1 {
+        let x = domain.element(i);
+        let mut num = Scalar::ONE;
+        let mut den = Scalar::ONE;
+        for j in 0..3 {
+            let w = columns[j][i].add(&gamma);
+            num = num.mul(&w.add(&beta.mul(&ks[j]).mul(&x)));
+            den = den.mul(&w.add(&beta.mul(&circuit.sigma_evals[j][i])));
+        }
+        let next = z_evals[i]
+            .mul(&num)
+            .mul(&den.inv().expect("zero permutation term"));
+        z_evals.push(next);
+    }
+    let z = domain.ifft(&z_evals);
+    let z_comm = ck.commit(&z);
+    transcript.append_point(b"z", &z_comm);
+    let alpha = transcript.challenge_scalar(b"alpha");
+
+    // 3. 商多項式
+    let gate = circuit
+        .q_m
+        .mul(&a)
+        .mul(&b)
+        .add(&circuit.q_l.mul(&a))
+        .add(&circuit.q_r.mul(&b))
+        .add(&circuit.q_o.mul(&c))
+        .add(&circuit.q_c);
+    let x_poly = Polynomial::new(vec![Scalar::ZERO, Scalar::ONE]);
+    let wires = [&a, &b, &c];
+    let gamma_poly = Polynomial::constant(gamma);
+    let mut id_part = z.clone();
+    let mut sigma_part = z.scale_input(&domain.omega);
+    for j in 0..3 {
+        let base = wires[j].add(&gamma_poly);
+        id_part = id_part.mul(&base.add(&x_poly.scale(&beta.mul(&ks[j]))));
+        sigma_part = sigma_part.mul(&base.add(&circuit.sigma[j].scale(&beta)));
+    }
+    let mut first = vec![Scalar::ZERO; n];
+    first[0] = Scalar::ONE;
+    let l1 = domain.ifft(&first);
+    let boundary = l1.mul(&z.sub(&Polynomial::constant(Scalar::ONE)));
+    let numerator = gate
+        .add(&id_part.sub(&sigma_part).scale(&alpha))
+        .add(&boundary.scale(&alpha.mul(&alpha)));
+    let (t, rem) = numerator.div_rem(&Polynomial::vanishing(n));
+    if !rem.is_zero() {
+        panic!("Witness does not satisfy the circuit");
+    }
+    let t_comm = ck.commit(&t);
+    transcript.append_point(b"t", &t_comm);
+    let zeta = transcript.challenge_scalar(b"zeta");
+
+    // 4. 評価値と開示
+    let zeta_omega = zeta.mul(&domain.omega);
+    let evals = [&a, &b, &c, &z, &t].map(|p| p.evaluate(&zeta));
+    let z_omega_eval = z.evaluate(&zeta_omega);
+    for (label, e) in [&b"a_eval"[..], b"b_eval", b"c_eval", b"z_eval", b"t_eval"]
+        .iter()
+        .zip(&evals)
+    {
+        transcript.append_scalar(label, e);
+    }
+    transcript.append_scalar(b"z_omega_eval", &z_omega_eval);
+    let v = transcript.challenge_scalar(b"v");
+    let combined = [&a, &b, &c, &z, &t]
+        .iter()
+        .zip(batch(&v, 5))
+        .fold(Polynomial::zero(), |acc, (p, vi)| acc.add(&p.scale(&vi)));
+    let (_, opening) = ck.open(transcript, &combined, &zeta);
+    let (_, opening_omega) = ck.open(transcript, &z, &zeta_omega);
+
+    PlonkProof {
+        a: a_comm,
+        b: b_comm,
+        c: c_comm,
+        z: z_comm,
+        t: t_comm,
+        a_eval: evals[0],
+        b_eval: evals[1],
+        c_eval: evals[2],
+        z_eval: evals[3],
+        z_omega_eval,
+        t_eval: evals[4],
+        opening,
+        opening_omega,
+    }
+}
+
+pub fn verify(
+    ck: &CommitKey,
+    circuit: &Circuit,
+    proof: &PlonkProof,
+    transcript: &mut Transcript,
+) -> bool {
+    let domain = &circuit.domain;
+    start(ck, circuit, transcript);
+    transcript.append_point(b"a", &proof.a);
+    transcript.append_point(b"b", &proof.b);
+    transcript.append_point(b"c", &proof.c);
+    let beta = transcript.challenge_scalar(b"beta");
+    let gamma = transcript.challenge_scalar(b"gamma");
+    transcript.append_point(b"z", &proof.z);
+    let alpha = transcript.challenge_scalar(b"alpha");
+    transcript.append_point(b"t", &proof.t);
+    let zeta = transcript.challenge_scalar(b"zeta");
+    let evals = [
+        proof.a_eval,
+        proof.b_eval,
+        proof.c_eval,
+        proof.z_eval,
+        proof.t_eval,
+    ];
+    for (label, e) in [&b"a_eval"[..], b"b_eval", b"c_eval", b"z_eval", b"t_eval"]
+        .iter()
+        .zip(&evals)
+    {
+        transcript.append_scalar(label, e);
+    }
+    transcript.append_scalar(b"z_omega_eval", &proof.z_omega_eval);
+    let v = transcript.challenge_scalar(b"v");
+
+    // ζ での恒等式。セレクタと σ は検証者が自分で評価する
+    let (a, b, c) = (proof.a_eval, proof.b_eval, proof.c_eval);
+    let gate = circuit
+        .q_m
+        .evaluate(&zeta)
+        .mul(&a)
+        .mul(&b)
+        .add(&circuit.q_l.evaluate(&zeta).mul(&a))
+        .add(&circuit.q_r.evaluate(&zeta).mul(&b))
+        .add(&circuit.q_o.evaluate(&zeta).mul(&c))
+        .add(&circuit.q_c.evaluate(&zeta));
+    let ks = [Scalar::ONE, Scalar::new(K1), Scalar::new(K2)];
+    let mut id_part = proof.z_eval;
+    let mut sigma_part = proof.z_omega_eval;
+    for (j, w) in [a, b, c].iter().enumerate() {
+        let base = w.add(&gamma);
+        id_part = id_part.mul(&base.add(&beta.mul(&ks[j]).mul(&zeta)));
+        sigma_part = sigma_part.mul(&base.add(&beta.mul(&circuit.sigma[j].evaluate(&zeta))));
+    }
+    let boundary = domain
+        .first_lagrange_eval(&zeta)
+        .mul(&proof.z_eval.sub(&Scalar::ONE));
+    let lhs = gate
+        .add(&alpha.mul(&id_part.sub(&sigma_part)))
+        .add(&alpha.mul(&alpha).mul(&boundary));
+    if lhs != proof.t_eval.mul(&domain.vanishing_eval(&zeta)) {
+        return false;
+    }
+
+    // コミットメントは加法的なので、まとめた多項式のコミットメントは線形結合で得られる
+    let vs = batch(&v, 5);
+    let combined = msm(
+        &vs,
+        &[
+            proof.a.clone(),
+            proof.b.clone(),
+            proof.c.clone(),
+            proof.z.clone(),
+            proof.t.clone(),
+        ],
+    );
+    let combined_eval = evals
+        .iter()
+        .zip(&vs)
+        .fold(Scalar::ZERO, |acc, (e, vi)| acc.add(&e.mul(vi)));
+    ck.verify(transcript, &combined, &zeta, &combined_eval, &proof.opening)
+        && ck.verify(
+            transcript,
+            &proof.z,
+            &zeta.mul(&domain.omega),
+            &proof.z_omega_eval,
+            &proof.opening_omega,
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plonk::CircuitBuilder;
+
+    // x^3 + x + 5 = 35 を x = 3 で
+    fn cubic() -> (Circuit, Witness) {
+        let mut cs = CircuitBuilder::new();
+        let x = cs.variable(Scalar::new(3));
+        let x2 = cs.mul(x, x);
+        let x3 = cs.mul(x2, x);
+        let sum = cs.add(x3, x);
+        let out = cs.add_constant(sum, Scalar::new(5));
+        cs.assert_constant(out, Scalar::new(35));
+        cs.build()
+    }
+
+    #[test]
+    fn test_prove_and_verify() {
+        let (circuit, witness) = cubic();
+        let ck = commit_key(&circuit);
+        let proof = prove(&ck, &circuit, &witness, &mut Transcript::new(b"plonk-test"));
+        assert!(verify(
+            &ck,
+            &circuit,
+            &proof,
+            &mut Transcript::new(b"plonk-test")
+        ));
+        // 評価値を 1 つでも変えると恒等式か開示が破れる
+        let mut bad = proof.clone();
+        bad.c_eval = bad.c_eval.add(&Scalar::ONE);
+        assert!(!verify(
+            &ck,
+            &circuit,
+            &bad,
+            &mut Transcript::new(b"plonk-test")
+        ));
+        let mut bad = proof.clone();
+        bad.z_omega_eval = bad.z_omega_eval.add(&Scalar::ONE);
+        assert!(!verify(
+            &ck,
+            &circuit,
+            &bad,
+            &mut Transcript::new(b"plonk-test")
+        ));
+        // 別の回路 (出力 36) に対しては通らない
+        let mut cs = CircuitBuilder::new();
+        let x = cs.variable(Scalar::new(3));
+        let x2 = cs.mul(x, x);
+        let x3 = cs.mul(x2, x);
+        let sum = cs.add(x3, x);
+        let out = cs.add_constant(sum, Scalar::new(5));
+        cs.assert_constant(out, Scalar::new(36));
+        let (other, _) = cs.build();
+        assert!(!verify(
+            &ck,
+            &other,
+            &proof,
+            &mut Transcript::new(b"plonk-test")
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "does not satisfy")]
+    fn test_copy_constraint_violation_cannot_be_proven() {
+        let (circuit, mut witness) = cubic();
+        // ゲートごとには正しいが、2 番目の乗算に別の x を使った割り当て
+        witness.b[1] = Scalar::new(4);
+        witness.c[1] = Scalar::new(36);
+        let ck = commit_key(&circuit);
+        prove(&ck, &circuit, &witness, &mut Transcript::new(b"plonk-test"));
+    }
+}
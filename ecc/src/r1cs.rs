@@ -0,0 +1,249 @@
+// ランク 1 制約系 (R1CS)。各制約は <A_i, z> * <B_i, z> = <C_i, z> の形で、
+// z = (1, 公開入力, 秘密の中間値) が割り当て全体。SNARK のフロントエンドは
+// 回路をこの形に落とし、証明系は z の存在を示す。体は曲線の位数 N を法とする Scalar
+use crate::curve::Scalar;
+
+// 0 番は定数 1、以降は割り当てた順の添字
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Variable(usize);
+
+impl Variable {
+    pub const ONE: Variable = Variable(0);
+
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+// Σ c_j z_j。同じ変数の項は足し合わせず並べたまま持つ
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct LinearCombination {
+    pub terms: Vec<(Variable, Scalar)>,
+}
+
+impl LinearCombination {
+    pub fn zero() -> Self {
+        LinearCombination { terms: vec![] }
+    }
+
+    pub fn constant(value: Scalar) -> Self {
+        LinearCombination::from_term(Variable::ONE, value)
+    }
+
+    pub fn from_var(var: Variable) -> Self {
+        LinearCombination::from_term(var, Scalar::ONE)
+    }
+
+    pub fn from_term(var: Variable, coeff: Scalar) -> Self {
+        LinearCombination {
+            terms: vec![(var, coeff)],
+        }
+    }
+
+    pub fn add_term(mut self, var: Variable, coeff: Scalar) -> Self {
+        self.terms.push((var, coeff));
+        self
+    }
+
+    pub fn add(&self, other: &LinearCombination) -> LinearCombination {
+        LinearCombination {
+            terms: [&self.terms[..], &other.terms].concat(),
+        }
+    }
+
+    pub fn sub(&self, other: &LinearCombination) -> LinearCombination {
+        self.add(&other.scale(&Scalar::ONE.neg()))
+    }
+
+    pub fn scale(&self, c: &Scalar) -> LinearCombination {
+        LinearCombination {
+            terms: self.terms.iter().map(|(v, k)| (*v, k.mul(c))).collect(),
+        }
+    }
+
+    pub fn evaluate(&self, assignment: &[Scalar]) -> Scalar {
+        self.terms.iter().fold(Scalar::ZERO, |acc, (v, k)| {
+            acc.add(&k.mul(&assignment[v.0]))
+        })
+    }
+
+    // 変数ごとに係数をまとめた長さ len の密なベクトル
+    pub fn to_dense(&self, len: usize) -> Vec<Scalar> {
+        let mut row = vec![Scalar::ZERO; len];
+        for (v, k) in &self.terms {
+            row[v.0] = row[v.0].add(k);
+        }
+        row
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Constraint {
+    pub a: LinearCombination,
+    pub b: LinearCombination,
+    pub c: LinearCombination,
+}
+
+// 変数を割り当てながら値も記録する。公開入力は秘密の値より前に並べる
+#[derive(Debug, Clone)]
+pub struct ConstraintSystem {
+    inputs: Vec<Scalar>,
+    witness: Vec<Scalar>,
+    constraints: Vec<Constraint>,
+}
+
+impl Default for ConstraintSystem {
+    fn default() -> Self {
+        ConstraintSystem::new()
+    }
+}
+
+impl ConstraintSystem {
+    pub fn new() -> Self {
+        ConstraintSystem {
+            inputs: vec![],
+            witness: vec![],
+            constraints: vec![],
+        }
+    }
+
+    pub fn alloc_input(&mut self, value: Scalar) -> Variable {
+        if !self.witness.is_empty() {
+            panic!("Public inputs must be allocated before witness variables");
+        }
+        self.inputs.push(value);
+        Variable(self.inputs.len())
+    }
+
+    pub fn alloc_witness(&mut self, value: Scalar) -> Variable {
+        self.witness.push(value);
+        Variable(self.inputs.len() + self.witness.len())
+    }
+
+    pub fn enforce(&mut self, a: LinearCombination, b: LinearCombination, c: LinearCombination) {
+        let len = self.num_variables();
+        let mut all = a.terms.iter().chain(&b.terms).chain(&c.terms);
+        if all.any(|(v, _)| v.0 >= len) {
+            panic!("Constraint refers to an unallocated variable");
+        }
+        self.constraints.push(Constraint { a, b, c });
+    }
+
+    pub fn num_inputs(&self) -> usize {
+        self.inputs.len()
+    }
+
+    // 定数 1 を含む変数の総数
+    pub fn num_variables(&self) -> usize {
+        1 + self.inputs.len() + self.witness.len()
+    }
+
+    pub fn constraints(&self) -> &[Constraint] {
+        &self.constraints
+    }
+
+    pub fn public_inputs(&self) -> &[Scalar] {
+        &self.inputs
+    }
+
+    // z = (1, 公開入力, 秘密の値)
+    pub fn assignment(&self) -> Vec<Scalar> {
+        [&[Scalar::ONE][..], &self.inputs, &self.witness].concat()
+    }
+
+    // 満たされない最初の制約の番号
+    pub fn unsatisfied_constraint(&self) -> Option<usize> {
+        self.first_unsatisfied(&self.assignment())
+    }
+
+    pub fn is_satisfied(&self) -> bool {
+        self.unsatisfied_constraint().is_none()
+    }
+
+    // 記録した値の代わりに外から与えた割り当てを確かめる
+    pub fn is_satisfied_by(&self, assignment: &[Scalar]) -> bool {
+        if assignment.len() != self.num_variables() || assignment[0] != Scalar::ONE {
+            return false;
+        }
+        self.first_unsatisfied(assignment).is_none()
+    }
+
+    fn first_unsatisfied(&self, z: &[Scalar]) -> Option<usize> {
+        self.constraints
+            .iter()
+            .position(|c| c.a.evaluate(z).mul(&c.b.evaluate(z)) != c.c.evaluate(z))
+    }
+
+    // 行列 A, B, C (制約数 × 変数数) に展開する。QAP への変換などで使う
+    pub fn to_matrices(&self) -> [Vec<Vec<Scalar>>; 3] {
+        let len = self.num_variables();
+        let dense = |f: fn(&Constraint) -> &LinearCombination| {
+            self.constraints
+                .iter()
+                .map(|c| f(c).to_dense(len))
+                .collect()
+        };
+        [dense(|c| &c.a), dense(|c| &c.b), dense(|c| &c.c)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // x^3 + x + 5 = out を 4 つの制約に平坦化する (Vitalik の QAP 解説の例)
+    fn cubic(x: u64, out: u64) -> ConstraintSystem {
+        let mut cs = ConstraintSystem::new();
+        let out = cs.alloc_input(Scalar::new(out));
+        let x_val = Scalar::new(x);
+        let x = cs.alloc_witness(x_val);
+        let sym1 = cs.alloc_witness(x_val.mul(&x_val));
+        let y = cs.alloc_witness(x_val.mul(&x_val).mul(&x_val));
+        let sym2 = cs.alloc_witness(x_val.mul(&x_val).mul(&x_val).add(&x_val));
+        let lc = LinearCombination::from_var;
+        cs.enforce(lc(x), lc(x), lc(sym1));
+        cs.enforce(lc(sym1), lc(x), lc(y));
+        cs.enforce(lc(y).add(&lc(x)), lc(Variable::ONE), lc(sym2));
+        cs.enforce(
+            lc(sym2).add(&LinearCombination::constant(Scalar::new(5))),
+            lc(Variable::ONE),
+            lc(out),
+        );
+        cs
+    }
+
+    #[test]
+    fn test_cubic_circuit() {
+        let cs = cubic(3, 35);
+        assert!(cs.is_satisfied());
+        assert_eq!(cs.num_variables(), 6);
+        assert_eq!(cs.public_inputs(), &[Scalar::new(35)]);
+        // 公開出力が違えば最後の制約が破れる
+        assert_eq!(cubic(3, 36).unsatisfied_constraint(), Some(3));
+        // 割り当ての中間値を書き換えると 1 番目の制約で破れる
+        let mut z = cs.assignment();
+        z[3] = z[3].add(&Scalar::ONE);
+        assert!(!cs.is_satisfied_by(&z));
+        z[3] = Scalar::new(9);
+        assert!(cs.is_satisfied_by(&z));
+    }
+
+    #[test]
+    fn test_matrices() {
+        let cs = cubic(3, 35);
+        let [a, b, c] = cs.to_matrices();
+        let z = cs.assignment();
+        let dot = |row: &[Scalar]| {
+            row.iter()
+                .zip(&z)
+                .fold(Scalar::ZERO, |acc, (r, v)| acc.add(&r.mul(v)))
+        };
+        assert_eq!(a.len(), 4);
+        for ((ra, rb), rc) in a.iter().zip(&b).zip(&c) {
+            assert_eq!(dot(ra).mul(&dot(rb)), dot(rc));
+        }
+        // 4 番目の制約の A 行は 5·1 + sym2 (変数の並びは 1, out, x, sym1, y, sym2)
+        let row: Vec<u64> = a[3].iter().map(|s| s.value()).collect();
+        assert_eq!(row, vec![5, 0, 0, 0, 0, 1]);
+    }
+}
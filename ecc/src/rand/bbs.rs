@@ -0,0 +1,117 @@
+// Blum Blum Shub: n = pq (p ≡ q ≡ 3 mod 4) を法に x ← x^2 を繰り返し、最下位ビットを出力する。
+// 出力の予測は n の素因数分解 (平方剰余性の判定) と同じくらい難しいが、とても遅い
+use super::RngCore;
+use crate::bignum::BigUint;
+use crate::number_theory::{gcd_big, generate_prime_with_condition};
+
+#[derive(Debug, Clone)]
+pub struct Bbs {
+    n: BigUint,
+    state: BigUint,
+}
+
+// p ≡ q ≡ 3 mod 4 の相異なる素数の組 (積が Blum 整数)
+pub fn blum_primes<R: RngCore>(bits: usize, rng: &mut R) -> (BigUint, BigUint) {
+    let gen = |rng: &mut R| generate_prime_with_condition(bits / 2, rng, |p| p.rem_u64(4) == 3);
+    let p = gen(rng);
+    loop {
+        let q = gen(rng);
+        if q != p {
+            return (p, q);
+        }
+    }
+}
+
+impl Bbs {
+    // seed は n と互いに素で、x_0 = seed^2 mod n から始める
+    pub fn from_modulus(n: &BigUint, seed: &BigUint) -> Self {
+        let x0 = seed.mul(seed).rem(n);
+        if !gcd_big(seed, n).is_one() || x0.is_one() || x0.is_zero() {
+            panic!("BBS seed must be coprime to n and not ±1");
+        }
+        Bbs {
+            n: n.clone(),
+            state: x0,
+        }
+    }
+
+    // bits ビットの Blum 整数を作り、因数は捨てる
+    pub fn generate<R: RngCore>(bits: usize, rng: &mut R) -> Self {
+        let (p, q) = blum_primes(bits, rng);
+        let n = p.mul(&q);
+        loop {
+            let seed = BigUint::random_below(&n, rng);
+            if gcd_big(&seed, &n).is_one() && !seed.mul(&seed).rem(&n).is_one() {
+                return Bbs::from_modulus(&n, &seed);
+            }
+        }
+    }
+
+    pub fn state(&self) -> &BigUint {
+        &self.state
+    }
+
+    pub fn next_bit(&mut self) -> bool {
+        self.state = self.state.mul(&self.state).rem(&self.n);
+        self.state.bit(0)
+    }
+}
+
+impl RngCore for Bbs {
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for byte in dest.iter_mut() {
+            *byte = (0..8).fold(0, |acc, i| acc | (self.next_bit() as u8) << i);
+        }
+    }
+}
+
+// 素因数を知っていれば x_i = x_0^(2^i mod λ(n)) mod n で任意の位置へ直接飛べる
+pub fn state_at(x0: &BigUint, i: u64, p: &BigUint, q: &BigUint) -> BigUint {
+    let one = BigUint::one();
+    let (p1, q1) = (p.sub(&one), q.sub(&one));
+    let lambda = p1.mul(&q1).div(&gcd_big(&p1, &q1));
+    let e = BigUint::from_u64(2).modpow(&BigUint::from_u64(i), &lambda);
+    x0.modpow(&e, &p.mul(q))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::TestRng;
+
+    #[test]
+    fn test_toy_blum_integer() {
+        // n = 11 * 23 = 253, seed 3 で x_0 = 9
+        let (p, q) = (BigUint::from_u64(11), BigUint::from_u64(23));
+        let n = p.mul(&q);
+        let mut bbs = Bbs::from_modulus(&n, &BigUint::from_u64(3));
+        let x0 = bbs.state().clone();
+        assert_eq!(x0, BigUint::from_u64(9));
+        let mut states = vec![];
+        let bits: Vec<bool> = (0..8)
+            .map(|_| {
+                let b = bbs.next_bit();
+                states.push(bbs.state().to_u64().unwrap());
+                b
+            })
+            .collect();
+        assert_eq!(states, [81, 236, 36, 31, 202, 71, 234, 108]);
+        assert_eq!(bits, states.iter().map(|x| x & 1 == 1).collect::<Vec<_>>());
+        // 因数を知る者は途中を飛ばして同じ状態に到達する
+        for (i, &x) in states.iter().enumerate() {
+            assert_eq!(state_at(&x0, i as u64 + 1, &p, &q), BigUint::from_u64(x));
+        }
+    }
+
+    #[test]
+    fn test_generated_stream_is_balanced() {
+        let mut rng = TestRng::from_label(b"bbs");
+        let (p, q) = blum_primes(128, &mut rng);
+        assert_eq!((p.rem_u64(4), q.rem_u64(4)), (3, 3));
+        let mut bbs = Bbs::generate(128, &mut rng);
+        let mut out = [0u8; 256];
+        bbs.fill_bytes(&mut out);
+        let ones: u32 = out.iter().map(|b| b.count_ones()).sum();
+        assert!((900..1150).contains(&ones), "ones {}", ones);
+    }
+}
@@ -0,0 +1,127 @@
+// ChaCha20 の鍵ストリームを出力する CSPRNG。一定量ごとに自分の出力で鍵を置き換え
+// (fast key erasure)、後から内部状態が漏れても過去の出力は復元できない
+use super::{EntropySource, OsEntropy, RngCore};
+use crate::hash::sha256;
+use crate::symmetric::ChaCha20;
+
+// この出力量ごとに鍵を更新する
+const REKEY_INTERVAL: usize = 1 << 16;
+
+#[derive(Clone)]
+pub struct ChaCha20Rng {
+    cipher: ChaCha20,
+    since_rekey: usize,
+}
+
+impl ChaCha20Rng {
+    // 同じシードからは同じ列が出る。テストや再現用
+    pub fn from_seed(seed: [u8; 32]) -> Self {
+        ChaCha20Rng {
+            cipher: ChaCha20::new(&seed, &[0; 12], 0),
+            since_rekey: 0,
+        }
+    }
+
+    pub fn from_entropy<E: EntropySource>(source: &mut E) -> Self {
+        let mut seed = [0u8; 32];
+        source
+            .fill_entropy(&mut seed)
+            .expect("failed to read from entropy source");
+        ChaCha20Rng::from_seed(seed)
+    }
+
+    pub fn from_os_rng() -> Self {
+        ChaCha20Rng::from_entropy(&mut OsEntropy)
+    }
+
+    // 現在の状態から取り出した 32 バイトと entropy をハッシュして新しい鍵にする
+    pub fn reseed(&mut self, entropy: &[u8]) {
+        let mut current = [0u8; 32];
+        self.cipher.keystream(&mut current);
+        *self = ChaCha20Rng::from_seed(sha256(&[&current[..], entropy].concat()));
+    }
+
+    pub fn reseed_from_entropy<E: EntropySource>(&mut self, source: &mut E) {
+        let mut entropy = [0u8; 32];
+        source
+            .fill_entropy(&mut entropy)
+            .expect("failed to read from entropy source");
+        self.reseed(&entropy);
+    }
+
+    pub fn reseed_from_os(&mut self) {
+        self.reseed_from_entropy(&mut OsEntropy);
+    }
+
+    fn rekey(&mut self) {
+        let mut key = [0u8; 32];
+        self.cipher.keystream(&mut key);
+        *self = ChaCha20Rng::from_seed(key);
+    }
+}
+
+impl RngCore for ChaCha20Rng {
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        let mut dest = dest;
+        while !dest.is_empty() {
+            if self.since_rekey == REKEY_INTERVAL {
+                self.rekey();
+            }
+            let take = (REKEY_INTERVAL - self.since_rekey).min(dest.len());
+            let (head, rest) = dest.split_at_mut(take);
+            self.cipher.keystream(head);
+            self.since_rekey += take;
+            dest = rest;
+        }
+    }
+}
+
+// 鍵生成や nonce 生成で特に指定がなければこれを使う。OS から一度だけシードを読む
+pub fn default_rng() -> ChaCha20Rng {
+    ChaCha20Rng::from_os_rng()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::symmetric::chacha20::chacha20_block;
+
+    #[test]
+    fn test_seeded_stream_is_chacha20_keystream() {
+        let seed = [7u8; 32];
+        let mut rng = ChaCha20Rng::from_seed(seed);
+        let mut out = [0u8; 128];
+        rng.fill_bytes(&mut out);
+        assert_eq!(out[..64], chacha20_block(&seed, 0, &[0; 12]));
+        assert_eq!(out[64..], chacha20_block(&seed, 1, &[0; 12]));
+        // 分けて読んでも同じ列になる
+        let mut again = ChaCha20Rng::from_seed(seed);
+        let mut parts = [0u8; 128];
+        let (a, b) = parts.split_at_mut(5);
+        again.fill_bytes(a);
+        again.fill_bytes(b);
+        assert_eq!(parts, out);
+    }
+
+    #[test]
+    fn test_rekey_and_reseed() {
+        let seed = [1u8; 32];
+        let mut rng = ChaCha20Rng::from_seed(seed);
+        let mut buf = vec![0u8; REKEY_INTERVAL + 64];
+        rng.fill_bytes(&mut buf);
+        // 鍵の更新後は元の鍵ストリームの続きではない
+        let mut plain = ChaCha20::new(&seed, &[0; 12], 0);
+        let mut expected = vec![0u8; REKEY_INTERVAL + 64];
+        plain.keystream(&mut expected);
+        assert_eq!(buf[..REKEY_INTERVAL], expected[..REKEY_INTERVAL]);
+        assert_ne!(buf[REKEY_INTERVAL..], expected[REKEY_INTERVAL..]);
+
+        let mut a = ChaCha20Rng::from_seed(seed);
+        let mut b = ChaCha20Rng::from_seed(seed);
+        a.reseed(b"fresh entropy");
+        assert_ne!(a.next_u64(), b.next_u64());
+        let mut c = default_rng();
+        c.reseed_from_os();
+        assert_ne!(c.next_u64(), ChaCha20Rng::from_seed(seed).next_u64());
+    }
+}
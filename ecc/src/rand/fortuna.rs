@@ -0,0 +1,201 @@
+// Fortuna (Ferguson–Schneier)。イベントを 32 個のプールに振り分けて蓄え、
+// プール i は 2^i 回に 1 回だけ再シードに使う。攻撃者が一部の入力を知っていても、
+// いずれ十分なエントロピーが溜まったプールで回復する
+use super::RngCore;
+use crate::hash::{sha256, sha256d, Sha256};
+use crate::symmetric::Aes;
+use std::time::{Duration, Instant};
+
+pub const NUM_POOLS: usize = 32;
+// プール 0 にこれだけ溜まったら再シードする
+const MIN_POOL_SIZE: usize = 64;
+// 1 回の要求で出せる最大バイト数
+const MAX_REQUEST: usize = 1 << 20;
+const DEFAULT_RESEED_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FortunaError {
+    // まだ一度も再シードされていない
+    NotSeeded,
+}
+
+// AES-256 をカウンタモードで回す生成器。要求ごとに鍵を更新する
+#[derive(Clone)]
+struct Generator {
+    key: [u8; 32],
+    // 0 は未シードを表す
+    counter: u128,
+}
+
+impl Generator {
+    fn reseed(&mut self, seed: &[u8]) {
+        self.key = sha256d(&[&self.key[..], seed].concat());
+        self.counter += 1;
+    }
+
+    fn blocks(&mut self, aes: &Aes, out: &mut [u8]) {
+        for chunk in out.chunks_mut(16) {
+            let mut block = self.counter.to_le_bytes();
+            aes.encrypt_block(&mut block);
+            chunk.copy_from_slice(&block[..chunk.len()]);
+            self.counter += 1;
+        }
+    }
+
+    fn pseudo_random_data(&mut self, out: &mut [u8]) {
+        let aes = Aes::new(&self.key);
+        self.blocks(&aes, out);
+        let mut key = [0u8; 32];
+        self.blocks(&aes, &mut key);
+        self.key = key;
+    }
+}
+
+#[derive(Clone)]
+pub struct Fortuna {
+    generator: Generator,
+    pools: Vec<Sha256>,
+    pool0_len: usize,
+    reseed_count: u64,
+    last_reseed: Option<Instant>,
+    reseed_interval: Duration,
+    // ソースごとに次に書き込むプール
+    next_pool: [usize; 256],
+}
+
+impl Default for Fortuna {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Fortuna {
+    pub fn new() -> Self {
+        Fortuna::with_reseed_interval(DEFAULT_RESEED_INTERVAL)
+    }
+
+    // 再シードの最短間隔を変える。テストでは 0 にする
+    pub fn with_reseed_interval(reseed_interval: Duration) -> Self {
+        Fortuna {
+            generator: Generator {
+                key: [0; 32],
+                counter: 0,
+            },
+            pools: vec![Sha256::new(); NUM_POOLS],
+            pool0_len: 0,
+            reseed_count: 0,
+            last_reseed: None,
+            reseed_interval,
+            next_pool: [0; 256],
+        }
+    }
+
+    // source_id ごとにイベントを順番にプールへ振り分ける。1 イベントは 32 バイトまで
+    pub fn add_entropy(&mut self, source_id: u8, data: &[u8]) {
+        if data.is_empty() || data.len() > 32 {
+            panic!("Fortuna events must be 1 to 32 bytes");
+        }
+        let i = self.next_pool[source_id as usize];
+        self.next_pool[source_id as usize] = (i + 1) % NUM_POOLS;
+        self.pools[i].update(&[source_id, data.len() as u8]);
+        self.pools[i].update(data);
+        if i == 0 {
+            self.pool0_len += data.len() + 2;
+        }
+    }
+
+    pub fn reseed_count(&self) -> u64 {
+        self.reseed_count
+    }
+
+    fn maybe_reseed(&mut self) {
+        let due = self
+            .last_reseed
+            .is_none_or(|t| t.elapsed() >= self.reseed_interval);
+        if self.pool0_len < MIN_POOL_SIZE || !due {
+            return;
+        }
+        self.reseed_count += 1;
+        // reseed_count が 2^i で割り切れるプールだけを使う
+        let mut seed = Vec::new();
+        for pool in self.pools.iter_mut() {
+            let used = std::mem::take(pool);
+            seed.extend_from_slice(&sha256(&used.finalize()));
+            if !self.reseed_count.is_multiple_of(1 << (seed.len() / 32)) {
+                break;
+            }
+        }
+        self.generator.reseed(&seed);
+        self.pool0_len = 0;
+        self.last_reseed = Some(Instant::now());
+    }
+
+    pub fn random_data(&mut self, out: &mut [u8]) -> Result<(), FortunaError> {
+        self.maybe_reseed();
+        if self.generator.counter == 0 {
+            return Err(FortunaError::NotSeeded);
+        }
+        for chunk in out.chunks_mut(MAX_REQUEST) {
+            self.generator.pseudo_random_data(chunk);
+        }
+        Ok(())
+    }
+}
+
+impl RngCore for Fortuna {
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.random_data(dest).expect("Fortuna has not been seeded");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed(f: &mut Fortuna, events: usize) {
+        for i in 0..events {
+            f.add_entropy((i % 3) as u8, &[i as u8; 32]);
+        }
+    }
+
+    #[test]
+    fn test_requires_seed_then_generates() {
+        let mut f = Fortuna::with_reseed_interval(Duration::ZERO);
+        let mut out = [0u8; 32];
+        assert_eq!(f.random_data(&mut out), Err(FortunaError::NotSeeded));
+        // プール 0 に 64 バイト溜まるまでは再シードされない
+        f.add_entropy(0, &[1; 32]);
+        assert_eq!(f.random_data(&mut out), Err(FortunaError::NotSeeded));
+        feed(&mut f, 3 * NUM_POOLS);
+        assert!(f.random_data(&mut out).is_ok());
+        let mut next = [0u8; 32];
+        f.random_data(&mut next).unwrap();
+        assert_ne!(out, next);
+        // 同じイベント列からは同じ出力になる
+        let mut g = Fortuna::with_reseed_interval(Duration::ZERO);
+        g.add_entropy(0, &[1; 32]);
+        g.random_data(&mut [0u8; 32]).unwrap_err();
+        feed(&mut g, 3 * NUM_POOLS);
+        let mut again = [0u8; 32];
+        g.random_data(&mut again).unwrap();
+        assert_eq!(again, out);
+    }
+
+    #[test]
+    fn test_pool_schedule_and_rate_limit() {
+        let mut f = Fortuna::with_reseed_interval(Duration::ZERO);
+        let mut out = [0u8; 16];
+        for round in 1..=4 {
+            feed(&mut f, 3 * NUM_POOLS);
+            f.random_data(&mut out).unwrap();
+            assert_eq!(f.reseed_count(), round);
+        }
+        // 既定の間隔では続けざまに再シードしない
+        let mut slow = Fortuna::new();
+        feed(&mut slow, 3 * NUM_POOLS);
+        slow.random_data(&mut out).unwrap();
+        feed(&mut slow, 3 * NUM_POOLS);
+        slow.random_data(&mut out).unwrap();
+        assert_eq!(slow.reseed_count(), 1);
+    }
+}
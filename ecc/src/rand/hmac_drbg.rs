@@ -0,0 +1,170 @@
+// HMAC-DRBG (NIST SP 800-90A)。RFC 6979 の決定的 nonce もこの生成器をそのまま使う
+use super::{EntropySource, RngCore};
+use crate::bignum::BigUint;
+use crate::hash::Digest;
+use crate::mac::Hmac;
+
+// reseed なしで generate を呼べる回数の上限
+const RESEED_INTERVAL: u64 = 1 << 48;
+// 1 回の generate で出せる最大バイト数 (2^19 ビット)
+const MAX_REQUEST: usize = 1 << 16;
+
+#[derive(Clone)]
+pub struct HmacDrbg<D: Digest> {
+    k: Vec<u8>,
+    v: Vec<u8>,
+    reseed_counter: u64,
+    _digest: std::marker::PhantomData<D>,
+}
+
+fn hmac_parts<D: Digest>(key: &[u8], parts: &[&[u8]]) -> Vec<u8> {
+    let mut mac = Hmac::<D>::new(key);
+    for part in parts {
+        mac.update(part);
+    }
+    mac.finalize()
+}
+
+impl<D: Digest> HmacDrbg<D> {
+    pub fn instantiate(entropy: &[u8], nonce: &[u8], personalization: &[u8]) -> Self {
+        let mut drbg = HmacDrbg {
+            k: vec![0; D::OUTPUT_SIZE],
+            v: vec![1; D::OUTPUT_SIZE],
+            reseed_counter: 1,
+            _digest: std::marker::PhantomData,
+        };
+        drbg.update(&[entropy, nonce, personalization]);
+        drbg
+    }
+
+    // エントロピー源から 32 バイトの入力と 16 バイトの nonce を取って初期化する
+    pub fn from_entropy<E: EntropySource>(source: &mut E, personalization: &[u8]) -> Self {
+        let mut input = [0u8; 48];
+        source
+            .fill_entropy(&mut input)
+            .expect("failed to read from entropy source");
+        HmacDrbg::instantiate(&input[..32], &input[32..], personalization)
+    }
+
+    // 追加データがなければ 1 段だけ回す
+    fn update(&mut self, data: &[&[u8]]) {
+        let empty = data.iter().all(|d| d.is_empty());
+        for round in [0u8, 1] {
+            if round == 1 && empty {
+                break;
+            }
+            let round = [round];
+            let parts: Vec<&[u8]> = [&self.v[..], &round[..]]
+                .into_iter()
+                .chain(data.iter().copied())
+                .collect();
+            self.k = hmac_parts::<D>(&self.k, &parts);
+            self.v = hmac_parts::<D>(&self.k, &[&self.v]);
+        }
+    }
+
+    pub fn reseed(&mut self, entropy: &[u8], additional: &[u8]) {
+        self.update(&[entropy, additional]);
+        self.reseed_counter = 1;
+    }
+
+    pub fn generate(&mut self, out: &mut [u8], additional: &[u8]) {
+        if out.len() > MAX_REQUEST {
+            panic!("HMAC-DRBG request of {} bytes is too large", out.len());
+        }
+        if self.reseed_counter > RESEED_INTERVAL {
+            panic!("HMAC-DRBG must be reseeded");
+        }
+        if !additional.is_empty() {
+            self.update(&[additional]);
+        }
+        for chunk in out.chunks_mut(D::OUTPUT_SIZE) {
+            self.v = hmac_parts::<D>(&self.k, &[&self.v]);
+            chunk.copy_from_slice(&self.v[..chunk.len()]);
+        }
+        self.update(&[additional]);
+        self.reseed_counter += 1;
+    }
+}
+
+impl<D: Digest> RngCore for HmacDrbg<D> {
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(MAX_REQUEST) {
+            self.generate(chunk, &[]);
+        }
+    }
+}
+
+// RFC 6979 の bits2int: 先頭 qlen ビットを整数として読む
+fn bits2int(bytes: &[u8], qlen: usize) -> BigUint {
+    let x = BigUint::from_bytes_be(bytes);
+    let blen = 8 * bytes.len();
+    if blen > qlen {
+        x.shr(blen - qlen)
+    } else {
+        x
+    }
+}
+
+// RFC 6979 の決定的な nonce k。x は秘密鍵、q は群の位数、h1 はメッセージのハッシュ
+pub fn rfc6979_nonce<D: Digest>(x: &BigUint, q: &BigUint, h1: &[u8]) -> BigUint {
+    let qlen = q.bits();
+    let rlen = qlen.div_ceil(8);
+    let mut z = bits2int(h1, qlen);
+    if z >= *q {
+        z = z.sub(q);
+    }
+    let mut drbg = HmacDrbg::<D>::instantiate(
+        &x.to_bytes_be_padded(rlen),
+        &z.to_bytes_be_padded(rlen),
+        &[],
+    );
+    let mut t = vec![0u8; rlen];
+    loop {
+        drbg.generate(&mut t, &[]);
+        let k = bits2int(&t, qlen);
+        if !k.is_zero() && k < *q {
+            return k;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::{sha256, Sha256};
+    use crate::test_util::{hex, unhex};
+
+    #[test]
+    fn test_nist_cavp_sha256_no_reseed() {
+        let mut drbg = HmacDrbg::<Sha256>::instantiate(
+            &unhex("ca851911349384bffe89de1cbdc46e6831e44d34a4fb935ee285dd14b71a7488"),
+            &unhex("659ba96c601dc69fc902940805ec0ca8"),
+            &[],
+        );
+        // CAVP では 2 回目の generate の出力を比較する
+        let mut out = [0u8; 128];
+        drbg.generate(&mut out, &[]);
+        drbg.generate(&mut out, &[]);
+        assert_eq!(
+            hex(&out),
+            "e528e9abf2dece54d47c7e75e5fe302149f817ea9fb4bee6f4199697d04d5b89d54fbb978a15b5c443c9ec21036d2460b6f73ebad0dc2aba6e624abf07745bc107694bb7547bb0995f70de25d6b29e2d3011bb19d27676c07162c8b5ccde0668961df86803482cb37ed6d5c0bb8d50cf1f50d476aa0458bdaba806f48be9dcb8"
+        );
+        // reseed すると列が変わる
+        let mut a = drbg.clone();
+        a.reseed(b"more entropy", &[]);
+        assert_ne!(a.next_u64(), drbg.next_u64());
+    }
+
+    #[test]
+    fn test_rfc6979_appendix_a1() {
+        // 163 ビットの位数 q での詳細例 (A.1.2)
+        let q = BigUint::from_hex("4000000000000000000020108a2e0cc0d99f8a5ef").unwrap();
+        let x = BigUint::from_hex("09a4d6792295a7f730fc3f2b49cbc0f62e862272f").unwrap();
+        let k = rfc6979_nonce::<Sha256>(&x, &q, &sha256(b"sample"));
+        assert_eq!(
+            k,
+            BigUint::from_hex("23af4074c90a02b3fe61d286d5c87f425e6bdd81b").unwrap()
+        );
+    }
+}
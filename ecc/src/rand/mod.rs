@@ -0,0 +1,94 @@
+pub mod bbs;
+pub mod chacha;
+pub mod entropy;
+pub mod fortuna;
+pub mod hmac_drbg;
+pub mod test_rng;
+
+pub use bbs::Bbs;
+pub use chacha::{default_rng, ChaCha20Rng};
+pub use entropy::{EntropyRng, EntropySource, FixedEntropy, OsEntropy};
+pub use fortuna::{Fortuna, FortunaError};
+pub use hmac_drbg::{rfc6979_nonce, HmacDrbg};
+pub use test_rng::TestRng;
+
+pub trait RngCore {
+    fn fill_bytes(&mut self, dest: &mut [u8]);
+
+    fn next_u32(&mut self) -> u32 {
+        let mut buf = [0u8; 4];
+        self.fill_bytes(&mut buf);
+        u32::from_le_bytes(buf)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut buf = [0u8; 8];
+        self.fill_bytes(&mut buf);
+        u64::from_le_bytes(buf)
+    }
+}
+
+impl<R: RngCore + ?Sized> RngCore for &mut R {
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        (**self).fill_bytes(dest);
+    }
+}
+
+// [0, bound) の一様乱数。next_u64() % bound は小さい値に偏るので、
+// bound 以上の 2 冪でマスクし、範囲外は棄却してやり直す
+pub fn uniform_below<R: RngCore>(bound: u64, rng: &mut R) -> u64 {
+    if bound == 0 {
+        panic!("uniform_below needs a positive bound");
+    }
+    let mask = u64::MAX >> (bound - 1).leading_zeros().min(63);
+    loop {
+        let x = rng.next_u64() & mask;
+        if x < bound {
+            return x;
+        }
+    }
+}
+
+// OS の乱数源 (/dev/urandom) から読み出す
+pub struct OsRng;
+
+impl RngCore for OsRng {
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        OsEntropy
+            .fill_entropy(dest)
+            .expect("failed to read from /dev/urandom");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_os_rng_fills_buffer() {
+        let mut a = [0u8; 32];
+        let mut b = [0u8; 32];
+        OsRng.fill_bytes(&mut a);
+        OsRng.fill_bytes(&mut b);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_uniform_below_has_no_modulo_bias() {
+        // 1 バイトを 200 で割った余りでは 0..56 が 2 倍出やすい (期待値 0.4375)。
+        // 棄却サンプリングなら一様で 56 / 200 = 0.28
+        let trials = 20_000;
+        let mut rng = OsRng;
+        let naive = (0..trials)
+            .filter(|_| (rng.next_u32() as u8) % 200 < 56)
+            .count() as f64
+            / trials as f64;
+        let uniform = (0..trials)
+            .filter(|_| uniform_below(200, &mut rng) < 56)
+            .count() as f64
+            / trials as f64;
+        assert!((naive - 0.4375).abs() < 0.02, "naive {}", naive);
+        assert!((uniform - 0.28).abs() < 0.02, "uniform {}", uniform);
+        assert!((0..1000).all(|_| uniform_below(1, &mut rng) == 0));
+    }
+}
@@ -0,0 +1,134 @@
+// 教科書 RSA (パディングなし)
+use crate::bignum::BigUint;
+use crate::number_theory::{carmichael_lambda_from_factors, crt, generate_prime, mod_inverse_big};
+use crate::rand::RngCore;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RsaPublicKey {
+    pub n: BigUint,
+    pub e: BigUint,
+}
+
+#[derive(Debug, Clone)]
+pub struct RsaPrivateKey {
+    pub n: BigUint,
+    pub d: BigUint,
+    p: BigUint,
+    q: BigUint,
+    dp: BigUint,
+    dq: BigUint,
+}
+
+pub fn keygen<R: RngCore>(bits: usize, rng: &mut R) -> (RsaPublicKey, RsaPrivateKey) {
+    let e = BigUint::from_u64(65537);
+    loop {
+        let p = generate_prime(bits / 2, rng);
+        let q = generate_prime(bits - bits / 2, rng);
+        if p == q {
+            continue;
+        }
+        if let Some(sk) = RsaPrivateKey::from_primes(p, q, &e) {
+            if sk.n.bits() != bits {
+                continue;
+            }
+            let pk = RsaPublicKey {
+                n: sk.n.clone(),
+                e: e.clone(),
+            };
+            return (pk, sk);
+        }
+    }
+}
+
+impl RsaPublicKey {
+    pub fn encrypt(&self, m: &BigUint) -> BigUint {
+        if *m >= self.n {
+            panic!("Message {} is not smaller than modulus {}", m, self.n);
+        }
+        m.modpow(&self.e, &self.n)
+    }
+}
+
+impl RsaPrivateKey {
+    // e が φ(n) と互いに素でなければ None
+    pub fn from_primes(p: BigUint, q: BigUint, e: &BigUint) -> Option<Self> {
+        let one = BigUint::one();
+        let p1 = p.sub(&one);
+        let q1 = q.sub(&one);
+        let d = mod_inverse_big(e, &p1.mul(&q1))?;
+        Some(RsaPrivateKey {
+            n: p.mul(&q),
+            dp: d.rem(&p1),
+            dq: d.rem(&q1),
+            d,
+            p,
+            q,
+        })
+    }
+
+    pub fn primes(&self) -> (&BigUint, &BigUint) {
+        (&self.p, &self.q)
+    }
+
+    // n = pq かつ ed ≡ 1 (mod λ(n)) を確認する。d は φ(n) ではなく λ(n) を法として一意
+    pub fn validate(&self, pk: &RsaPublicKey) -> bool {
+        if self.p == self.q || self.p.mul(&self.q) != self.n || pk.n != self.n {
+            return false;
+        }
+        let lambda = carmichael_lambda_from_factors(&[(self.p.clone(), 1), (self.q.clone(), 1)]);
+        pk.e.mul(&self.d).rem(&lambda).is_one()
+    }
+
+    // CRT を使った復号: 半分のサイズの冪乗 2 回で済むので約 4 倍速い
+    pub fn decrypt(&self, c: &BigUint) -> BigUint {
+        if *c >= self.n {
+            panic!("Ciphertext {} is not smaller than modulus {}", c, self.n);
+        }
+        let m_p = c.rem(&self.p).modpow_ct(&self.dp, &self.p);
+        let m_q = c.rem(&self.q).modpow_ct(&self.dq, &self.q);
+        crt(&[(m_p, self.p.clone()), (m_q, self.q.clone())]).unwrap()
+    }
+
+    pub fn decrypt_without_crt(&self, c: &BigUint) -> BigUint {
+        if *c >= self.n {
+            panic!("Ciphertext {} is not smaller than modulus {}", c, self.n);
+        }
+        c.modpow_ct(&self.d, &self.n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::OsRng;
+
+    #[test]
+    fn test_rsa_roundtrip() {
+        let (pk, sk) = keygen(512, &mut OsRng);
+        assert_eq!(pk.n.bits(), 512);
+        let m = BigUint::from_bytes_be(b"attack at dawn");
+        let c = pk.encrypt(&m);
+        assert_eq!(sk.decrypt(&c), m);
+        let (p, q) = sk.primes();
+        assert_eq!(p.mul(q), pk.n);
+        assert!(sk.validate(&pk));
+        let wrong_e = RsaPublicKey {
+            n: pk.n.clone(),
+            e: BigUint::from_u64(3),
+        };
+        assert!(!sk.validate(&wrong_e));
+    }
+
+    #[test]
+    fn test_crt_decryption_matches_plain() {
+        // 教科書の小さな例: p = 61, q = 53, e = 17
+        let e = BigUint::from_u64(17);
+        let sk =
+            RsaPrivateKey::from_primes(BigUint::from_u64(61), BigUint::from_u64(53), &e).unwrap();
+        assert_eq!(sk.d, BigUint::from_u64(2753));
+        for c in [0u64, 1, 2790, 3232] {
+            let c = BigUint::from_u64(c);
+            assert_eq!(sk.decrypt(&c), sk.decrypt_without_crt(&c));
+        }
+    }
+}
@@ -0,0 +1,186 @@
+// ECDSA:  R = k·G,  r = R.x mod n,  s = k^{-1} (z + r·d) mod n  (z はメッセージのハッシュ)。
+// k が漏れるか 2 回使われると d が求まるので、k は RFC 6979 で (d, z) から HMAC-SHA256 で決定的に作る。
+// (r, s) と (r, n - s) はどちらも有効なので、Bitcoin の慣習に合わせて s ≤ n/2 (low-S) にそろえる。
+// 直列化は DER: 0x30 len 0x02 len r 0x02 len s (整数は最小長、最上位ビットが立つなら 0x00 を前置)
+use super::{n, Point, PublicKey, Secp256k1Error, SecretKey};
+use crate::bignum::BigUint;
+use crate::hash::Sha256;
+use crate::mac::hmac;
+use crate::number_theory::mod_inverse_big;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signature {
+    pub r: BigUint,
+    pub s: BigUint,
+}
+
+fn hmac256(key: &[u8], parts: &[&[u8]]) -> Vec<u8> {
+    hmac::<Sha256>(key, &parts.concat())
+}
+
+// RFC 6979 3.2 (qlen = hlen = 256 なので bits2octets は mod n だけ)
+fn nonce(secret: &SecretKey, z: &BigUint) -> BigUint {
+    let x = secret.to_bytes();
+    let h = z.rem(n()).to_bytes_be_padded(32);
+    let mut v = vec![0x01; 32];
+    let mut k = hmac256(&[0; 32], &[&v, &[0x00], &x, &h]);
+    v = hmac256(&k, &[&v]);
+    k = hmac256(&k, &[&v, &[0x01], &x, &h]);
+    v = hmac256(&k, &[&v]);
+    loop {
+        v = hmac256(&k, &[&v]);
+        let candidate = BigUint::from_bytes_be(&v);
+        if !candidate.is_zero() && &candidate < n() {
+            return candidate;
+        }
+        k = hmac256(&k, &[&v, &[0x00]]);
+        v = hmac256(&k, &[&v]);
+    }
+}
+
+pub fn sign(secret: &SecretKey, msg_hash: &[u8; 32]) -> Signature {
+    let z = BigUint::from_bytes_be(msg_hash);
+    let k = nonce(secret, &z);
+    let r = Point::mul_base(&k).x().unwrap().rem(n());
+    let k_inv = mod_inverse_big(&k, n()).unwrap();
+    let s = k_inv.mul(&z.add(&r.mul(secret.scalar()))).rem(n());
+    // 確率 2^-256 程度で起きるが、そのときは RFC 6979 の次の候補を使うべき
+    assert!(!r.is_zero() && !s.is_zero(), "Degenerate signature");
+    let signature = Signature { r, s };
+    signature.normalize_s()
+}
+
+pub fn verify(public: &PublicKey, msg_hash: &[u8; 32], sig: &Signature) -> bool {
+    let in_range = |v: &BigUint| !v.is_zero() && v < n();
+    if !in_range(&sig.r) || !in_range(&sig.s) {
+        return false;
+    }
+    let z = BigUint::from_bytes_be(msg_hash);
+    let w = mod_inverse_big(&sig.s, n()).unwrap();
+    let u1 = z.mul(&w).rem(n());
+    let u2 = sig.r.mul(&w).rem(n());
+    let point = Point::mul_base(&u1).add(&public.0.mul(&u2));
+    point.x().is_some_and(|x| x.rem(n()) == sig.r)
+}
+
+fn der_integer(v: &BigUint) -> Vec<u8> {
+    let mut bytes = v.to_bytes_be();
+    if bytes.is_empty() || bytes[0] & 0x80 != 0 {
+        bytes.insert(0, 0);
+    }
+    let mut out = vec![0x02, bytes.len() as u8];
+    out.extend(bytes);
+    out
+}
+
+// 先頭の INTEGER を読んで (値, 残り) を返す
+fn parse_der_integer(data: &[u8]) -> Result<(BigUint, &[u8]), Secp256k1Error> {
+    let [0x02, len, rest @ ..] = data else {
+        return Err(Secp256k1Error::InvalidEncoding);
+    };
+    let len = *len as usize;
+    if len == 0 || len > 33 || rest.len() < len {
+        return Err(Secp256k1Error::InvalidEncoding);
+    }
+    let (bytes, rest) = rest.split_at(len);
+    // 負の数と、必要のない 0x00 の前置は受け付けない
+    let negative = bytes[0] & 0x80 != 0;
+    let padded = len > 1 && bytes[0] == 0 && bytes[1] & 0x80 == 0;
+    if negative || padded {
+        return Err(Secp256k1Error::InvalidEncoding);
+    }
+    Ok((BigUint::from_bytes_be(bytes), rest))
+}
+
+impl Signature {
+    pub fn is_low_s(&self) -> bool {
+        self.s <= n().shr(1)
+    }
+
+    pub fn normalize_s(self) -> Self {
+        if self.is_low_s() {
+            self
+        } else {
+            Signature {
+                s: n().sub(&self.s),
+                r: self.r,
+            }
+        }
+    }
+
+    pub fn to_der(&self) -> Vec<u8> {
+        let body = [der_integer(&self.r), der_integer(&self.s)].concat();
+        let mut out = vec![0x30, body.len() as u8];
+        out.extend(body);
+        out
+    }
+
+    pub fn from_der(der: &[u8]) -> Result<Self, Secp256k1Error> {
+        let [0x30, len, body @ ..] = der else {
+            return Err(Secp256k1Error::InvalidEncoding);
+        };
+        if *len as usize != body.len() {
+            return Err(Secp256k1Error::InvalidEncoding);
+        }
+        let (r, rest) = parse_der_integer(body)?;
+        let (s, rest) = parse_der_integer(rest)?;
+        if !rest.is_empty() {
+            return Err(Secp256k1Error::InvalidEncoding);
+        }
+        Ok(Signature { r, s })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::sha256;
+    use crate::test_util::{hex, unhex};
+
+    #[test]
+    fn test_rfc6979_vector() {
+        let secret = SecretKey::from_scalar(BigUint::one()).unwrap();
+        let digest = sha256(b"Satoshi Nakamoto");
+        let sig = sign(&secret, &digest);
+        assert_eq!(
+            sig.r.to_hex(),
+            "934b1ea10a4b3c1757e2b0c017d0b6143ce3c9a7e6a4a49860d7a6ab210ee3d8"
+        );
+        assert_eq!(
+            sig.s.to_hex(),
+            "2442ce9d2b916064108014783e923ec36b49743e2ffa1c4496f01a512aafd9e5"
+        );
+        assert!(verify(&secret.public_key(), &digest, &sig));
+        // 高い s も ECDSA としては通る
+        let high = Signature {
+            r: sig.r.clone(),
+            s: n().sub(&sig.s),
+        };
+        assert!(!high.is_low_s());
+        assert!(verify(&secret.public_key(), &digest, &high));
+        assert!(!verify(&secret.public_key(), &sha256(b"Satoshi"), &sig));
+        let other = SecretKey::from_bytes(&[2; 32]).unwrap().public_key();
+        assert!(!verify(&other, &digest, &sig));
+    }
+
+    #[test]
+    fn test_der_encoding() {
+        let sig = sign(&SecretKey::from_scalar(BigUint::one()).unwrap(), &[0; 32]);
+        assert_eq!(Signature::from_der(&sig.to_der()), Ok(sig.clone()));
+        // r の最上位ビットが立つので 0x00 が前置される
+        let der = unhex("3045022100934b1ea10a4b3c1757e2b0c017d0b6143ce3c9a7e6a4a49860d7a6ab210ee3d802202442ce9d2b916064108014783e923ec36b49743e2ffa1c4496f01a512aafd9e5");
+        let parsed = Signature::from_der(&der).unwrap();
+        assert_eq!(hex(&parsed.to_der()), hex(&der));
+        // 長さの不一致、余計な 0x00、負の整数
+        let mut bad_len = der.clone();
+        bad_len[1] = 0x46;
+        let padded = unhex("3008020300007f020101");
+        let negative = unhex("30060201ff020101");
+        for bad in [&bad_len[..], &padded, &negative, &der[..10]] {
+            assert_eq!(
+                Signature::from_der(bad),
+                Err(Secp256k1Error::InvalidEncoding)
+            );
+        }
+    }
+}
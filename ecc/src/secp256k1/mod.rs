@@ -0,0 +1,404 @@
+// secp256k1 (y^2 = x^3 + 7 over F_p, p = 2^256 - 2^32 - 977)。Bitcoin が使う曲線で、
+// クレートのトイ曲線と違って BigUint で計算する。スカラー倍は Jacobian 座標
+// (x, y) = (X/Z^2, Y/Z^3) で行い、逆元は最後に 1 回だけ取る。定数時間ではないので学習用
+pub mod ecdsa;
+pub mod schnorr;
+
+use crate::bignum::BigUint;
+use crate::number_theory::mod_inverse_big;
+use crate::rand::RngCore;
+use std::sync::OnceLock;
+
+const P_HEX: &str = "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f";
+const N_HEX: &str = "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141";
+const GX_HEX: &str = "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+const GY_HEX: &str = "483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Secp256k1Error {
+    InvalidEncoding,
+    NotOnCurve,
+    // 秘密鍵が 0 または位数以上
+    InvalidSecretKey,
+}
+
+// 体の標数
+pub fn p() -> &'static BigUint {
+    static P: OnceLock<BigUint> = OnceLock::new();
+    P.get_or_init(|| BigUint::from_hex(P_HEX).unwrap())
+}
+
+// 生成元の位数
+pub fn n() -> &'static BigUint {
+    static N: OnceLock<BigUint> = OnceLock::new();
+    N.get_or_init(|| BigUint::from_hex(N_HEX).unwrap())
+}
+
+fn fadd(a: &BigUint, b: &BigUint) -> BigUint {
+    let s = a.add(b);
+    if &s >= p() {
+        s.sub(p())
+    } else {
+        s
+    }
+}
+
+fn fsub(a: &BigUint, b: &BigUint) -> BigUint {
+    if a >= b {
+        a.sub(b)
+    } else {
+        a.add(p()).sub(b)
+    }
+}
+
+fn fmul(a: &BigUint, b: &BigUint) -> BigUint {
+    a.mul(b).rem(p())
+}
+
+fn fsmall(k: u64, a: &BigUint) -> BigUint {
+    fmul(&BigUint::from_u64(k), a)
+}
+
+// p ≡ 3 (mod 4) なので平方根は a^{(p+1)/4}
+fn fsqrt(a: &BigUint) -> Option<BigUint> {
+    let exponent = p().add(&BigUint::one()).shr(2);
+    let r = a.modpow(&exponent, p());
+    (fmul(&r, &r) == a.rem(p())).then_some(r)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Point {
+    Infinity,
+    Affine { x: BigUint, y: BigUint },
+}
+
+// Z = 0 が無限遠点
+#[derive(Debug, Clone)]
+struct Jacobian {
+    x: BigUint,
+    y: BigUint,
+    z: BigUint,
+}
+
+impl Jacobian {
+    fn infinity() -> Self {
+        Jacobian {
+            x: BigUint::one(),
+            y: BigUint::one(),
+            z: BigUint::zero(),
+        }
+    }
+
+    fn from_affine(point: &Point) -> Self {
+        match point {
+            Point::Infinity => Jacobian::infinity(),
+            Point::Affine { x, y } => Jacobian {
+                x: x.clone(),
+                y: y.clone(),
+                z: BigUint::one(),
+            },
+        }
+    }
+
+    fn to_affine(&self) -> Point {
+        if self.z.is_zero() {
+            return Point::Infinity;
+        }
+        let zinv = mod_inverse_big(&self.z, p()).unwrap();
+        let zinv2 = fmul(&zinv, &zinv);
+        Point::Affine {
+            x: fmul(&self.x, &zinv2),
+            y: fmul(&self.y, &fmul(&zinv2, &zinv)),
+        }
+    }
+
+    // a = 0 の倍算
+    fn double(&self) -> Self {
+        if self.z.is_zero() || self.y.is_zero() {
+            return Jacobian::infinity();
+        }
+        let yy = fmul(&self.y, &self.y);
+        let s = fsmall(4, &fmul(&self.x, &yy));
+        let m = fsmall(3, &fmul(&self.x, &self.x));
+        let x = fsub(&fmul(&m, &m), &fadd(&s, &s));
+        let y = fsub(&fmul(&m, &fsub(&s, &x)), &fsmall(8, &fmul(&yy, &yy)));
+        let z = fsmall(2, &fmul(&self.y, &self.z));
+        Jacobian { x, y, z }
+    }
+
+    fn add(&self, other: &Jacobian) -> Self {
+        if self.z.is_zero() {
+            return other.clone();
+        }
+        if other.z.is_zero() {
+            return self.clone();
+        }
+        let z1z1 = fmul(&self.z, &self.z);
+        let z2z2 = fmul(&other.z, &other.z);
+        let u1 = fmul(&self.x, &z2z2);
+        let u2 = fmul(&other.x, &z1z1);
+        let s1 = fmul(&self.y, &fmul(&z2z2, &other.z));
+        let s2 = fmul(&other.y, &fmul(&z1z1, &self.z));
+        if u1 == u2 {
+            return if s1 == s2 {
+                self.double()
+            } else {
+                Jacobian::infinity()
+            };
+        }
+        let h = fsub(&u2, &u1);
+        let r = fsub(&s2, &s1);
+        let hh = fmul(&h, &h);
+        let hhh = fmul(&hh, &h);
+        let u1hh = fmul(&u1, &hh);
+        let x = fsub(&fsub(&fmul(&r, &r), &hhh), &fadd(&u1hh, &u1hh));
+        let y = fsub(&fmul(&r, &fsub(&u1hh, &x)), &fmul(&s1, &hhh));
+        let z = fmul(&h, &fmul(&self.z, &other.z));
+        Jacobian { x, y, z }
+    }
+}
+
+impl Point {
+    pub fn generator() -> Self {
+        static G: OnceLock<Point> = OnceLock::new();
+        G.get_or_init(|| Point::Affine {
+            x: BigUint::from_hex(GX_HEX).unwrap(),
+            y: BigUint::from_hex(GY_HEX).unwrap(),
+        })
+        .clone()
+    }
+
+    pub fn is_infinity(&self) -> bool {
+        matches!(self, Point::Infinity)
+    }
+
+    pub fn x(&self) -> Option<&BigUint> {
+        match self {
+            Point::Infinity => None,
+            Point::Affine { x, .. } => Some(x),
+        }
+    }
+
+    pub fn y(&self) -> Option<&BigUint> {
+        match self {
+            Point::Infinity => None,
+            Point::Affine { y, .. } => Some(y),
+        }
+    }
+
+    pub fn has_even_y(&self) -> bool {
+        self.y().is_some_and(|y| y.is_even())
+    }
+
+    pub fn is_on_curve(&self) -> bool {
+        match self {
+            Point::Infinity => true,
+            Point::Affine { x, y } => {
+                x < p()
+                    && y < p()
+                    && fmul(y, y) == fadd(&fmul(&fmul(x, x), x), &BigUint::from_u64(7))
+            }
+        }
+    }
+
+    // x 座標から y が偶数の点を復元する (BIP-340 の lift_x)
+    pub fn lift_x(x: &BigUint) -> Option<Self> {
+        if x >= p() {
+            return None;
+        }
+        let c = fadd(&fmul(&fmul(x, x), x), &BigUint::from_u64(7));
+        let y = fsqrt(&c)?;
+        let y = if y.is_even() { y } else { p().sub(&y) };
+        Some(Point::Affine { x: x.clone(), y })
+    }
+
+    pub fn neg(&self) -> Self {
+        match self {
+            Point::Infinity => Point::Infinity,
+            Point::Affine { x, y } => Point::Affine {
+                x: x.clone(),
+                y: fsub(&BigUint::zero(), y),
+            },
+        }
+    }
+
+    pub fn add(&self, other: &Point) -> Self {
+        Jacobian::from_affine(self)
+            .add(&Jacobian::from_affine(other))
+            .to_affine()
+    }
+
+    pub fn mul(&self, k: &BigUint) -> Self {
+        let base = Jacobian::from_affine(self);
+        let mut acc = Jacobian::infinity();
+        for i in (0..k.bits()).rev() {
+            acc = acc.double();
+            if k.bit(i) {
+                acc = acc.add(&base);
+            }
+        }
+        acc.to_affine()
+    }
+
+    pub fn mul_base(k: &BigUint) -> Self {
+        Point::generator().mul(k)
+    }
+
+    // SEC1 圧縮形式: 0x02 / 0x03 (y の偶奇) || x
+    pub fn to_compressed(&self) -> [u8; 33] {
+        let Point::Affine { x, y } = self else {
+            panic!("Cannot serialize the point at infinity");
+        };
+        let mut out = [0u8; 33];
+        out[0] = if y.is_even() { 0x02 } else { 0x03 };
+        out[1..].copy_from_slice(&x.to_bytes_be_padded(32));
+        out
+    }
+
+    // SEC1 非圧縮形式: 0x04 || x || y
+    pub fn to_uncompressed(&self) -> [u8; 65] {
+        let Point::Affine { x, y } = self else {
+            panic!("Cannot serialize the point at infinity");
+        };
+        let mut out = [0u8; 65];
+        out[0] = 0x04;
+        out[1..33].copy_from_slice(&x.to_bytes_be_padded(32));
+        out[33..].copy_from_slice(&y.to_bytes_be_padded(32));
+        out
+    }
+
+    pub fn from_sec1(bytes: &[u8]) -> Result<Self, Secp256k1Error> {
+        let point = match (bytes.first(), bytes.len()) {
+            (Some(&tag @ (0x02 | 0x03)), 33) => {
+                let lifted = Point::lift_x(&BigUint::from_bytes_be(&bytes[1..]))
+                    .ok_or(Secp256k1Error::NotOnCurve)?;
+                if (tag == 0x03) == lifted.has_even_y() {
+                    lifted.neg()
+                } else {
+                    lifted
+                }
+            }
+            (Some(0x04), 65) => Point::Affine {
+                x: BigUint::from_bytes_be(&bytes[1..33]),
+                y: BigUint::from_bytes_be(&bytes[33..]),
+            },
+            _ => return Err(Secp256k1Error::InvalidEncoding),
+        };
+        if !point.is_on_curve() {
+            return Err(Secp256k1Error::NotOnCurve);
+        }
+        Ok(point)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct SecretKey(BigUint);
+
+// 秘密鍵を Debug で表示しない
+impl std::fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "SecretKey(..)")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicKey(pub Point);
+
+impl SecretKey {
+    pub fn from_scalar(k: BigUint) -> Result<Self, Secp256k1Error> {
+        if k.is_zero() || &k >= n() {
+            return Err(Secp256k1Error::InvalidSecretKey);
+        }
+        Ok(SecretKey(k))
+    }
+
+    pub fn from_bytes(bytes: &[u8; 32]) -> Result<Self, Secp256k1Error> {
+        SecretKey::from_scalar(BigUint::from_bytes_be(bytes))
+    }
+
+    pub fn random<R: RngCore>(rng: &mut R) -> Self {
+        let k = BigUint::random_below(&n().sub(&BigUint::one()), rng).add(&BigUint::one());
+        SecretKey(k)
+    }
+
+    pub fn scalar(&self) -> &BigUint {
+        &self.0
+    }
+
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.0.to_bytes_be_padded(32).try_into().unwrap()
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey(Point::mul_base(&self.0))
+    }
+}
+
+impl PublicKey {
+    pub fn from_sec1(bytes: &[u8]) -> Result<Self, Secp256k1Error> {
+        match Point::from_sec1(bytes)? {
+            Point::Infinity => Err(Secp256k1Error::InvalidEncoding),
+            point => Ok(PublicKey(point)),
+        }
+    }
+
+    pub fn to_compressed(&self) -> [u8; 33] {
+        self.0.to_compressed()
+    }
+
+    pub fn to_uncompressed(&self) -> [u8; 65] {
+        self.0.to_uncompressed()
+    }
+
+    // BIP-340 の x-only 公開鍵
+    pub fn x_only(&self) -> [u8; 32] {
+        self.to_compressed()[1..].try_into().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::hex;
+
+    #[test]
+    fn test_group_law() {
+        let g = Point::generator();
+        assert!(g.is_on_curve());
+        let two_g = g.add(&g);
+        assert_eq!(
+            two_g.x().unwrap().to_hex(),
+            "c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5"
+        );
+        assert_eq!(two_g, Point::mul_base(&BigUint::from_u64(2)));
+        let three_g = Point::mul_base(&BigUint::from_u64(3));
+        assert_eq!(two_g.add(&g), three_g);
+        assert_eq!(three_g.add(&three_g.neg()), Point::Infinity);
+        // 位数倍で無限遠点に戻る
+        assert!(g.mul(n()).is_infinity());
+        assert_eq!(g.mul(&n().sub(&BigUint::one())), g.neg());
+    }
+
+    #[test]
+    fn test_key_serialization() {
+        let sk = SecretKey::from_scalar(BigUint::one()).unwrap();
+        let pk = sk.public_key();
+        assert_eq!(
+            hex(&pk.to_compressed()),
+            "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"
+        );
+        assert_eq!(PublicKey::from_sec1(&pk.to_compressed()), Ok(pk.clone()));
+        assert_eq!(PublicKey::from_sec1(&pk.to_uncompressed()), Ok(pk.clone()));
+        // y が奇数の点も圧縮から戻る
+        let odd = PublicKey(pk.0.neg());
+        assert_eq!(odd.to_compressed()[0], 0x03);
+        assert_eq!(PublicKey::from_sec1(&odd.to_compressed()), Ok(odd));
+        let mut bad = pk.to_uncompressed();
+        bad[64] ^= 1;
+        assert_eq!(PublicKey::from_sec1(&bad), Err(Secp256k1Error::NotOnCurve));
+        assert_eq!(
+            SecretKey::from_bytes(&n().to_bytes_be_padded(32).try_into().unwrap()),
+            Err(Secp256k1Error::InvalidSecretKey)
+        );
+    }
+}
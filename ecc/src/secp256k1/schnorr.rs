@@ -0,0 +1,122 @@
+// BIP-340 Schnorr 署名。公開鍵は x 座標 32 バイトだけで、y が偶数の点を指すと決めておく
+// (秘密鍵 d の d·G が奇数なら n - d で署名する)。ナンスも R の y が偶数になるように取り、
+//   e = H_challenge(R.x || P.x || m),  s = k + e·d,  署名 = R.x || s
+// 検証は s·G - e·P の x が R.x に一致し y が偶数かを見る。
+// ナンスは秘密鍵と補助乱数 aux から決定的に作るので、aux が偏っても鍵は漏れない
+use super::{n, p, Point, SecretKey};
+use crate::bignum::BigUint;
+use crate::hash::sha256;
+
+// SHA256(SHA256(tag) || SHA256(tag) || data)。用途ごとにハッシュを分ける
+pub fn tagged_hash(tag: &str, data: &[&[u8]]) -> [u8; 32] {
+    let tag_hash = sha256(tag.as_bytes());
+    let mut input = [tag_hash, tag_hash].concat();
+    for part in data {
+        input.extend_from_slice(part);
+    }
+    sha256(&input)
+}
+
+fn bytes32(x: &BigUint) -> [u8; 32] {
+    x.to_bytes_be_padded(32).try_into().unwrap()
+}
+
+fn challenge(r: &[u8], px: &[u8], msg: &[u8]) -> BigUint {
+    BigUint::from_bytes_be(&tagged_hash("BIP0340/challenge", &[r, px, msg])).rem(n())
+}
+
+pub fn sign(secret: &SecretKey, msg: &[u8], aux_rand: &[u8; 32]) -> [u8; 64] {
+    let public = secret.public_key().0;
+    let d = if public.has_even_y() {
+        secret.scalar().clone()
+    } else {
+        n().sub(secret.scalar())
+    };
+    let px = bytes32(public.x().unwrap());
+    let mask = tagged_hash("BIP0340/aux", &[aux_rand]);
+    let t: Vec<u8> = bytes32(&d).iter().zip(mask).map(|(a, b)| a ^ b).collect();
+    let k0 = BigUint::from_bytes_be(&tagged_hash("BIP0340/nonce", &[&t, &px, msg])).rem(n());
+    assert!(!k0.is_zero(), "Nonce is zero");
+    let r = Point::mul_base(&k0);
+    let k = if r.has_even_y() { k0 } else { n().sub(&k0) };
+    let rx = bytes32(r.x().unwrap());
+    let e = challenge(&rx, &px, msg);
+    let s = k.add(&e.mul(&d)).rem(n());
+    let mut sig = [0u8; 64];
+    sig[..32].copy_from_slice(&rx);
+    sig[32..].copy_from_slice(&bytes32(&s));
+    sig
+}
+
+pub fn verify(public_key: &[u8; 32], msg: &[u8], sig: &[u8; 64]) -> bool {
+    let Some(public) = Point::lift_x(&BigUint::from_bytes_be(public_key)) else {
+        return false;
+    };
+    let r = BigUint::from_bytes_be(&sig[..32]);
+    let s = BigUint::from_bytes_be(&sig[32..]);
+    if &r >= p() || &s >= n() {
+        return false;
+    }
+    let e = challenge(&sig[..32], public_key, msg);
+    // s·G - e·P = s·G + (n - e)·P
+    let point = Point::mul_base(&s).add(&public.mul(&n().sub(&e)));
+    point.has_even_y() && point.x() == Some(&r)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{hex, unhex};
+
+    fn array<const L: usize>(s: &str) -> [u8; L] {
+        unhex(s).try_into().unwrap()
+    }
+
+    #[test]
+    fn test_bip340_vectors() {
+        // (秘密鍵, 公開鍵, aux, メッセージ, 署名)
+        let vectors = [
+            (
+                "0000000000000000000000000000000000000000000000000000000000000003",
+                "f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9",
+                "0000000000000000000000000000000000000000000000000000000000000000",
+                "0000000000000000000000000000000000000000000000000000000000000000",
+                "e907831f80848d1069a5371b402410364bdf1c5f8307b0084c55f1ce2dca821525f66a4a85ea8b71e482a74f382d2ce5ebeee8fdb2172f477df4900d310536c0",
+            ),
+            (
+                "b7e151628aed2a6abf7158809cf4f3c762e7160f38b4da56a784d9045190cfef",
+                "dff1d77f2a671c5f36183726db2341be58feae1da2deced843240f7b502ba659",
+                "0000000000000000000000000000000000000000000000000000000000000001",
+                "243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c89",
+                "6896bd60eeae296db48a229ff71dfe071bde413e6d43f917dc8dcf8c78de33418906d11ac976abccb20b091292bff4ea897efcb639ea871cfa95f6de339e4b0a",
+            ),
+        ];
+        for (secret, public, aux, msg, sig) in vectors {
+            let secret = SecretKey::from_bytes(&array(secret)).unwrap();
+            assert_eq!(hex(&secret.public_key().x_only()), public);
+            let signature = sign(&secret, &unhex(msg), &array(aux));
+            assert_eq!(hex(&signature), sig);
+            assert!(verify(&array(public), &unhex(msg), &signature));
+        }
+    }
+
+    #[test]
+    fn test_verify_rejects_tampering() {
+        let secret = SecretKey::from_bytes(&[7; 32]).unwrap();
+        let public = secret.public_key().x_only();
+        let sig = sign(&secret, b"message", &[0; 32]);
+        assert!(verify(&public, b"message", &sig));
+        assert!(!verify(&public, b"massage", &sig));
+        let mut bad = sig;
+        bad[63] ^= 1;
+        assert!(!verify(&public, b"message", &bad));
+        // 曲線上にない x の公開鍵
+        let mut off_curve = [0u8; 32];
+        off_curve[31] = 5;
+        assert!(!verify(&off_curve, b"message", &sig));
+        // s ≥ n
+        let mut overflow = sig;
+        overflow[32..].copy_from_slice(&[0xff; 32]);
+        assert!(!verify(&public, b"message", &overflow));
+    }
+}
@@ -0,0 +1,493 @@
+// Σ プロトコルの合成。AND は同じチャレンジに両方で答え、OR (Cramer–Damgård–Schoenmakers) は
+// 知らない側をシミュレートしてチャレンジを c = c_L + c_R と分ける。
+// 検証者にはどちらの witness を知っているのか分からない
+use super::SigmaProtocol;
+use crate::curve::Scalar;
+use crate::rand::RngCore;
+use crate::transcript::Transcript;
+
+#[derive(Debug, Clone)]
+pub struct And<A, B>(pub A, pub B);
+
+impl<A: SigmaProtocol, B: SigmaProtocol> SigmaProtocol for And<A, B> {
+    type Witness = (A::Witness, B::Witness);
+    type Commitment = (A::Commitment, B::Commitment);
+    type Response = (A::Response, B::Response);
+    type State = (A::State, B::State);
+
+    fn commit<R: RngCore>(
+        &self,
+        witness: &Self::Witness,
+        rng: &mut R,
+    ) -> (Self::State, Self::Commitment) {
+        let (sa, ca) = self.0.commit(&witness.0, rng);
+        let (sb, cb) = self.1.commit(&witness.1, rng);
+        ((sa, sb), (ca, cb))
+    }
+
+    fn respond(
+        &self,
+        witness: &Self::Witness,
+        state: Self::State,
+        challenge: &Scalar,
+    ) -> Self::Response {
+        (
+            self.0.respond(&witness.0, state.0, challenge),
+            self.1.respond(&witness.1, state.1, challenge),
+        )
+    }
+
+    fn verify(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &Scalar,
+        response: &Self::Response,
+    ) -> bool {
+        self.0.verify(&commitment.0, challenge, &response.0)
+            && self.1.verify(&commitment.1, challenge, &response.1)
+    }
+
+    fn simulate<R: RngCore>(
+        &self,
+        challenge: &Scalar,
+        rng: &mut R,
+    ) -> (Self::Commitment, Self::Response) {
+        let (ca, ra) = self.0.simulate(challenge, rng);
+        let (cb, rb) = self.1.simulate(challenge, rng);
+        ((ca, cb), (ra, rb))
+    }
+
+    fn absorb(&self, transcript: &mut Transcript, commitment: &Self::Commitment) {
+        transcript.append_message(b"dom-sep", b"and");
+        self.0.absorb(transcript, &commitment.0);
+        self.1.absorb(transcript, &commitment.1);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Or<A, B>(pub A, pub B);
+
+// OR の証明者はどちらか一方の witness だけを持つ
+#[derive(Debug, Clone)]
+pub enum OrWitness<WA, WB> {
+    Left(WA),
+    Right(WB),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrResponse<RA, RB> {
+    // 左側のチャレンジ。右側は c - c_left
+    pub left_challenge: Scalar,
+    pub left: RA,
+    pub right: RB,
+}
+
+pub enum OrState<A: SigmaProtocol, B: SigmaProtocol> {
+    // 左を本当に証明し、右はシミュレート済み
+    Left(A::State, Scalar, B::Response),
+    Right(B::State, Scalar, A::Response),
+}
+
+impl<A: SigmaProtocol, B: SigmaProtocol> SigmaProtocol for Or<A, B> {
+    type Witness = OrWitness<A::Witness, B::Witness>;
+    type Commitment = (A::Commitment, B::Commitment);
+    type Response = OrResponse<A::Response, B::Response>;
+    type State = OrState<A, B>;
+
+    fn commit<R: RngCore>(
+        &self,
+        witness: &Self::Witness,
+        rng: &mut R,
+    ) -> (Self::State, Self::Commitment) {
+        let simulated = Scalar::random(rng);
+        match witness {
+            OrWitness::Left(w) => {
+                let (state, ca) = self.0.commit(w, rng);
+                let (cb, rb) = self.1.simulate(&simulated, rng);
+                (OrState::Left(state, simulated, rb), (ca, cb))
+            }
+            OrWitness::Right(w) => {
+                let (state, cb) = self.1.commit(w, rng);
+                let (ca, ra) = self.0.simulate(&simulated, rng);
+                (OrState::Right(state, simulated, ra), (ca, cb))
+            }
+        }
+    }
+
+    fn respond(
+        &self,
+        witness: &Self::Witness,
+        state: Self::State,
+        challenge: &Scalar,
+    ) -> Self::Response {
+        match (witness, state) {
+            (OrWitness::Left(w), OrState::Left(state, c_right, right)) => {
+                let c_left = challenge.sub(&c_right);
+                OrResponse {
+                    left_challenge: c_left,
+                    left: self.0.respond(w, state, &c_left),
+                    right,
+                }
+            }
+            (OrWitness::Right(w), OrState::Right(state, c_left, left)) => OrResponse {
+                left_challenge: c_left,
+                left,
+                right: self.1.respond(w, state, &challenge.sub(&c_left)),
+            },
+            _ => panic!("OR witness does not match the committed branch"),
+        }
+    }
+
+    fn verify(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &Scalar,
+        response: &Self::Response,
+    ) -> bool {
+        let c_right = challenge.sub(&response.left_challenge);
+        self.0
+            .verify(&commitment.0, &response.left_challenge, &response.left)
+            && self.1.verify(&commitment.1, &c_right, &response.right)
+    }
+
+    fn simulate<R: RngCore>(
+        &self,
+        challenge: &Scalar,
+        rng: &mut R,
+    ) -> (Self::Commitment, Self::Response) {
+        let c_left = Scalar::random(rng);
+        let (ca, ra) = self.0.simulate(&c_left, rng);
+        let (cb, rb) = self.1.simulate(&challenge.sub(&c_left), rng);
+        (
+            (ca, cb),
+            OrResponse {
+                left_challenge: c_left,
+                left: ra,
+                right: rb,
+            },
+        )
+    }
+
+    fn absorb(&self, transcript: &mut Transcript, commitment: &Self::Commitment) {
+        transcript.append_message(b"dom-sep", b"or");
+        self.0.absorb(transcript, &commitment.0);
+        self.1.absorb(transcript, &commitment.1);
+    }
+}
+
+// n 個の主張のどれか 1 つ。OR と同じくチャレンジを c = Σ c_j と分け、知っている枝以外は
+// すべてシミュレートする
+#[derive(Debug, Clone)]
+pub struct AnyOf<S>(pub Vec<S>);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnyOfResponse<R> {
+    pub challenges: Vec<Scalar>,
+    pub responses: Vec<R>,
+}
+
+pub struct AnyOfState<S: SigmaProtocol> {
+    state: S::State,
+    simulated: Vec<Option<(Scalar, S::Response)>>,
+}
+
+impl<S: SigmaProtocol> SigmaProtocol for AnyOf<S> {
+    // 知っている枝の番号とその witness
+    type Witness = (usize, S::Witness);
+    type Commitment = Vec<S::Commitment>;
+    type Response = AnyOfResponse<S::Response>;
+    type State = AnyOfState<S>;
+
+    fn commit<R: RngCore>(
+        &self,
+        witness: &Self::Witness,
+        rng: &mut R,
+    ) -> (Self::State, Self::Commitment) {
+        let (index, w) = witness;
+        if *index >= self.0.len() {
+            panic!(
+                "Branch {} out of range for {} statements",
+                index,
+                self.0.len()
+            );
+        }
+        let mut state = None;
+        let mut simulated = Vec::with_capacity(self.0.len());
+        let mut commitments = Vec::with_capacity(self.0.len());
+        for (j, statement) in self.0.iter().enumerate() {
+            if j == *index {
+                let (st, t) = statement.commit(w, rng);
+                state = Some(st);
+                simulated.push(None);
+                commitments.push(t);
+            } else {
+                let c = Scalar::random(rng);
+                let (t, r) = statement.simulate(&c, rng);
+                simulated.push(Some((c, r)));
+                commitments.push(t);
+            }
+        }
+        let state = AnyOfState {
+            state: state.unwrap(),
+            simulated,
+        };
+        (state, commitments)
+    }
+
+    fn respond(
+        &self,
+        witness: &Self::Witness,
+        state: Self::State,
+        challenge: &Scalar,
+    ) -> Self::Response {
+        let (index, w) = witness;
+        let c_real = state
+            .simulated
+            .iter()
+            .flatten()
+            .fold(*challenge, |acc, (c, _)| acc.sub(c));
+        let mut real = Some(self.0[*index].respond(w, state.state, &c_real));
+        let (challenges, responses) = state
+            .simulated
+            .into_iter()
+            .map(|branch| match branch {
+                Some(simulated) => simulated,
+                None => (
+                    c_real,
+                    real.take()
+                        .expect("AnyOf witness does not match the committed branch"),
+                ),
+            })
+            .unzip();
+        AnyOfResponse {
+            challenges,
+            responses,
+        }
+    }
+
+    fn verify(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &Scalar,
+        response: &Self::Response,
+    ) -> bool {
+        let n = self.0.len();
+        if n == 0
+            || commitment.len() != n
+            || response.challenges.len() != n
+            || response.responses.len() != n
+        {
+            return false;
+        }
+        let sum = response
+            .challenges
+            .iter()
+            .fold(Scalar::new(0), |acc, c| acc.add(c));
+        sum == *challenge
+            && self
+                .0
+                .iter()
+                .zip(commitment)
+                .zip(response.challenges.iter().zip(&response.responses))
+                .all(|((s, t), (c, r))| s.verify(t, c, r))
+    }
+
+    fn simulate<R: RngCore>(
+        &self,
+        challenge: &Scalar,
+        rng: &mut R,
+    ) -> (Self::Commitment, Self::Response) {
+        let n = self.0.len();
+        let mut challenges: Vec<Scalar> = (1..n).map(|_| Scalar::random(rng)).collect();
+        let last = challenges.iter().fold(*challenge, |acc, c| acc.sub(c));
+        challenges.push(last);
+        let (commitments, responses) = self
+            .0
+            .iter()
+            .zip(&challenges)
+            .map(|(s, c)| s.simulate(c, rng))
+            .unzip();
+        (
+            commitments,
+            AnyOfResponse {
+                challenges,
+                responses,
+            },
+        )
+    }
+
+    fn absorb(&self, transcript: &mut Transcript, commitment: &Self::Commitment) {
+        transcript.append_message(b"dom-sep", b"any-of");
+        transcript.append_u64(b"n", self.0.len() as u64);
+        for (s, t) in self.0.iter().zip(commitment) {
+            s.absorb(transcript, t);
+        }
+    }
+}
+
+// n 個の主張すべて。AND と同じく全部に同じチャレンジで答える
+#[derive(Debug, Clone)]
+pub struct AllOf<S>(pub Vec<S>);
+
+impl<S: SigmaProtocol> SigmaProtocol for AllOf<S> {
+    type Witness = Vec<S::Witness>;
+    type Commitment = Vec<S::Commitment>;
+    type Response = Vec<S::Response>;
+    type State = Vec<S::State>;
+
+    fn commit<R: RngCore>(
+        &self,
+        witness: &Self::Witness,
+        rng: &mut R,
+    ) -> (Self::State, Self::Commitment) {
+        if witness.len() != self.0.len() {
+            panic!(
+                "{} witnesses given for {} statements",
+                witness.len(),
+                self.0.len()
+            );
+        }
+        self.0
+            .iter()
+            .zip(witness)
+            .map(|(s, w)| s.commit(w, rng))
+            .unzip()
+    }
+
+    fn respond(
+        &self,
+        witness: &Self::Witness,
+        state: Self::State,
+        challenge: &Scalar,
+    ) -> Self::Response {
+        self.0
+            .iter()
+            .zip(witness)
+            .zip(state)
+            .map(|((s, w), st)| s.respond(w, st, challenge))
+            .collect()
+    }
+
+    fn verify(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &Scalar,
+        response: &Self::Response,
+    ) -> bool {
+        let n = self.0.len();
+        commitment.len() == n
+            && response.len() == n
+            && self
+                .0
+                .iter()
+                .zip(commitment)
+                .zip(response)
+                .all(|((s, t), r)| s.verify(t, challenge, r))
+    }
+
+    fn simulate<R: RngCore>(
+        &self,
+        challenge: &Scalar,
+        rng: &mut R,
+    ) -> (Self::Commitment, Self::Response) {
+        self.0.iter().map(|s| s.simulate(challenge, rng)).unzip()
+    }
+
+    fn absorb(&self, transcript: &mut Transcript, commitment: &Self::Commitment) {
+        transcript.append_message(b"dom-sep", b"all-of");
+        transcript.append_u64(b"n", self.0.len() as u64);
+        for (s, t) in self.0.iter().zip(commitment) {
+            s.absorb(transcript, t);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curve;
+    use crate::rand::TestRng;
+    use crate::sigma::{prove, verify, Dleq, Dlog};
+
+    fn keypair(rng: &mut TestRng) -> (Scalar, Dlog) {
+        let x = Scalar::random_nonzero(rng);
+        (x, Dlog::new(curve::generator().scalar_mul(x.value())))
+    }
+
+    #[test]
+    fn test_one_of_two_ring_signature() {
+        // 「pk1 か pk2 のどちらかの秘密鍵を知っている」をメッセージに束縛して示す
+        let mut rng = TestRng::from_label(b"or");
+        let (x1, pk1) = keypair(&mut rng);
+        let (x2, pk2) = keypair(&mut rng);
+        let ring = Or(pk1, pk2);
+        let sign = |w: OrWitness<Scalar, Scalar>, rng: &mut TestRng| {
+            let mut t = Transcript::new(b"ring-sig");
+            t.append_message(b"msg", b"leak");
+            prove(&ring, &w, &mut t, rng)
+        };
+        let check = |proof: &_, msg: &[u8]| {
+            let mut t = Transcript::new(b"ring-sig");
+            t.append_message(b"msg", msg);
+            verify(&ring, proof, &mut t)
+        };
+        let by_first = sign(OrWitness::Left(x1), &mut rng);
+        let by_second = sign(OrWitness::Right(x2), &mut rng);
+        assert!(check(&by_first, b"leak"));
+        assert!(check(&by_second, b"leak"));
+        assert!(!check(&by_first, b"other"));
+        // 間違った鍵では作れない
+        let forged = sign(OrWitness::Left(x2), &mut rng);
+        assert!(!check(&forged, b"leak"));
+    }
+
+    #[test]
+    fn test_and_composition_with_dleq() {
+        let mut rng = TestRng::from_label(b"and");
+        let (x, pk) = keypair(&mut rng);
+        let h = curve::hash_to_curve(b"H");
+        let g = curve::generator();
+        let dleq = Dleq::new(
+            g.clone(),
+            g.scalar_mul(x.value()),
+            h.clone(),
+            h.scalar_mul(x.value()),
+        );
+        let (y, pk2) = keypair(&mut rng);
+        let both = And(dleq, pk2.clone());
+        let proof = prove(&both, &(x, y), &mut Transcript::new(b"and"), &mut rng);
+        assert!(verify(&both, &proof, &mut Transcript::new(b"and")));
+        // 片方しか知らなければ AND は通らない
+        let wrong = prove(&both, &(x, x), &mut Transcript::new(b"and"), &mut rng);
+        assert!(!verify(&both, &wrong, &mut Transcript::new(b"and")));
+        // 入れ子にもできる: (DLEQ かつ pk2) または pk
+        let nested = Or(both, pk);
+        let proof = prove(
+            &nested,
+            &OrWitness::Right(x),
+            &mut Transcript::new(b"nest"),
+            &mut rng,
+        );
+        assert!(verify(&nested, &proof, &mut Transcript::new(b"nest")));
+    }
+
+    #[test]
+    fn test_all_of() {
+        let mut rng = TestRng::from_label(b"all-of");
+        let (keys, statements): (Vec<Scalar>, Vec<Dlog>) =
+            (0..3).map(|_| keypair(&mut rng)).unzip();
+        let all = AllOf(statements);
+        let proof = prove(&all, &keys, &mut Transcript::new(b"all"), &mut rng);
+        assert!(verify(&all, &proof, &mut Transcript::new(b"all")));
+        // 1 つでも違う鍵が混ざると通らない
+        let mut wrong = keys.clone();
+        wrong[1] = wrong[0];
+        let proof = prove(&all, &wrong, &mut Transcript::new(b"all"), &mut rng);
+        assert!(!verify(&all, &proof, &mut Transcript::new(b"all")));
+        // 枝の数が合わない証明
+        let (mut t, mut r) = proof;
+        t.pop();
+        r.pop();
+        assert!(!verify(&all, &(t, r), &mut Transcript::new(b"all")));
+    }
+}
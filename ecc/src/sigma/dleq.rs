@@ -0,0 +1,164 @@
+// Chaum–Pedersen の離散対数等価性証明 (DLEQ): A = xG と B = xH の x が同じであることを示す。
+// VRF の出力や OPRF の評価が公開鍵と同じ鍵で計算されたことの検証に使う
+use super::{ProofError, SigmaProtocol};
+use crate::curve::{decode_point, Scalar};
+use crate::rand::RngCore;
+use crate::transcript::Transcript;
+use crate::Point;
+
+const POINT_LEN: usize = 17;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DleqProof {
+    pub t1: Point,
+    pub t2: Point,
+    pub response: Scalar,
+}
+
+impl DleqProof {
+    // 同じ乱数 k で T1 = kG, T2 = kH にコミットし、s = k + cx で答える
+    pub fn prove<R: RngCore>(
+        secret: &Scalar,
+        g: &Point,
+        h: &Point,
+        transcript: &mut Transcript,
+        rng: &mut R,
+    ) -> Self {
+        let (a, b) = (g.scalar_mul(secret.value()), h.scalar_mul(secret.value()));
+        let statement = Dleq::new(g.clone(), a, h.clone(), b);
+        let ((t1, t2), response) = super::prove(&statement, secret, transcript, rng);
+        DleqProof { t1, t2, response }
+    }
+
+    pub fn verify(
+        &self,
+        (g, a): (&Point, &Point),
+        (h, b): (&Point, &Point),
+        transcript: &mut Transcript,
+    ) -> bool {
+        let statement = Dleq::new(g.clone(), a.clone(), h.clone(), b.clone());
+        let proof = ((self.t1.clone(), self.t2.clone()), self.response);
+        super::verify(&statement, &proof, transcript)
+    }
+
+    // T1 || T2 || s
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = self.t1.to_bytes();
+        out.extend_from_slice(&self.t2.to_bytes());
+        out.extend_from_slice(&self.response.to_bytes());
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofError> {
+        if bytes.len() != 2 * POINT_LEN + 8 {
+            return Err(ProofError::InvalidEncoding);
+        }
+        let point = |b: &[u8]| decode_point(b).ok_or(ProofError::InvalidEncoding);
+        Ok(DleqProof {
+            t1: point(&bytes[..POINT_LEN])?,
+            t2: point(&bytes[POINT_LEN..2 * POINT_LEN])?,
+            response: Scalar::from_bytes(bytes[2 * POINT_LEN..].try_into().unwrap())
+                .ok_or(ProofError::InvalidEncoding)?,
+        })
+    }
+}
+
+// 合成用の主張「A = xG かつ B = xH」
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dleq {
+    pub g: Point,
+    pub a: Point,
+    pub h: Point,
+    pub b: Point,
+}
+
+impl Dleq {
+    pub fn new(g: Point, a: Point, h: Point, b: Point) -> Self {
+        Dleq { g, a, h, b }
+    }
+}
+
+impl SigmaProtocol for Dleq {
+    type Witness = Scalar;
+    type Commitment = (Point, Point);
+    type Response = Scalar;
+    type State = Scalar;
+
+    fn commit<R: RngCore>(&self, _witness: &Scalar, rng: &mut R) -> (Scalar, (Point, Point)) {
+        let k = Scalar::random(rng);
+        (
+            k,
+            (self.g.scalar_mul(k.value()), self.h.scalar_mul(k.value())),
+        )
+    }
+
+    fn respond(&self, witness: &Scalar, state: Scalar, challenge: &Scalar) -> Scalar {
+        state.add(&challenge.mul(witness))
+    }
+
+    fn verify(&self, (t1, t2): &(Point, Point), challenge: &Scalar, response: &Scalar) -> bool {
+        let (s, c) = (response.value(), challenge.value());
+        self.g.scalar_mul(s) == t1.add(&self.a.scalar_mul(c))
+            && self.h.scalar_mul(s) == t2.add(&self.b.scalar_mul(c))
+    }
+
+    fn simulate<R: RngCore>(&self, challenge: &Scalar, rng: &mut R) -> ((Point, Point), Scalar) {
+        let s = Scalar::random(rng);
+        let back = |base: &Point, public: &Point| {
+            base.scalar_mul(s.value())
+                .add(&public.scalar_mul(challenge.value()).neg())
+        };
+        ((back(&self.g, &self.a), back(&self.h, &self.b)), s)
+    }
+
+    fn absorb(&self, transcript: &mut Transcript, (t1, t2): &(Point, Point)) {
+        transcript.append_message(b"dom-sep", b"dleq");
+        for (label, p) in [
+            (&b"G"[..], &self.g),
+            (b"A", &self.a),
+            (b"H", &self.h),
+            (b"B", &self.b),
+            (b"T1", t1),
+            (b"T2", t2),
+        ] {
+            transcript.append_point(label, p);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curve;
+    use crate::rand::TestRng;
+
+    #[test]
+    fn test_vrf_style_evaluation() {
+        // 公開鍵 pk = xG と、入力のハッシュ点への評価 y = x·H(input) が同じ鍵によることを示す
+        let mut rng = TestRng::from_label(b"dleq");
+        let x = Scalar::random_nonzero(&mut rng);
+        let g = curve::generator();
+        let h = curve::hash_to_curve(b"vrf input");
+        let (pk, y) = (g.scalar_mul(x.value()), h.scalar_mul(x.value()));
+        let proof = DleqProof::prove(&x, &g, &h, &mut Transcript::new(b"vrf"), &mut rng);
+        assert!(proof.verify((&g, &pk), (&h, &y), &mut Transcript::new(b"vrf")));
+        // 別の鍵で計算した出力は通らない
+        let forged = h.scalar_mul(x.add(&Scalar::ONE).value());
+        assert!(!proof.verify((&g, &pk), (&h, &forged), &mut Transcript::new(b"vrf")));
+        assert!(!proof.verify((&g, &pk), (&h, &y), &mut Transcript::new(b"other")));
+    }
+
+    #[test]
+    fn test_encoding_roundtrip() {
+        let mut rng = TestRng::from_label(b"dleq-bytes");
+        let x = Scalar::random_nonzero(&mut rng);
+        let (g, h) = (curve::generator(), curve::hash_to_curve(b"H"));
+        let proof = DleqProof::prove(&x, &g, &h, &mut Transcript::new(b"t"), &mut rng);
+        let bytes = proof.to_bytes();
+        assert_eq!(DleqProof::from_bytes(&bytes), Ok(proof));
+        assert_eq!(
+            DleqProof::from_bytes(&bytes[..40]),
+            Err(ProofError::InvalidEncoding)
+        );
+    }
+}
@@ -0,0 +1,79 @@
+// Σ プロトコル (コミット・チャレンジ・レスポンスの 3 手番の知識証明)
+pub mod composition;
+pub mod dleq;
+pub mod pedersen;
+pub mod schnorr;
+
+pub use composition::{AllOf, And, AnyOf, AnyOfResponse, Or, OrResponse, OrWitness};
+pub use dleq::{Dleq, DleqProof};
+pub use pedersen::{Product, ProductWitness, SameValue};
+pub use schnorr::{Dlog, SchnorrProof, SchnorrProver};
+
+use crate::curve::Scalar;
+use crate::rand::RngCore;
+use crate::transcript::Transcript;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProofError {
+    // 長さが違う、点が曲線上にない、スカラーが位数以上
+    InvalidEncoding,
+}
+
+// 曲線の位数を法とするチャレンジを使う Σ プロトコル。self が公開された主張 (statement)
+pub trait SigmaProtocol {
+    type Witness;
+    type Commitment: Clone;
+    type Response: Clone;
+    // コミットからレスポンスまで証明者が持ち越す秘密 (nonce など)
+    type State;
+
+    fn commit<R: RngCore>(
+        &self,
+        witness: &Self::Witness,
+        rng: &mut R,
+    ) -> (Self::State, Self::Commitment);
+    fn respond(
+        &self,
+        witness: &Self::Witness,
+        state: Self::State,
+        challenge: &Scalar,
+    ) -> Self::Response;
+    fn verify(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &Scalar,
+        response: &Self::Response,
+    ) -> bool;
+    // 与えたチャレンジに対して受理されるトランスクリプトを witness なしで作る
+    fn simulate<R: RngCore>(
+        &self,
+        challenge: &Scalar,
+        rng: &mut R,
+    ) -> (Self::Commitment, Self::Response);
+    // 主張とコミットをトランスクリプトに入れる
+    fn absorb(&self, transcript: &mut Transcript, commitment: &Self::Commitment);
+}
+
+// 任意の Σ プロトコルの Fiat–Shamir 変換
+pub fn prove<S: SigmaProtocol, R: RngCore>(
+    statement: &S,
+    witness: &S::Witness,
+    transcript: &mut Transcript,
+    rng: &mut R,
+) -> (S::Commitment, S::Response) {
+    let (state, commitment) = statement.commit(witness, rng);
+    statement.absorb(transcript, &commitment);
+    let c = transcript.challenge_scalar(b"c");
+    let response = statement.respond(witness, state, &c);
+    (commitment, response)
+}
+
+pub fn verify<S: SigmaProtocol>(
+    statement: &S,
+    proof: &(S::Commitment, S::Response),
+    transcript: &mut Transcript,
+) -> bool {
+    statement.absorb(transcript, &proof.0);
+    let c = transcript.challenge_scalar(b"c");
+    statement.verify(&proof.0, &c, &proof.1)
+}
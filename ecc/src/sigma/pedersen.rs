@@ -0,0 +1,234 @@
+// Pedersen コミットメント (C = vG + rH) についての Σ プロトコル。
+// SameValue は A と B が同じ値へのコミットメント (A - B = rH) であることを、
+// Product は Z が X と Y の値の積へのコミットメントであることを示す。
+// Product は X = xG + r_x H と Z = xY + tH (t = r_z - x·r_y) の両方の表現を同じ x で知っていると示す
+use super::SigmaProtocol;
+use crate::commitment::{pedersen_g, pedersen_h};
+use crate::curve::Scalar;
+use crate::rand::RngCore;
+use crate::transcript::Transcript;
+use crate::Point;
+
+// 合成用の主張「A と B は同じ値にコミットしている」。witness はブラインドの差
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SameValue {
+    pub a: Point,
+    pub b: Point,
+}
+
+impl SameValue {
+    pub fn new(a: Point, b: Point) -> Self {
+        SameValue { a, b }
+    }
+
+    fn difference(&self) -> Point {
+        self.a.add(&self.b.neg())
+    }
+}
+
+impl SigmaProtocol for SameValue {
+    type Witness = Scalar;
+    type Commitment = Point;
+    type Response = Scalar;
+    type State = Scalar;
+
+    fn commit<R: RngCore>(&self, _witness: &Scalar, rng: &mut R) -> (Scalar, Point) {
+        let k = Scalar::random(rng);
+        (k, pedersen_h().scalar_mul(k.value()))
+    }
+
+    fn respond(&self, witness: &Scalar, state: Scalar, challenge: &Scalar) -> Scalar {
+        state.add(&challenge.mul(witness))
+    }
+
+    fn verify(&self, commitment: &Point, challenge: &Scalar, response: &Scalar) -> bool {
+        pedersen_h().scalar_mul(response.value())
+            == commitment.add(&self.difference().scalar_mul(challenge.value()))
+    }
+
+    fn simulate<R: RngCore>(&self, challenge: &Scalar, rng: &mut R) -> (Point, Scalar) {
+        let s = Scalar::random(rng);
+        let t = pedersen_h()
+            .scalar_mul(s.value())
+            .add(&self.difference().scalar_mul(challenge.value()).neg());
+        (t, s)
+    }
+
+    fn absorb(&self, transcript: &mut Transcript, commitment: &Point) {
+        transcript.append_message(b"dom-sep", b"same-value");
+        transcript.append_point(b"A", &self.a);
+        transcript.append_point(b"B", &self.b);
+        transcript.append_point(b"T", commitment);
+    }
+}
+
+// 合成用の主張「Z の値は X の値と Y の値の積」
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Product {
+    pub x: Point,
+    pub y: Point,
+    pub z: Point,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProductWitness {
+    pub x: Scalar,
+    pub x_blinding: Scalar,
+    // r_z - x·r_y
+    pub t: Scalar,
+}
+
+impl ProductWitness {
+    // 各コミットメントの (値, ブラインド) の x と、Y, Z のブラインドから作る
+    pub fn new(x: &Scalar, x_blinding: &Scalar, y_blinding: &Scalar, z_blinding: &Scalar) -> Self {
+        ProductWitness {
+            x: *x,
+            x_blinding: *x_blinding,
+            t: z_blinding.sub(&x.mul(y_blinding)),
+        }
+    }
+}
+
+impl Product {
+    pub fn new(x: Point, y: Point, z: Point) -> Self {
+        Product { x, y, z }
+    }
+}
+
+impl SigmaProtocol for Product {
+    type Witness = ProductWitness;
+    type Commitment = (Point, Point);
+    type Response = (Scalar, Scalar, Scalar);
+    type State = (Scalar, Scalar, Scalar);
+
+    // T1 = aG + bH, T2 = aY + dH
+    fn commit<R: RngCore>(
+        &self,
+        _witness: &ProductWitness,
+        rng: &mut R,
+    ) -> (Self::State, (Point, Point)) {
+        let (a, b, d) = (
+            Scalar::random(rng),
+            Scalar::random(rng),
+            Scalar::random(rng),
+        );
+        let h = pedersen_h();
+        let t1 = pedersen_g()
+            .scalar_mul(a.value())
+            .add(&h.scalar_mul(b.value()));
+        let t2 = self.y.scalar_mul(a.value()).add(&h.scalar_mul(d.value()));
+        ((a, b, d), (t1, t2))
+    }
+
+    fn respond(
+        &self,
+        witness: &ProductWitness,
+        (a, b, d): Self::State,
+        challenge: &Scalar,
+    ) -> Self::Response {
+        (
+            a.add(&challenge.mul(&witness.x)),
+            b.add(&challenge.mul(&witness.x_blinding)),
+            d.add(&challenge.mul(&witness.t)),
+        )
+    }
+
+    fn verify(
+        &self,
+        (t1, t2): &(Point, Point),
+        challenge: &Scalar,
+        (s1, s2, s3): &Self::Response,
+    ) -> bool {
+        let (h, c) = (pedersen_h(), challenge.value());
+        pedersen_g()
+            .scalar_mul(s1.value())
+            .add(&h.scalar_mul(s2.value()))
+            == t1.add(&self.x.scalar_mul(c))
+            && self.y.scalar_mul(s1.value()).add(&h.scalar_mul(s3.value()))
+                == t2.add(&self.z.scalar_mul(c))
+    }
+
+    fn simulate<R: RngCore>(
+        &self,
+        challenge: &Scalar,
+        rng: &mut R,
+    ) -> ((Point, Point), Self::Response) {
+        let (s1, s2, s3) = (
+            Scalar::random(rng),
+            Scalar::random(rng),
+            Scalar::random(rng),
+        );
+        let (h, c) = (pedersen_h(), challenge.value());
+        let t1 = pedersen_g()
+            .scalar_mul(s1.value())
+            .add(&h.scalar_mul(s2.value()))
+            .add(&self.x.scalar_mul(c).neg());
+        let t2 = self
+            .y
+            .scalar_mul(s1.value())
+            .add(&h.scalar_mul(s3.value()))
+            .add(&self.z.scalar_mul(c).neg());
+        ((t1, t2), (s1, s2, s3))
+    }
+
+    fn absorb(&self, transcript: &mut Transcript, (t1, t2): &(Point, Point)) {
+        transcript.append_message(b"dom-sep", b"product");
+        for (label, p) in [
+            (&b"X"[..], &self.x),
+            (b"Y", &self.y),
+            (b"Z", &self.z),
+            (b"T1", t1),
+            (b"T2", t2),
+        ] {
+            transcript.append_point(label, p);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commitment::PedersenCommitment;
+    use crate::rand::TestRng;
+    use crate::sigma::{prove, verify};
+
+    fn commit(value: u64, rng: &mut TestRng) -> (Scalar, Scalar, Point) {
+        let v = Scalar::new(value);
+        let (c, r) = PedersenCommitment::commit_random(&v, rng);
+        (v, r, c.as_point().clone())
+    }
+
+    #[test]
+    fn test_same_value() {
+        let mut rng = TestRng::from_label(b"same-value");
+        let (_, ra, a) = commit(7, &mut rng);
+        let (_, rb, b) = commit(7, &mut rng);
+        let (_, rc, c) = commit(8, &mut rng);
+        let same = SameValue::new(a.clone(), b);
+        let proof = prove(&same, &ra.sub(&rb), &mut Transcript::new(b"eq"), &mut rng);
+        assert!(verify(&same, &proof, &mut Transcript::new(b"eq")));
+        // 値が違えばブラインドの差では開けない
+        let differ = SameValue::new(a, c);
+        let proof = prove(&differ, &ra.sub(&rc), &mut Transcript::new(b"eq"), &mut rng);
+        assert!(!verify(&differ, &proof, &mut Transcript::new(b"eq")));
+    }
+
+    #[test]
+    fn test_product() {
+        let mut rng = TestRng::from_label(b"product");
+        let (x, rx, cx) = commit(6, &mut rng);
+        let (_, ry, cy) = commit(7, &mut rng);
+        let (_, rz, cz) = commit(42, &mut rng);
+        let statement = Product::new(cx.clone(), cy.clone(), cz);
+        let witness = ProductWitness::new(&x, &rx, &ry, &rz);
+        let proof = prove(&statement, &witness, &mut Transcript::new(b"mul"), &mut rng);
+        assert!(verify(&statement, &proof, &mut Transcript::new(b"mul")));
+        assert!(!verify(&statement, &proof, &mut Transcript::new(b"other")));
+        // 6·7 ≠ 43
+        let (_, rw, cw) = commit(43, &mut rng);
+        let statement = Product::new(cx, cy, cw);
+        let witness = ProductWitness::new(&x, &rx, &ry, &rw);
+        let proof = prove(&statement, &witness, &mut Transcript::new(b"mul"), &mut rng);
+        assert!(!verify(&statement, &proof, &mut Transcript::new(b"mul")));
+    }
+}
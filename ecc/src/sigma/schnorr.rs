@@ -0,0 +1,212 @@
+// Schnorr の離散対数の知識証明: X = xG を知っていることを、x を明かさずに示す。
+// 対話版は正直な検証者に対してゼロ知識、Fiat–Shamir 変換でチャレンジをハッシュに置き換えると非対話になる
+use super::{ProofError, SigmaProtocol};
+use crate::curve::{self, decode_point, Scalar};
+use crate::rand::RngCore;
+use crate::transcript::Transcript;
+use crate::Point;
+
+const POINT_LEN: usize = 17;
+
+// 対話版の証明者。コミット T = kG を送った後、チャレンジ c に s = k + cx で答える
+pub struct SchnorrProver {
+    secret: Scalar,
+    nonce: Scalar,
+}
+
+impl SchnorrProver {
+    pub fn commit<R: RngCore>(secret: &Scalar, rng: &mut R) -> (Self, Point) {
+        let nonce = Scalar::random(rng);
+        let commitment = curve::generator().scalar_mul(nonce.value());
+        (
+            SchnorrProver {
+                secret: *secret,
+                nonce,
+            },
+            commitment,
+        )
+    }
+
+    // nonce を使い回すと x が漏れるので self を消費する
+    pub fn respond(self, challenge: &Scalar) -> Scalar {
+        self.nonce.add(&challenge.mul(&self.secret))
+    }
+}
+
+// sG = T + cX を確かめる
+pub fn verify_response(
+    public: &Point,
+    commitment: &Point,
+    challenge: &Scalar,
+    response: &Scalar,
+) -> bool {
+    let g = curve::generator();
+    g.scalar_mul(response.value()) == commitment.add(&public.scalar_mul(challenge.value()))
+}
+
+// 検証者を使わずに正しく見えるトランスクリプトを作る (ゼロ知識性の根拠)
+pub fn simulate<R: RngCore>(public: &Point, rng: &mut R) -> (Point, Scalar, Scalar) {
+    let (c, s) = (Scalar::random(rng), Scalar::random(rng));
+    let t = curve::generator()
+        .scalar_mul(s.value())
+        .add(&public.scalar_mul(c.value()).neg());
+    (t, c, s)
+}
+
+// 同じコミットに対する 2 つの受理トランスクリプトから秘密を取り出す (特別健全性)
+pub fn extract(c1: &Scalar, s1: &Scalar, c2: &Scalar, s2: &Scalar) -> Option<Scalar> {
+    Some(s1.sub(s2).mul(&c1.sub(c2).inv()?))
+}
+
+// 合成用の主張「X = xG の x を知っている」
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dlog {
+    pub public: Point,
+}
+
+impl Dlog {
+    pub fn new(public: Point) -> Self {
+        Dlog { public }
+    }
+}
+
+impl SigmaProtocol for Dlog {
+    type Witness = Scalar;
+    type Commitment = Point;
+    type Response = Scalar;
+    type State = Scalar;
+
+    fn commit<R: RngCore>(&self, _witness: &Scalar, rng: &mut R) -> (Scalar, Point) {
+        let k = Scalar::random(rng);
+        (k, curve::generator().scalar_mul(k.value()))
+    }
+
+    fn respond(&self, witness: &Scalar, state: Scalar, challenge: &Scalar) -> Scalar {
+        state.add(&challenge.mul(witness))
+    }
+
+    fn verify(&self, commitment: &Point, challenge: &Scalar, response: &Scalar) -> bool {
+        verify_response(&self.public, commitment, challenge, response)
+    }
+
+    fn simulate<R: RngCore>(&self, challenge: &Scalar, rng: &mut R) -> (Point, Scalar) {
+        let s = Scalar::random(rng);
+        let t = curve::generator()
+            .scalar_mul(s.value())
+            .add(&self.public.scalar_mul(challenge.value()).neg());
+        (t, s)
+    }
+
+    fn absorb(&self, transcript: &mut Transcript, commitment: &Point) {
+        transcript.append_message(b"dom-sep", b"schnorr-pok");
+        transcript.append_point(b"X", &self.public);
+        transcript.append_point(b"T", commitment);
+    }
+}
+
+// Fiat–Shamir 変換した非対話証明。証明を使う場面 (メッセージやセッション ID) は
+// 呼び出し側がトランスクリプトに入れておく
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchnorrProof {
+    pub commitment: Point,
+    pub response: Scalar,
+}
+
+impl SchnorrProof {
+    pub fn prove<R: RngCore>(secret: &Scalar, transcript: &mut Transcript, rng: &mut R) -> Self {
+        let statement = Dlog::new(curve::generator().scalar_mul(secret.value()));
+        let (commitment, response) = super::prove(&statement, secret, transcript, rng);
+        SchnorrProof {
+            commitment,
+            response,
+        }
+    }
+
+    pub fn verify(&self, public: &Point, transcript: &mut Transcript) -> bool {
+        let proof = (self.commitment.clone(), self.response);
+        super::verify(&Dlog::new(public.clone()), &proof, transcript)
+    }
+
+    // T (17 バイト) || s (8 バイト)
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = self.commitment.to_bytes();
+        out.extend_from_slice(&self.response.to_bytes());
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofError> {
+        if bytes.len() != POINT_LEN + 8 {
+            return Err(ProofError::InvalidEncoding);
+        }
+        let commitment = decode_point(&bytes[..POINT_LEN]).ok_or(ProofError::InvalidEncoding)?;
+        let response = Scalar::from_bytes(bytes[POINT_LEN..].try_into().unwrap())
+            .ok_or(ProofError::InvalidEncoding)?;
+        Ok(SchnorrProof {
+            commitment,
+            response,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::TestRng;
+
+    #[test]
+    fn test_interactive_protocol() {
+        let mut rng = TestRng::from_label(b"schnorr");
+        let x = Scalar::random_nonzero(&mut rng);
+        let public = curve::generator().scalar_mul(x.value());
+        let (prover, t) = SchnorrProver::commit(&x, &mut rng);
+        let c = Scalar::random(&mut rng);
+        let s = prover.respond(&c);
+        assert!(verify_response(&public, &t, &c, &s));
+        assert!(!verify_response(&public, &t, &c.add(&Scalar::ONE), &s));
+        // シミュレータの出力も受理される
+        let (t, c, s) = simulate(&public, &mut rng);
+        assert!(verify_response(&public, &t, &c, &s));
+    }
+
+    #[test]
+    fn test_nonce_reuse_reveals_secret() {
+        let mut rng = TestRng::from_label(b"schnorr-extract");
+        let x = Scalar::random_nonzero(&mut rng);
+        // 同じ乱数で 2 回コミットすると、2 つのチャレンジへの応答から x が解ける
+        let (c1, c2) = (Scalar::new(11), Scalar::new(29));
+        let (p1, t1) = SchnorrProver::commit(&x, &mut TestRng::new());
+        let (p2, t2) = SchnorrProver::commit(&x, &mut TestRng::new());
+        assert_eq!(t1, t2);
+        let (s1, s2) = (p1.respond(&c1), p2.respond(&c2));
+        assert_eq!(extract(&c1, &s1, &c2, &s2), Some(x));
+    }
+
+    #[test]
+    fn test_non_interactive_proof_and_encoding() {
+        let mut rng = TestRng::from_label(b"schnorr-ni");
+        let x = Scalar::random_nonzero(&mut rng);
+        let public = curve::generator().scalar_mul(x.value());
+        let session = |id: &[u8]| {
+            let mut t = Transcript::new(b"schnorr-test");
+            t.append_message(b"session", id);
+            t
+        };
+        let proof = SchnorrProof::prove(&x, &mut session(b"1"), &mut rng);
+        assert!(proof.verify(&public, &mut session(b"1")));
+        assert!(!proof.verify(&public, &mut session(b"2")));
+        assert!(!proof.verify(&public.add(&curve::generator()), &mut session(b"1")));
+
+        let bytes = proof.to_bytes();
+        assert_eq!(SchnorrProof::from_bytes(&bytes), Ok(proof));
+        assert_eq!(
+            SchnorrProof::from_bytes(&bytes[1..]),
+            Err(ProofError::InvalidEncoding)
+        );
+        let mut bad = bytes.clone();
+        bad[17..].copy_from_slice(&curve::N.to_be_bytes());
+        assert_eq!(
+            SchnorrProof::from_bytes(&bad),
+            Err(ProofError::InvalidEncoding)
+        );
+    }
+}
@@ -0,0 +1,189 @@
+// SPAKE2 (RFC 9382 をこのクレートのトイ曲線向けに簡略化したもの)
+use crate::curve::{self, Scalar};
+use crate::hash::sha256;
+use crate::mac::ct_eq;
+use crate::rand::RngCore;
+use crate::Point;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    A,
+    B,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Spake2Error {
+    InvalidMessage,
+    ConfirmationFailed,
+}
+
+pub fn m() -> Point {
+    curve::hash_to_curve(b"SPAKE2 M")
+}
+
+pub fn n() -> Point {
+    curve::hash_to_curve(b"SPAKE2 N")
+}
+
+pub fn password_to_scalar(password: &[u8]) -> u64 {
+    let mut input = b"SPAKE2 password".to_vec();
+    input.extend_from_slice(password);
+    curve::hash_to_scalar(&input)
+}
+
+fn append_with_len(tt: &mut Vec<u8>, data: &[u8]) {
+    tt.extend_from_slice(&(data.len() as u64).to_le_bytes());
+    tt.extend_from_slice(data);
+}
+
+pub struct Spake2 {
+    role: Role,
+    id_a: Vec<u8>,
+    id_b: Vec<u8>,
+    w: u64,
+    secret: u64,
+    message: Point,
+}
+
+impl Spake2 {
+    pub fn new<R: RngCore>(
+        role: Role,
+        password: &[u8],
+        id_a: &[u8],
+        id_b: &[u8],
+        rng: &mut R,
+    ) -> Self {
+        let w = password_to_scalar(password);
+        let secret = Scalar::random(rng).value();
+        let blind = match role {
+            Role::A => m(),
+            Role::B => n(),
+        };
+        // pA = x*G + w*M, pB = y*G + w*N
+        let message = curve::generator()
+            .scalar_mul(secret)
+            .add(&blind.scalar_mul(w));
+        Spake2 {
+            role,
+            id_a: id_a.to_vec(),
+            id_b: id_b.to_vec(),
+            w,
+            secret,
+            message,
+        }
+    }
+
+    pub fn message(&self) -> &Point {
+        &self.message
+    }
+
+    pub fn finish(self, peer_message: &Point) -> Result<Spake2Session, Spake2Error> {
+        if peer_message.is_infinity() {
+            return Err(Spake2Error::InvalidMessage);
+        }
+        let peer_blind = match self.role {
+            Role::A => n(),
+            Role::B => m(),
+        };
+        // K = x * (pB - w*N) = y * (pA - w*M) = x*y*G
+        let k = peer_message
+            .add(&peer_blind.scalar_mul(self.w).neg())
+            .scalar_mul(self.secret);
+        if k.is_infinity() {
+            return Err(Spake2Error::InvalidMessage);
+        }
+
+        let (p_a, p_b) = match self.role {
+            Role::A => (&self.message, peer_message),
+            Role::B => (peer_message, &self.message),
+        };
+        let mut tt = Vec::new();
+        append_with_len(&mut tt, &self.id_a);
+        append_with_len(&mut tt, &self.id_b);
+        append_with_len(&mut tt, &p_a.to_bytes());
+        append_with_len(&mut tt, &p_b.to_bytes());
+        append_with_len(&mut tt, &k.to_bytes());
+        append_with_len(&mut tt, &self.w.to_be_bytes());
+
+        // Hash(TT) = Ke || Ka, KDF(Ka) = KcA || KcB
+        let hash = sha256(&tt);
+        let mut shared_key = [0u8; 16];
+        shared_key.copy_from_slice(&hash[..16]);
+        let mut kc_input = hash[16..].to_vec();
+        kc_input.extend_from_slice(b"ConfirmationKeys");
+        let kc = sha256(&kc_input);
+
+        let mac = |key: &[u8]| {
+            let mut input = key.to_vec();
+            input.extend_from_slice(&tt);
+            sha256(&input)
+        };
+        let conf_a = mac(&kc[..16]);
+        let conf_b = mac(&kc[16..]);
+        let (confirmation, peer_confirmation) = match self.role {
+            Role::A => (conf_a, conf_b),
+            Role::B => (conf_b, conf_a),
+        };
+
+        Ok(Spake2Session {
+            shared_key,
+            confirmation,
+            peer_confirmation,
+        })
+    }
+}
+
+pub struct Spake2Session {
+    shared_key: [u8; 16],
+    confirmation: [u8; 32],
+    peer_confirmation: [u8; 32],
+}
+
+impl Spake2Session {
+    pub fn confirmation(&self) -> [u8; 32] {
+        self.confirmation
+    }
+
+    // 相手の確認値を検証できた場合のみ共有鍵を返す
+    pub fn verify(self, peer_confirmation: &[u8; 32]) -> Result<[u8; 16], Spake2Error> {
+        if ct_eq(&self.peer_confirmation, peer_confirmation) {
+            Ok(self.shared_key)
+        } else {
+            Err(Spake2Error::ConfirmationFailed)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::OsRng;
+
+    fn run(password_a: &[u8], password_b: &[u8]) -> (Spake2Session, Spake2Session) {
+        let a = Spake2::new(Role::A, password_a, b"alice", b"bob", &mut OsRng);
+        let b = Spake2::new(Role::B, password_b, b"alice", b"bob", &mut OsRng);
+        let msg_a = a.message().clone();
+        let msg_b = b.message().clone();
+        (a.finish(&msg_b).unwrap(), b.finish(&msg_a).unwrap())
+    }
+
+    #[test]
+    fn test_spake2_same_password() {
+        let (session_a, session_b) = run(b"password", b"password");
+        let conf_a = session_a.confirmation();
+        let conf_b = session_b.confirmation();
+        let key_a = session_a.verify(&conf_b).unwrap();
+        let key_b = session_b.verify(&conf_a).unwrap();
+        assert_eq!(key_a, key_b);
+    }
+
+    #[test]
+    fn test_spake2_wrong_password() {
+        let (session_a, session_b) = run(b"password", b"passw0rd");
+        let conf_b = session_b.confirmation();
+        assert_eq!(
+            session_a.verify(&conf_b),
+            Err(Spake2Error::ConfirmationFailed)
+        );
+    }
+}
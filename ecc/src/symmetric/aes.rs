@@ -0,0 +1,188 @@
+// AES (FIPS 197)。S-box は GF(2^8) の逆元とアフィン変換から組み立てる
+use crate::binary_field::Gf256;
+use std::sync::OnceLock;
+
+struct Tables {
+    sbox: [u8; 256],
+    inv_sbox: [u8; 256],
+}
+
+fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        let mut sbox = [0u8; 256];
+        let mut inv_sbox = [0u8; 256];
+        for a in 0..=255u8 {
+            let b = Gf256(a).inv().0;
+            let s = b
+                ^ b.rotate_left(1)
+                ^ b.rotate_left(2)
+                ^ b.rotate_left(3)
+                ^ b.rotate_left(4)
+                ^ 0x63;
+            sbox[a as usize] = s;
+            inv_sbox[s as usize] = a;
+        }
+        Tables { sbox, inv_sbox }
+    })
+}
+
+#[derive(Clone)]
+pub struct Aes {
+    // ラウンド鍵をワード列のまま 16 バイトずつ並べたもの
+    round_keys: Vec<[u8; 16]>,
+}
+
+impl Aes {
+    pub const BLOCK_SIZE: usize = 16;
+
+    // 鍵長 16 / 24 / 32 バイトに応じて AES-128 / 192 / 256 になる
+    pub fn new(key: &[u8]) -> Self {
+        let nk = match key.len() {
+            16 | 24 | 32 => key.len() / 4,
+            n => panic!("Invalid AES key length {}", n),
+        };
+        let rounds = nk + 6;
+        let sbox = &tables().sbox;
+        let mut w: Vec<[u8; 4]> = key.chunks(4).map(|c| c.try_into().unwrap()).collect();
+        let mut rcon = Gf256::ONE;
+        for i in nk..4 * (rounds + 1) {
+            let mut t = w[i - 1];
+            if i % nk == 0 {
+                t.rotate_left(1);
+                t = t.map(|b| sbox[b as usize]);
+                t[0] ^= rcon.0;
+                rcon = rcon.double();
+            } else if nk > 6 && i % nk == 4 {
+                t = t.map(|b| sbox[b as usize]);
+            }
+            let prev = w[i - nk];
+            w.push([0, 1, 2, 3].map(|j| prev[j] ^ t[j]));
+        }
+        let round_keys = w
+            .chunks(4)
+            .map(|c| c.concat().try_into().unwrap())
+            .collect();
+        Aes { round_keys }
+    }
+
+    pub fn rounds(&self) -> usize {
+        self.round_keys.len() - 1
+    }
+
+    pub fn encrypt_block(&self, block: &mut [u8; 16]) {
+        let sbox = &tables().sbox;
+        let last = self.rounds();
+        add_round_key(block, &self.round_keys[0]);
+        for round in 1..=last {
+            for b in block.iter_mut() {
+                *b = sbox[*b as usize];
+            }
+            shift_rows(block);
+            if round != last {
+                mix_columns(block, [2, 3, 1, 1]);
+            }
+            add_round_key(block, &self.round_keys[round]);
+        }
+    }
+
+    pub fn decrypt_block(&self, block: &mut [u8; 16]) {
+        let inv_sbox = &tables().inv_sbox;
+        let last = self.rounds();
+        add_round_key(block, &self.round_keys[last]);
+        for round in (0..last).rev() {
+            inv_shift_rows(block);
+            for b in block.iter_mut() {
+                *b = inv_sbox[*b as usize];
+            }
+            add_round_key(block, &self.round_keys[round]);
+            if round != 0 {
+                mix_columns(block, [14, 11, 13, 9]);
+            }
+        }
+    }
+}
+
+fn add_round_key(block: &mut [u8; 16], key: &[u8; 16]) {
+    for (b, k) in block.iter_mut().zip(key) {
+        *b ^= k;
+    }
+}
+
+// 状態は列優先で、バイト i は (行 i % 4, 列 i / 4)
+fn shift_rows(block: &mut [u8; 16]) {
+    let s = *block;
+    for (i, b) in block.iter_mut().enumerate() {
+        let (row, col) = (i % 4, i / 4);
+        *b = s[row + 4 * ((col + row) % 4)];
+    }
+}
+
+fn inv_shift_rows(block: &mut [u8; 16]) {
+    let s = *block;
+    for (i, b) in block.iter_mut().enumerate() {
+        let (row, col) = (i % 4, i / 4);
+        *b = s[row + 4 * ((col + 4 - row) % 4)];
+    }
+}
+
+// 各列に巡回行列 circ(c0, c1, c2, c3) を掛ける。逆変換は係数を変えるだけ
+fn mix_columns(block: &mut [u8; 16], coeffs: [u8; 4]) {
+    for col in block.chunks_mut(4) {
+        let a = [col[0], col[1], col[2], col[3]].map(Gf256);
+        for (row, out) in col.iter_mut().enumerate() {
+            let mut acc = Gf256::ZERO;
+            for (j, &x) in a.iter().enumerate() {
+                acc = acc.add(&Gf256(coeffs[(j + 4 - row) % 4]).mul(&x));
+            }
+            *out = acc.0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{hex, unhex};
+
+    #[test]
+    fn test_fips197_appendix_c_vectors() {
+        let plaintext: [u8; 16] = unhex("00112233445566778899aabbccddeeff")
+            .try_into()
+            .unwrap();
+        let cases = [
+            (
+                "000102030405060708090a0b0c0d0e0f",
+                "69c4e0d86a7b0430d8cdb78070b4c55a",
+            ),
+            (
+                "000102030405060708090a0b0c0d0e0f1011121314151617",
+                "dda97ca4864cdfe06eaf70a0ec0d7191",
+            ),
+            (
+                "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+                "8ea2b7ca516745bfeafc49904b496089",
+            ),
+        ];
+        for (key, expected) in cases {
+            let aes = Aes::new(&unhex(key));
+            let mut block = plaintext;
+            aes.encrypt_block(&mut block);
+            assert_eq!(hex(&block), expected);
+            aes.decrypt_block(&mut block);
+            assert_eq!(block, plaintext);
+        }
+    }
+
+    #[test]
+    fn test_sbox_and_key_schedule() {
+        let t = tables();
+        assert_eq!(t.sbox[0x00], 0x63);
+        assert_eq!(t.sbox[0x53], 0xed);
+        assert_eq!(t.inv_sbox[0x63], 0x00);
+        // FIPS 197 A.1: AES-128 の最後のラウンド鍵
+        let aes = Aes::new(&unhex("2b7e151628aed2a6abf7158809cf4f3c"));
+        assert_eq!(aes.rounds(), 10);
+        assert_eq!(hex(&aes.round_keys[10]), "d014f9a8c9ee2589e13f0cc8b6630ca6");
+    }
+}
@@ -0,0 +1,155 @@
+// ChaCha20 (RFC 8439)。32 ビットのブロックカウンタと 96 ビットの nonce を使う
+const CONSTANTS: [u32; 4] = [0x61707865, 0x3320646e, 0x79622d32, 0x6b206574];
+
+pub fn quarter_round(s: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    s[a] = s[a].wrapping_add(s[b]);
+    s[d] = (s[d] ^ s[a]).rotate_left(16);
+    s[c] = s[c].wrapping_add(s[d]);
+    s[b] = (s[b] ^ s[c]).rotate_left(12);
+    s[a] = s[a].wrapping_add(s[b]);
+    s[d] = (s[d] ^ s[a]).rotate_left(8);
+    s[c] = s[c].wrapping_add(s[d]);
+    s[b] = (s[b] ^ s[c]).rotate_left(7);
+}
+
+fn words<const N: usize>(bytes: &[u8]) -> [u32; N] {
+    let mut w = [0u32; N];
+    for (word, chunk) in w.iter_mut().zip(bytes.chunks(4)) {
+        *word = u32::from_le_bytes(chunk.try_into().unwrap());
+    }
+    w
+}
+
+pub fn chacha20_block(key: &[u8; 32], counter: u32, nonce: &[u8; 12]) -> [u8; 64] {
+    let mut state = [0u32; 16];
+    state[..4].copy_from_slice(&CONSTANTS);
+    state[4..12].copy_from_slice(&words::<8>(key));
+    state[12] = counter;
+    state[13..].copy_from_slice(&words::<3>(nonce));
+
+    let mut x = state;
+    // 列ラウンドと対角ラウンドを 10 回ずつ
+    for _ in 0..10 {
+        quarter_round(&mut x, 0, 4, 8, 12);
+        quarter_round(&mut x, 1, 5, 9, 13);
+        quarter_round(&mut x, 2, 6, 10, 14);
+        quarter_round(&mut x, 3, 7, 11, 15);
+        quarter_round(&mut x, 0, 5, 10, 15);
+        quarter_round(&mut x, 1, 6, 11, 12);
+        quarter_round(&mut x, 2, 7, 8, 13);
+        quarter_round(&mut x, 3, 4, 9, 14);
+    }
+
+    let mut out = [0u8; 64];
+    for (chunk, (a, b)) in out.chunks_mut(4).zip(x.iter().zip(state)) {
+        chunk.copy_from_slice(&a.wrapping_add(b).to_le_bytes());
+    }
+    out
+}
+
+#[derive(Clone)]
+pub struct ChaCha20 {
+    key: [u8; 32],
+    nonce: [u8; 12],
+    // 次に生成するブロックの番号
+    counter: u32,
+    block: [u8; 64],
+    pos: usize,
+    exhausted: bool,
+}
+
+impl ChaCha20 {
+    pub fn new(key: &[u8; 32], nonce: &[u8; 12], counter: u32) -> Self {
+        ChaCha20 {
+            key: *key,
+            nonce: *nonce,
+            counter,
+            block: [0; 64],
+            pos: 64,
+            exhausted: false,
+        }
+    }
+
+    // 暗号化せずに鍵ストリームそのものを書き出す。乱数生成器が使う
+    pub fn keystream(&mut self, out: &mut [u8]) {
+        for byte in out.iter_mut() {
+            if self.pos == 64 {
+                // 2^32 ブロックを超えると同じ鍵ストリームが繰り返される
+                if self.exhausted {
+                    panic!("ChaCha20 block counter overflow");
+                }
+                self.block = chacha20_block(&self.key, self.counter, &self.nonce);
+                self.counter = self.counter.wrapping_add(1);
+                self.exhausted = self.counter == 0;
+                self.pos = 0;
+            }
+            *byte = self.block[self.pos];
+            self.pos += 1;
+        }
+    }
+
+    pub fn apply_keystream(&mut self, data: &mut [u8]) {
+        for chunk in data.chunks_mut(64) {
+            let mut ks = [0u8; 64];
+            self.keystream(&mut ks[..chunk.len()]);
+            for (b, k) in chunk.iter_mut().zip(ks) {
+                *b ^= k;
+            }
+        }
+    }
+}
+
+// 暗号化と復号は同じ操作
+pub fn chacha20(key: &[u8; 32], nonce: &[u8; 12], counter: u32, data: &[u8]) -> Vec<u8> {
+    let mut out = data.to_vec();
+    ChaCha20::new(key, nonce, counter).apply_keystream(&mut out);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{hex, unhex};
+
+    fn key() -> [u8; 32] {
+        std::array::from_fn(|i| i as u8)
+    }
+
+    #[test]
+    fn test_rfc8439_quarter_round_and_block() {
+        // 2.1.1
+        let mut s = [0u32; 16];
+        s[..4].copy_from_slice(&[0x11111111, 0x01020304, 0x9b8d6f43, 0x01234567]);
+        quarter_round(&mut s, 0, 1, 2, 3);
+        assert_eq!(s[..4], [0xea2a92f4, 0xcb1cf8ce, 0x4581472e, 0x5881c4bb]);
+        // 2.3.2
+        let nonce = unhex("000000090000004a00000000").try_into().unwrap();
+        assert_eq!(
+            hex(&chacha20_block(&key(), 1, &nonce)),
+            "10f1e7e4d13b5915500fdd1fa32071c4c7d1f4c733c068030422aa9ac3d46c4ed2826446079faa0914c2d705d98b02a2b5129cd1de164eb9cbd083e8a2503c4e"
+        );
+    }
+
+    #[test]
+    fn test_rfc8439_encryption() {
+        // 2.4.2
+        let nonce = unhex("000000000000004a00000000").try_into().unwrap();
+        let pt = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.";
+        let ct = chacha20(&key(), &nonce, 1, pt);
+        assert_eq!(hex(&ct), "6e2e359a2568f98041ba0728dd0d6981e97e7aec1d4360c20a27afccfd9fae0bf91b65c5524733ab8f593dabcd62b3571639d624e65152ab8f530c359f0861d807ca0dbf500d6a6156a38e088a22b65e52bc514d16ccf806818ce91ab77937365af90bbf74a35be6b40b8eedf2785e42874d");
+        // 細切れに処理しても鍵ストリームの位置は引き継がれる
+        let mut cipher = ChaCha20::new(&key(), &nonce, 1);
+        let mut data = ct.clone();
+        for chunk in data.chunks_mut(13) {
+            cipher.apply_keystream(chunk);
+        }
+        assert_eq!(data, pt);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_counter_overflow_panics() {
+        let mut cipher = ChaCha20::new(&key(), &[0; 12], u32::MAX);
+        cipher.keystream(&mut [0u8; 65]);
+    }
+}
@@ -0,0 +1,105 @@
+// ChaCha20-Poly1305 (RFC 8439 2.8)。ブロック 0 から Poly1305 の使い捨て鍵を取り、
+// ブロック 1 以降で暗号化する
+use super::chacha20::{chacha20, chacha20_block};
+use super::{check_key, check_nonce, Aead, AeadError};
+use crate::mac::{ct_eq, Poly1305};
+
+pub const TAG_LEN: usize = 16;
+
+#[derive(Clone)]
+pub struct ChaCha20Poly1305 {
+    key: [u8; 32],
+}
+
+fn pad16(mac: &mut Poly1305, len: usize) {
+    if !len.is_multiple_of(16) {
+        mac.update(&[0u8; 16][..16 - len % 16]);
+    }
+}
+
+impl ChaCha20Poly1305 {
+    pub fn new(key: &[u8; 32]) -> Self {
+        ChaCha20Poly1305 { key: *key }
+    }
+
+    fn tag(&self, nonce: &[u8; 12], aad: &[u8], ciphertext: &[u8]) -> [u8; 16] {
+        let block = chacha20_block(&self.key, 0, nonce);
+        let mut mac = Poly1305::new(block[..32].try_into().unwrap());
+        mac.update(aad);
+        pad16(&mut mac, aad.len());
+        mac.update(ciphertext);
+        pad16(&mut mac, ciphertext.len());
+        mac.update(&(aad.len() as u64).to_le_bytes());
+        mac.update(&(ciphertext.len() as u64).to_le_bytes());
+        mac.finalize()
+    }
+
+    pub fn encrypt(&self, nonce: &[u8; 12], aad: &[u8], plaintext: &[u8]) -> Vec<u8> {
+        let mut out = chacha20(&self.key, nonce, 1, plaintext);
+        let tag = self.tag(nonce, aad, &out);
+        out.extend_from_slice(&tag);
+        out
+    }
+
+    pub fn decrypt(
+        &self,
+        nonce: &[u8; 12],
+        aad: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>, AeadError> {
+        if ciphertext.len() < TAG_LEN {
+            return Err(AeadError::InvalidLength);
+        }
+        let (ciphertext, tag) = ciphertext.split_at(ciphertext.len() - TAG_LEN);
+        if !ct_eq(&self.tag(nonce, aad, ciphertext), tag) {
+            return Err(AeadError::InvalidTag);
+        }
+        Ok(chacha20(&self.key, nonce, 1, ciphertext))
+    }
+}
+
+impl Aead for ChaCha20Poly1305 {
+    const KEY_SIZE: usize = 32;
+    const NONCE_SIZE: usize = 12;
+    const TAG_SIZE: usize = TAG_LEN;
+
+    fn new(key: &[u8]) -> Self {
+        check_key::<Self>(key);
+        ChaCha20Poly1305::new(key.try_into().unwrap())
+    }
+
+    fn encrypt(&self, nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> Vec<u8> {
+        check_nonce::<Self>(nonce);
+        ChaCha20Poly1305::encrypt(self, nonce.try_into().unwrap(), aad, plaintext)
+    }
+
+    fn decrypt(&self, nonce: &[u8], aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, AeadError> {
+        check_nonce::<Self>(nonce);
+        ChaCha20Poly1305::decrypt(self, nonce.try_into().unwrap(), aad, ciphertext)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{hex, unhex};
+
+    #[test]
+    fn test_rfc8439_aead_vector() {
+        // 2.8.2
+        let key: [u8; 32] = std::array::from_fn(|i| 0x80 + i as u8);
+        let nonce = unhex("070000004041424344454647").try_into().unwrap();
+        let aad = unhex("50515253c0c1c2c3c4c5c6c7");
+        let pt = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.";
+        let aead = ChaCha20Poly1305::new(&key);
+        let ct = aead.encrypt(&nonce, &aad, pt);
+        assert_eq!(hex(&ct), "d31a8d34648e60db7b86afbc53ef7ec2a4aded51296e08fea9e2b5a736ee62d63dbea45e8ca9671282fafb69da92728b1a71de0a9e060b2905d6a5b67ecd3b3692ddbd7f2d778b8c9803aee328091b58fab324e4fad675945585808b4831d7bc3ff4def08e4b7a9de576d26586cec64b61161ae10b594f09e26a7e902ecbd0600691");
+        assert_eq!(aead.decrypt(&nonce, &aad, &ct).unwrap(), pt);
+        let mut tampered = ct.clone();
+        *tampered.last_mut().unwrap() ^= 0x80;
+        assert_eq!(
+            aead.decrypt(&nonce, &aad, &tampered),
+            Err(AeadError::InvalidTag)
+        );
+    }
+}
@@ -0,0 +1,252 @@
+// DES (FIPS 46-3) と 3DES-EDE。56 ビット鍵は総当たりできる長さなので教育用に限る。
+// 表の位置は仕様書どおり最上位ビットを 1 とした番号
+use super::BlockCipher;
+
+const IP: [u8; 64] = [
+    58, 50, 42, 34, 26, 18, 10, 2, 60, 52, 44, 36, 28, 20, 12, 4, 62, 54, 46, 38, 30, 22, 14, 6,
+    64, 56, 48, 40, 32, 24, 16, 8, 57, 49, 41, 33, 25, 17, 9, 1, 59, 51, 43, 35, 27, 19, 11, 3, 61,
+    53, 45, 37, 29, 21, 13, 5, 63, 55, 47, 39, 31, 23, 15, 7,
+];
+
+const FP: [u8; 64] = [
+    40, 8, 48, 16, 56, 24, 64, 32, 39, 7, 47, 15, 55, 23, 63, 31, 38, 6, 46, 14, 54, 22, 62, 30,
+    37, 5, 45, 13, 53, 21, 61, 29, 36, 4, 44, 12, 52, 20, 60, 28, 35, 3, 43, 11, 51, 19, 59, 27,
+    34, 2, 42, 10, 50, 18, 58, 26, 33, 1, 41, 9, 49, 17, 57, 25,
+];
+
+const E: [u8; 48] = [
+    32, 1, 2, 3, 4, 5, 4, 5, 6, 7, 8, 9, 8, 9, 10, 11, 12, 13, 12, 13, 14, 15, 16, 17, 16, 17, 18,
+    19, 20, 21, 20, 21, 22, 23, 24, 25, 24, 25, 26, 27, 28, 29, 28, 29, 30, 31, 32, 1,
+];
+
+const P: [u8; 32] = [
+    16, 7, 20, 21, 29, 12, 28, 17, 1, 15, 23, 26, 5, 18, 31, 10, 2, 8, 24, 14, 32, 27, 3, 9, 19,
+    13, 30, 6, 22, 11, 4, 25,
+];
+
+const PC1: [u8; 56] = [
+    57, 49, 41, 33, 25, 17, 9, 1, 58, 50, 42, 34, 26, 18, 10, 2, 59, 51, 43, 35, 27, 19, 11, 3, 60,
+    52, 44, 36, 63, 55, 47, 39, 31, 23, 15, 7, 62, 54, 46, 38, 30, 22, 14, 6, 61, 53, 45, 37, 29,
+    21, 13, 5, 28, 20, 12, 4,
+];
+
+const PC2: [u8; 48] = [
+    14, 17, 11, 24, 1, 5, 3, 28, 15, 6, 21, 10, 23, 19, 12, 4, 26, 8, 16, 7, 27, 20, 13, 2, 41, 52,
+    31, 37, 47, 55, 30, 40, 51, 45, 33, 48, 44, 49, 39, 56, 34, 53, 46, 42, 50, 36, 29, 32,
+];
+
+const SHIFTS: [u32; 16] = [1, 1, 2, 2, 2, 2, 2, 2, 1, 2, 2, 2, 2, 2, 2, 1];
+
+const SBOX: [[u8; 64]; 8] = [
+    [
+        14, 4, 13, 1, 2, 15, 11, 8, 3, 10, 6, 12, 5, 9, 0, 7, 0, 15, 7, 4, 14, 2, 13, 1, 10, 6, 12,
+        11, 9, 5, 3, 8, 4, 1, 14, 8, 13, 6, 2, 11, 15, 12, 9, 7, 3, 10, 5, 0, 15, 12, 8, 2, 4, 9,
+        1, 7, 5, 11, 3, 14, 10, 0, 6, 13,
+    ],
+    [
+        15, 1, 8, 14, 6, 11, 3, 4, 9, 7, 2, 13, 12, 0, 5, 10, 3, 13, 4, 7, 15, 2, 8, 14, 12, 0, 1,
+        10, 6, 9, 11, 5, 0, 14, 7, 11, 10, 4, 13, 1, 5, 8, 12, 6, 9, 3, 2, 15, 13, 8, 10, 1, 3, 15,
+        4, 2, 11, 6, 7, 12, 0, 5, 14, 9,
+    ],
+    [
+        10, 0, 9, 14, 6, 3, 15, 5, 1, 13, 12, 7, 11, 4, 2, 8, 13, 7, 0, 9, 3, 4, 6, 10, 2, 8, 5,
+        14, 12, 11, 15, 1, 13, 6, 4, 9, 8, 15, 3, 0, 11, 1, 2, 12, 5, 10, 14, 7, 1, 10, 13, 0, 6,
+        9, 8, 7, 4, 15, 14, 3, 11, 5, 2, 12,
+    ],
+    [
+        7, 13, 14, 3, 0, 6, 9, 10, 1, 2, 8, 5, 11, 12, 4, 15, 13, 8, 11, 5, 6, 15, 0, 3, 4, 7, 2,
+        12, 1, 10, 14, 9, 10, 6, 9, 0, 12, 11, 7, 13, 15, 1, 3, 14, 5, 2, 8, 4, 3, 15, 0, 6, 10, 1,
+        13, 8, 9, 4, 5, 11, 12, 7, 2, 14,
+    ],
+    [
+        2, 12, 4, 1, 7, 10, 11, 6, 8, 5, 3, 15, 13, 0, 14, 9, 14, 11, 2, 12, 4, 7, 13, 1, 5, 0, 15,
+        10, 3, 9, 8, 6, 4, 2, 1, 11, 10, 13, 7, 8, 15, 9, 12, 5, 6, 3, 0, 14, 11, 8, 12, 7, 1, 14,
+        2, 13, 6, 15, 0, 9, 10, 4, 5, 3,
+    ],
+    [
+        12, 1, 10, 15, 9, 2, 6, 8, 0, 13, 3, 4, 14, 7, 5, 11, 10, 15, 4, 2, 7, 12, 9, 5, 6, 1, 13,
+        14, 0, 11, 3, 8, 9, 14, 15, 5, 2, 8, 12, 3, 7, 0, 4, 10, 1, 13, 11, 6, 4, 3, 2, 12, 9, 5,
+        15, 10, 11, 14, 1, 7, 6, 0, 8, 13,
+    ],
+    [
+        4, 11, 2, 14, 15, 0, 8, 13, 3, 12, 9, 7, 5, 10, 6, 1, 13, 0, 11, 7, 4, 9, 1, 10, 14, 3, 5,
+        12, 2, 15, 8, 6, 1, 4, 11, 13, 12, 3, 7, 14, 10, 15, 6, 8, 0, 5, 9, 2, 6, 11, 13, 8, 1, 4,
+        10, 7, 9, 5, 0, 15, 14, 2, 3, 12,
+    ],
+    [
+        13, 2, 8, 4, 6, 15, 11, 1, 10, 9, 3, 14, 5, 0, 12, 7, 1, 15, 13, 8, 10, 3, 7, 4, 12, 5, 6,
+        11, 0, 14, 9, 2, 7, 11, 4, 1, 9, 12, 14, 2, 0, 6, 10, 13, 15, 3, 5, 8, 2, 1, 14, 7, 4, 10,
+        8, 13, 15, 12, 9, 0, 3, 5, 6, 11,
+    ],
+];
+
+// in_bits ビットの入力から table の順にビットを取り出す
+fn permute(input: u64, in_bits: u32, table: &[u8]) -> u64 {
+    table.iter().fold(0, |acc, &pos| {
+        (acc << 1) | ((input >> (in_bits - pos as u32)) & 1)
+    })
+}
+
+fn feistel(r: u32, subkey: u64) -> u32 {
+    let x = permute(r as u64, 32, &E) ^ subkey;
+    let mut out = 0u32;
+    for (i, sbox) in SBOX.iter().enumerate() {
+        let six = ((x >> (42 - 6 * i)) & 0x3f) as usize;
+        // 両端のビットが行、中央の 4 ビットが列
+        let row = ((six >> 4) & 2) | (six & 1);
+        let col = (six >> 1) & 0xf;
+        out = (out << 4) | sbox[row * 16 + col] as u32;
+    }
+    permute(out as u64, 32, &P) as u32
+}
+
+#[derive(Clone)]
+pub struct Des {
+    subkeys: [u64; 16],
+}
+
+impl Des {
+    pub const BLOCK_SIZE: usize = 8;
+
+    // 各バイトの最下位ビットはパリティで、鍵としては使われない
+    pub fn new(key: &[u8; 8]) -> Self {
+        let cd = permute(u64::from_be_bytes(*key), 64, &PC1);
+        let mask = (1u64 << 28) - 1;
+        let (mut c, mut d) = (cd >> 28, cd & mask);
+        let mut subkeys = [0u64; 16];
+        for (subkey, &shift) in subkeys.iter_mut().zip(&SHIFTS) {
+            c = ((c << shift) | (c >> (28 - shift))) & mask;
+            d = ((d << shift) | (d >> (28 - shift))) & mask;
+            *subkey = permute((c << 28) | d, 56, &PC2);
+        }
+        Des { subkeys }
+    }
+
+    fn crypt(&self, block: u64, decrypt: bool) -> u64 {
+        let x = permute(block, 64, &IP);
+        let (mut l, mut r) = ((x >> 32) as u32, x as u32);
+        for i in 0..16 {
+            let k = if decrypt {
+                self.subkeys[15 - i]
+            } else {
+                self.subkeys[i]
+            };
+            (l, r) = (r, l ^ feistel(r, k));
+        }
+        // 最後のラウンドの後は左右を入れ替えない
+        permute(((r as u64) << 32) | l as u64, 64, &FP)
+    }
+
+    pub fn encrypt_u64(&self, block: u64) -> u64 {
+        self.crypt(block, false)
+    }
+
+    pub fn decrypt_u64(&self, block: u64) -> u64 {
+        self.crypt(block, true)
+    }
+}
+
+// 3DES-EDE: E_k3(D_k2(E_k1(x)))。16 バイト鍵なら k3 = k1 (2 鍵 3DES)
+#[derive(Clone)]
+pub struct TripleDes {
+    k1: Des,
+    k2: Des,
+    k3: Des,
+}
+
+impl TripleDes {
+    pub fn new(key: &[u8]) -> Self {
+        let part = |i: usize| Des::new(key[8 * i..8 * i + 8].try_into().unwrap());
+        match key.len() {
+            16 => TripleDes {
+                k1: part(0),
+                k2: part(1),
+                k3: part(0),
+            },
+            24 => TripleDes {
+                k1: part(0),
+                k2: part(1),
+                k3: part(2),
+            },
+            n => panic!("Invalid 3DES key length {}", n),
+        }
+    }
+
+    pub fn encrypt_u64(&self, block: u64) -> u64 {
+        self.k3
+            .encrypt_u64(self.k2.decrypt_u64(self.k1.encrypt_u64(block)))
+    }
+
+    pub fn decrypt_u64(&self, block: u64) -> u64 {
+        self.k1
+            .decrypt_u64(self.k2.encrypt_u64(self.k3.decrypt_u64(block)))
+    }
+}
+
+macro_rules! impl_block_cipher {
+    ($name:ty) => {
+        impl BlockCipher for $name {
+            const BLOCK_SIZE: usize = 8;
+
+            fn encrypt_block(&self, block: &mut [u8]) {
+                let x = u64::from_be_bytes(block.try_into().expect("DES block must be 8 bytes"));
+                block.copy_from_slice(&self.encrypt_u64(x).to_be_bytes());
+            }
+
+            fn decrypt_block(&self, block: &mut [u8]) {
+                let x = u64::from_be_bytes(block.try_into().expect("DES block must be 8 bytes"));
+                block.copy_from_slice(&self.decrypt_u64(x).to_be_bytes());
+            }
+        }
+    };
+}
+
+impl_block_cipher!(Des);
+impl_block_cipher!(TripleDes);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::symmetric::{cbc_decrypt, cbc_encrypt};
+    use crate::test_util::unhex;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_des_and_3des_vectors() {
+        // よく知られた DES の例
+        let des = Des::new(&0x133457799bbcdff1u64.to_be_bytes());
+        assert_eq!(des.encrypt_u64(0x0123456789abcdef), 0x85e813540f0ab405);
+        assert_eq!(des.decrypt_u64(0x85e813540f0ab405), 0x0123456789abcdef);
+        // パリティビットだけが違う鍵は同じ鍵
+        let flipped = Des::new(&0x123456789abcdef0u64.to_be_bytes());
+        assert_eq!(flipped.encrypt_u64(0), des.encrypt_u64(0));
+
+        // 3 つの鍵がすべて同じなら単一の DES に退化する
+        let same = [0x133457799bbcdff1u64.to_be_bytes(); 3].concat();
+        assert_eq!(
+            TripleDes::new(&same).encrypt_u64(0x0123456789abcdef),
+            0x85e813540f0ab405
+        );
+        let tdes = TripleDes::new(&unhex("0123456789abcdef23456789abcdef01456789abcdef0123"));
+        let block = u64::from_be_bytes(*b"The qufc");
+        assert_eq!(tdes.encrypt_u64(block), 0xa826fd8ce53b855f);
+        let iv = [0u8; 8];
+        let ct = cbc_encrypt(&tdes, &iv, b"sixteen byte msg");
+        assert_eq!(cbc_decrypt(&tdes, &iv, &ct), b"sixteen byte msg");
+    }
+
+    #[test]
+    fn test_meet_in_the_middle_on_double_des() {
+        // 鍵空間を 2^10 に絞った 2 重 DES。全組み合わせ 2^20 ではなく 2 × 2^10 回で鍵が見つかる
+        // 各バイトの最下位ビットはパリティなので避けて 10 ビットを埋め込む
+        let key = |i: u64| Des::new(&((i & 0x7f) << 1 | (i >> 7) << 9).to_be_bytes());
+        let (k1, k2) = (345, 678);
+        let pt = 0x0123456789abcdefu64;
+        let ct = key(k2).encrypt_u64(key(k1).encrypt_u64(pt));
+        let forward: HashMap<u64, u64> = (0..1024).map(|i| (key(i).encrypt_u64(pt), i)).collect();
+        let found: Vec<(u64, u64)> = (0..1024)
+            .filter_map(|j| forward.get(&key(j).decrypt_u64(ct)).map(|&i| (i, j)))
+            .collect();
+        assert!(found.contains(&(k1, k2)));
+    }
+}
@@ -0,0 +1,128 @@
+// 汎用の Feistel 構造。ラウンド関数が可逆でなくても全体は置換になる
+use crate::rand::RngCore;
+
+// f(round, right) は右半分と同じ長さの値を返す。round を使って鍵スケジュールを表す
+pub struct Feistel<F: Fn(usize, &[u8]) -> Vec<u8>> {
+    half_len: usize,
+    rounds: usize,
+    f: F,
+}
+
+impl<F: Fn(usize, &[u8]) -> Vec<u8>> Feistel<F> {
+    pub fn new(half_len: usize, rounds: usize, f: F) -> Self {
+        if half_len == 0 || rounds == 0 {
+            panic!("Feistel network needs a non-empty half and at least one round");
+        }
+        Feistel {
+            half_len,
+            rounds,
+            f,
+        }
+    }
+
+    pub fn block_size(&self) -> usize {
+        2 * self.half_len
+    }
+
+    fn round(&self, round: usize, l: &mut [u8], r: &[u8]) {
+        let out = (self.f)(round, r);
+        if out.len() != self.half_len {
+            panic!(
+                "Round function returned {} bytes, expected {}",
+                out.len(),
+                self.half_len
+            );
+        }
+        for (a, b) in l.iter_mut().zip(out) {
+            *a ^= b;
+        }
+    }
+
+    // (L, R) -> (R, L ^ f(R)) を繰り返す。最後に左右を戻さないので復号は鍵順を逆にするだけ
+    pub fn encrypt_block(&self, block: &mut [u8]) {
+        if block.len() != self.block_size() {
+            panic!("Block must be {} bytes", self.block_size());
+        }
+        let (l, r) = block.split_at_mut(self.half_len);
+        for i in 0..self.rounds {
+            self.round(i, l, r);
+            l.swap_with_slice(r);
+        }
+        l.swap_with_slice(r);
+    }
+
+    pub fn decrypt_block(&self, block: &mut [u8]) {
+        if block.len() != self.block_size() {
+            panic!("Block must be {} bytes", self.block_size());
+        }
+        let (l, r) = block.split_at_mut(self.half_len);
+        for i in (0..self.rounds).rev() {
+            self.round(i, l, r);
+            l.swap_with_slice(r);
+        }
+        l.swap_with_slice(r);
+    }
+}
+
+// 入力の 1 ビットを反転したとき出力ビットが反転する割合の平均。理想的には 0.5
+pub fn avalanche<F, R>(cipher: &Feistel<F>, samples: usize, rng: &mut R) -> f64
+where
+    F: Fn(usize, &[u8]) -> Vec<u8>,
+    R: RngCore,
+{
+    let n = cipher.block_size();
+    let mut flipped = 0u64;
+    for _ in 0..samples {
+        let mut x = vec![0u8; n];
+        rng.fill_bytes(&mut x);
+        let mut base = x.clone();
+        cipher.encrypt_block(&mut base);
+        for bit in 0..8 * n {
+            let mut y = x.clone();
+            y[bit / 8] ^= 1 << (bit % 8);
+            cipher.encrypt_block(&mut y);
+            flipped += base
+                .iter()
+                .zip(&y)
+                .map(|(a, b)| (a ^ b).count_ones() as u64)
+                .sum::<u64>();
+        }
+    }
+    flipped as f64 / (samples * 8 * n * 8 * n) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::sha256;
+    use crate::rand::OsRng;
+
+    fn toy_cipher(rounds: usize) -> Feistel<impl Fn(usize, &[u8]) -> Vec<u8>> {
+        Feistel::new(4, rounds, |round, r| {
+            sha256(&[&[round as u8][..], b"toy key", r].concat())[..4].to_vec()
+        })
+    }
+
+    #[test]
+    fn test_roundtrip_with_any_round_count() {
+        for rounds in [1, 2, 3, 7, 16] {
+            let cipher = toy_cipher(rounds);
+            let mut block = *b"feistel!";
+            cipher.encrypt_block(&mut block);
+            assert_ne!(&block, b"feistel!");
+            cipher.decrypt_block(&mut block);
+            assert_eq!(&block, b"feistel!");
+        }
+    }
+
+    #[test]
+    fn test_diffusion_grows_with_rounds() {
+        // 1 ラウンドでは左半分のビット反転はそのまま 1 ビットの反転にしかならない
+        let one = avalanche(&toy_cipher(1), 8, &mut OsRng);
+        let two = avalanche(&toy_cipher(2), 8, &mut OsRng);
+        let many = avalanche(&toy_cipher(8), 8, &mut OsRng);
+        assert!(one < 0.2);
+        assert!(one < two && two < many);
+        assert!((many - 0.5).abs() < 0.05);
+    }
+}
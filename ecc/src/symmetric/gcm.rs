@@ -0,0 +1,204 @@
+// AES-GCM (NIST SP 800-38D)。CTR で暗号化し、GF(2^128) 上の多項式評価 GHASH で認証する
+use super::{check_key, check_nonce, Aead, AeadError, Aes};
+use crate::binary_field::Gf128;
+use crate::mac::ct_eq;
+
+pub const TAG_LEN: usize = 16;
+
+// H を評価点として、16 バイトずつ区切った入力を Horner 法で評価する
+fn ghash_update(h: &Gf128, y: &mut Gf128, data: &[u8]) {
+    for chunk in data.chunks(16) {
+        let mut block = [0u8; 16];
+        block[..chunk.len()].copy_from_slice(chunk);
+        *y = y.add(&Gf128::from_bytes(&block)).mul(h);
+    }
+}
+
+pub fn ghash(h: &[u8; 16], aad: &[u8], ciphertext: &[u8]) -> [u8; 16] {
+    let h = Gf128::from_bytes(h);
+    let mut y = Gf128::ZERO;
+    ghash_update(&h, &mut y, aad);
+    ghash_update(&h, &mut y, ciphertext);
+    let mut lengths = [0u8; 16];
+    lengths[..8].copy_from_slice(&(aad.len() as u64 * 8).to_be_bytes());
+    lengths[8..].copy_from_slice(&(ciphertext.len() as u64 * 8).to_be_bytes());
+    ghash_update(&h, &mut y, &lengths);
+    y.to_bytes()
+}
+
+#[derive(Clone)]
+pub struct AesGcm {
+    aes: Aes,
+    h: [u8; 16],
+}
+
+impl AesGcm {
+    pub fn new(key: &[u8]) -> Self {
+        let aes = Aes::new(key);
+        let mut h = [0u8; 16];
+        aes.encrypt_block(&mut h);
+        AesGcm { aes, h }
+    }
+
+    // 96 ビットの nonce はそのまま使い、それ以外は GHASH で 16 バイトにする
+    fn initial_counter(&self, nonce: &[u8]) -> [u8; 16] {
+        if nonce.is_empty() {
+            panic!("GCM nonce must not be empty");
+        }
+        if nonce.len() == 12 {
+            let mut j0 = [0u8; 16];
+            j0[..12].copy_from_slice(nonce);
+            j0[15] = 1;
+            j0
+        } else {
+            ghash(&self.h, &[], nonce)
+        }
+    }
+
+    // 下位 32 ビットだけを増やす
+    fn apply_ctr(&self, j0: &[u8; 16], data: &mut [u8]) {
+        let mut counter = *j0;
+        for chunk in data.chunks_mut(16) {
+            let c = u32::from_be_bytes(counter[12..].try_into().unwrap()).wrapping_add(1);
+            counter[12..].copy_from_slice(&c.to_be_bytes());
+            let mut keystream = counter;
+            self.aes.encrypt_block(&mut keystream);
+            for (b, k) in chunk.iter_mut().zip(keystream) {
+                *b ^= k;
+            }
+        }
+    }
+
+    fn tag(&self, j0: &[u8; 16], aad: &[u8], ciphertext: &[u8]) -> [u8; 16] {
+        let mut tag = ghash(&self.h, aad, ciphertext);
+        let mut mask = *j0;
+        self.aes.encrypt_block(&mut mask);
+        for (t, m) in tag.iter_mut().zip(mask) {
+            *t ^= m;
+        }
+        tag
+    }
+
+    // 暗号文の後ろに 16 バイトのタグを付けて返す
+    pub fn encrypt(&self, nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> Vec<u8> {
+        let j0 = self.initial_counter(nonce);
+        let mut out = plaintext.to_vec();
+        self.apply_ctr(&j0, &mut out);
+        let tag = self.tag(&j0, aad, &out);
+        out.extend_from_slice(&tag);
+        out
+    }
+
+    pub fn decrypt(
+        &self,
+        nonce: &[u8],
+        aad: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>, AeadError> {
+        if ciphertext.len() < TAG_LEN {
+            return Err(AeadError::InvalidLength);
+        }
+        let (ciphertext, tag) = ciphertext.split_at(ciphertext.len() - TAG_LEN);
+        let j0 = self.initial_counter(nonce);
+        // タグを確認してから復号する
+        if !ct_eq(&self.tag(&j0, aad, ciphertext), tag) {
+            return Err(AeadError::InvalidTag);
+        }
+        let mut out = ciphertext.to_vec();
+        self.apply_ctr(&j0, &mut out);
+        Ok(out)
+    }
+}
+
+// 鍵長を型で固定したもの。Aead として使う
+macro_rules! fixed_key_gcm {
+    ($name:ident, $key_len:expr) => {
+        #[derive(Clone)]
+        pub struct $name(AesGcm);
+
+        impl Aead for $name {
+            const KEY_SIZE: usize = $key_len;
+            const NONCE_SIZE: usize = 12;
+            const TAG_SIZE: usize = TAG_LEN;
+
+            fn new(key: &[u8]) -> Self {
+                check_key::<Self>(key);
+                $name(AesGcm::new(key))
+            }
+
+            fn encrypt(&self, nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> Vec<u8> {
+                check_nonce::<Self>(nonce);
+                self.0.encrypt(nonce, aad, plaintext)
+            }
+
+            fn decrypt(
+                &self,
+                nonce: &[u8],
+                aad: &[u8],
+                ciphertext: &[u8],
+            ) -> Result<Vec<u8>, AeadError> {
+                check_nonce::<Self>(nonce);
+                self.0.decrypt(nonce, aad, ciphertext)
+            }
+        }
+    };
+}
+
+fixed_key_gcm!(Aes128Gcm, 16);
+fixed_key_gcm!(Aes256Gcm, 32);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{hex, unhex};
+
+    #[test]
+    fn test_gcm_spec_vectors() {
+        // GCM 仕様書のテストケース 1, 2
+        let gcm = AesGcm::new(&[0u8; 16]);
+        assert_eq!(
+            hex(&gcm.encrypt(&[0u8; 12], &[], &[])),
+            "58e2fccefa7e3061367f1d57a4e7455a"
+        );
+        assert_eq!(
+            hex(&gcm.encrypt(&[0u8; 12], &[], &[0u8; 16])),
+            "0388dace60b6a392f328c2b971b2fe78ab6e47d42cec13bdf53a67b21257bddf"
+        );
+
+        // テストケース 4 (AAD あり、端数ブロック) と 5 (64 ビットの nonce)
+        let gcm = AesGcm::new(&unhex("feffe9928665731c6d6a8f9467308308"));
+        let pt = unhex("d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a721c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b39");
+        let aad = unhex("feedfacedeadbeeffeedfacedeadbeefabaddad2");
+        let nonce = unhex("cafebabefacedbaddecaf888");
+        let ct = gcm.encrypt(&nonce, &aad, &pt);
+        assert_eq!(hex(&ct), "42831ec2217774244b7221b784d0d49ce3aa212f2c02a4e035c17e2329aca12e21d514b25466931c7d8f6a5aac84aa051ba30b396a0aac973d58e0915bc94fbc3221a5db94fae95ae7121a47");
+        assert_eq!(gcm.decrypt(&nonce, &aad, &ct), Ok(pt.clone()));
+        let short_nonce = unhex("cafebabefacedbad");
+        assert_eq!(hex(&gcm.encrypt(&short_nonce, &aad, &pt)), "61353b4c2806934a777ff51fa22a4755699b2a714fcdc6f83766e5f97b6c742373806900e49f24b22b097544d4896b424989b5e1ebac0f07c23f45983612d2e79e3b0785561be14aaca2fccb");
+    }
+
+    #[test]
+    fn test_tampering_is_detected() {
+        let gcm = AesGcm::new(&[7u8; 32]);
+        let nonce = [1u8; 12];
+        let ct = gcm.encrypt(&nonce, b"header", b"attack at dawn");
+        let mut flipped = ct.clone();
+        flipped[0] ^= 1;
+        assert_eq!(
+            gcm.decrypt(&nonce, b"header", &flipped),
+            Err(AeadError::InvalidTag)
+        );
+        assert_eq!(
+            gcm.decrypt(&nonce, b"other", &ct),
+            Err(AeadError::InvalidTag)
+        );
+        assert_eq!(
+            gcm.decrypt(&nonce, b"header", &ct[..10]),
+            Err(AeadError::InvalidLength)
+        );
+        assert_eq!(
+            gcm.decrypt(&nonce, b"header", &ct).unwrap(),
+            b"attack at dawn"
+        );
+    }
+}
@@ -0,0 +1,186 @@
+// AES 鍵ラップ (RFC 3394) とパディング付き鍵ラップ (RFC 5649)。
+// 鍵素材のように乱数的なデータを、nonce なしで決定的に認証付き暗号化する
+use super::Aes;
+use crate::mac::ct_eq;
+
+const DEFAULT_IV: [u8; 8] = [0xa6; 8];
+const PADDED_IV_PREFIX: [u8; 4] = [0xa6, 0x59, 0x59, 0xa6];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyWrapError {
+    InvalidLength,
+    // 鍵が違うか改竄されている
+    IntegrityCheckFailed,
+}
+
+fn wrap_blocks(aes: &Aes, iv: [u8; 8], blocks: &mut [[u8; 8]]) -> [u8; 8] {
+    let n = blocks.len();
+    let mut a = iv;
+    for j in 0..6 {
+        for (i, r) in blocks.iter_mut().enumerate() {
+            let mut b = [0u8; 16];
+            b[..8].copy_from_slice(&a);
+            b[8..].copy_from_slice(r);
+            aes.encrypt_block(&mut b);
+            let t = (n * j + i + 1) as u64;
+            a = (u64::from_be_bytes(b[..8].try_into().unwrap()) ^ t).to_be_bytes();
+            r.copy_from_slice(&b[8..]);
+        }
+    }
+    a
+}
+
+fn unwrap_blocks(aes: &Aes, mut a: [u8; 8], blocks: &mut [[u8; 8]]) -> [u8; 8] {
+    let n = blocks.len();
+    for j in (0..6).rev() {
+        for (i, r) in blocks.iter_mut().enumerate().rev() {
+            let t = (n * j + i + 1) as u64;
+            let mut b = [0u8; 16];
+            b[..8].copy_from_slice(&(u64::from_be_bytes(a) ^ t).to_be_bytes());
+            b[8..].copy_from_slice(r);
+            aes.decrypt_block(&mut b);
+            a.copy_from_slice(&b[..8]);
+            r.copy_from_slice(&b[8..]);
+        }
+    }
+    a
+}
+
+fn to_blocks(data: &[u8]) -> Vec<[u8; 8]> {
+    data.chunks(8).map(|c| c.try_into().unwrap()).collect()
+}
+
+// 鍵は 8 バイトの倍数で 16 バイト以上
+pub fn aes_key_wrap(kek: &[u8], key: &[u8]) -> Vec<u8> {
+    if key.len() < 16 || !key.len().is_multiple_of(8) {
+        panic!("Key to wrap must be a multiple of 8 bytes and at least 16 bytes");
+    }
+    let mut blocks = to_blocks(key);
+    let a = wrap_blocks(&Aes::new(kek), DEFAULT_IV, &mut blocks);
+    [a.to_vec(), blocks.concat()].concat()
+}
+
+pub fn aes_key_unwrap(kek: &[u8], wrapped: &[u8]) -> Result<Vec<u8>, KeyWrapError> {
+    if wrapped.len() < 24 || !wrapped.len().is_multiple_of(8) {
+        return Err(KeyWrapError::InvalidLength);
+    }
+    let mut blocks = to_blocks(&wrapped[8..]);
+    let a = unwrap_blocks(
+        &Aes::new(kek),
+        wrapped[..8].try_into().unwrap(),
+        &mut blocks,
+    );
+    if !ct_eq(&a, &DEFAULT_IV) {
+        return Err(KeyWrapError::IntegrityCheckFailed);
+    }
+    Ok(blocks.concat())
+}
+
+// 任意長 (1 バイト以上) を扱える版。IV の後半 4 バイトに元の長さを入れる
+pub fn aes_key_wrap_with_padding(kek: &[u8], key: &[u8]) -> Vec<u8> {
+    if key.is_empty() || key.len() > u32::MAX as usize {
+        panic!("Key to wrap must be 1 to 2^32 - 1 bytes");
+    }
+    let mut iv = [0u8; 8];
+    iv[..4].copy_from_slice(&PADDED_IV_PREFIX);
+    iv[4..].copy_from_slice(&(key.len() as u32).to_be_bytes());
+    let mut padded = key.to_vec();
+    padded.resize(key.len().div_ceil(8) * 8, 0);
+    let aes = Aes::new(kek);
+    if padded.len() == 8 {
+        // 1 ブロックだけなら IV と合わせて 1 回暗号化する
+        let mut b = [0u8; 16];
+        b[..8].copy_from_slice(&iv);
+        b[8..].copy_from_slice(&padded);
+        aes.encrypt_block(&mut b);
+        return b.to_vec();
+    }
+    let mut blocks = to_blocks(&padded);
+    let a = wrap_blocks(&aes, iv, &mut blocks);
+    [a.to_vec(), blocks.concat()].concat()
+}
+
+pub fn aes_key_unwrap_with_padding(kek: &[u8], wrapped: &[u8]) -> Result<Vec<u8>, KeyWrapError> {
+    if wrapped.len() < 16 || !wrapped.len().is_multiple_of(8) {
+        return Err(KeyWrapError::InvalidLength);
+    }
+    let aes = Aes::new(kek);
+    let (a, padded) = if wrapped.len() == 16 {
+        let mut b: [u8; 16] = wrapped.try_into().unwrap();
+        aes.decrypt_block(&mut b);
+        (b[..8].try_into().unwrap(), b[8..].to_vec())
+    } else {
+        let mut blocks = to_blocks(&wrapped[8..]);
+        let a = unwrap_blocks(&aes, wrapped[..8].try_into().unwrap(), &mut blocks);
+        (a, blocks.concat())
+    };
+    // 長さが範囲内で、埋めた部分がすべて 0 であることを確かめる
+    let len = u32::from_be_bytes(a[4..].try_into().unwrap()) as usize;
+    let valid = ct_eq(&a[..4], &PADDED_IV_PREFIX)
+        && len + 8 > padded.len()
+        && len <= padded.len()
+        && padded[len..].iter().all(|&b| b == 0);
+    if !valid {
+        return Err(KeyWrapError::IntegrityCheckFailed);
+    }
+    Ok(padded[..len].to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{hex, unhex};
+
+    #[test]
+    fn test_rfc3394_vectors() {
+        // 4.1: 128 ビットの KEK で 128 ビットの鍵
+        let kek = unhex("000102030405060708090a0b0c0d0e0f");
+        let key = unhex("00112233445566778899aabbccddeeff");
+        let wrapped = aes_key_wrap(&kek, &key);
+        assert_eq!(
+            hex(&wrapped),
+            "1fa68b0a8112b447aef34bd8fb5a7b829d3e862371d2cfe5"
+        );
+        assert_eq!(aes_key_unwrap(&kek, &wrapped), Ok(key));
+        // 4.6: 256 ビットの KEK で 256 ビットの鍵
+        let kek = unhex("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f");
+        let key = unhex("00112233445566778899aabbccddeeff000102030405060708090a0b0c0d0e0f");
+        let wrapped = aes_key_wrap(&kek, &key);
+        assert_eq!(
+            hex(&wrapped),
+            "28c9f404c4b810f4cbccb35cfb87f8263f5786e2d80ed326cbc7f0e71a99f43bfb988b9b7a02dd21"
+        );
+        let mut tampered = wrapped.clone();
+        tampered[30] ^= 1;
+        assert_eq!(
+            aes_key_unwrap(&kek, &tampered),
+            Err(KeyWrapError::IntegrityCheckFailed)
+        );
+        assert_eq!(
+            aes_key_unwrap(&kek, &wrapped[..20]),
+            Err(KeyWrapError::InvalidLength)
+        );
+    }
+
+    #[test]
+    fn test_rfc5649_vectors() {
+        let kek = unhex("5840df6e29b02af1ab493b705bf16ea1ae8338f4dcc176a8");
+        let key = unhex("c37b7e6492584340bed12207808941155068f738");
+        let wrapped = aes_key_wrap_with_padding(&kek, &key);
+        assert_eq!(
+            hex(&wrapped),
+            "138bdeaa9b8fa7fc61f97742e72248ee5ae6ae5360d1ae6a5f54f373fa543b6a"
+        );
+        assert_eq!(aes_key_unwrap_with_padding(&kek, &wrapped), Ok(key));
+        // 1 ブロックに収まる場合
+        let short = unhex("466f7250617369");
+        let wrapped = aes_key_wrap_with_padding(&kek, &short);
+        assert_eq!(hex(&wrapped), "afbeb0f07dfbf5419200f2ccb50bb24f");
+        assert_eq!(aes_key_unwrap_with_padding(&kek, &wrapped), Ok(short));
+        // パディングなしの形式とは互換性がない
+        assert_eq!(
+            aes_key_unwrap(&kek, &aes_key_wrap_with_padding(&kek, &[7u8; 24])),
+            Err(KeyWrapError::IntegrityCheckFailed)
+        );
+    }
+}
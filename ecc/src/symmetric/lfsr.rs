@@ -0,0 +1,135 @@
+// 線形帰還シフトレジスタ (LFSR) と Geffe 生成器。どちらも鍵ストリームの線形複雑度が小さく、
+// Berlekamp–Massey 法で短い観測から全体を復元できる
+use std::collections::VecDeque;
+
+// 接続多項式 C(x) = 1 + c_1 x + ... + c_L x^L に対して s_n = Σ c_i s_{n-i}
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lfsr {
+    taps: Vec<bool>,
+    // 先頭が次に出力するビット
+    state: VecDeque<bool>,
+}
+
+impl Lfsr {
+    // taps[i] が c_{i+1}、seed は最初に出力される L ビット
+    pub fn new(taps: &[bool], seed: &[bool]) -> Self {
+        if taps.len() != seed.len() {
+            panic!("LFSR seed must have one bit per stage");
+        }
+        Lfsr {
+            taps: taps.to_vec(),
+            state: seed.iter().copied().collect(),
+        }
+    }
+
+    // u64 のビット列から作る。taps のビット i が c_{i+1}、seed のビット i が i 番目の出力
+    pub fn from_bits(len: usize, taps: u64, seed: u64) -> Self {
+        if len == 0 || len > 64 {
+            panic!("LFSR length must be 1 to 64");
+        }
+        let bits = |x: u64| (0..len).map(|i| (x >> i) & 1 == 1).collect::<Vec<bool>>();
+        Lfsr::new(&bits(taps), &bits(seed))
+    }
+
+    pub fn len(&self) -> usize {
+        self.taps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.taps.is_empty()
+    }
+
+    pub fn next_bit(&mut self) -> bool {
+        if self.taps.is_empty() {
+            return false;
+        }
+        // state[j] = s_{n+j} なので s_{n+L-i} は state[L - i]
+        let l = self.taps.len();
+        let feedback = self
+            .taps
+            .iter()
+            .enumerate()
+            .fold(false, |acc, (i, &c)| acc ^ (c & self.state[l - 1 - i]));
+        let out = self.state.pop_front().unwrap();
+        self.state.push_back(feedback);
+        out
+    }
+
+    pub fn bits(&mut self, n: usize) -> Vec<bool> {
+        (0..n).map(|_| self.next_bit()).collect()
+    }
+}
+
+// 3 本の LFSR の出力を x2 で選択する: z = x1 x2 ⊕ (1 ⊕ x2) x3
+#[derive(Debug, Clone)]
+pub struct Geffe {
+    lfsrs: [Lfsr; 3],
+}
+
+impl Geffe {
+    pub fn new(a: Lfsr, b: Lfsr, c: Lfsr) -> Self {
+        Geffe { lfsrs: [a, b, c] }
+    }
+
+    pub fn next_bit(&mut self) -> bool {
+        let [x1, x2, x3] = [0, 1, 2].map(|i| self.lfsrs[i].next_bit());
+        if x2 {
+            x1
+        } else {
+            x3
+        }
+    }
+
+    pub fn bits(&mut self, n: usize) -> Vec<bool> {
+        (0..n).map(|_| self.next_bit()).collect()
+    }
+
+    // 各バイトは下位ビットから順に鍵ストリームを XOR する
+    pub fn apply_keystream(&mut self, data: &mut [u8]) {
+        for byte in data.iter_mut() {
+            for i in 0..8 {
+                *byte ^= (self.next_bit() as u8) << i;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_primitive_lfsr_has_maximal_period() {
+        // x^7 + x^6 + 1 は原始多項式なので周期は 2^7 - 1
+        let mut lfsr = Lfsr::from_bits(7, 0b1100000, 1);
+        let start = lfsr.clone();
+        let period = (1..=200)
+            .find(|_| {
+                lfsr.next_bit();
+                lfsr == start
+            })
+            .unwrap();
+        assert_eq!(period, 127);
+    }
+
+    #[test]
+    fn test_geffe_roundtrip_and_bias() {
+        let make = || {
+            Geffe::new(
+                Lfsr::from_bits(5, 0b10100, 0b10011),
+                Lfsr::from_bits(7, 0b1100000, 0b0101101),
+                Lfsr::from_bits(11, 0b10100000000, 0b11100001010),
+            )
+        };
+        let mut data = *b"geffe generator";
+        make().apply_keystream(&mut data);
+        assert_ne!(&data, b"geffe generator");
+        make().apply_keystream(&mut data);
+        assert_eq!(&data, b"geffe generator");
+        // 出力は x1 と 3/4 の確率で一致する (相関攻撃の入口)
+        let z = make().bits(4000);
+        let x1 = Lfsr::from_bits(5, 0b10100, 0b10011).bits(4000);
+        let agree = z.iter().zip(&x1).filter(|(a, b)| a == b).count();
+        assert!((2800..3200).contains(&agree), "agree {}", agree);
+    }
+}
@@ -0,0 +1,133 @@
+pub mod aes;
+pub mod chacha20;
+pub mod chacha20poly1305;
+pub mod des;
+pub mod feistel;
+pub mod ff1;
+pub mod gcm;
+pub mod keywrap;
+pub mod lfsr;
+pub mod modes;
+pub mod otp;
+pub mod padding;
+pub mod rc4;
+pub mod salsa20;
+pub mod secretbox;
+pub mod simon;
+pub mod siv;
+pub mod speck;
+pub mod xts;
+
+pub use aes::Aes;
+pub use chacha20::{chacha20, ChaCha20};
+pub use chacha20poly1305::ChaCha20Poly1305;
+pub use des::{Des, TripleDes};
+pub use feistel::Feistel;
+pub use ff1::Ff1;
+pub use gcm::{Aes128Gcm, Aes256Gcm, AesGcm};
+pub use keywrap::{
+    aes_key_unwrap, aes_key_unwrap_with_padding, aes_key_wrap, aes_key_wrap_with_padding,
+    KeyWrapError,
+};
+pub use lfsr::{Geffe, Lfsr};
+pub use modes::{
+    cbc_decrypt, cbc_decrypt_padded, cbc_encrypt, cbc_encrypt_padded, ctr_apply, ecb_decrypt,
+    ecb_encrypt, Ctr,
+};
+pub use otp::{otp_decrypt, otp_encrypt};
+pub use padding::{pkcs7_pad, pkcs7_unpad, PaddingError};
+pub use rc4::Rc4;
+pub use salsa20::{hsalsa20, Salsa20};
+pub use secretbox::{secretbox_open, secretbox_seal};
+pub use simon::{Simon128_128, Simon128_192, Simon128_256, Simon32_64, Simon64_128, Simon64_96};
+pub use siv::AesSiv;
+pub use speck::{Speck128_128, Speck128_192, Speck128_256, Speck32_64, Speck64_128, Speck64_96};
+pub use xts::AesXts;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AeadError {
+    // 暗号文がタグより短い
+    InvalidLength,
+    // 改竄されたか、鍵・nonce・AAD が違う
+    InvalidTag,
+}
+
+// 認証付き暗号の共通インターフェース。encrypt は暗号文の後ろにタグを付ける
+pub trait Aead: Sized {
+    const KEY_SIZE: usize;
+    const NONCE_SIZE: usize;
+    const TAG_SIZE: usize;
+
+    fn new(key: &[u8]) -> Self;
+    fn encrypt(&self, nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> Vec<u8>;
+    fn decrypt(&self, nonce: &[u8], aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, AeadError>;
+}
+
+fn check_key<A: Aead>(key: &[u8]) {
+    if key.len() != A::KEY_SIZE {
+        panic!("AEAD key must be {} bytes", A::KEY_SIZE);
+    }
+}
+
+fn check_nonce<A: Aead>(nonce: &[u8]) {
+    if nonce.len() != A::NONCE_SIZE {
+        panic!("AEAD nonce must be {} bytes", A::NONCE_SIZE);
+    }
+}
+
+// ブロック単位の置換。block の長さは BLOCK_SIZE でなければならない
+pub trait BlockCipher {
+    const BLOCK_SIZE: usize;
+
+    fn encrypt_block(&self, block: &mut [u8]);
+    fn decrypt_block(&self, block: &mut [u8]);
+}
+
+impl BlockCipher for Aes {
+    const BLOCK_SIZE: usize = 16;
+
+    fn encrypt_block(&self, block: &mut [u8]) {
+        Aes::encrypt_block(self, block.try_into().expect("AES block must be 16 bytes"))
+    }
+
+    fn decrypt_block(&self, block: &mut [u8]) {
+        Aes::decrypt_block(self, block.try_into().expect("AES block must be 16 bytes"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip<A: Aead>() {
+        let key: Vec<u8> = (0..A::KEY_SIZE as u8).collect();
+        let nonce = vec![9u8; A::NONCE_SIZE];
+        let aead = A::new(&key);
+        let ct = aead.encrypt(&nonce, b"aad", b"generic over the cipher");
+        assert_eq!(ct.len(), 23 + A::TAG_SIZE);
+        assert_eq!(
+            aead.decrypt(&nonce, b"aad", &ct).unwrap(),
+            b"generic over the cipher"
+        );
+        let mut other_nonce = nonce.clone();
+        other_nonce[0] ^= 1;
+        assert_eq!(
+            aead.decrypt(&other_nonce, b"aad", &ct),
+            Err(AeadError::InvalidTag)
+        );
+    }
+
+    #[test]
+    fn test_aead_implementations() {
+        roundtrip::<Aes128Gcm>();
+        roundtrip::<Aes256Gcm>();
+        roundtrip::<ChaCha20Poly1305>();
+        roundtrip::<AesSiv>();
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_wrong_key_size_panics() {
+        <Aes128Gcm as Aead>::new(&[0u8; 32]);
+    }
+}
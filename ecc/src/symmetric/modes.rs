@@ -0,0 +1,220 @@
+// ブロック暗号の利用モード。ECB は構造が漏れる悪い例として残している
+use super::padding::{pkcs7_pad, pkcs7_unpad, PaddingError};
+use super::BlockCipher;
+use crate::rand::RngCore;
+
+fn check_blocks<C: BlockCipher>(data: &[u8]) {
+    if !data.len().is_multiple_of(C::BLOCK_SIZE) {
+        panic!(
+            "Data length {} is not a multiple of the block size {}",
+            data.len(),
+            C::BLOCK_SIZE
+        );
+    }
+}
+
+fn check_iv<C: BlockCipher>(iv: &[u8]) {
+    if iv.len() != C::BLOCK_SIZE {
+        panic!("IV must be {} bytes", C::BLOCK_SIZE);
+    }
+}
+
+pub fn ecb_encrypt<C: BlockCipher>(cipher: &C, data: &[u8]) -> Vec<u8> {
+    check_blocks::<C>(data);
+    let mut out = data.to_vec();
+    for block in out.chunks_mut(C::BLOCK_SIZE) {
+        cipher.encrypt_block(block);
+    }
+    out
+}
+
+pub fn ecb_decrypt<C: BlockCipher>(cipher: &C, data: &[u8]) -> Vec<u8> {
+    check_blocks::<C>(data);
+    let mut out = data.to_vec();
+    for block in out.chunks_mut(C::BLOCK_SIZE) {
+        cipher.decrypt_block(block);
+    }
+    out
+}
+
+// CBC の IV は予測できない値でなければならない
+pub fn random_iv<C: BlockCipher, R: RngCore>(rng: &mut R) -> Vec<u8> {
+    let mut iv = vec![0u8; C::BLOCK_SIZE];
+    rng.fill_bytes(&mut iv);
+    iv
+}
+
+pub fn cbc_encrypt<C: BlockCipher>(cipher: &C, iv: &[u8], data: &[u8]) -> Vec<u8> {
+    check_iv::<C>(iv);
+    check_blocks::<C>(data);
+    let mut out = data.to_vec();
+    let mut prev = iv.to_vec();
+    for block in out.chunks_mut(C::BLOCK_SIZE) {
+        for (b, p) in block.iter_mut().zip(&prev) {
+            *b ^= p;
+        }
+        cipher.encrypt_block(block);
+        prev.copy_from_slice(block);
+    }
+    out
+}
+
+pub fn cbc_decrypt<C: BlockCipher>(cipher: &C, iv: &[u8], data: &[u8]) -> Vec<u8> {
+    check_iv::<C>(iv);
+    check_blocks::<C>(data);
+    let mut out = data.to_vec();
+    let mut prev = iv.to_vec();
+    for block in out.chunks_mut(C::BLOCK_SIZE) {
+        let ciphertext = block.to_vec();
+        cipher.decrypt_block(block);
+        for (b, p) in block.iter_mut().zip(&prev) {
+            *b ^= p;
+        }
+        prev = ciphertext;
+    }
+    out
+}
+
+// PKCS#7 でパディングしてから CBC で暗号化する
+pub fn cbc_encrypt_padded<C: BlockCipher>(cipher: &C, iv: &[u8], data: &[u8]) -> Vec<u8> {
+    cbc_encrypt(cipher, iv, &pkcs7_pad(data, C::BLOCK_SIZE))
+}
+
+// 認証なしの CBC でパディングの正否を相手に見せると、パディングオラクル攻撃が成立する
+pub fn cbc_decrypt_padded<C: BlockCipher>(
+    cipher: &C,
+    iv: &[u8],
+    data: &[u8],
+) -> Result<Vec<u8>, PaddingError> {
+    if data.is_empty() || !data.len().is_multiple_of(C::BLOCK_SIZE) {
+        return Err(PaddingError::InvalidLength);
+    }
+    pkcs7_unpad(&cbc_decrypt(cipher, iv, data), C::BLOCK_SIZE)
+}
+
+// カウンタモード。初期カウンタブロック全体をビッグエンディアン整数として増やす
+#[derive(Clone)]
+pub struct Ctr<C: BlockCipher> {
+    cipher: C,
+    counter: Vec<u8>,
+    keystream: Vec<u8>,
+    pos: usize,
+}
+
+impl<C: BlockCipher> Ctr<C> {
+    pub fn new(cipher: C, initial_counter: &[u8]) -> Self {
+        check_iv::<C>(initial_counter);
+        Ctr {
+            cipher,
+            counter: initial_counter.to_vec(),
+            keystream: vec![0; C::BLOCK_SIZE],
+            pos: C::BLOCK_SIZE,
+        }
+    }
+
+    fn refill(&mut self) {
+        self.keystream.copy_from_slice(&self.counter);
+        self.cipher.encrypt_block(&mut self.keystream);
+        for byte in self.counter.iter_mut().rev() {
+            *byte = byte.wrapping_add(1);
+            if *byte != 0 {
+                break;
+            }
+        }
+        self.pos = 0;
+    }
+
+    // 任意長のデータに鍵ストリームを XOR する。暗号化と復号は同じ操作
+    pub fn apply_keystream(&mut self, data: &mut [u8]) {
+        for byte in data.iter_mut() {
+            if self.pos == C::BLOCK_SIZE {
+                self.refill();
+            }
+            *byte ^= self.keystream[self.pos];
+            self.pos += 1;
+        }
+    }
+}
+
+pub fn ctr_apply<C: BlockCipher + Clone>(
+    cipher: &C,
+    initial_counter: &[u8],
+    data: &[u8],
+) -> Vec<u8> {
+    let mut out = data.to_vec();
+    Ctr::new(cipher.clone(), initial_counter).apply_keystream(&mut out);
+    out
+}
+
+// 「ECB ペンギン」: 生の画素列を ECB で暗号化する。端数は 0 で埋める。
+// 同じ色の領域は同じ暗号文ブロックになるので、画像として表示すると輪郭が残る
+pub fn ecb_penguin<C: BlockCipher>(cipher: &C, pixels: &[u8]) -> Vec<u8> {
+    let mut data = pixels.to_vec();
+    data.resize(pixels.len().div_ceil(C::BLOCK_SIZE) * C::BLOCK_SIZE, 0);
+    let mut out = ecb_encrypt(cipher, &data);
+    out.truncate(pixels.len());
+    out
+}
+
+// 直前までに一度でも現れたブロックと同じブロックの個数。ECB の検出に使える
+pub fn repeated_blocks(data: &[u8], block_size: usize) -> usize {
+    let mut seen = std::collections::HashSet::new();
+    data.chunks_exact(block_size)
+        .filter(|block| !seen.insert(*block))
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::OsRng;
+    use crate::symmetric::Aes;
+    use crate::test_util::{hex, unhex};
+
+    // NIST SP 800-38A 付録 F の AES-128 のベクトル
+    const KEY: &str = "2b7e151628aed2a6abf7158809cf4f3c";
+    const PLAINTEXT: &str = "6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e5130c81c46a35ce411e5fbc1191a0a52eff69f2445df4f9b17ad2b417be66c3710";
+
+    #[test]
+    fn test_sp800_38a_vectors() {
+        let aes = Aes::new(&unhex(KEY));
+        let pt = unhex(PLAINTEXT);
+        let ecb = ecb_encrypt(&aes, &pt);
+        assert_eq!(hex(&ecb), "3ad77bb40d7a3660a89ecaf32466ef97f5d3d58503b9699de785895a96fdbaaf43b1cd7f598ece23881b00e3ed0306887b0c785e27e8ad3f8223207104725dd4");
+        assert_eq!(ecb_decrypt(&aes, &ecb), pt);
+
+        let iv = unhex("000102030405060708090a0b0c0d0e0f");
+        let cbc = cbc_encrypt(&aes, &iv, &pt);
+        assert_eq!(hex(&cbc), "7649abac8119b246cee98e9b12e9197d5086cb9b507219ee95db113a917678b273bed6b8e3c1743b7116e69e222295163ff1caa1681fac09120eca307586e1a7");
+        assert_eq!(cbc_decrypt(&aes, &iv, &cbc), pt);
+
+        let counter = unhex("f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff");
+        let ctr = ctr_apply(&aes, &counter, &pt);
+        assert_eq!(hex(&ctr), "874d6191b620e3261bef6864990db6ce9806f66b7970fdff8617187bb9fffdff5ae4df3edbd5d35e5b4f09020db03eab1e031dda2fbe03d1792170a0f3009cee");
+        // ストリームとして少しずつ処理しても同じ
+        let mut stream = Ctr::new(aes, &counter);
+        let mut pieces = pt.clone();
+        for chunk in pieces.chunks_mut(7) {
+            stream.apply_keystream(chunk);
+        }
+        assert_eq!(pieces, ctr);
+    }
+
+    #[test]
+    fn test_ecb_penguin_leaks_structure() {
+        // 3 色だけからなる 64x64 の RGBA 画像
+        let pixels: Vec<u8> = (0..64 * 64)
+            .flat_map(|i| match (i % 64) / 22 {
+                0 => [0, 0, 0, 255],
+                1 => [255, 255, 255, 255],
+                _ => [255, 128, 0, 255],
+            })
+            .collect();
+        let aes = Aes::new(&unhex(KEY));
+        let ecb = ecb_penguin(&aes, &pixels);
+        assert_eq!(ecb.len(), pixels.len());
+        assert!(repeated_blocks(&ecb, 16) > 1000);
+        let iv = random_iv::<Aes, _>(&mut OsRng);
+        assert_eq!(repeated_blocks(&cbc_encrypt(&aes, &iv, &pixels), 16), 0);
+    }
+}
@@ -0,0 +1,36 @@
+// ワンタイムパッド。鍵がメッセージ以上の長さの一様乱数で、一度しか使わなければ完全秘匿
+use crate::rand::RngCore;
+
+pub fn generate_pad<R: RngCore>(len: usize, rng: &mut R) -> Vec<u8> {
+    let mut pad = vec![0u8; len];
+    rng.fill_bytes(&mut pad);
+    pad
+}
+
+pub fn otp_encrypt(pad: &[u8], message: &[u8]) -> Vec<u8> {
+    if pad.len() < message.len() {
+        panic!("One-time pad is shorter than the message");
+    }
+    message.iter().zip(pad).map(|(m, k)| m ^ k).collect()
+}
+
+pub fn otp_decrypt(pad: &[u8], ciphertext: &[u8]) -> Vec<u8> {
+    otp_encrypt(pad, ciphertext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::OsRng;
+
+    #[test]
+    fn test_otp_roundtrip() {
+        let pad = generate_pad(32, &mut OsRng);
+        let ct = otp_encrypt(&pad, b"meet me at noon");
+        assert_eq!(ct.len(), 15);
+        assert_eq!(otp_decrypt(&pad, &ct), b"meet me at noon");
+        // 暗号文は任意の同じ長さの平文に対応する鍵を持つ
+        let fake_pad = otp_encrypt(&ct, b"retreat at once");
+        assert_eq!(otp_decrypt(&fake_pad, &ct), b"retreat at once");
+    }
+}
@@ -0,0 +1,70 @@
+// PKCS#7 パディング。常に 1 から block_size バイトを付け、各バイトの値はその個数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaddingError {
+    InvalidLength,
+    InvalidPadding,
+}
+
+pub fn pkcs7_pad(data: &[u8], block_size: usize) -> Vec<u8> {
+    if block_size == 0 || block_size > 255 {
+        panic!("Invalid PKCS#7 block size {}", block_size);
+    }
+    let n = block_size - data.len() % block_size;
+    let mut out = data.to_vec();
+    out.resize(data.len() + n, n as u8);
+    out
+}
+
+pub fn pkcs7_unpad(data: &[u8], block_size: usize) -> Result<Vec<u8>, PaddingError> {
+    if data.is_empty() || !data.len().is_multiple_of(block_size) {
+        return Err(PaddingError::InvalidLength);
+    }
+    let n = *data.last().unwrap() as usize;
+    if n == 0 || n > block_size || data[data.len() - n..].iter().any(|&b| b as usize != n) {
+        return Err(PaddingError::InvalidPadding);
+    }
+    Ok(data[..data.len() - n].to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pad_unpad() {
+        assert_eq!(
+            pkcs7_pad(b"YELLOW SUBMARINE", 20),
+            b"YELLOW SUBMARINE\x04\x04\x04\x04"
+        );
+        // ちょうどブロック長なら 1 ブロック丸ごと付ける
+        assert_eq!(pkcs7_pad(&[1u8; 16], 16).len(), 32);
+        for len in 0..40 {
+            let data = vec![0xaa; len];
+            assert_eq!(pkcs7_unpad(&pkcs7_pad(&data, 16), 16), Ok(data));
+        }
+    }
+
+    #[test]
+    fn test_invalid_padding() {
+        assert_eq!(
+            pkcs7_unpad(b"ICE ICE BABY\x04\x04\x04\x04", 16).unwrap(),
+            b"ICE ICE BABY"
+        );
+        assert_eq!(
+            pkcs7_unpad(b"ICE ICE BABY\x05\x05\x05\x05", 16),
+            Err(PaddingError::InvalidPadding)
+        );
+        assert_eq!(
+            pkcs7_unpad(b"ICE ICE BABY\x01\x02\x03\x04", 16),
+            Err(PaddingError::InvalidPadding)
+        );
+        assert_eq!(
+            pkcs7_unpad(&[0u8; 16], 16),
+            Err(PaddingError::InvalidPadding)
+        );
+        assert_eq!(
+            pkcs7_unpad(&[1u8; 15], 16),
+            Err(PaddingError::InvalidLength)
+        );
+    }
+}
@@ -0,0 +1,89 @@
+// RC4 (教育用)。鍵ストリームの先頭に統計的な偏りがあり、RFC 7465 で TLS での使用が禁止された
+use crate::rand::RngCore;
+
+#[derive(Clone)]
+pub struct Rc4 {
+    s: [u8; 256],
+    i: u8,
+    j: u8,
+}
+
+impl Rc4 {
+    // KSA
+    pub fn new(key: &[u8]) -> Self {
+        if key.is_empty() || key.len() > 256 {
+            panic!("RC4 key must be 1 to 256 bytes");
+        }
+        let mut s: [u8; 256] = std::array::from_fn(|i| i as u8);
+        let mut j = 0u8;
+        for i in 0..256 {
+            j = j.wrapping_add(s[i]).wrapping_add(key[i % key.len()]);
+            s.swap(i, j as usize);
+        }
+        Rc4 { s, i: 0, j: 0 }
+    }
+
+    // PRGA
+    pub fn keystream(&mut self, out: &mut [u8]) {
+        for byte in out.iter_mut() {
+            self.i = self.i.wrapping_add(1);
+            self.j = self.j.wrapping_add(self.s[self.i as usize]);
+            self.s.swap(self.i as usize, self.j as usize);
+            let t = self.s[self.i as usize].wrapping_add(self.s[self.j as usize]);
+            *byte = self.s[t as usize];
+        }
+    }
+
+    pub fn apply_keystream(&mut self, data: &mut [u8]) {
+        for byte in data.iter_mut() {
+            let mut k = [0u8];
+            self.keystream(&mut k);
+            *byte ^= k[0];
+        }
+    }
+}
+
+// ランダムな 16 バイト鍵で 2 バイト目が 0 になる割合。
+// 一様なら 1/256 だが、Mantin–Shamir の偏りにより約 2/256 になる
+pub fn second_byte_zero_frequency<R: RngCore>(keys: usize, rng: &mut R) -> f64 {
+    let mut key_material = vec![0u8; 16 * keys];
+    rng.fill_bytes(&mut key_material);
+    let zeros = key_material
+        .chunks(16)
+        .filter(|key| {
+            let mut out = [0u8; 2];
+            Rc4::new(key).keystream(&mut out);
+            out[1] == 0
+        })
+        .count();
+    zeros as f64 / keys as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::OsRng;
+    use crate::test_util::hex;
+
+    #[test]
+    fn test_rc4_vectors() {
+        let cases: [(&[u8], &[u8], &str); 3] = [
+            (b"Key", b"Plaintext", "bbf316e8d940af0ad3"),
+            (b"Wiki", b"pedia", "1021bf0420"),
+            (b"Secret", b"Attack at dawn", "45a01f645fc35b383552544b9bf5"),
+        ];
+        for (key, pt, expected) in cases {
+            let mut data = pt.to_vec();
+            Rc4::new(key).apply_keystream(&mut data);
+            assert_eq!(hex(&data), expected);
+        }
+    }
+
+    #[test]
+    fn test_second_byte_bias() {
+        let freq = second_byte_zero_frequency(40_000, &mut OsRng);
+        // 期待値 2/256 ≈ 0.0078、標準偏差はおよそ 0.00044
+        assert!(freq > 1.5 / 256.0, "frequency {}", freq);
+        assert!(freq < 2.5 / 256.0, "frequency {}", freq);
+    }
+}
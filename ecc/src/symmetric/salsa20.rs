@@ -0,0 +1,165 @@
+// Salsa20 と XSalsa20。ChaCha20 と同じ ARX 構造だが、状態の配置と 4 分の 1 ラウンドが異なる
+const SIGMA: &[u8; 16] = b"expand 32-byte k";
+
+fn quarter_round(x: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    x[b] ^= x[a].wrapping_add(x[d]).rotate_left(7);
+    x[c] ^= x[b].wrapping_add(x[a]).rotate_left(9);
+    x[d] ^= x[c].wrapping_add(x[b]).rotate_left(13);
+    x[a] ^= x[d].wrapping_add(x[c]).rotate_left(18);
+}
+
+fn double_rounds(x: &mut [u32; 16], rounds: usize) {
+    for _ in 0..rounds / 2 {
+        // 列ラウンド
+        quarter_round(x, 0, 4, 8, 12);
+        quarter_round(x, 5, 9, 13, 1);
+        quarter_round(x, 10, 14, 2, 6);
+        quarter_round(x, 15, 3, 7, 11);
+        // 行ラウンド
+        quarter_round(x, 0, 1, 2, 3);
+        quarter_round(x, 5, 6, 7, 4);
+        quarter_round(x, 10, 11, 8, 9);
+        quarter_round(x, 15, 12, 13, 14);
+    }
+}
+
+// ラウンド後に入力を足し戻す Salsa20 のコア。scrypt は rounds = 8 で使う
+pub fn salsa20_core(input: &[u32; 16], rounds: usize) -> [u32; 16] {
+    let mut x = *input;
+    double_rounds(&mut x, rounds);
+    for (xi, ii) in x.iter_mut().zip(input) {
+        *xi = xi.wrapping_add(*ii);
+    }
+    x
+}
+
+fn words<const N: usize>(bytes: &[u8]) -> [u32; N] {
+    let mut w = [0u32; N];
+    for (word, chunk) in w.iter_mut().zip(bytes.chunks(4)) {
+        *word = u32::from_le_bytes(chunk.try_into().unwrap());
+    }
+    w
+}
+
+// 定数を対角に、鍵を両側に、16 バイトの入力を中央に置く
+fn initial_state(key: &[u8; 32], input: &[u8; 16]) -> [u32; 16] {
+    let (c, k, n) = (words::<4>(SIGMA), words::<8>(key), words::<4>(input));
+    [
+        c[0], k[0], k[1], k[2], k[3], c[1], n[0], n[1], n[2], n[3], c[2], k[4], k[5], k[6], k[7],
+        c[3],
+    ]
+}
+
+// HSalsa20: 足し戻しをせず、定数と入力の位置の語を取り出して新しい鍵にする
+pub fn hsalsa20(key: &[u8; 32], input: &[u8; 16]) -> [u8; 32] {
+    let mut x = initial_state(key, input);
+    double_rounds(&mut x, 20);
+    let mut out = [0u8; 32];
+    for (chunk, i) in out.chunks_mut(4).zip([0, 5, 10, 15, 6, 7, 8, 9]) {
+        chunk.copy_from_slice(&x[i].to_le_bytes());
+    }
+    out
+}
+
+pub fn salsa20_block(key: &[u8; 32], nonce: &[u8; 8], counter: u64) -> [u8; 64] {
+    let mut input = [0u8; 16];
+    input[..8].copy_from_slice(nonce);
+    input[8..].copy_from_slice(&counter.to_le_bytes());
+    let x = salsa20_core(&initial_state(key, &input), 20);
+    let mut out = [0u8; 64];
+    for (chunk, w) in out.chunks_mut(4).zip(x) {
+        chunk.copy_from_slice(&w.to_le_bytes());
+    }
+    out
+}
+
+#[derive(Clone)]
+pub struct Salsa20 {
+    key: [u8; 32],
+    nonce: [u8; 8],
+    counter: u64,
+    block: [u8; 64],
+    pos: usize,
+}
+
+impl Salsa20 {
+    pub fn new(key: &[u8; 32], nonce: &[u8; 8]) -> Self {
+        Salsa20 {
+            key: *key,
+            nonce: *nonce,
+            counter: 0,
+            block: [0; 64],
+            pos: 64,
+        }
+    }
+
+    // XSalsa20: nonce の前半 16 バイトで HSalsa20 により鍵を導出し、後半 8 バイトを nonce にする。
+    // 192 ビットの nonce はランダムに選んでも衝突しない
+    pub fn xsalsa20(key: &[u8; 32], nonce: &[u8; 24]) -> Self {
+        let subkey = hsalsa20(key, nonce[..16].try_into().unwrap());
+        Salsa20::new(&subkey, nonce[16..].try_into().unwrap())
+    }
+
+    pub fn keystream(&mut self, out: &mut [u8]) {
+        for byte in out.iter_mut() {
+            if self.pos == 64 {
+                self.block = salsa20_block(&self.key, &self.nonce, self.counter);
+                self.counter = self.counter.wrapping_add(1);
+                self.pos = 0;
+            }
+            *byte = self.block[self.pos];
+            self.pos += 1;
+        }
+    }
+
+    pub fn apply_keystream(&mut self, data: &mut [u8]) {
+        for chunk in data.chunks_mut(64) {
+            let mut ks = [0u8; 64];
+            self.keystream(&mut ks[..chunk.len()]);
+            for (b, k) in chunk.iter_mut().zip(ks) {
+                *b ^= k;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{hex, unhex};
+
+    #[test]
+    fn test_salsa20_spec_and_hsalsa20_vectors() {
+        // Salsa20 仕様書の展開関数の例: k0 = 1..16, k1 = 201..216, n = 101..116
+        let key: [u8; 32] =
+            std::array::from_fn(|i| if i < 16 { i as u8 + 1 } else { i as u8 + 185 });
+        let input: [u8; 16] = std::array::from_fn(|i| i as u8 + 101);
+        let x = salsa20_core(&initial_state(&key, &input), 20);
+        assert_eq!(x[0].to_le_bytes(), [69, 37, 68, 39]);
+        assert_eq!(x[1].to_le_bytes(), [41, 15, 107, 193]);
+        // NaCl の core1 テスト
+        let shared = unhex("4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742");
+        assert_eq!(
+            hex(&hsalsa20(&shared.try_into().unwrap(), &[0; 16])),
+            "1b27556473e985d462cd51197a9a46c76009549eac6474f206c4ee0844f68389"
+        );
+    }
+
+    #[test]
+    fn test_salsa20_stream() {
+        let key: [u8; 32] = std::array::from_fn(|i| i as u8);
+        let nonce: [u8; 8] = std::array::from_fn(|i| i as u8);
+        let mut data = b"Salsa20 shares most of its structure with ChaCha20!".to_vec();
+        Salsa20::new(&key, &nonce).apply_keystream(&mut data);
+        assert_eq!(hex(&data), "7dcc632c796519eea51ad2db4d97749a40a8308814faf8bb6d97cc67da8bb3df03ca771a762ebe015068bbbb9f35f4c4cfe824");
+        // 3 ブロック目 (カウンタ 2) の先頭
+        let mut ks = [0u8; 160];
+        let mut cipher = Salsa20::new(&key, &nonce);
+        cipher.keystream(&mut ks[..100]);
+        cipher.keystream(&mut ks[100..]);
+        assert_eq!(
+            hex(&ks[128..]),
+            "8740bfe7d23f9c0432c259039c79573aa220c0722b049623f3a19e9cd1b143f2"
+        );
+    }
+}
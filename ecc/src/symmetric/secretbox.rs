@@ -0,0 +1,68 @@
+// NaCl の crypto_secretbox (XSalsa20-Poly1305)。鍵ストリームの先頭 32 バイトを
+// Poly1305 の鍵にし、残りで暗号化する。出力はタグ || 暗号文
+use super::salsa20::Salsa20;
+use super::AeadError;
+use crate::mac::{ct_eq, poly1305};
+
+pub const TAG_LEN: usize = 16;
+
+fn poly_key(cipher: &mut Salsa20) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    cipher.keystream(&mut key);
+    key
+}
+
+pub fn secretbox_seal(key: &[u8; 32], nonce: &[u8; 24], message: &[u8]) -> Vec<u8> {
+    let mut cipher = Salsa20::xsalsa20(key, nonce);
+    let mac_key = poly_key(&mut cipher);
+    let mut ct = message.to_vec();
+    cipher.apply_keystream(&mut ct);
+    let mut out = poly1305(&mac_key, &ct).to_vec();
+    out.extend_from_slice(&ct);
+    out
+}
+
+pub fn secretbox_open(
+    key: &[u8; 32],
+    nonce: &[u8; 24],
+    boxed: &[u8],
+) -> Result<Vec<u8>, AeadError> {
+    if boxed.len() < TAG_LEN {
+        return Err(AeadError::InvalidLength);
+    }
+    let (tag, ct) = boxed.split_at(TAG_LEN);
+    let mut cipher = Salsa20::xsalsa20(key, nonce);
+    let mac_key = poly_key(&mut cipher);
+    if !ct_eq(&poly1305(&mac_key, ct), tag) {
+        return Err(AeadError::InvalidTag);
+    }
+    let mut message = ct.to_vec();
+    cipher.apply_keystream(&mut message);
+    Ok(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{hex, unhex};
+
+    #[test]
+    fn test_secretbox_roundtrip() {
+        let key = unhex("1b27556473e985d462cd51197a9a46c76009549eac6474f206c4ee0844f68389")
+            .try_into()
+            .unwrap();
+        let nonce = unhex("69696ee955b62b73cd62bda875fc73d68219e0036b7a0b37")
+            .try_into()
+            .unwrap();
+        let msg = b"NaCl secretbox: XSalsa20 + Poly1305";
+        let boxed = secretbox_seal(&key, &nonce, msg);
+        assert_eq!(hex(&boxed), "3e0cb89951d5e634979ce0792a02cac27eff2736549a85c57fe737ceb66f409542488ae1294e6f402c221c0e3ae92ea716034e");
+        assert_eq!(secretbox_open(&key, &nonce, &boxed).unwrap(), msg);
+        let mut tampered = boxed.clone();
+        tampered[20] ^= 4;
+        assert_eq!(
+            secretbox_open(&key, &nonce, &tampered),
+            Err(AeadError::InvalidTag)
+        );
+    }
+}
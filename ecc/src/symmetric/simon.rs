@@ -0,0 +1,165 @@
+// Simon (Beaulieu ら, 2013)。AND・回転・XOR だけの Feistel 型軽量ブロック暗号で、
+// Speck とは逆にハードウェア実装向けに設計されている
+use super::BlockCipher;
+
+// 鍵スケジュールの定数列 z_0..z_4 (周期 62)。上位ビットから順に使う
+const Z: [u64; 5] = [
+    0b11111010001001010110000111001101111101000100101011000011100110,
+    0b10001110111110010011000010110101000111011111001001100001011010,
+    0b10101111011100000011010010011000101000010001111110010110110011,
+    0b11011011101011000110010111100000010010001010011100110100001111,
+    0b11010001111001101011011000100000010111000011001010010011101111,
+];
+
+fn z_bit(seq: usize, i: usize) -> u64 {
+    (Z[seq] >> (61 - i % 62)) & 1
+}
+
+// (型名, ワード型, 鍵ワード数 m, ラウンド数 T, 定数列の番号)
+macro_rules! simon {
+    ($name:ident, $w:ty, $m:expr, $rounds:expr, $z:expr) => {
+        #[derive(Clone)]
+        pub struct $name {
+            round_keys: Vec<$w>,
+        }
+
+        impl $name {
+            pub const ROUNDS: usize = $rounds;
+            const WORD_BYTES: usize = <$w>::BITS as usize / 8;
+
+            pub fn new(key: &[u8]) -> Self {
+                Self::with_rounds(key, $rounds)
+            }
+
+            // ラウンド数を減らした版。解析の練習用
+            pub fn with_rounds(key: &[u8], rounds: usize) -> Self {
+                if key.len() != $m * Self::WORD_BYTES {
+                    panic!(
+                        "{} key must be {} bytes",
+                        stringify!($name),
+                        $m * Self::WORD_BYTES
+                    );
+                }
+                if rounds == 0 || rounds > $rounds {
+                    panic!("{} has 1 to {} rounds", stringify!($name), $rounds);
+                }
+                // 鍵のバイト列はリトルエンディアンのワード k_0, k_1, ... の順
+                let mut k: Vec<$w> = key
+                    .chunks(Self::WORD_BYTES)
+                    .map(|c| <$w>::from_le_bytes(c.try_into().unwrap()))
+                    .collect();
+                for i in $m..rounds {
+                    let mut tmp = k[i - 1].rotate_right(3);
+                    if $m == 4 {
+                        tmp ^= k[i - 3];
+                    }
+                    tmp ^= tmp.rotate_right(1);
+                    k.push(!k[i - $m] ^ tmp ^ z_bit($z, i - $m) as $w ^ 3);
+                }
+                k.truncate(rounds);
+                $name { round_keys: k }
+            }
+
+            fn f(x: $w) -> $w {
+                (x.rotate_left(1) & x.rotate_left(8)) ^ x.rotate_left(2)
+            }
+
+            pub fn encrypt_words(&self, mut x: $w, mut y: $w) -> ($w, $w) {
+                for &k in &self.round_keys {
+                    (x, y) = (y ^ Self::f(x) ^ k, x);
+                }
+                (x, y)
+            }
+
+            pub fn decrypt_words(&self, mut x: $w, mut y: $w) -> ($w, $w) {
+                for &k in self.round_keys.iter().rev() {
+                    (x, y) = (y, x ^ Self::f(y) ^ k);
+                }
+                (x, y)
+            }
+
+            fn split(block: &[u8]) -> ($w, $w) {
+                if block.len() != 2 * Self::WORD_BYTES {
+                    panic!(
+                        "{} block must be {} bytes",
+                        stringify!($name),
+                        2 * Self::WORD_BYTES
+                    );
+                }
+                let (y, x) = block.split_at(Self::WORD_BYTES);
+                (
+                    <$w>::from_le_bytes(x.try_into().unwrap()),
+                    <$w>::from_le_bytes(y.try_into().unwrap()),
+                )
+            }
+
+            fn join(block: &mut [u8], x: $w, y: $w) {
+                let (lo, hi) = block.split_at_mut(Self::WORD_BYTES);
+                lo.copy_from_slice(&y.to_le_bytes());
+                hi.copy_from_slice(&x.to_le_bytes());
+            }
+        }
+
+        // ブロックのバイト列は Speck と同じく y, x の順にリトルエンディアン
+        impl BlockCipher for $name {
+            const BLOCK_SIZE: usize = 2 * <$w>::BITS as usize / 8;
+
+            fn encrypt_block(&self, block: &mut [u8]) {
+                let (x, y) = Self::split(block);
+                let (x, y) = self.encrypt_words(x, y);
+                Self::join(block, x, y);
+            }
+
+            fn decrypt_block(&self, block: &mut [u8]) {
+                let (x, y) = Self::split(block);
+                let (x, y) = self.decrypt_words(x, y);
+                Self::join(block, x, y);
+            }
+        }
+    };
+}
+
+simon!(Simon32_64, u16, 4, 32, 0);
+simon!(Simon64_96, u32, 3, 42, 2);
+simon!(Simon64_128, u32, 4, 44, 3);
+simon!(Simon128_128, u64, 2, 68, 2);
+simon!(Simon128_192, u64, 3, 69, 3);
+simon!(Simon128_256, u64, 4, 72, 4);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::symmetric::modes::{ecb_decrypt, ecb_encrypt};
+    use crate::test_util::unhex;
+
+    #[test]
+    fn test_paper_vectors() {
+        let c = Simon32_64::new(&unhex("0001080910111819"));
+        assert_eq!(c.encrypt_words(0x6565, 0x6877), (0xc69b, 0xe9bb));
+        assert_eq!(c.decrypt_words(0xc69b, 0xe9bb), (0x6565, 0x6877));
+        let c = Simon64_96::new(&unhex("0001020308090a0b10111213"));
+        assert_eq!(
+            c.encrypt_words(0x6f722067, 0x6e696c63),
+            (0x5ca2e27f, 0x111a8fc8)
+        );
+        let c = Simon64_128::new(&unhex("0001020308090a0b1011121318191a1b"));
+        assert_eq!(
+            c.encrypt_words(0x656b696c, 0x20646e75),
+            (0x44c8fc20, 0xb9dfa07a)
+        );
+        let c = Simon128_128::new(&unhex("000102030405060708090a0b0c0d0e0f"));
+        assert_eq!(
+            c.encrypt_words(0x6373656420737265, 0x6c6c657661727420),
+            (0x49681b1e1e54fe3f, 0x65aa832af84e0bbc)
+        );
+    }
+
+    #[test]
+    fn test_block_cipher_trait_in_ecb() {
+        let c = Simon64_128::new(&[7; 16]);
+        let pt = b"sixteen byte msg".to_vec();
+        let ct = ecb_encrypt(&c, &pt);
+        assert_ne!(ct, pt);
+        assert_eq!(ecb_decrypt(&c, &ct), pt);
+    }
+}
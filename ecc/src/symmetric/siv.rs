@@ -0,0 +1,180 @@
+// AES-SIV (RFC 5297)。平文と関連データ全体の CMAC (S2V) を IV にするので、
+// nonce を使い回しても漏れるのは「同じメッセージかどうか」だけになる
+use super::modes::Ctr;
+use super::{check_key, check_nonce, Aead, AeadError, Aes};
+use crate::mac::cmac::{cmac, dbl};
+use crate::mac::ct_eq;
+
+pub const SIV_LEN: usize = 16;
+
+#[derive(Clone)]
+pub struct AesSiv {
+    mac: Aes,
+    ctr: Aes,
+}
+
+fn xor_into(a: &mut [u8], b: &[u8]) {
+    for (x, y) in a.iter_mut().zip(b) {
+        *x ^= y;
+    }
+}
+
+impl AesSiv {
+    // 鍵の前半が S2V 用、後半が CTR 用。32 / 48 / 64 バイト
+    pub fn new(key: &[u8]) -> Self {
+        if !matches!(key.len(), 32 | 48 | 64) {
+            panic!("Invalid AES-SIV key length {}", key.len());
+        }
+        let (k1, k2) = key.split_at(key.len() / 2);
+        AesSiv {
+            mac: Aes::new(k1),
+            ctr: Aes::new(k2),
+        }
+    }
+
+    fn s2v(&self, headers: &[&[u8]], plaintext: &[u8]) -> Vec<u8> {
+        let mut d = cmac(&self.mac, &[0u8; 16]);
+        for header in headers {
+            d = dbl(&d);
+            xor_into(&mut d, &cmac(&self.mac, header));
+        }
+        let t = if plaintext.len() >= 16 {
+            // 末尾 16 バイトに D を XOR する
+            let mut t = plaintext.to_vec();
+            let offset = t.len() - 16;
+            xor_into(&mut t[offset..], &d);
+            t
+        } else {
+            let mut t = dbl(&d);
+            let mut padded = plaintext.to_vec();
+            padded.push(0x80);
+            padded.resize(16, 0);
+            xor_into(&mut t, &padded);
+            t
+        };
+        cmac(&self.mac, &t)
+    }
+
+    // 31 ビット目と 63 ビット目を落としたものを CTR の初期値にする
+    fn apply_ctr(&self, siv: &[u8], data: &mut [u8]) {
+        let mut q = siv.to_vec();
+        q[8] &= 0x7f;
+        q[12] &= 0x7f;
+        Ctr::new(self.ctr.clone(), &q).apply_keystream(data);
+    }
+
+    // headers は関連データの列。nonce を使う場合は最後の要素として渡す
+    pub fn encrypt(&self, headers: &[&[u8]], plaintext: &[u8]) -> Vec<u8> {
+        let siv = self.s2v(headers, plaintext);
+        let mut out = plaintext.to_vec();
+        self.apply_ctr(&siv, &mut out);
+        [siv, out].concat()
+    }
+
+    pub fn decrypt(&self, headers: &[&[u8]], ciphertext: &[u8]) -> Result<Vec<u8>, AeadError> {
+        if ciphertext.len() < SIV_LEN {
+            return Err(AeadError::InvalidLength);
+        }
+        let (siv, ct) = ciphertext.split_at(SIV_LEN);
+        let mut plaintext = ct.to_vec();
+        self.apply_ctr(siv, &mut plaintext);
+        if !ct_eq(&self.s2v(headers, &plaintext), siv) {
+            return Err(AeadError::InvalidTag);
+        }
+        Ok(plaintext)
+    }
+}
+
+// Aead としては 32 バイト鍵 (AES-128 × 2) で、関連データ、nonce の順に S2V へ渡す。
+// タグ (SIV) は他の AEAD と揃えて暗号文の後ろに置く
+impl Aead for AesSiv {
+    const KEY_SIZE: usize = 32;
+    const NONCE_SIZE: usize = 16;
+    const TAG_SIZE: usize = SIV_LEN;
+
+    fn new(key: &[u8]) -> Self {
+        check_key::<Self>(key);
+        AesSiv::new(key)
+    }
+
+    fn encrypt(&self, nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> Vec<u8> {
+        check_nonce::<Self>(nonce);
+        let out = AesSiv::encrypt(self, &[aad, nonce], plaintext);
+        [&out[SIV_LEN..], &out[..SIV_LEN]].concat()
+    }
+
+    fn decrypt(&self, nonce: &[u8], aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, AeadError> {
+        check_nonce::<Self>(nonce);
+        if ciphertext.len() < SIV_LEN {
+            return Err(AeadError::InvalidLength);
+        }
+        let (ct, siv) = ciphertext.split_at(ciphertext.len() - SIV_LEN);
+        AesSiv::decrypt(self, &[aad, nonce], &[siv, ct].concat())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::symmetric::AesGcm;
+    use crate::test_util::{hex, unhex};
+
+    #[test]
+    fn test_rfc5297_vectors() {
+        // A.1 決定的な認証付き暗号
+        let siv = AesSiv::new(&unhex(
+            "fffefdfcfbfaf9f8f7f6f5f4f3f2f1f0f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff",
+        ));
+        let ad = unhex("101112131415161718191a1b1c1d1e1f2021222324252627");
+        let pt = unhex("112233445566778899aabbccddee");
+        let ct = siv.encrypt(&[&ad], &pt);
+        assert_eq!(
+            hex(&ct),
+            "85632d07c6e8f37f950acd320a2ecc9340c02b9690c4dc04daef7f6afe5c"
+        );
+        assert_eq!(siv.decrypt(&[&ad], &ct).unwrap(), pt);
+
+        // A.2 複数の関連データと nonce
+        let siv = AesSiv::new(&unhex(
+            "7f7e7d7c7b7a79787776757473727170404142434445464748494a4b4c4d4e4f",
+        ));
+        let ad1 = unhex(
+            "00112233445566778899aabbccddeeffdeaddadadeaddadaffeeddccbbaa99887766554433221100",
+        );
+        let ad2 = unhex("102030405060708090a0");
+        let nonce = unhex("09f911029d74e35bd84156c5635688c0");
+        let pt = unhex("7468697320697320736f6d6520706c61696e7465787420746f20656e6372797074207573696e67205349562d414553");
+        let ct = siv.encrypt(&[&ad1, &ad2, &nonce], &pt);
+        assert_eq!(hex(&ct), "7bdb6e3b432667eb06f4d14bff2fbd0fcb900f2fddbe404326601965c889bf17dba77ceb094fa663b7a3f748ba8af829ea64ad544a272e9c485b62a3fd5c0d");
+        assert_eq!(
+            siv.decrypt(&[&ad1, &nonce], &ct),
+            Err(AeadError::InvalidTag)
+        );
+    }
+
+    #[test]
+    fn test_nonce_reuse_compared_with_gcm() {
+        let key = [0x42u8; 32];
+        let m1 = b"transfer 100 to alice";
+        let m2 = b"transfer 999 to mallo";
+        let xor = |a: &[u8], b: &[u8]| -> Vec<u8> { a.iter().zip(b).map(|(x, y)| x ^ y).collect() };
+
+        // GCM で nonce を使い回すと、暗号文の XOR が平文の XOR になる
+        let gcm = AesGcm::new(&key);
+        let nonce = [0u8; 12];
+        let (g1, g2) = (gcm.encrypt(&nonce, b"", m1), gcm.encrypt(&nonce, b"", m2));
+        assert_eq!(xor(&g1[..m1.len()], &g2[..m2.len()]), xor(m1, m2));
+
+        // SIV では IV がメッセージごとに変わるので鍵ストリームも変わる。
+        // 漏れるのは同じメッセージを 2 回暗号化したという事実だけ
+        let siv = <AesSiv as Aead>::new(&key);
+        let nonce = [0u8; 16];
+        let (s1, s2) = (
+            Aead::encrypt(&siv, &nonce, b"", m1),
+            Aead::encrypt(&siv, &nonce, b"", m2),
+        );
+        assert_ne!(xor(&s1[..m1.len()], &s2[..m2.len()]), xor(m1, m2));
+        assert_eq!(Aead::encrypt(&siv, &nonce, b"", m1), s1);
+        assert_eq!(Aead::decrypt(&siv, &nonce, b"", &s2).unwrap(), m2);
+    }
+}
@@ -0,0 +1,183 @@
+// Speck (Beaulieu ら, 2013)。加算・回転・XOR だけからなる ARX 型の軽量ブロック暗号で、
+// ラウンド数を減らしたものは差分解読の練習台になる
+use super::BlockCipher;
+
+// (型名, ワード型, 鍵ワード数 m, ラウンド数 T, 回転量 α, β)
+macro_rules! speck {
+    ($name:ident, $w:ty, $m:expr, $rounds:expr, $alpha:expr, $beta:expr) => {
+        #[derive(Clone)]
+        pub struct $name {
+            round_keys: Vec<$w>,
+        }
+
+        impl $name {
+            pub const ROUNDS: usize = $rounds;
+            const WORD_BYTES: usize = <$w>::BITS as usize / 8;
+
+            pub fn new(key: &[u8]) -> Self {
+                Self::with_rounds(key, $rounds)
+            }
+
+            // ラウンド数を減らした版。解析の練習用
+            pub fn with_rounds(key: &[u8], rounds: usize) -> Self {
+                if key.len() != $m * Self::WORD_BYTES {
+                    panic!(
+                        "{} key must be {} bytes",
+                        stringify!($name),
+                        $m * Self::WORD_BYTES
+                    );
+                }
+                if rounds == 0 || rounds > $rounds {
+                    panic!("{} has 1 to {} rounds", stringify!($name), $rounds);
+                }
+                // 鍵のバイト列はリトルエンディアンのワード k_0, l_0, l_1, ... の順
+                let words: Vec<$w> = key
+                    .chunks(Self::WORD_BYTES)
+                    .map(|c| <$w>::from_le_bytes(c.try_into().unwrap()))
+                    .collect();
+                let mut k = words[0];
+                let mut l = words[1..].to_vec();
+                let mut round_keys = Vec::with_capacity(rounds);
+                for i in 0..rounds {
+                    round_keys.push(k);
+                    // 鍵スケジュールはラウンド関数をそのまま使い、定数としてラウンド番号を入れる
+                    let li = (k.wrapping_add(l[i].rotate_right($alpha))) ^ i as $w;
+                    k = k.rotate_left($beta) ^ li;
+                    l.push(li);
+                }
+                $name { round_keys }
+            }
+
+            pub fn encrypt_words(&self, mut x: $w, mut y: $w) -> ($w, $w) {
+                for &k in &self.round_keys {
+                    x = x.rotate_right($alpha).wrapping_add(y) ^ k;
+                    y = y.rotate_left($beta) ^ x;
+                }
+                (x, y)
+            }
+
+            pub fn decrypt_words(&self, mut x: $w, mut y: $w) -> ($w, $w) {
+                for &k in self.round_keys.iter().rev() {
+                    y = (y ^ x).rotate_right($beta);
+                    x = (x ^ k).wrapping_sub(y).rotate_left($alpha);
+                }
+                (x, y)
+            }
+
+            fn split(block: &[u8]) -> ($w, $w) {
+                if block.len() != 2 * Self::WORD_BYTES {
+                    panic!(
+                        "{} block must be {} bytes",
+                        stringify!($name),
+                        2 * Self::WORD_BYTES
+                    );
+                }
+                let (y, x) = block.split_at(Self::WORD_BYTES);
+                (
+                    <$w>::from_le_bytes(x.try_into().unwrap()),
+                    <$w>::from_le_bytes(y.try_into().unwrap()),
+                )
+            }
+
+            fn join(block: &mut [u8], x: $w, y: $w) {
+                let (lo, hi) = block.split_at_mut(Self::WORD_BYTES);
+                lo.copy_from_slice(&y.to_le_bytes());
+                hi.copy_from_slice(&x.to_le_bytes());
+            }
+        }
+
+        // ブロックのバイト列は y, x の順にリトルエンディアン (実装ガイドの規約)
+        impl BlockCipher for $name {
+            const BLOCK_SIZE: usize = 2 * <$w>::BITS as usize / 8;
+
+            fn encrypt_block(&self, block: &mut [u8]) {
+                let (x, y) = Self::split(block);
+                let (x, y) = self.encrypt_words(x, y);
+                Self::join(block, x, y);
+            }
+
+            fn decrypt_block(&self, block: &mut [u8]) {
+                let (x, y) = Self::split(block);
+                let (x, y) = self.decrypt_words(x, y);
+                Self::join(block, x, y);
+            }
+        }
+    };
+}
+
+speck!(Speck32_64, u16, 4, 22, 7, 2);
+speck!(Speck64_96, u32, 3, 26, 8, 3);
+speck!(Speck64_128, u32, 4, 27, 8, 3);
+speck!(Speck128_128, u64, 2, 32, 8, 3);
+speck!(Speck128_192, u64, 3, 33, 8, 3);
+speck!(Speck128_256, u64, 4, 34, 8, 3);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::{OsRng, RngCore};
+    use crate::test_util::{hex, unhex};
+
+    // 論文の鍵はワードを上位から (l_{m-2}, ..., l_0, k_0) と並べたもの
+    fn key_from_words(words: &[u64], bytes: usize) -> Vec<u8> {
+        words
+            .iter()
+            .rev()
+            .flat_map(|w| w.to_le_bytes()[..bytes].to_vec())
+            .collect()
+    }
+
+    #[test]
+    fn test_paper_vectors() {
+        let c = Speck32_64::new(&key_from_words(&[0x1918, 0x1110, 0x0908, 0x0100], 2));
+        assert_eq!(c.encrypt_words(0x6574, 0x694c), (0xa868, 0x42f2));
+        assert_eq!(c.decrypt_words(0xa868, 0x42f2), (0x6574, 0x694c));
+        let c = Speck64_96::new(&key_from_words(&[0x13121110, 0x0b0a0908, 0x03020100], 4));
+        assert_eq!(
+            c.encrypt_words(0x74614620, 0x736e6165),
+            (0x9f7952ec, 0x4175946c)
+        );
+        let c = Speck64_128::new(&key_from_words(
+            &[0x1b1a1918, 0x13121110, 0x0b0a0908, 0x03020100],
+            4,
+        ));
+        assert_eq!(
+            c.encrypt_words(0x3b726574, 0x7475432d),
+            (0x8c6fa548, 0x454e028b)
+        );
+        let c = Speck128_256::new(&unhex(
+            "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+        ));
+        assert_eq!(
+            c.encrypt_words(0x65736f6874206e49, 0x202e72656e6f6f70),
+            (0x4109010405c0f53e, 0x4eeeb48d9c188f43)
+        );
+        // バイト列の規約: 平文 " made it equival"
+        let c = Speck128_128::new(&unhex("000102030405060708090a0b0c0d0e0f"));
+        let mut block = *b" made it equival";
+        c.encrypt_block(&mut block);
+        assert_eq!(hex(&block), "180d575cdffe60786532787951985da6");
+        c.decrypt_block(&mut block);
+        assert_eq!(&block, b" made it equival");
+    }
+
+    #[test]
+    fn test_one_round_differential() {
+        // 入力差分 (0x0040, 0x8000) は回転後の最上位ビット同士が加算で打ち消し合うので、
+        // 1 ラウンド後に確率 1 で (0x0000, 0x0002) になる
+        for _ in 0..100 {
+            let mut key = [0u8; 8];
+            OsRng.fill_bytes(&mut key);
+            let c = Speck32_64::with_rounds(&key, 1);
+            let (x, y) = (OsRng.next_u32() as u16, OsRng.next_u32() as u16);
+            let (x1, y1) = c.encrypt_words(x, y);
+            let (x2, y2) = c.encrypt_words(x ^ 0x0040, y ^ 0x8000);
+            assert_eq!((x1 ^ x2, y1 ^ y2), (0x0000, 0x0002));
+        }
+        // 全ラウンドでは差分が散らばる
+        let c = Speck32_64::new(&[0; 8]);
+        let (x1, y1) = c.encrypt_words(0, 0);
+        let (x2, y2) = c.encrypt_words(0x0040, 0x8000);
+        assert_ne!((x1 ^ x2, y1 ^ y2), (0x0000, 0x0002));
+    }
+}
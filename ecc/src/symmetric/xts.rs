@@ -0,0 +1,156 @@
+// XTS-AES (IEEE 1619, SP 800-38E)。セクタ番号から作ったトウィークを
+// ブロックごとに x 倍して XEX を適用する。ディスク暗号化のように長さを変えられない用途向け
+use super::Aes;
+use crate::binary_field::Gf128;
+
+#[derive(Clone)]
+pub struct AesXts {
+    data: Aes,
+    tweak: Aes,
+}
+
+fn xor(block: &mut [u8; 16], t: &[u8; 16]) {
+    for (b, x) in block.iter_mut().zip(t) {
+        *b ^= x;
+    }
+}
+
+fn next_tweak(t: &[u8; 16]) -> [u8; 16] {
+    Gf128::from_le_poly_bytes(t)
+        .mul(&Gf128::X)
+        .to_le_poly_bytes()
+}
+
+impl AesXts {
+    // 32 バイトなら XTS-AES-128、64 バイトなら XTS-AES-256。前半がデータ用、後半がトウィーク用
+    pub fn new(key: &[u8]) -> Self {
+        if key.len() != 32 && key.len() != 64 {
+            panic!("Invalid XTS key length {}", key.len());
+        }
+        let (k1, k2) = key.split_at(key.len() / 2);
+        if k1 == k2 {
+            panic!("XTS data and tweak keys must differ");
+        }
+        AesXts {
+            data: Aes::new(k1),
+            tweak: Aes::new(k2),
+        }
+    }
+
+    fn xex(&self, block: &mut [u8; 16], t: &[u8; 16], decrypt: bool) {
+        xor(block, t);
+        if decrypt {
+            self.data.decrypt_block(block);
+        } else {
+            self.data.encrypt_block(block);
+        }
+        xor(block, t);
+    }
+
+    // 16 の倍数でない長さは暗号文窃取で処理する
+    fn process(&self, tweak: &[u8; 16], data: &mut [u8], decrypt: bool) {
+        if data.len() < 16 {
+            panic!("XTS needs at least one full block");
+        }
+        let mut t = *tweak;
+        self.tweak.encrypt_block(&mut t);
+        let full = data.len() / 16;
+        let rem = data.len() % 16;
+        // 端数があるときは最後の完全ブロックを窃取側で扱う
+        let normal = if rem == 0 { full } else { full - 1 };
+        for chunk in data[..16 * normal].chunks_mut(16) {
+            let block: &mut [u8; 16] = chunk.try_into().unwrap();
+            self.xex(block, &t, decrypt);
+            t = next_tweak(&t);
+        }
+        if rem == 0 {
+            return;
+        }
+        let (t_last, t_tail) = (t, next_tweak(&t));
+        // 復号では 2 つのトウィークを逆の順で使う
+        let (first, second) = if decrypt {
+            (t_tail, t_last)
+        } else {
+            (t_last, t_tail)
+        };
+        let offset = 16 * normal;
+        let mut cc: [u8; 16] = data[offset..offset + 16].try_into().unwrap();
+        self.xex(&mut cc, &first, decrypt);
+        let mut pp = cc;
+        pp[..rem].copy_from_slice(&data[offset + 16..]);
+        data[offset + 16..].copy_from_slice(&cc[..rem]);
+        self.xex(&mut pp, &second, decrypt);
+        data[offset..offset + 16].copy_from_slice(&pp);
+    }
+
+    pub fn encrypt(&self, tweak: &[u8; 16], data: &mut [u8]) {
+        self.process(tweak, data, false);
+    }
+
+    pub fn decrypt(&self, tweak: &[u8; 16], data: &mut [u8]) {
+        self.process(tweak, data, true);
+    }
+
+    // セクタ番号をリトルエンディアンで 16 バイトに広げたものをトウィークにする
+    pub fn encrypt_sector(&self, sector: u64, data: &mut [u8]) {
+        self.encrypt(&(sector as u128).to_le_bytes(), data);
+    }
+
+    pub fn decrypt_sector(&self, sector: u64, data: &mut [u8]) {
+        self.decrypt(&(sector as u128).to_le_bytes(), data);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{hex, unhex};
+
+    #[test]
+    fn test_ieee1619_vector_4() {
+        let xts = AesXts::new(&unhex(
+            "2718281828459045235360287471352631415926535897932384626433832795",
+        ));
+        let pt: Vec<u8> = (0..512).map(|i| i as u8).collect();
+        let mut sector = pt.clone();
+        xts.encrypt_sector(0, &mut sector);
+        assert_eq!(
+            hex(&sector[..32]),
+            "27a7479befa1d476489f308cd4cfa6e2a96e4bbe3208ff25287dd3819616e89c"
+        );
+        assert_eq!(hex(&sector[496..]), "0a282df920147beabe421ee5319d0568");
+        xts.decrypt_sector(0, &mut sector);
+        assert_eq!(sector, pt);
+        // 同じ内容でもセクタが違えば暗号文も違う
+        let (mut a, mut b) = (pt.clone(), pt.clone());
+        xts.encrypt_sector(1, &mut a);
+        xts.encrypt_sector(2, &mut b);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_ciphertext_stealing() {
+        let xts = AesXts::new(&unhex(
+            "fffefdfcfbfaf9f8f7f6f5f4f3f2f1f0bfbebdbcbbbab9b8b7b6b5b4b3b2b1b0",
+        ));
+        let tweak = unhex("9a785634120000000000000000000000")
+            .try_into()
+            .unwrap();
+        let cases = [
+            (17, "6c1625db4671522d3d7599601de7ca09ed"),
+            (20, "9d84c813f719aa2c7be3f66171c7c5c2edbf9dac"),
+            (
+                33,
+                "edbf9dace45d6f6a7306e64be5dd824b797b04bc8cf39759db5d32dc5204cbfb25",
+            ),
+        ];
+        for (len, expected) in cases {
+            let pt: Vec<u8> = (0..len as u8).collect();
+            let mut data = pt.clone();
+            xts.encrypt(&tweak, &mut data);
+            assert_eq!(hex(&data), expected);
+            xts.decrypt(&tweak, &mut data);
+            assert_eq!(data, pt);
+        }
+    }
+}
@@ -0,0 +1,106 @@
+// Rivest–Shamir–Wagner の時間錠パズル。メッセージを鍵 K = H(a^{2^t} mod n) で暗号化し、
+// (n, a, t) と暗号文を公開する。n の素因数分解を知る作成者は e = 2^t mod φ(n) として
+// a^e を一瞬で求められるが、解く側は t 回の二乗を順に行うしかない
+use crate::bignum::BigUint;
+use crate::hash::sha256;
+use crate::number_theory::{euler_phi_from_factors, generate_prime};
+use crate::rand::RngCore;
+use crate::symmetric::{AeadError, ChaCha20Poly1305};
+
+const MODULUS_BITS: usize = 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeLockPuzzle {
+    pub n: BigUint,
+    pub a: BigUint,
+    pub t: u64,
+    pub ciphertext: Vec<u8>,
+}
+
+fn key(n: &BigUint, b: &BigUint) -> [u8; 32] {
+    sha256(&b.to_bytes_be_padded(n.bits().div_ceil(8)))
+}
+
+// 暗号文は公開パラメータに束縛しておく
+fn aad(n: &BigUint, a: &BigUint, t: u64) -> Vec<u8> {
+    let len = n.bits().div_ceil(8);
+    let mut out = n.to_bytes_be_padded(len);
+    out.extend_from_slice(&a.to_bytes_be_padded(len));
+    out.extend_from_slice(&t.to_be_bytes());
+    out
+}
+
+fn seal(n: &BigUint, a: &BigUint, t: u64, b: &BigUint, message: &[u8]) -> Vec<u8> {
+    ChaCha20Poly1305::new(&key(n, b)).encrypt(&[0; 12], &aad(n, a, t), message)
+}
+
+pub fn create_puzzle<R: RngCore>(message: &[u8], t: u64, rng: &mut R) -> TimeLockPuzzle {
+    let p = generate_prime(MODULUS_BITS / 2, rng);
+    let q = generate_prime(MODULUS_BITS / 2, rng);
+    let n = p.mul(&q);
+    let phi = euler_phi_from_factors(&[(p, 1), (q, 1)]);
+    let a = BigUint::random_below(&n.sub(&BigUint::from_u64(2)), rng).add(&BigUint::from_u64(2));
+    // 落とし戸による近道
+    let e = BigUint::from_u64(2).modpow(&BigUint::from_u64(t), &phi);
+    let b = a.modpow(&e, &n);
+    TimeLockPuzzle {
+        ciphertext: seal(&n, &a, t, &b, message),
+        n,
+        a,
+        t,
+    }
+}
+
+pub fn solve_puzzle(puzzle: &TimeLockPuzzle) -> Result<Vec<u8>, AeadError> {
+    let mut b = puzzle.a.clone();
+    for _ in 0..puzzle.t {
+        b = b.mul(&b).rem(&puzzle.n);
+    }
+    ChaCha20Poly1305::new(&key(&puzzle.n, &b)).decrypt(
+        &[0; 12],
+        &aad(&puzzle.n, &puzzle.a, puzzle.t),
+        &puzzle.ciphertext,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::TestRng;
+
+    #[test]
+    fn test_solve_recovers_message() {
+        let mut rng = TestRng::from_label(b"timelock");
+        let puzzle = create_puzzle(b"open after the auction", 2000, &mut rng);
+        assert_eq!(solve_puzzle(&puzzle).unwrap(), b"open after the auction");
+        // 近道で求めた値は逐次二乗の結果と一致する
+        let exponent = BigUint::one().shl(2000);
+        let b = puzzle.a.modpow(&exponent, &puzzle.n);
+        let direct = ChaCha20Poly1305::new(&key(&puzzle.n, &b)).decrypt(
+            &[0; 12],
+            &aad(&puzzle.n, &puzzle.a, 2000),
+            &puzzle.ciphertext,
+        );
+        assert_eq!(direct.unwrap(), b"open after the auction");
+    }
+
+    #[test]
+    fn test_wrong_parameters_fail() {
+        let mut rng = TestRng::from_label(b"timelock-bad");
+        let puzzle = create_puzzle(b"secret", 100, &mut rng);
+        // 二乗の回数が足りなければ鍵が違う。パズル (AAD の t) はそのままで鍵だけ 99 回から作る
+        let square =
+            |times: usize| (0..times).fold(puzzle.a.clone(), |b, _| b.mul(&b).rem(&puzzle.n));
+        let short_key = key(&puzzle.n, &square(99));
+        assert_ne!(short_key, key(&puzzle.n, &square(100)));
+        let short = ChaCha20Poly1305::new(&short_key).decrypt(
+            &[0; 12],
+            &aad(&puzzle.n, &puzzle.a, puzzle.t),
+            &puzzle.ciphertext,
+        );
+        assert_eq!(short, Err(AeadError::InvalidTag));
+        let mut tampered = puzzle.clone();
+        tampered.ciphertext[0] ^= 1;
+        assert_eq!(solve_puzzle(&tampered), Err(AeadError::InvalidTag));
+    }
+}
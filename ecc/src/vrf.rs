@@ -0,0 +1,91 @@
+// DLEQ による VRF (ECVRF の簡略版)。入力 α を曲線に写した H に対して Γ = xH を計算し、
+// 出力は H(Γ)、証明は Γ と公開鍵 X = xG の離散対数が同じことの DLEQ。
+// Γ は鍵と入力から一意に決まるので、鍵の持ち主でも出力を選び直せない
+use crate::curve::{self, Scalar};
+use crate::hash::sha256;
+use crate::rand::RngCore;
+use crate::sigma::DleqProof;
+use crate::transcript::Transcript;
+use crate::Point;
+
+const DOMAIN: &[u8] = b"fundamental-cryptography/vrf";
+
+pub struct VrfSecretKey(Scalar);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VrfPublicKey(pub Point);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VrfProof {
+    pub gamma: Point,
+    pub dleq: DleqProof,
+}
+
+fn input_point(input: &[u8]) -> Point {
+    curve::hash_to_curve(&[DOMAIN, b"/input/", input].concat())
+}
+
+fn output(gamma: &Point) -> [u8; 32] {
+    sha256(&[DOMAIN, b"/output/", &gamma.to_bytes()].concat())
+}
+
+fn transcript(public: &VrfPublicKey, input: &[u8]) -> Transcript {
+    let mut t = Transcript::new(DOMAIN);
+    t.append_point(b"X", &public.0);
+    t.append_message(b"alpha", input);
+    t
+}
+
+pub fn keygen<R: RngCore>(rng: &mut R) -> (VrfSecretKey, VrfPublicKey) {
+    let sk = VrfSecretKey(Scalar::random_nonzero(rng));
+    let pk = sk.public_key();
+    (sk, pk)
+}
+
+impl VrfSecretKey {
+    pub fn public_key(&self) -> VrfPublicKey {
+        VrfPublicKey(curve::generator().scalar_mul(self.0.value()))
+    }
+
+    pub fn prove<R: RngCore>(&self, input: &[u8], rng: &mut R) -> ([u8; 32], VrfProof) {
+        let h = input_point(input);
+        let gamma = h.scalar_mul(self.0.value());
+        let mut t = transcript(&self.public_key(), input);
+        let dleq = DleqProof::prove(&self.0, &curve::generator(), &h, &mut t, rng);
+        (output(&gamma), VrfProof { gamma, dleq })
+    }
+}
+
+impl VrfPublicKey {
+    // 証明が正しければ出力を返す
+    pub fn verify(&self, input: &[u8], proof: &VrfProof) -> Option<[u8; 32]> {
+        let h = input_point(input);
+        let mut t = transcript(self, input);
+        proof
+            .dleq
+            .verify((&curve::generator(), &self.0), (&h, &proof.gamma), &mut t)
+            .then(|| output(&proof.gamma))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::TestRng;
+
+    #[test]
+    fn test_prove_and_verify() {
+        let mut rng = TestRng::from_label(b"vrf");
+        let (sk, pk) = keygen(&mut rng);
+        let (out, proof) = sk.prove(b"round 1", &mut rng);
+        assert_eq!(pk.verify(b"round 1", &proof), Some(out));
+        assert_eq!(pk.verify(b"round 2", &proof), None);
+        let (_, other) = keygen(&mut rng);
+        assert_eq!(other.verify(b"round 1", &proof), None);
+        // 証明の乱数が変わっても出力は同じ
+        let (again, proof2) = sk.prove(b"round 1", &mut rng);
+        assert_eq!(again, out);
+        assert_ne!(proof2.dleq, proof.dleq);
+        assert_ne!(sk.prove(b"round 2", &mut rng).0, out);
+    }
+}